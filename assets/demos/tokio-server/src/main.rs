@@ -3,7 +3,6 @@ use argon2::Argon2;
 use futures::{SinkExt, StreamExt};
 use imap_codec::imap_types::{
     command::CommandBody,
-    core::Vec1,
     response::{Capability, CommandContinuationRequest, Data, Greeting, Response, Status},
 };
 use tokio::{self, net::TcpListener};
@@ -63,8 +62,10 @@ async fn main() -> Result<(), Error> {
 
                 match (cmd.tag, cmd.body) {
                     (tag, CommandBody::Capability) => {
-                        let rsp =
-                            Response::Data(Data::Capability(Vec1::from(Capability::Imap4Rev1)));
+                        let rsp = Response::Data(
+                            Data::capability(Capability::Imap4Rev1)
+                                .context("Could not create `Data::Capability`")?,
+                        );
                         framed.send(&rsp).await.context("Could not send response")?;
                         println!("S: {BLUE}{rsp:#?}{RESET}");
 
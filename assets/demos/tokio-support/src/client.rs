@@ -132,31 +132,35 @@ impl TokioDecoder for ImapClientCodec {
                                             unreachable!();
                                         }
                                         // We found a literal.
-                                        ResponseDecodeError::LiteralFound { length } => {
-                                            if length <= self.max_literal_length {
-                                                src.reserve(length as usize);
+                                        ResponseDecodeError::LiteralFound { length }
+                                            if length <= self.max_literal_length =>
+                                        {
+                                            src.reserve(length as usize);
 
-                                                self.state = FramingState::ReadLiteral {
-                                                    to_consume_acc: *to_consume_acc,
+                                            self.state = FramingState::ReadLiteral {
+                                                to_consume_acc: *to_consume_acc,
+                                                length,
+                                            };
+
+                                            return Ok(None);
+                                        }
+                                        // We found a literal that is too long.
+                                        ResponseDecodeError::LiteralFound { length }
+                                        | ResponseDecodeError::LiteralTooLong { length } => {
+                                            src.advance(*to_consume_acc);
+
+                                            self.state =
+                                                FramingState::ReadLine { to_consume_acc: 0 };
+
+                                            return Err(ImapClientCodecError::Framing(
+                                                FramingError::LiteralTooLarge {
+                                                    max_literal_length: self.max_literal_length,
                                                     length,
-                                                };
-
-                                                return Ok(None);
-                                            } else {
-                                                src.advance(*to_consume_acc);
-
-                                                self.state =
-                                                    FramingState::ReadLine { to_consume_acc: 0 };
-
-                                                return Err(ImapClientCodecError::Framing(
-                                                    FramingError::LiteralTooLarge {
-                                                        max_literal_length: self.max_literal_length,
-                                                        length,
-                                                    },
-                                                ));
-                                            }
+                                                },
+                                            ));
                                         }
-                                        ResponseDecodeError::Failed => {
+                                        ResponseDecodeError::MessageTooLong
+                                        | ResponseDecodeError::Failed => {
                                             let consumed = src.split_to(*to_consume_acc);
                                             self.state =
                                                 FramingState::ReadLine { to_consume_acc: 0 };
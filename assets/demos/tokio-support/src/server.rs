@@ -103,30 +103,33 @@ impl TokioDecoder for ImapServerCodec {
                                         unreachable!();
                                     }
                                     // We found a literal.
-                                    CommandDecodeError::LiteralFound { length, .. } => {
-                                        if length as usize <= self.max_literal_size {
-                                            src.reserve(length as usize);
-
-                                            self.state = FramingState::ReadLiteral {
-                                                to_consume_acc: *to_consume_acc,
-                                                length,
-                                            };
-
-                                            return Ok(Some(Event::ActionRequired(
-                                                Action::SendLiteralAck(length),
-                                            )));
-                                        } else {
-                                            src.advance(*to_consume_acc);
-
-                                            self.state =
-                                                FramingState::ReadLine { to_consume_acc: 0 };
-
-                                            return Ok(Some(Event::ActionRequired(
-                                                Action::SendLiteralReject(length),
-                                            )));
-                                        }
+                                    CommandDecodeError::LiteralFound { length, .. }
+                                        if length as usize <= self.max_literal_size =>
+                                    {
+                                        src.reserve(length as usize);
+
+                                        self.state = FramingState::ReadLiteral {
+                                            to_consume_acc: *to_consume_acc,
+                                            length,
+                                        };
+
+                                        return Ok(Some(Event::ActionRequired(
+                                            Action::SendLiteralAck(length),
+                                        )));
                                     }
-                                    CommandDecodeError::Failed => {
+                                    // We found a literal that is too long.
+                                    CommandDecodeError::LiteralFound { length, .. }
+                                    | CommandDecodeError::LiteralTooLong { length, .. } => {
+                                        src.advance(*to_consume_acc);
+
+                                        self.state = FramingState::ReadLine { to_consume_acc: 0 };
+
+                                        return Ok(Some(Event::ActionRequired(
+                                            Action::SendLiteralReject(length),
+                                        )));
+                                    }
+                                    CommandDecodeError::MessageTooLong
+                                    | CommandDecodeError::Failed => {
                                         let consumed = src.split_to(*to_consume_acc);
                                         self.state = FramingState::ReadLine { to_consume_acc: 0 };
 
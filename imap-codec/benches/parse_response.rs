@@ -16,6 +16,21 @@ fn criterion_benchmark(c: &mut Criterion) {
             parse_response(black_box(&input[..]));
         })
     });
+
+    // Literals borrow from the input, i.e., decoding a large body should not copy it.
+    let input = {
+        let size = 10 * 1024 * 1024;
+        let mut input = format!("* 1 FETCH (BODY[] {{{size}}}\r\n").into_bytes();
+        input.resize(input.len() + size, b'A');
+        input.extend_from_slice(b")\r\n");
+        input
+    };
+
+    c.bench_function("parse_response_fetch_body_10mib", |b| {
+        b.iter(|| {
+            parse_response(black_box(&input[..]));
+        })
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -33,6 +33,15 @@ fn criterion_benchmark(c: &mut Criterion) {
             out.clear();
         })
     });
+
+    c.bench_function("serialize_response_into", |b| {
+        b.iter(|| {
+            ResponseCodec::default().encode_into(black_box(&rsp), &mut out);
+            black_box(&out);
+
+            out.clear();
+        })
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
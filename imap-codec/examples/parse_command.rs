@@ -52,6 +52,14 @@ fn main() {
                 // ... and read more data.
                 read_more(&mut buffer, Role::Client);
             }
+            // Literal or command exceeds the configured limits.
+            Err(CommandDecodeError::LiteralTooLong { .. } | CommandDecodeError::MessageTooLong) => {
+                println!("Command too long.");
+                println!("Clearing buffer.");
+
+                // Clear the buffer and proceed with loop.
+                buffer.clear();
+            }
             // Parser failed.
             Err(CommandDecodeError::Failed) => {
                 println!("Error parsing command.");
@@ -53,6 +53,16 @@ fn main() {
                 // Read more data.
                 read_more(&mut buffer, Role::Server);
             }
+            // Literal or response exceeds the configured limits.
+            Err(
+                ResponseDecodeError::LiteralTooLong { .. } | ResponseDecodeError::MessageTooLong,
+            ) => {
+                println!("Response too long.");
+                println!("Clearing buffer.");
+
+                // Clear the buffer and proceed with loop.
+                buffer.clear();
+            }
             // Parser failed.
             Err(ResponseDecodeError::Failed) => {
                 println!("Error parsing response.");
@@ -1,20 +1,15 @@
-#[cfg(not(feature = "quirk_crlf_relaxed"))]
-use abnf_core::streaming::crlf;
-#[cfg(feature = "quirk_crlf_relaxed")]
-use abnf_core::streaming::crlf_relaxed as crlf;
 use imap_types::{
     auth::{AuthMechanism, AuthenticateData},
     secret::Secret,
 };
 use nom::{
-    branch::alt,
     bytes::streaming::tag,
     combinator::{map, value},
     sequence::{terminated, tuple},
 };
 
 use crate::{
-    core::{atom, base64},
+    core::{atom, base64, crlf},
     decode::IMAPResult,
 };
 
@@ -40,12 +35,14 @@ pub(crate) fn auth_type(input: &[u8]) -> IMAPResult<&[u8], AuthMechanism> {
 ///                FIXME: Multiline base64 currently does not work.
 /// ```
 pub(crate) fn authenticate_data(input: &[u8]) -> IMAPResult<&[u8], AuthenticateData> {
-    alt((
+    // Note: We don't use `alt` here because it would hide a `BadBase64` error.
+    if input.starts_with(b"*") {
+        value(AuthenticateData::Cancel, tuple((tag("*"), crlf)))(input)
+    } else {
         map(terminated(base64, crlf), |data| {
             AuthenticateData::Continue(Secret::new(data))
-        }),
-        value(AuthenticateData::Cancel, tuple((tag("*"), crlf))),
-    ))(input)
+        })(input)
+    }
 }
 
 #[cfg(test)]
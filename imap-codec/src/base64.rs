@@ -0,0 +1,65 @@
+//! IMAP-flavored base64.
+//!
+//! IMAP uses the standard alphabet, requires padding, and doesn't allow line breaks [RFC 3501,
+//! section 9]. All base64 in imap-codec, e.g., in AUTHENTICATE or command continuation requests,
+//! must go through these functions.
+
+use ::base64::{engine::general_purpose::STANDARD, DecodeError, Engine};
+
+/// Encodes `data` using the standard alphabet, with padding, and without line breaks.
+pub(crate) fn encode_b64(data: &[u8]) -> String {
+    STANDARD.encode(data)
+}
+
+/// Decodes `data` using the standard alphabet.
+///
+/// Fails on missing padding, non-zero pad bits, and any character outside of the alphabet,
+/// including whitespace and line breaks.
+pub(crate) fn decode_b64(data: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    STANDARD.decode(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_b64_padding() {
+        let tests = [
+            // No padding needed.
+            (b"".as_ref(), b"".as_ref()),
+            (b"abc", b"YWJj"),
+            (b"\x00\x00\x00", b"AAAA"),
+            // Padding needed.
+            (b"\x00", b"AA=="),
+            (b"i", b"aQ=="),
+            (b"Test", b"VGVzdA=="),
+            (b"Test1", b"VGVzdDE="),
+        ];
+
+        for (decoded, encoded) in tests {
+            assert_eq!(encode_b64(decoded).as_bytes(), encoded);
+            assert_eq!(decode_b64(encoded).unwrap(), decoded);
+        }
+    }
+
+    #[test]
+    fn test_b64_invalid() {
+        let tests = [
+            // Missing padding.
+            b"VGVzdA".as_ref(),
+            b"VGVzdDE",
+            // Note: "pad bits MUST be set to zero by conforming encoders" [RFC 4648, sec. 3.5].
+            b"aa==",
+            // Line wrapping or whitespace.
+            b"VGVz\r\ndA==",
+            b"VGVz dA==",
+            // URL-safe alphabet.
+            b"-_-_",
+        ];
+
+        for test in tests {
+            assert!(decode_b64(test).is_err());
+        }
+    }
+}
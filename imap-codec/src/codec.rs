@@ -1,5 +1,8 @@
 pub mod decode;
 pub mod encode;
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub mod tokio;
 
 /// Codec for greetings.
 #[derive(Debug, Default)]
@@ -8,12 +11,52 @@ pub mod encode;
 // This allows to add configuration options later. For example, the
 // codec could transparently replace all literals with non-sync literals.
 #[non_exhaustive]
-pub struct GreetingCodec;
+pub struct GreetingCodec {
+    /// Accept a bare `\n` (instead of `\r\n`) as line ending.
+    ///
+    /// This affects the line ending that terminates a message and the one after a literal
+    /// announcement, e.g., `{5}\n`. Literal data is never interpreted. The encoder always emits
+    /// `\r\n`.
+    pub allow_lf_line_endings: bool,
+}
+
+/// Default value for `max_literal_length`, i.e., 25 MiB.
+pub const DEFAULT_MAX_LITERAL_LENGTH: u32 = 25 * 1024 * 1024;
+
+/// Default value for `max_message_length`, i.e., 64 MiB.
+pub const DEFAULT_MAX_MESSAGE_LENGTH: usize = 64 * 1024 * 1024;
 
 /// Codec for commands.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 #[non_exhaustive]
-pub struct CommandCodec;
+pub struct CommandCodec {
+    /// Maximum length of a single literal.
+    ///
+    /// Announcing a longer literal yields [`CommandDecodeError::LiteralTooLong`](crate::decode::CommandDecodeError::LiteralTooLong).
+    pub max_literal_length: u32,
+
+    /// Maximum length of a whole command, including all literals.
+    ///
+    /// Exceeding it yields [`CommandDecodeError::MessageTooLong`](crate::decode::CommandDecodeError::MessageTooLong).
+    pub max_message_length: usize,
+
+    /// Accept a bare `\n` (instead of `\r\n`) as line ending.
+    ///
+    /// This affects the line ending that terminates a message and the one after a literal
+    /// announcement, e.g., `{5}\n`. Literal data is never interpreted. The encoder always emits
+    /// `\r\n`.
+    pub allow_lf_line_endings: bool,
+}
+
+impl Default for CommandCodec {
+    fn default() -> Self {
+        Self {
+            max_literal_length: DEFAULT_MAX_LITERAL_LENGTH,
+            max_message_length: DEFAULT_MAX_MESSAGE_LENGTH,
+            allow_lf_line_endings: false,
+        }
+    }
+}
 
 /// Codec for authenticate data lines.
 #[derive(Debug, Default)]
@@ -21,15 +64,53 @@ pub struct CommandCodec;
 pub struct AuthenticateDataCodec;
 
 /// Codec for responses.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 #[non_exhaustive]
-pub struct ResponseCodec;
+pub struct ResponseCodec {
+    /// Maximum length of a single literal.
+    ///
+    /// Announcing a longer literal yields [`ResponseDecodeError::LiteralTooLong`](crate::decode::ResponseDecodeError::LiteralTooLong).
+    pub max_literal_length: u32,
+
+    /// Maximum length of a whole response, including all literals.
+    ///
+    /// Exceeding it yields [`ResponseDecodeError::MessageTooLong`](crate::decode::ResponseDecodeError::MessageTooLong).
+    pub max_message_length: usize,
+
+    /// Accept a bare `\n` (instead of `\r\n`) as line ending.
+    ///
+    /// This affects the line ending that terminates a message and the one after a literal
+    /// announcement, e.g., `{5}\n`. Literal data is never interpreted. The encoder always emits
+    /// `\r\n`.
+    pub allow_lf_line_endings: bool,
+}
+
+impl Default for ResponseCodec {
+    fn default() -> Self {
+        Self {
+            max_literal_length: DEFAULT_MAX_LITERAL_LENGTH,
+            max_message_length: DEFAULT_MAX_MESSAGE_LENGTH,
+            allow_lf_line_endings: false,
+        }
+    }
+}
 
 /// Codec for idle dones.
 #[derive(Debug, Default)]
 #[non_exhaustive]
 pub struct IdleDoneCodec;
 
+/// Codec for idle accepts, i.e., the command continuation request (e.g., `+ idling`) that a server
+/// sends in response to IDLE.
+///
+/// Note: This codec only decodes command continuation requests. Before accepting IDLE, the server
+/// may still send untagged responses, and it may reject IDLE with a tagged status response. For
+/// these, decoding fails with [`IdleAcceptDecodeError::Failed`](crate::decode::IdleAcceptDecodeError::Failed)
+/// and callers must fall back to [`ResponseCodec`](crate::ResponseCodec).
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct IdleAcceptCodec;
+
 macro_rules! impl_codec_new {
     ($codec:ty) => {
         impl $codec {
@@ -46,6 +127,7 @@ impl_codec_new!(CommandCodec);
 impl_codec_new!(AuthenticateDataCodec);
 impl_codec_new!(ResponseCodec);
 impl_codec_new!(IdleDoneCodec);
+impl_codec_new!(IdleAcceptCodec);
 
 #[cfg(test)]
 mod tests {
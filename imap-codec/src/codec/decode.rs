@@ -9,7 +9,10 @@
 //!
 //! Have a look at the [parse_command](https://github.com/duesee/imap-codec/blob/main/imap-codec/examples/parse_command.rs) example to see how a real-world application could decode IMAP.
 
-use std::num::{ParseIntError, TryFromIntError};
+use std::{
+    num::{ParseIntError, TryFromIntError},
+    ops::Range,
+};
 
 #[cfg(feature = "bounded-static")]
 use bounded_static::{IntoBoundedStatic, ToStatic};
@@ -18,16 +21,18 @@ use imap_types::{
     command::Command,
     core::{LiteralMode, Tag},
     extensions::idle::IdleDone,
-    response::{Greeting, Response},
+    response::{CommandContinuationRequest, Greeting, Response},
 };
 use nom::error::{ErrorKind, FromExternalError, ParseError};
 
 use crate::{
     auth::authenticate_data,
     command::command,
-    extensions::idle::idle_done,
+    core::tag_imap,
+    extensions::idle::{idle_accept, idle_done},
     response::{greeting, response},
-    AuthenticateDataCodec, CommandCodec, GreetingCodec, IdleDoneCodec, ResponseCodec,
+    AuthenticateDataCodec, CommandCodec, GreetingCodec, IdleAcceptCodec, IdleDoneCodec,
+    ResponseCodec,
 };
 
 /// An extended version of [`nom::IResult`].
@@ -123,6 +128,62 @@ pub trait Decoder {
         let (remaining, value) = self.decode(input).map_err(IntoBoundedStatic::into_static)?;
         Ok((remaining, value.into_static()))
     }
+
+    /// Decode exactly one message and require that `input` contains nothing else.
+    ///
+    /// This is useful when `input` is already framed, e.g., in tests, and trailing bytes indicate
+    /// a framing bug rather than the start of the next message.
+    fn decode_exact<'a>(
+        &self,
+        input: &'a [u8],
+    ) -> Result<Self::Message<'a>, DecodeExactError<Self::Error<'a>>> {
+        let (remaining, value) = self.decode(input).map_err(DecodeExactError::Decode)?;
+
+        if !remaining.is_empty() {
+            return Err(DecodeExactError::TrailingData {
+                length: remaining.len(),
+            });
+        }
+
+        Ok(value)
+    }
+
+    /// Decode as many (pipelined) messages as possible.
+    ///
+    /// Decoding stops at the first message that can't be decoded (yet), e.g., because it was only
+    /// partially received or announces a literal. The returned remainder starts exactly at this
+    /// message, so it can be kept and decoded again when more data is available, or decoded with
+    /// [`Decoder::decode`] to learn why decoding stopped.
+    fn decode_many<'a>(&self, input: &'a [u8]) -> (&'a [u8], Vec<Self::Message<'a>>) {
+        let mut remaining = input;
+        let mut messages = Vec::new();
+
+        while !remaining.is_empty() {
+            match self.decode(remaining) {
+                Ok((rem, message)) => {
+                    remaining = rem;
+                    messages.push(message);
+                }
+                Err(_) => break,
+            }
+        }
+
+        (remaining, messages)
+    }
+}
+
+/// Error during exact decoding, see [`Decoder::decode_exact`].
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DecodeExactError<E> {
+    /// Decoding failed.
+    Decode(E),
+
+    /// A message was decoded but was followed by more data.
+    TrailingData {
+        /// Number of remaining bytes.
+        length: usize,
+    },
 }
 
 /// Error during greeting decoding.
@@ -192,6 +253,23 @@ pub enum CommandDecodeError<'a> {
         mode: LiteralMode,
     },
 
+    /// The decoder found a literal longer than [`CommandCodec::max_literal_length`].
+    ///
+    /// A server should reject the literal, e.g., with a tagged `NO` (or `BAD`) response.
+    LiteralTooLong {
+        /// The corresponding command (tag) to which this literal is bound.
+        tag: Tag<'a>,
+
+        /// Literal length.
+        length: u32,
+
+        /// Literal mode, i.e., sync or non-sync.
+        mode: LiteralMode,
+    },
+
+    /// The command is (or would become) longer than [`CommandCodec::max_message_length`].
+    MessageTooLong,
+
     /// Decoding failed.
     Failed,
 }
@@ -203,6 +281,9 @@ pub enum AuthenticateDataDecodeError {
     /// More data is needed.
     Incomplete,
 
+    /// The line is not valid base64, e.g., a malformed SASL response.
+    BadBase64,
+
     /// Decoding failed.
     Failed,
 }
@@ -222,11 +303,22 @@ pub enum ResponseDecodeError {
     ///
     /// It can implement a discarding mechanism, basically, consuming the whole literal but not
     /// saving the bytes in memory. Or, it can close the connection.
+    ///
+    /// Note: An empty literal, i.e., `{0}`, is never reported here but as [`Self::Incomplete`].
     LiteralFound {
         /// Literal length.
         length: u32,
     },
 
+    /// The decoder found a literal longer than [`ResponseCodec::max_literal_length`].
+    LiteralTooLong {
+        /// Literal length.
+        length: u32,
+    },
+
+    /// The response is (or would become) longer than [`ResponseCodec::max_message_length`].
+    MessageTooLong,
+
     /// Decoding failed.
     Failed,
 }
@@ -242,6 +334,21 @@ pub enum IdleDoneDecodeError {
     Failed,
 }
 
+/// Error during idle accept decoding.
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum IdleAcceptDecodeError {
+    /// More data is needed.
+    Incomplete,
+
+    /// Decoding failed.
+    ///
+    /// Note: This is also returned for any other response, e.g., untagged data or a tagged `NO`
+    /// that rejects IDLE. Callers must decode the same input with
+    /// [`ResponseCodec`](crate::ResponseCodec) then.
+    Failed,
+}
+
 // -------------------------------------------------------------------------------------------------
 
 impl Decoder for GreetingCodec {
@@ -252,7 +359,7 @@ impl Decoder for GreetingCodec {
         &self,
         input: &'a [u8],
     ) -> Result<(&'a [u8], Self::Message<'a>), Self::Error<'static>> {
-        match greeting(input) {
+        match greeting(self.allow_lf_line_endings)(input) {
             Ok((rem, grt)) => Ok((rem, grt)),
             Err(nom::Err::Incomplete(_)) => Err(GreetingDecodeError::Incomplete),
             Err(nom::Err::Failure(_)) | Err(nom::Err::Error(_)) => Err(GreetingDecodeError::Failed),
@@ -268,19 +375,59 @@ impl Decoder for CommandCodec {
         &self,
         input: &'a [u8],
     ) -> Result<(&'a [u8], Self::Message<'a>), Self::Error<'a>> {
-        match command(input) {
-            Ok((rem, cmd)) => Ok((rem, cmd)),
-            Err(nom::Err::Incomplete(_)) => Err(CommandDecodeError::Incomplete),
+        match command(self.allow_lf_line_endings)(input) {
+            Ok((rem, _)) if input.len() - rem.len() > self.max_message_length => {
+                Err(CommandDecodeError::MessageTooLong)
+            }
+            Ok((rem, cmd)) => {
+                // Note: Literals followed by data don't signal `LiteralFound` and are checked here.
+                // The final line ending can't complete a literal prefix and is skipped.
+                match literal_too_long(
+                    &input[..input.len() - rem.len() - 1],
+                    self.max_literal_length,
+                    self.allow_lf_line_endings,
+                ) {
+                    Some((length, mode)) => Err(CommandDecodeError::LiteralTooLong {
+                        tag: cmd.tag,
+                        length,
+                        mode,
+                    }),
+                    None => Ok((rem, cmd)),
+                }
+            }
+            Err(nom::Err::Incomplete(_)) if input.len() > self.max_message_length => {
+                Err(CommandDecodeError::MessageTooLong)
+            }
+            Err(nom::Err::Incomplete(_)) => {
+                match (
+                    tag_imap(input),
+                    literal_too_long(input, self.max_literal_length, self.allow_lf_line_endings),
+                ) {
+                    (Ok((_, tag)), Some((length, mode))) => {
+                        Err(CommandDecodeError::LiteralTooLong { tag, length, mode })
+                    }
+                    _ => Err(CommandDecodeError::Incomplete),
+                }
+            }
             Err(nom::Err::Failure(error)) => match error {
                 IMAPParseError {
                     input: _,
                     kind: IMAPErrorKind::Literal { tag, length, mode },
-                } => Err(CommandDecodeError::LiteralFound {
+                } => {
                     // Unwrap: We *must* receive a `tag` during command parsing.
-                    tag: tag.expect("Expected `Some(tag)` in `IMAPErrorKind::Literal`, got `None`"),
-                    length,
-                    mode,
-                }),
+                    let tag =
+                        tag.expect("Expected `Some(tag)` in `IMAPErrorKind::Literal`, got `None`");
+
+                    // Note: A literal is only announced when `input` ends with its prefix.
+                    if length > self.max_literal_length {
+                        Err(CommandDecodeError::LiteralTooLong { tag, length, mode })
+                    } else if input.len().saturating_add(length as usize) > self.max_message_length
+                    {
+                        Err(CommandDecodeError::MessageTooLong)
+                    } else {
+                        Err(CommandDecodeError::LiteralFound { tag, length, mode })
+                    }
+                }
                 _ => Err(CommandDecodeError::Failed),
             },
             Err(nom::Err::Error(_)) => Err(CommandDecodeError::Failed),
@@ -288,6 +435,140 @@ impl Decoder for CommandCodec {
     }
 }
 
+impl CommandCodec {
+    /// Decode a command and record where its parts are located in `input`.
+    ///
+    /// This works like [`Decoder::decode`] but additionally returns [`CommandSpans`], e.g., to
+    /// rewrite or redact parts of a command without re-encoding it.
+    pub fn decode_spanned<'a>(
+        &self,
+        input: &'a [u8],
+    ) -> Result<(&'a [u8], Command<'a>, CommandSpans), CommandDecodeError<'a>> {
+        let (remaining, command) = self.decode(input)?;
+        let spans = CommandSpans::new(
+            &input[..input.len() - remaining.len()],
+            command.tag.inner().len(),
+            self.allow_lf_line_endings,
+        );
+
+        Ok((remaining, command, spans))
+    }
+}
+
+/// Byte ranges of the parts of a command, relative to the start of the decoded input.
+///
+/// ```text
+/// a select {5}\r\ninbox\r\n
+/// ^          ^         ^^^^^ literals[0]
+/// ^          ^^^^^^^^^^^^^^^^ body
+/// ^ tag
+/// ```
+///
+/// Note: Only top-level spans are recorded (for now).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CommandSpans {
+    /// The tag, e.g., `a`.
+    pub tag: Range<usize>,
+    /// Everything between the tag and the final line ending, e.g., `select {5}\r\ninbox`.
+    pub body: Range<usize>,
+    /// The data of each literal (without its `{<length>}\r\n` prefix), in order.
+    pub literals: Vec<Range<usize>>,
+}
+
+impl CommandSpans {
+    /// Compute the spans of a successfully decoded `command`.
+    fn new(command: &[u8], tag_length: usize, allow_lf: bool) -> Self {
+        let line_ending = if command.ends_with(b"\r\n") { 2 } else { 1 };
+        let body = tag_length + 1..command.len() - line_ending;
+
+        let literals = literals(&command[..body.end], allow_lf)
+            .map(|(range, _)| range)
+            .collect();
+
+        Self {
+            tag: 0..tag_length,
+            body,
+            literals,
+        }
+    }
+}
+
+/// Returns the data range and mode of every literal in a (partially) decoded message.
+///
+/// Note: `{` can't be part of an atom and quoted strings can't contain line endings.
+/// Thus, every `{<length>}\r\n` outside of literal data introduces a literal.
+/// A bare `LF` ends a literal prefix only when `allow_lf` is set (or with `quirk_crlf_relaxed`).
+fn literals(
+    message: &[u8],
+    allow_lf: bool,
+) -> impl Iterator<Item = (Range<usize>, LiteralMode)> + '_ {
+    let mut position = 0;
+
+    std::iter::from_fn(move || {
+        while position < message.len() {
+            match literal_prefix(&message[position..], allow_lf) {
+                Some((prefix, length, mode)) => {
+                    let start = position + prefix;
+                    position = start.saturating_add(length as usize);
+                    return Some((start..position, mode));
+                }
+                None => match message[position + 1..].iter().position(|b| *b == b'{') {
+                    Some(next) => position += 1 + next,
+                    None => position = message.len(),
+                },
+            }
+        }
+
+        None
+    })
+}
+
+/// Returns the mode of the literal prefix, e.g., `{5}\r\n`, that a (partially) decoded message ends with.
+///
+/// In this case, no data of this literal was received yet.
+#[cfg(feature = "tokio")]
+pub(crate) fn trailing_literal_prefix(message: &[u8], allow_lf: bool) -> Option<LiteralMode> {
+    literals(message, allow_lf)
+        .last()
+        .filter(|(range, _)| range.start == message.len())
+        .map(|(_, mode)| mode)
+}
+
+/// Returns the length and mode of the first literal exceeding `max_literal_length`.
+fn literal_too_long(
+    message: &[u8],
+    max_literal_length: u32,
+    allow_lf: bool,
+) -> Option<(u32, LiteralMode)> {
+    literals(message, allow_lf)
+        .find(|(range, _)| range.len() > max_literal_length as usize)
+        .map(|(range, mode)| (range.len() as u32, mode))
+}
+
+/// Returns the length of a literal prefix, e.g., `{5}\r\n`, the announced literal length, and its mode.
+fn literal_prefix(input: &[u8], allow_lf: bool) -> Option<(usize, u32, LiteralMode)> {
+    let input = input.strip_prefix(b"{")?;
+    let close = input.iter().position(|byte| *byte == b'}')?;
+    let (digits, mode) = match input[..close].strip_suffix(b"+") {
+        Some(digits) => (digits, LiteralMode::NonSync),
+        None => (&input[..close], LiteralMode::Sync),
+    };
+
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+
+    let length = std::str::from_utf8(digits).ok()?.parse().ok()?;
+    let line_ending = match &input[close + 1..] {
+        [b'\r', b'\n', ..] => 2,
+        [b'\n', ..] if allow_lf || cfg!(feature = "quirk_crlf_relaxed") => 1,
+        _ => return None,
+    };
+
+    Some((1 + close + 1 + line_ending, length, mode))
+}
+
 impl Decoder for ResponseCodec {
     type Message<'a> = Response<'a>;
     type Error<'a> = ResponseDecodeError;
@@ -296,14 +577,50 @@ impl Decoder for ResponseCodec {
         &self,
         input: &'a [u8],
     ) -> Result<(&'a [u8], Self::Message<'a>), Self::Error<'static>> {
-        match response(input) {
-            Ok((rem, rsp)) => Ok((rem, rsp)),
-            Err(nom::Err::Incomplete(_)) => Err(ResponseDecodeError::Incomplete),
+        match response(self.allow_lf_line_endings)(input) {
+            Ok((rem, _)) if input.len() - rem.len() > self.max_message_length => {
+                Err(ResponseDecodeError::MessageTooLong)
+            }
+            Ok((rem, rsp)) => {
+                // Note: Literals followed by data don't signal `LiteralFound` and are checked here.
+                // The final line ending can't complete a literal prefix and is skipped.
+                match literal_too_long(
+                    &input[..input.len() - rem.len() - 1],
+                    self.max_literal_length,
+                    self.allow_lf_line_endings,
+                ) {
+                    Some((length, _)) => Err(ResponseDecodeError::LiteralTooLong { length }),
+                    None => Ok((rem, rsp)),
+                }
+            }
+            Err(nom::Err::Incomplete(_)) if input.len() > self.max_message_length => {
+                Err(ResponseDecodeError::MessageTooLong)
+            }
+            Err(nom::Err::Incomplete(_)) => {
+                match literal_too_long(input, self.max_literal_length, self.allow_lf_line_endings) {
+                    Some((length, _)) => Err(ResponseDecodeError::LiteralTooLong { length }),
+                    None => Err(ResponseDecodeError::Incomplete),
+                }
+            }
             Err(nom::Err::Error(error) | nom::Err::Failure(error)) => match error {
                 IMAPParseError {
                     kind: IMAPErrorKind::Literal { length, .. },
                     ..
-                } => Err(ResponseDecodeError::LiteralFound { length }),
+                } => {
+                    // Note: A literal is only announced when `input` ends with its prefix.
+                    if length == 0 {
+                        // An empty literal has no data to wait for (and there is no continuation
+                        // request in responses). We just need the rest of the response.
+                        Err(ResponseDecodeError::Incomplete)
+                    } else if length > self.max_literal_length {
+                        Err(ResponseDecodeError::LiteralTooLong { length })
+                    } else if input.len().saturating_add(length as usize) > self.max_message_length
+                    {
+                        Err(ResponseDecodeError::MessageTooLong)
+                    } else {
+                        Err(ResponseDecodeError::LiteralFound { length })
+                    }
+                }
                 _ => Err(ResponseDecodeError::Failed),
             },
         }
@@ -321,9 +638,10 @@ impl Decoder for AuthenticateDataCodec {
         match authenticate_data(input) {
             Ok((rem, rsp)) => Ok((rem, rsp)),
             Err(nom::Err::Incomplete(_)) => Err(AuthenticateDataDecodeError::Incomplete),
-            Err(nom::Err::Failure(_)) | Err(nom::Err::Error(_)) => {
-                Err(AuthenticateDataDecodeError::Failed)
-            }
+            Err(nom::Err::Failure(error)) | Err(nom::Err::Error(error)) => match error.kind {
+                IMAPErrorKind::BadBase64 => Err(AuthenticateDataDecodeError::BadBase64),
+                _ => Err(AuthenticateDataDecodeError::Failed),
+            },
         }
     }
 }
@@ -344,6 +662,24 @@ impl Decoder for IdleDoneCodec {
     }
 }
 
+impl Decoder for IdleAcceptCodec {
+    type Message<'a> = CommandContinuationRequest<'a>;
+    type Error<'a> = IdleAcceptDecodeError;
+
+    fn decode<'a>(
+        &self,
+        input: &'a [u8],
+    ) -> Result<(&'a [u8], Self::Message<'a>), Self::Error<'static>> {
+        match idle_accept(input) {
+            Ok((rem, rsp)) => Ok((rem, rsp)),
+            Err(nom::Err::Incomplete(_)) => Err(IdleAcceptDecodeError::Incomplete),
+            Err(nom::Err::Failure(_)) | Err(nom::Err::Error(_)) => {
+                Err(IdleAcceptDecodeError::Failed)
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::num::NonZeroU32;
@@ -573,6 +909,15 @@ mod tests {
                 b" V GVzdA= \r\n".as_ref(),
                 Err(AuthenticateDataDecodeError::Failed),
             ),
+            // Bad base64
+            (
+                b"VGVzdA\r\n".as_ref(),
+                Err(AuthenticateDataDecodeError::BadBase64),
+            ),
+            (
+                b"aa==\r\n".as_ref(),
+                Err(AuthenticateDataDecodeError::BadBase64),
+            ),
         ];
 
         for (test, expected) in tests {
@@ -670,6 +1015,22 @@ mod tests {
                 b"* 1 FETCH (RFC822 {5}\r\n".as_ref(),
                 Err(ResponseDecodeError::LiteralFound { length: 5 }),
             ),
+            (
+                b"* 1 FETCH (RFC822 {0}\r\n".as_ref(),
+                Err(ResponseDecodeError::Incomplete),
+            ),
+            (
+                b"* 1 FETCH (RFC822 {0}\r\n)\r\n".as_ref(),
+                Ok((
+                    b"".as_ref(),
+                    Response::Data(Data::Fetch {
+                        seq: NonZeroU32::new(1).unwrap(),
+                        items: Vec1::from(MessageDataItem::Rfc822(NString(Some(
+                            IString::Literal(Literal::try_from(b"".as_ref()).unwrap()),
+                        )))),
+                    }),
+                )),
+            ),
             // Failed
             (
                 b"*  search 1 2 3\r\n".as_ref(),
@@ -690,4 +1051,326 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_decode_limits() {
+        let codec = CommandCodec {
+            max_literal_length: 4,
+            max_message_length: 24,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            codec.decode(b"a login {5}\r\n"),
+            Err(CommandDecodeError::LiteralTooLong {
+                tag: Tag::try_from("a").unwrap(),
+                length: 5,
+                mode: LiteralMode::Sync,
+            })
+        );
+        assert_eq!(
+            codec.decode(b"a login {4}\r\n"),
+            Err(CommandDecodeError::LiteralFound {
+                tag: Tag::try_from("a").unwrap(),
+                length: 4,
+                mode: LiteralMode::Sync,
+            })
+        );
+        assert_eq!(
+            codec.decode(b"a login {4}\r\nuser {4}\r\n"),
+            Err(CommandDecodeError::MessageTooLong)
+        );
+        assert_eq!(
+            codec.decode(b"a login aaaaaaaaaaaaaaaaaaaaaaaaaaaa"),
+            Err(CommandDecodeError::MessageTooLong)
+        );
+        assert_eq!(
+            codec.decode(b"a login aaaaaaaaaaaaaaaaaaaaaaa b\r\n"),
+            Err(CommandDecodeError::MessageTooLong)
+        );
+        assert!(codec.decode(b"a login {4}\r\nuser pass\r\n").is_ok());
+        // Note: Even an empty synchronizing literal requires a continuation request.
+        assert_eq!(
+            codec.decode(b"a login {0}\r\n"),
+            Err(CommandDecodeError::LiteralFound {
+                tag: Tag::try_from("a").unwrap(),
+                length: 0,
+                mode: LiteralMode::Sync,
+            })
+        );
+        assert!(codec.decode(b"a login {0}\r\n {0+}\r\n\r\n").is_ok());
+        // Note: Literals followed by data must be checked as well.
+        assert_eq!(
+            codec.decode(b"a login {5+}\r\naaaaa x\r\n"),
+            Err(CommandDecodeError::LiteralTooLong {
+                tag: Tag::try_from("a").unwrap(),
+                length: 5,
+                mode: LiteralMode::NonSync,
+            })
+        );
+        assert_eq!(
+            codec.decode(b"a login {5+}\r\naa"),
+            Err(CommandDecodeError::LiteralTooLong {
+                tag: Tag::try_from("a").unwrap(),
+                length: 5,
+                mode: LiteralMode::NonSync,
+            })
+        );
+
+        let codec = ResponseCodec {
+            max_literal_length: 4,
+            max_message_length: 32,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            codec.decode(b"* 1 FETCH (RFC822 {5}\r\n"),
+            Err(ResponseDecodeError::LiteralTooLong { length: 5 })
+        );
+        assert_eq!(
+            codec.decode(b"* 1 FETCH (RFC822 {4}\r\n"),
+            Err(ResponseDecodeError::LiteralFound { length: 4 })
+        );
+        assert_eq!(
+            codec.decode(b"* 1 FETCH (RFC822 {5}\r\naaaaa)\r\n"),
+            Err(ResponseDecodeError::LiteralTooLong { length: 5 })
+        );
+        assert_eq!(
+            codec.decode(b"* 1 FETCH (RFC822 {5}\r\naa"),
+            Err(ResponseDecodeError::LiteralTooLong { length: 5 })
+        );
+        assert!(codec.decode(b"* 1 FETCH (RFC822 {4}\r\naaaa)\r\n").is_ok());
+        assert_eq!(
+            codec.decode(b"* OK aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"),
+            Err(ResponseDecodeError::MessageTooLong)
+        );
+    }
+
+    #[test]
+    fn test_decode_spanned() {
+        let codec = CommandCodec::default();
+
+        let input = b"a1 select {5}\r\ninbox\r\nxxx".as_ref();
+        let (remaining, command, spans) = codec.decode_spanned(input).unwrap();
+        assert_eq!(remaining, b"xxx");
+        assert_eq!(
+            command,
+            Command::new("a1", CommandBody::select("inbox").unwrap()).unwrap()
+        );
+        assert_eq!(&input[spans.tag.clone()], b"a1");
+        assert_eq!(&input[spans.body.clone()], b"select {5}\r\ninbox");
+        assert_eq!(spans.literals, vec![15..20]);
+
+        // Literal data is never interpreted.
+        let input = b"a login \"{3}\" {6+}\r\n{1}\r\nx\r\n".as_ref();
+        let (_, _, spans) = codec.decode_spanned(input).unwrap();
+        assert_eq!(
+            &input[spans.body.clone()],
+            b"login \"{3}\" {6+}\r\n{1}\r\nx"
+        );
+        let literals: Vec<_> = spans
+            .literals
+            .iter()
+            .map(|span| &input[span.clone()])
+            .collect();
+        assert_eq!(literals, [b"{1}\r\nx".as_ref()]);
+
+        let (_, _, spans) = codec.decode_spanned(b"tag noop\r\n").unwrap();
+        assert_eq!(
+            spans,
+            CommandSpans {
+                tag: 0..3,
+                body: 4..8,
+                literals: vec![],
+            }
+        );
+
+        assert_eq!(
+            codec.decode_spanned(b"a select {5}\r\n"),
+            Err(CommandDecodeError::LiteralFound {
+                tag: Tag::try_from("a").unwrap(),
+                length: 5,
+                mode: LiteralMode::Sync,
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_exact() {
+        assert_eq!(
+            GreetingCodec::default().decode_exact(b"* OK ...\r\n"),
+            Ok(Greeting::new(GreetingKind::Ok, None, "...").unwrap())
+        );
+        assert_eq!(
+            GreetingCodec::default().decode_exact(b"* OK ...\r\n* "),
+            Err(DecodeExactError::TrailingData { length: 2 })
+        );
+
+        assert_eq!(
+            CommandCodec::default().decode_exact(b"a noop\r\n"),
+            Ok(Command::new("a", CommandBody::Noop).unwrap())
+        );
+        assert_eq!(
+            CommandCodec::default().decode_exact(b"a noop\r\nb noop\r\n"),
+            Err(DecodeExactError::TrailingData { length: 8 })
+        );
+        assert_eq!(
+            CommandCodec::default().decode_exact(b"a noop"),
+            Err(DecodeExactError::Decode(CommandDecodeError::Incomplete))
+        );
+
+        assert_eq!(
+            ResponseCodec::default().decode_exact(b"* SEARCH 1\r\n"),
+            Ok(Response::Data(Data::Search(vec![
+                NonZeroU32::new(1).unwrap()
+            ])))
+        );
+        assert_eq!(
+            ResponseCodec::default().decode_exact(b"* SEARCH 1\r\n\r\n"),
+            Err(DecodeExactError::TrailingData { length: 2 })
+        );
+        assert_eq!(
+            ResponseCodec::default().decode_exact(b"* SEARCH X\r\n"),
+            Err(DecodeExactError::Decode(ResponseDecodeError::Failed))
+        );
+    }
+
+    #[test]
+    fn test_decode_lf_line_endings() {
+        #[cfg(not(feature = "quirk_crlf_relaxed"))]
+        assert_eq!(
+            CommandCodec::default().decode(b"a NOOP\n"),
+            Err(CommandDecodeError::Failed)
+        );
+
+        let command_codec = CommandCodec {
+            allow_lf_line_endings: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            command_codec.decode(b"a NOOP\nb"),
+            Ok((b"b".as_ref(), Command::new("a", CommandBody::Noop).unwrap()))
+        );
+        assert_eq!(
+            command_codec.decode(b"a NOOP\r\n"),
+            Ok((b"".as_ref(), Command::new("a", CommandBody::Noop).unwrap()))
+        );
+        // A `\n` inside a literal is data, not a line ending.
+        assert_eq!(
+            command_codec.decode(b"A LOGIN {5}\r\nal\nce pass\n"),
+            Ok((
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::login(Literal::try_from(b"al\nce".as_ref()).unwrap(), "pass")
+                        .unwrap()
+                )
+                .unwrap()
+            ))
+        );
+
+        // A literal announcement may end with a bare `\n`, too.
+        assert_eq!(
+            command_codec.decode(b"A LOGIN {5}\nalice pass\n"),
+            Ok((
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::login(Literal::try_from(b"alice".as_ref()).unwrap(), "pass")
+                        .unwrap()
+                )
+                .unwrap()
+            ))
+        );
+        assert_eq!(
+            command_codec.decode(b"A LOGIN {5}\n"),
+            Err(CommandDecodeError::LiteralFound {
+                tag: Tag::try_from("A").unwrap(),
+                length: 5,
+                mode: LiteralMode::Sync,
+            })
+        );
+        #[cfg(not(feature = "quirk_crlf_relaxed"))]
+        assert_eq!(
+            CommandCodec::default().decode(b"A LOGIN {5}\nalice pass\r\n"),
+            Err(CommandDecodeError::Failed)
+        );
+
+        // The setting doesn't leak into other codecs.
+        #[cfg(not(feature = "quirk_crlf_relaxed"))]
+        assert_eq!(
+            CommandCodec::default().decode(b"a NOOP\n"),
+            Err(CommandDecodeError::Failed)
+        );
+
+        let greeting_codec = GreetingCodec {
+            allow_lf_line_endings: true,
+        };
+        assert_eq!(
+            greeting_codec.decode(b"* OK ...\n"),
+            Ok((
+                b"".as_ref(),
+                Greeting::new(GreetingKind::Ok, None, "...").unwrap()
+            ))
+        );
+
+        let response_codec = ResponseCodec {
+            allow_lf_line_endings: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            response_codec.decode(b"* SEARCH 1\n"),
+            Ok((
+                b"".as_ref(),
+                Response::Data(Data::Search(vec![NonZeroU32::new(1).unwrap()]))
+            ))
+        );
+        assert!(matches!(
+            response_codec.decode(b"* 1 FETCH (BODY[] {3}\nabc)\n"),
+            Ok((b"", Response::Data(Data::Fetch { .. })))
+        ));
+        assert_eq!(
+            response_codec.decode(b"* 1 FETCH (BODY[] {3}\n"),
+            Err(ResponseDecodeError::LiteralFound { length: 3 })
+        );
+        #[cfg(not(feature = "quirk_crlf_relaxed"))]
+        assert_eq!(
+            ResponseCodec::default().decode(b"* 1 FETCH (BODY[] {3}\nabc)\r\n"),
+            Err(ResponseDecodeError::Failed)
+        );
+    }
+
+    #[test]
+    fn test_decode_many() {
+        let codec = CommandCodec::default();
+
+        let (remaining, commands) =
+            codec.decode_many(b"a NOOP\r\nb SELECT INBOX\r\nc LOGIN alice pass\r\nd FETCH 1");
+        assert_eq!(remaining, b"d FETCH 1");
+        assert_eq!(
+            commands,
+            vec![
+                Command::new("a", CommandBody::Noop).unwrap(),
+                Command::new("b", CommandBody::select("INBOX").unwrap()).unwrap(),
+                Command::new("c", CommandBody::login("alice", "pass").unwrap()).unwrap(),
+            ]
+        );
+        assert_eq!(codec.decode(remaining), Err(CommandDecodeError::Incomplete));
+
+        // Stops at (and keeps) a message that announces a literal or is broken.
+        let (remaining, commands) = codec.decode_many(b"a NOOP\r\nb LOGIN {5}\r\n");
+        assert_eq!(remaining, b"b LOGIN {5}\r\n");
+        assert_eq!(commands.len(), 1);
+
+        let (remaining, commands) = codec.decode_many(b"a NOOP\r\n???\r\nb NOOP\r\n");
+        assert_eq!(remaining, b"???\r\nb NOOP\r\n");
+        assert_eq!(commands.len(), 1);
+
+        let (remaining, commands) =
+            ResponseCodec::default().decode_many(b"* SEARCH 1\r\n* SEARCH\r\n");
+        assert!(remaining.is_empty());
+        assert_eq!(commands.len(), 2);
+
+        assert_eq!(codec.decode_many(b""), (b"".as_ref(), vec![]));
+    }
 }
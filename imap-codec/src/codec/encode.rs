@@ -45,9 +45,15 @@
 //! C: Pa²²W0rD
 //! ```
 
-use std::{borrow::Borrow, io::Write, num::NonZeroU32};
+use std::{
+    borrow::Borrow,
+    fmt::{Display, Formatter},
+    io::Write,
+    num::NonZeroU32,
+    str::from_utf8,
+    string::FromUtf8Error,
+};
 
-use base64::{engine::general_purpose::STANDARD as base64, Engine};
 use chrono::{DateTime as ChronoDateTime, FixedOffset};
 use imap_types::{
     auth::{AuthMechanism, AuthenticateData},
@@ -57,8 +63,8 @@ use imap_types::{
     },
     command::{Command, CommandBody},
     core::{
-        AString, Atom, AtomExt, Charset, IString, Literal, LiteralMode, NString, Quoted,
-        QuotedChar, Tag, Text,
+        AString, Atom, AtomExt, Charset, ExtensionArguments, ExtensionName, IString, Literal,
+        LiteralMode, NString, Quoted, QuotedChar, Tag, Text,
     },
     datetime::{DateTime, NaiveDate},
     envelope::{Address, Envelope},
@@ -79,7 +85,16 @@ use imap_types::{
 };
 use utils::{join_serializable, List1AttributeValueOrNil, List1OrNil};
 
-use crate::{AuthenticateDataCodec, CommandCodec, GreetingCodec, IdleDoneCodec, ResponseCodec};
+#[cfg(feature = "ext_annotate")]
+use crate::extensions::annotate::encode_one_or_list;
+#[cfg(feature = "ext_context")]
+use crate::extensions::context::encode_search_return_opts;
+#[cfg(feature = "ext_urlauth")]
+use crate::extensions::urlauth::encode_url_quoted;
+use crate::{
+    base64::encode_b64, AuthenticateDataCodec, CommandCodec, GreetingCodec, IdleAcceptCodec,
+    IdleDoneCodec, ResponseCodec,
+};
 
 /// Encoder.
 ///
@@ -91,6 +106,111 @@ pub trait Encoder {
     ///
     /// This will return an [`Encoded`] message.
     fn encode(&self, message: &Self::Message<'_>) -> Encoded;
+
+    /// Encode this message and append it to `buffer`.
+    ///
+    /// This equals [`Encoded::dump`] but writes directly into an existing buffer. Thus, a buffer can
+    /// be reused to encode many messages without allocating for each of them. As with
+    /// [`Encoded::dump`], the message flow guided by [`Fragment`]s is lost.
+    fn encode_into(&self, message: &Self::Message<'_>, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(&self.encode(message).dump());
+    }
+
+    /// Encode this message into a [`String`].
+    ///
+    /// This is a convenience method, e.g., for logging or testing, and equals [`Encoded::dump`]
+    /// followed by [`String::from_utf8`]. Thus, the message flow guided by [`Fragment`]s is lost.
+    ///
+    /// Returns an error (instead of panicking) when the encoding is not valid UTF-8, e.g., because
+    /// a literal contains arbitrary bytes.
+    fn encode_to_string(&self, message: &Self::Message<'_>) -> Result<String, FromUtf8Error> {
+        let mut buffer = Vec::new();
+        self.encode_into(message, &mut buffer);
+        String::from_utf8(buffer)
+    }
+
+    /// Compute the exact length of the encoded message without encoding it into a buffer.
+    ///
+    /// This equals the length of [`Encoded::dump`], i.e., includes all literal headers such as
+    /// `{1234}\r\n`, and is useful, e.g., to write a length-prefixed frame in a single pass.
+    /// It's a shorthand for the [`EncodeStats::byte_len`] of [`Encoder::encode_stats`].
+    fn encoded_len(&self, message: &Self::Message<'_>) -> usize {
+        self.encode_stats(message).byte_len
+    }
+
+    /// Compute statistics about the encoded message without encoding it into a buffer.
+    ///
+    /// This is useful, e.g., to enforce a size limit for outgoing messages or to decide whether a
+    /// message can be sent in a single frame, i.e., contains no literals.
+    fn encode_stats(&self, message: &Self::Message<'_>) -> EncodeStats {
+        let mut stats = EncodeStats::default();
+
+        for fragment in self.encode(message) {
+            match fragment {
+                Fragment::Line { data } => stats.byte_len += data.len(),
+                Fragment::Literal { data, .. } => {
+                    stats.byte_len += data.len();
+                    stats.literal_count += 1;
+                }
+            }
+        }
+
+        stats
+    }
+
+    /// Encode this message without the terminating `\r\n`.
+    ///
+    /// This is useful when messages are embedded into another framing. Only the line ending that
+    /// ends the message is omitted. Line endings within the message, e.g., after a literal header
+    /// such as `{1234}\r\n`, are kept.
+    fn encode_without_terminator(&self, message: &Self::Message<'_>) -> Encoded {
+        let mut encoded = self.encode(message);
+
+        if let Some(Fragment::Line { data }) = encoded.items.last_mut() {
+            if data.ends_with(b"\r\n") {
+                data.truncate(data.len() - 2);
+            }
+
+            if data.is_empty() {
+                encoded.items.pop();
+            }
+        }
+
+        encoded
+    }
+
+    /// Encode this message for logging, i.e., with all secrets replaced by `***`.
+    ///
+    /// Secrets are the password of LOGIN, the initial response of AUTHENTICATE, and the data of
+    /// an authentication exchange (see [`AuthenticateData`]). Everything else, e.g., the username
+    /// of LOGIN, is kept. Invalid UTF-8 is replaced.
+    ///
+    /// Note: The output is meant for humans and must not be sent.
+    ///
+    /// The default implementation doesn't know which parts of a message are secrets and thus
+    /// redacts the whole message. The codecs of this crate override it to redact only the secrets.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use imap_codec::{
+    ///     encode::Encoder,
+    ///     imap_types::command::{Command, CommandBody},
+    ///     CommandCodec,
+    /// };
+    ///
+    /// let command = Command::new("A1", CommandBody::login("alice", "password").unwrap()).unwrap();
+    ///
+    /// assert_eq!(
+    ///     CommandCodec::default().redacted_display(&command),
+    ///     "A1 LOGIN alice ***\r\n"
+    /// );
+    /// ```
+    fn redacted_display(&self, message: &Self::Message<'_>) -> String {
+        let _ = message;
+
+        String::from("***")
+    }
 }
 
 /// An encoded message.
@@ -124,6 +244,22 @@ pub struct Encoded {
 }
 
 impl Encoded {
+    /// Returns whether the (remaining) message contains a synchronizing literal.
+    ///
+    /// A client must wait for a command continuation request (`+ ...`) before it sends the data
+    /// of each synchronizing literal in a command.
+    pub fn needs_continuation(&self) -> bool {
+        self.items.iter().any(|fragment| {
+            matches!(
+                fragment,
+                Fragment::Literal {
+                    mode: LiteralMode::Sync,
+                    ..
+                }
+            )
+        })
+    }
+
     /// Dump the (remaining) encoded data without being guided by [`Fragment`]s.
     pub fn dump(self) -> Vec<u8> {
         let mut out = Vec::new();
@@ -151,6 +287,54 @@ impl Iterator for Encoded {
     }
 }
 
+/// Formats the (remaining) message for humans, e.g., for logging or error messages.
+///
+/// Lines are formatted as they appear on the wire (invalid UTF-8 is replaced). The data of a
+/// literal is shown when it is printable text and replaced by a `{N bytes}` placeholder otherwise.
+/// Thus, formatting never fails, even for binary data, but is not meant to be sent.
+///
+/// # Example
+///
+/// ```rust
+/// use imap_codec::{
+///     encode::Encoder,
+///     imap_types::command::{Command, CommandBody},
+///     CommandCodec,
+/// };
+///
+/// let command = Command::new(
+///     "A1",
+///     CommandBody::login("alice", b"\xCA\xFE".as_ref()).unwrap(),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(
+///     CommandCodec::default().encode(&command).to_string(),
+///     "A1 LOGIN alice {2}\r\n{2 bytes}\r\n"
+/// );
+/// ```
+impl Display for Encoded {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        for fragment in &self.items {
+            match fragment {
+                Fragment::Line { data } => write!(f, "{}", String::from_utf8_lossy(data))?,
+                Fragment::Literal { data, .. } => match from_utf8(data) {
+                    Ok(text)
+                        if text
+                            .chars()
+                            .all(|c| !c.is_control() || matches!(c, '\r' | '\n' | '\t')) =>
+                    {
+                        write!(f, "{text}")?
+                    }
+                    _ => write!(f, "{{{} bytes}}", data.len())?,
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// The intended action of a client or server.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Fragment {
@@ -161,12 +345,32 @@ pub enum Fragment {
     Literal { data: Vec<u8>, mode: LiteralMode },
 }
 
+/// Statistics about an encoded message (see [`Encoder::encode_stats`]).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub struct EncodeStats {
+    /// Length of the encoded message in bytes.
+    ///
+    /// This equals [`Encoder::encoded_len`], i.e., includes all literal headers and data.
+    pub byte_len: usize,
+    /// Number of literals in the encoded message.
+    pub literal_count: usize,
+}
+
 //--------------------------------------------------------------------------------------------------
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub(crate) struct EncodeContext {
     accumulator: Vec<u8>,
     items: Vec<Fragment>,
+    /// Don't split the output into fragments, i.e., only append to `accumulator`.
+    flat: bool,
+    /// Only count the output, i.e., don't append to `accumulator` (see [`Self::counting`]).
+    count: Option<usize>,
+    /// Number of literals pushed so far.
+    literals: usize,
+    /// Replace secrets with `***` (see [`Self::write_secret`]).
+    redact: bool,
 }
 
 impl EncodeContext {
@@ -174,23 +378,93 @@ impl EncodeContext {
         Self::default()
     }
 
+    /// Create a context that appends everything to `buffer` (see [`Self::into_buffer`]).
+    pub fn with_buffer(buffer: Vec<u8>) -> Self {
+        Self {
+            accumulator: buffer,
+            items: Vec::new(),
+            flat: true,
+            count: None,
+            literals: 0,
+            redact: false,
+        }
+    }
+
+    /// Create a context that redacts secrets (see [`Self::into_buffer`]).
+    pub fn redacted() -> Self {
+        Self {
+            redact: true,
+            ..Self::with_buffer(Vec::new())
+        }
+    }
+
+    /// Create a context that only counts the encoded bytes (see [`Self::into_stats`]).
+    pub fn counting() -> Self {
+        Self {
+            accumulator: Vec::new(),
+            items: Vec::new(),
+            flat: true,
+            count: Some(0),
+            literals: 0,
+            redact: false,
+        }
+    }
+
     pub fn push_line(&mut self) {
+        if self.flat {
+            return;
+        }
+
         self.items.push(Fragment::Line {
             data: std::mem::take(&mut self.accumulator),
         })
     }
 
+    /// Write a secret using `encode`, or `***` when redacting.
+    pub fn write_secret<F>(&mut self, encode: F) -> std::io::Result<()>
+    where
+        F: FnOnce(&mut Self) -> std::io::Result<()>,
+    {
+        if self.redact {
+            self.write_all(b"***")
+        } else {
+            encode(self)
+        }
+    }
+
     pub fn push_literal(&mut self, mode: LiteralMode) {
+        self.literals += 1;
+
+        if self.flat {
+            return;
+        }
+
         self.items.push(Fragment::Literal {
             data: std::mem::take(&mut self.accumulator),
             mode,
         })
     }
 
+    pub fn into_stats(self) -> EncodeStats {
+        debug_assert!(self.count.is_some());
+
+        EncodeStats {
+            byte_len: self.count.unwrap_or_default(),
+            literal_count: self.literals,
+        }
+    }
+
+    pub fn into_buffer(self) -> Vec<u8> {
+        debug_assert!(self.items.is_empty());
+
+        self.accumulator
+    }
+
     pub fn into_items(self) -> Vec<Fragment> {
         let Self {
             accumulator,
             mut items,
+            ..
         } = self;
 
         if !accumulator.is_empty() {
@@ -218,7 +492,11 @@ impl EncodeContext {
 
 impl Write for EncodeContext {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        self.accumulator.extend_from_slice(buf);
+        match &mut self.count {
+            Some(count) => *count += buf.len(),
+            None => self.accumulator.extend_from_slice(buf),
+        }
+
         Ok(buf.len())
     }
 
@@ -240,6 +518,27 @@ macro_rules! impl_encoder_for_codec {
                     items: encode_context.into_items(),
                 }
             }
+
+            fn encode_into(&self, message: &Self::Message<'_>, buffer: &mut Vec<u8>) {
+                let mut encode_context = EncodeContext::with_buffer(std::mem::take(buffer));
+                EncodeIntoContext::encode_ctx(message.borrow(), &mut encode_context).unwrap();
+
+                *buffer = encode_context.into_buffer();
+            }
+
+            fn encode_stats(&self, message: &Self::Message<'_>) -> EncodeStats {
+                let mut encode_context = EncodeContext::counting();
+                EncodeIntoContext::encode_ctx(message.borrow(), &mut encode_context).unwrap();
+
+                encode_context.into_stats()
+            }
+
+            fn redacted_display(&self, message: &Self::Message<'_>) -> String {
+                let mut encode_context = EncodeContext::redacted();
+                EncodeIntoContext::encode_ctx(message.borrow(), &mut encode_context).unwrap();
+
+                String::from_utf8_lossy(&encode_context.into_buffer()).into_owned()
+            }
         }
     };
 }
@@ -249,6 +548,7 @@ impl_encoder_for_codec!(CommandCodec, Command<'a>);
 impl_encoder_for_codec!(AuthenticateDataCodec, AuthenticateData);
 impl_encoder_for_codec!(ResponseCodec, Response<'a>);
 impl_encoder_for_codec!(IdleDoneCodec, IdleDone);
+impl_encoder_for_codec!(IdleAcceptCodec, CommandContinuationRequest<'a>);
 
 // -------------------------------------------------------------------------------------------------
 
@@ -306,14 +606,16 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
                 if let Some(ir) = initial_response {
                     ctx.write_all(b" ")?;
 
-                    // RFC 4959 (https://datatracker.ietf.org/doc/html/rfc4959#section-3)
-                    // "To send a zero-length initial response, the client MUST send a single pad character ("=").
-                    // This indicates that the response is present, but is a zero-length string."
-                    if ir.declassify().is_empty() {
-                        ctx.write_all(b"=")?;
-                    } else {
-                        ctx.write_all(base64.encode(ir.declassify()).as_bytes())?;
-                    };
+                    ctx.write_secret(|ctx| {
+                        // RFC 4959 (https://datatracker.ietf.org/doc/html/rfc4959#section-3)
+                        // "To send a zero-length initial response, the client MUST send a single pad character ("=").
+                        // This indicates that the response is present, but is a zero-length string."
+                        if ir.declassify().is_empty() {
+                            ctx.write_all(b"=")
+                        } else {
+                            ctx.write_all(encode_b64(ir.declassify()).as_bytes())
+                        }
+                    })?;
                 };
 
                 Ok(())
@@ -323,7 +625,7 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
                 ctx.write_all(b" ")?;
                 username.encode_ctx(ctx)?;
                 ctx.write_all(b" ")?;
-                password.declassify().encode_ctx(ctx)
+                ctx.write_secret(|ctx| password.declassify().encode_ctx(ctx))
             }
             CommandBody::Select { mailbox } => {
                 ctx.write_all(b"SELECT")?;
@@ -408,7 +710,7 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
                 ctx.write_all(b" ")?;
                 mailbox.encode_ctx(ctx)?;
 
-                if !flags.is_empty() {
+                if let Some(flags) = flags {
                     ctx.write_all(b" ")?;
                     ctx.write_all(b"(")?;
                     join_serializable(flags, b" ", ctx)?;
@@ -443,6 +745,26 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
                 ctx.write_all(b" ")?;
                 join_serializable(criteria.as_ref(), b" ", ctx)
             }
+            #[cfg(feature = "ext_context")]
+            CommandBody::ESearch {
+                return_options,
+                charset,
+                criteria,
+                uid,
+            } => {
+                if *uid {
+                    ctx.write_all(b"UID SEARCH")?;
+                } else {
+                    ctx.write_all(b"SEARCH")?;
+                }
+                encode_search_return_opts(return_options, ctx)?;
+                if let Some(charset) = charset {
+                    ctx.write_all(b" CHARSET ")?;
+                    charset.encode_ctx(ctx)?;
+                }
+                ctx.write_all(b" ")?;
+                join_serializable(criteria.as_ref(), b" ", ctx)
+            }
             #[cfg(feature = "ext_sort_thread")]
             CommandBody::Sort {
                 sort_criteria,
@@ -461,6 +783,27 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
                 ctx.write_all(b" ")?;
                 join_serializable(search_criteria.as_ref(), b" ", ctx)
             }
+            #[cfg(feature = "ext_context")]
+            CommandBody::ESort {
+                return_options,
+                sort_criteria,
+                charset,
+                search_criteria,
+                uid,
+            } => {
+                if *uid {
+                    ctx.write_all(b"UID SORT")?;
+                } else {
+                    ctx.write_all(b"SORT")?;
+                }
+                encode_search_return_opts(return_options, ctx)?;
+                ctx.write_all(b" (")?;
+                join_serializable(sort_criteria.as_ref(), b" ", ctx)?;
+                ctx.write_all(b") ")?;
+                charset.encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                join_serializable(search_criteria.as_ref(), b" ", ctx)
+            }
             #[cfg(feature = "ext_sort_thread")]
             CommandBody::Thread {
                 algorithm,
@@ -482,15 +825,14 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
             CommandBody::Fetch {
                 sequence_set,
                 macro_or_item_names,
-                uid,
             } => {
-                if *uid {
+                if sequence_set.is_uid() {
                     ctx.write_all(b"UID FETCH ")?;
                 } else {
                     ctx.write_all(b"FETCH ")?;
                 }
 
-                sequence_set.encode_ctx(ctx)?;
+                sequence_set.as_ref().encode_ctx(ctx)?;
                 ctx.write_all(b" ")?;
                 macro_or_item_names.encode_ctx(ctx)
             }
@@ -499,15 +841,14 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
                 kind,
                 response,
                 flags,
-                uid,
             } => {
-                if *uid {
+                if sequence_set.is_uid() {
                     ctx.write_all(b"UID STORE ")?;
                 } else {
                     ctx.write_all(b"STORE ")?;
                 }
 
-                sequence_set.encode_ctx(ctx)?;
+                sequence_set.as_ref().encode_ctx(ctx)?;
                 ctx.write_all(b" ")?;
 
                 match kind {
@@ -527,17 +868,53 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
                 join_serializable(flags, b" ", ctx)?;
                 ctx.write_all(b")")
             }
+            #[cfg(feature = "ext_gmail")]
+            CommandBody::StoreGmailLabels {
+                sequence_set,
+                kind,
+                response,
+                labels,
+            } => {
+                if sequence_set.is_uid() {
+                    ctx.write_all(b"UID STORE ")?;
+                } else {
+                    ctx.write_all(b"STORE ")?;
+                }
+
+                sequence_set.as_ref().encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+
+                match kind {
+                    StoreType::Add => ctx.write_all(b"+")?,
+                    StoreType::Remove => ctx.write_all(b"-")?,
+                    StoreType::Replace => {}
+                }
+
+                ctx.write_all(b"X-GM-LABELS")?;
+
+                match response {
+                    StoreResponse::Answer => {}
+                    StoreResponse::Silent => ctx.write_all(b".SILENT")?,
+                }
+
+                ctx.write_all(b" (")?;
+                join_serializable(labels, b" ", ctx)?;
+                ctx.write_all(b")")
+            }
             CommandBody::Copy {
                 sequence_set,
                 mailbox,
-                uid,
+            }
+            | CommandBody::Move {
+                sequence_set,
+                mailbox,
             } => {
-                if *uid {
-                    ctx.write_all(b"UID COPY ")?;
-                } else {
-                    ctx.write_all(b"COPY ")?;
+                if sequence_set.is_uid() {
+                    ctx.write_all(b"UID ")?;
                 }
-                sequence_set.encode_ctx(ctx)?;
+                ctx.write_all(self.name().as_bytes())?;
+                ctx.write_all(b" ")?;
+                sequence_set.as_ref().encode_ctx(ctx)?;
                 ctx.write_all(b" ")?;
                 mailbox.encode_ctx(ctx)
             }
@@ -565,20 +942,6 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
                 join_serializable(quotas.as_ref(), b" ", ctx)?;
                 ctx.write_all(b")")
             }
-            CommandBody::Move {
-                sequence_set,
-                mailbox,
-                uid,
-            } => {
-                if *uid {
-                    ctx.write_all(b"UID MOVE ")?;
-                } else {
-                    ctx.write_all(b"MOVE ")?;
-                }
-                sequence_set.encode_ctx(ctx)?;
-                ctx.write_all(b" ")?;
-                mailbox.encode_ctx(ctx)
-            }
             #[cfg(feature = "ext_id")]
             CommandBody::Id { parameters } => {
                 ctx.write_all(b"ID ")?;
@@ -614,6 +977,92 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
                     None => ctx.write_all(b"NIL"),
                 }
             }
+            #[cfg(feature = "ext_metadata")]
+            CommandBody::GetMetadata {
+                options,
+                mailbox,
+                entries,
+            } => {
+                ctx.write_all(b"GETMETADATA ")?;
+
+                if !options.is_empty() {
+                    ctx.write_all(b"(")?;
+                    join_serializable(options, b" ", ctx)?;
+                    ctx.write_all(b") ")?;
+                }
+
+                mailbox.encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                entries.encode_ctx(ctx)
+            }
+            #[cfg(feature = "ext_metadata")]
+            CommandBody::SetMetadata {
+                mailbox,
+                entry_values,
+            } => {
+                ctx.write_all(b"SETMETADATA ")?;
+                mailbox.encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                entry_values.encode_ctx(ctx)
+            }
+            #[cfg(feature = "ext_notify")]
+            CommandBody::Notify { set } => {
+                ctx.write_all(b"NOTIFY ")?;
+
+                match set {
+                    Some(set) => set.encode_ctx(ctx),
+                    None => ctx.write_all(b"NONE"),
+                }
+            }
+            #[cfg(feature = "ext_urlauth")]
+            CommandBody::GenUrlAuth { urls } => {
+                ctx.write_all(b"GENURLAUTH")?;
+
+                for (url, mechanism) in urls.as_ref() {
+                    ctx.write_all(b" ")?;
+                    encode_url_quoted(url, ctx)?;
+                    ctx.write_all(b" ")?;
+                    mechanism.encode_ctx(ctx)?;
+                }
+
+                Ok(())
+            }
+            #[cfg(feature = "ext_urlauth")]
+            CommandBody::ResetKey {
+                mailbox_and_mechanisms,
+            } => {
+                ctx.write_all(b"RESETKEY")?;
+
+                if let Some((mailbox, mechanisms)) = mailbox_and_mechanisms {
+                    ctx.write_all(b" ")?;
+                    mailbox.encode_ctx(ctx)?;
+
+                    for mechanism in mechanisms {
+                        ctx.write_all(b" ")?;
+                        mechanism.encode_ctx(ctx)?;
+                    }
+                }
+
+                Ok(())
+            }
+            #[cfg(feature = "ext_urlauth")]
+            CommandBody::UrlFetch { urls } => {
+                ctx.write_all(b"URLFETCH")?;
+
+                for url in urls.as_ref() {
+                    ctx.write_all(b" ")?;
+                    encode_url_quoted(url, ctx)?;
+                }
+
+                Ok(())
+            }
+            CommandBody::Extension { name, args } => {
+                name.encode_ctx(ctx)?;
+                args.encode_ctx(ctx)
+            }
+            // Note: `CommandBody` is `#[non_exhaustive]`, but all variants are handled above. Features
+            // enabled in imap-types but not here are rejected at compile time (see `lib.rs`).
+            _ => unreachable!(),
         }
     }
 }
@@ -628,8 +1077,7 @@ impl EncodeIntoContext for AuthenticateData {
     fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
         match self {
             Self::Continue(data) => {
-                let encoded = base64.encode(data.declassify());
-                ctx.write_all(encoded.as_bytes())?;
+                ctx.write_secret(|ctx| ctx.write_all(encode_b64(data.declassify()).as_bytes()))?;
                 ctx.write_all(b"\r\n")
             }
             Self::Cancel => ctx.write_all(b"*\r\n"),
@@ -730,6 +1178,8 @@ impl EncodeIntoContext for StatusDataItemName {
             Self::DeletedStorage => ctx.write_all(b"DELETED-STORAGE"),
             #[cfg(feature = "ext_condstore_qresync")]
             Self::HighestModSeq => ctx.write_all(b"HIGHESTMODSEQ"),
+            #[cfg(feature = "ext_appendlimit")]
+            Self::AppendLimit => ctx.write_all(b"APPENDLIMIT"),
         }
     }
 }
@@ -836,6 +1286,24 @@ impl<'a> EncodeIntoContext for SearchKey<'a> {
                 flag_keyword.encode_ctx(ctx)
             }
             SearchKey::Unseen => ctx.write_all(b"UNSEEN"),
+            #[cfg(feature = "ext_gmail")]
+            SearchKey::GmailRaw(query) => {
+                ctx.write_all(b"X-GM-RAW ")?;
+                query.encode_ctx(ctx)
+            }
+            #[cfg(feature = "ext_annotate")]
+            SearchKey::Annotation {
+                entry,
+                attribute,
+                value,
+            } => {
+                ctx.write_all(b"ANNOTATION ")?;
+                entry.encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                attribute.encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                value.encode_ctx(ctx)
+            }
             SearchKey::Draft => ctx.write_all(b"DRAFT"),
             SearchKey::Header(header_fld_name, astring) => {
                 ctx.write_all(b"HEADER ")?;
@@ -972,6 +1440,23 @@ impl<'a> EncodeIntoContext for MessageDataItemName<'a> {
             Self::Rfc822Size => ctx.write_all(b"RFC822.SIZE"),
             Self::Rfc822Text => ctx.write_all(b"RFC822.TEXT"),
             Self::Uid => ctx.write_all(b"UID"),
+            #[cfg(feature = "ext_gmail")]
+            Self::GmailLabels => ctx.write_all(b"X-GM-LABELS"),
+            #[cfg(feature = "ext_gmail")]
+            Self::GmailMsgId => ctx.write_all(b"X-GM-MSGID"),
+            #[cfg(feature = "ext_gmail")]
+            Self::GmailThrId => ctx.write_all(b"X-GM-THRID"),
+            #[cfg(feature = "ext_annotate")]
+            Self::Annotation {
+                entries,
+                attributes,
+            } => {
+                ctx.write_all(b"ANNOTATION (")?;
+                encode_one_or_list(entries, ctx)?;
+                ctx.write_all(b" ")?;
+                encode_one_or_list(attributes, ctx)?;
+                ctx.write_all(b")")
+            }
         }
     }
 }
@@ -1169,12 +1654,27 @@ impl<'a> EncodeIntoContext for Code<'a> {
             #[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
             Code::Referral(url) => {
                 ctx.write_all(b"REFERRAL ")?;
-                ctx.write_all(url.as_bytes())
+                url.encode_ctx(ctx)
             }
             Code::CompressionActive => ctx.write_all(b"COMPRESSIONACTIVE"),
             Code::OverQuota => ctx.write_all(b"OVERQUOTA"),
             Code::TooBig => ctx.write_all(b"TOOBIG"),
+            #[cfg(feature = "ext_notify")]
+            Code::NotificationOverflow => ctx.write_all(b"NOTIFICATIONOVERFLOW"),
+            #[cfg(feature = "ext_notify")]
+            Code::BadEvent(events) => {
+                ctx.write_all(b"BADEVENT (")?;
+                join_serializable(events.as_ref(), b" ", ctx)?;
+                ctx.write_all(b")")
+            }
+            #[cfg(feature = "ext_metadata")]
+            Code::Metadata(code) => code.encode_ctx(ctx),
+            #[cfg(feature = "ext_annotate")]
+            Code::Annotate(code) => code.encode_ctx(ctx),
             Code::Other(unknown) => unknown.encode_ctx(ctx),
+            // Note: `Code` is `#[non_exhaustive]`, but all variants are handled above. Features
+            // enabled in imap-types but not here are rejected at compile time (see `lib.rs`).
+            _ => unreachable!(),
         }
     }
 }
@@ -1185,6 +1685,24 @@ impl<'a> EncodeIntoContext for CodeOther<'a> {
     }
 }
 
+impl<'a> EncodeIntoContext for ExtensionName<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        ctx.write_all(self.inner().as_bytes())
+    }
+}
+
+impl<'a> EncodeIntoContext for ExtensionArguments<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        // Note: The separating space is only emitted when there are arguments.
+        if self.inner().is_empty() {
+            return Ok(());
+        }
+
+        ctx.write_all(b" ")?;
+        ctx.write_all(self.inner())
+    }
+}
+
 impl<'a> EncodeIntoContext for Text<'a> {
     fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
         ctx.write_all(self.inner().as_bytes())
@@ -1252,12 +1770,22 @@ impl<'a> EncodeIntoContext for Data<'a> {
                 }
             }
             #[cfg(feature = "ext_sort_thread")]
-            Data::Sort(seqs) => {
-                if seqs.is_empty() {
+            Data::Sort {
+                ids,
+                #[cfg(feature = "ext_condstore_qresync")]
+                modseq,
+            } => {
+                if ids.is_empty() {
                     ctx.write_all(b"* SORT")?;
                 } else {
                     ctx.write_all(b"* SORT ")?;
-                    join_serializable(seqs, b" ", ctx)?;
+                    join_serializable(ids, b" ", ctx)?;
+                }
+
+                // Note: RFC 7162 requires at least one number before the trailer.
+                #[cfg(feature = "ext_condstore_qresync")]
+                if let (false, Some(modseq)) = (ids.is_empty(), modseq) {
+                    write!(ctx, " (MODSEQ {modseq})")?;
                 }
             }
             #[cfg(feature = "ext_sort_thread")]
@@ -1307,6 +1835,29 @@ impl<'a> EncodeIntoContext for Data<'a> {
                     root.encode_ctx(ctx)?;
                 }
             }
+            #[cfg(feature = "ext_context")]
+            Data::ESearch {
+                correlator,
+                uid,
+                data,
+            } => {
+                ctx.write_all(b"* ESEARCH")?;
+
+                if let Some(correlator) = correlator {
+                    ctx.write_all(b" (TAG \"")?;
+                    ctx.write_all(correlator.inner().as_bytes())?;
+                    ctx.write_all(b"\")")?;
+                }
+
+                if *uid {
+                    ctx.write_all(b" UID")?;
+                }
+
+                for item in data {
+                    ctx.write_all(b" ")?;
+                    item.encode_ctx(ctx)?;
+                }
+            }
             #[cfg(feature = "ext_id")]
             Data::Id { parameters } => {
                 ctx.write_all(b"* ID ")?;
@@ -1344,6 +1895,55 @@ impl<'a> EncodeIntoContext for Data<'a> {
                     }
                 }
             }
+            #[cfg(feature = "ext_condstore_qresync")]
+            Data::Vanished {
+                earlier,
+                known_uids,
+            } => {
+                ctx.write_all(b"* VANISHED")?;
+
+                if *earlier {
+                    ctx.write_all(b" (EARLIER)")?;
+                }
+
+                ctx.write_all(b" ")?;
+                known_uids.encode_ctx(ctx)?;
+            }
+            #[cfg(feature = "ext_metadata")]
+            Data::Metadata { mailbox, items } => {
+                ctx.write_all(b"* METADATA ")?;
+                mailbox.encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                items.encode_ctx(ctx)?;
+            }
+            #[cfg(feature = "ext_urlauth")]
+            Data::GenUrlAuth { urls } => {
+                ctx.write_all(b"* GENURLAUTH")?;
+
+                for url in urls.as_ref() {
+                    ctx.write_all(b" ")?;
+                    encode_url_quoted(url, ctx)?;
+                }
+            }
+            #[cfg(feature = "ext_urlauth")]
+            Data::UrlFetch { items } => {
+                ctx.write_all(b"* URLFETCH")?;
+
+                for (url, data) in items.as_ref() {
+                    ctx.write_all(b" ")?;
+                    encode_url_quoted(url, ctx)?;
+                    ctx.write_all(b" ")?;
+                    data.encode_ctx(ctx)?;
+                }
+            }
+            Data::Extension { name, args } => {
+                ctx.write_all(b"* ")?;
+                name.encode_ctx(ctx)?;
+                args.encode_ctx(ctx)?;
+            }
+            // Note: `Data` is `#[non_exhaustive]`, but all variants are handled above. Features
+            // enabled in imap-types but not here are rejected at compile time (see `lib.rs`).
+            _ => unreachable!(),
         }
 
         ctx.write_all(b"\r\n")
@@ -1397,6 +1997,14 @@ impl EncodeIntoContext for StatusDataItem {
                 ctx.write_all(b"DELETED-STORAGE ")?;
                 count.encode_ctx(ctx)
             }
+            #[cfg(feature = "ext_appendlimit")]
+            Self::AppendLimit(limit) => {
+                ctx.write_all(b"APPENDLIMIT ")?;
+                match limit {
+                    Some(limit) => limit.encode_ctx(ctx),
+                    None => ctx.write_all(b"NIL"),
+                }
+            }
         }
     }
 }
@@ -1456,6 +2064,21 @@ impl<'a> EncodeIntoContext for MessageDataItem<'a> {
                 nstring.encode_ctx(ctx)
             }
             Self::Uid(uid) => write!(ctx, "UID {uid}"),
+            #[cfg(feature = "ext_gmail")]
+            Self::GmailLabels(labels) => {
+                ctx.write_all(b"X-GM-LABELS (")?;
+                join_serializable(labels, b" ", ctx)?;
+                ctx.write_all(b")")
+            }
+            #[cfg(feature = "ext_gmail")]
+            Self::GmailMsgId(id) => write!(ctx, "X-GM-MSGID {id}"),
+            #[cfg(feature = "ext_gmail")]
+            Self::GmailThrId(id) => write!(ctx, "X-GM-THRID {id}"),
+            #[cfg(feature = "ext_annotate")]
+            Self::Annotation(annotations) => {
+                ctx.write_all(b"ANNOTATION ")?;
+                annotations.encode_ctx(ctx)
+            }
         }
     }
 }
@@ -1715,14 +2338,14 @@ impl<'a> EncodeIntoContext for CommandContinuationRequest<'a> {
             },
             Self::Base64(data) => {
                 ctx.write_all(b"+ ")?;
-                ctx.write_all(base64.encode(data).as_bytes())?;
+                ctx.write_all(encode_b64(data).as_bytes())?;
                 ctx.write_all(b"\r\n")
             }
         }
     }
 }
 
-mod utils {
+pub(crate) mod utils {
     use std::io::Write;
 
     use super::{EncodeContext, EncodeIntoContext};
@@ -1854,6 +2477,50 @@ mod tests {
         assert_eq!(got_encoded, out);
     }
 
+    #[test]
+    fn test_encode_needs_continuation() {
+        let login = |username: Literal<'static>, password: Literal<'static>| {
+            Command::new("A", CommandBody::login(username, password).unwrap()).unwrap()
+        };
+
+        let sync = Literal::try_from_sync("alice").unwrap();
+        let non_sync = Literal::try_from_non_sync("password").unwrap();
+
+        // One sync and one non-sync literal.
+        let encoded = CommandCodec::default().encode(&login(sync.clone(), non_sync.clone()));
+        assert!(encoded.needs_continuation());
+        assert_eq!(
+            encoded.collect::<Vec<_>>(),
+            vec![
+                Fragment::Line {
+                    data: b"A LOGIN {5}\r\n".to_vec(),
+                },
+                Fragment::Literal {
+                    data: b"alice".to_vec(),
+                    mode: LiteralMode::Sync,
+                },
+                Fragment::Line {
+                    data: b" {8+}\r\n".to_vec(),
+                },
+                Fragment::Literal {
+                    data: b"password".to_vec(),
+                    mode: LiteralMode::NonSync,
+                },
+                Fragment::Line {
+                    data: b"\r\n".to_vec(),
+                },
+            ]
+        );
+
+        // Only non-sync literals.
+        let encoded = CommandCodec::default().encode(&login(non_sync.clone(), non_sync));
+        assert!(!encoded.needs_continuation());
+
+        // No literals.
+        let cmd = Command::new("A", CommandBody::login("alice", "password").unwrap()).unwrap();
+        assert!(!CommandCodec::default().encode(&cmd).needs_continuation());
+    }
+
     #[test]
     fn test_encode_command() {
         kat_encoder::<CommandCodec, Command<'_>, &[Fragment]>(&[
@@ -1864,6 +2531,20 @@ mod tests {
                 }]
                 .as_ref(),
             ),
+            (
+                Command::login("A", "alice", "pass word").unwrap(),
+                [Fragment::Line {
+                    data: b"A LOGIN alice \"pass word\"\r\n".to_vec(),
+                }]
+                .as_ref(),
+            ),
+            (
+                Command::login("A", "alice", "pass\"word").unwrap(),
+                [Fragment::Line {
+                    data: b"A LOGIN alice \"pass\\\"word\"\r\n".to_vec(),
+                }]
+                .as_ref(),
+            ),
             (
                 Command::new(
                     "A",
@@ -1978,6 +2659,323 @@ mod tests {
         ])
     }
 
+    #[test]
+    fn test_encode_into() {
+        let mut buffer = b"prefix ".to_vec();
+
+        let cmd = Command::new("A", CommandBody::login("alice", "Pa²²W0rD").unwrap()).unwrap();
+        CommandCodec::default().encode_into(&cmd, &mut buffer);
+        assert_eq!(
+            buffer,
+            b"prefix A LOGIN alice {10}\r\nPa\xc2\xb2\xc2\xb2W0rD\r\n"
+        );
+
+        buffer.clear();
+        let capacity = buffer.capacity();
+        let rsp = Response::Status(Status::ok(None, None, "...").unwrap());
+        ResponseCodec::default().encode_into(&rsp, &mut buffer);
+        assert_eq!(buffer, ResponseCodec::default().encode(&rsp).dump());
+        assert_eq!(buffer.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_encode_without_terminator() {
+        let cmd = Command::new("A", CommandBody::login("alice", "Pa²²W0rD").unwrap()).unwrap();
+        let encoded = CommandCodec::default().encode_without_terminator(&cmd);
+        assert_eq!(
+            encoded.clone().collect::<Vec<_>>(),
+            [
+                Fragment::Line {
+                    data: b"A LOGIN alice {10}\r\n".to_vec()
+                },
+                Fragment::Literal {
+                    data: "Pa²²W0rD".as_bytes().to_vec(),
+                    mode: LiteralMode::Sync,
+                },
+            ]
+        );
+        assert_eq!(
+            encoded.dump(),
+            b"A LOGIN alice {10}\r\nPa\xc2\xb2\xc2\xb2W0rD"
+        );
+
+        let rsp = Response::Status(Status::ok(None, None, "...").unwrap());
+        assert_eq!(
+            ResponseCodec::default()
+                .encode_without_terminator(&rsp)
+                .dump(),
+            b"* OK ..."
+        );
+        assert_eq!(
+            ResponseCodec::default().encode(&rsp).dump(),
+            b"* OK ...\r\n"
+        );
+    }
+
+    #[test]
+    fn test_encoded_len() {
+        let commands = [
+            Command::new("A", CommandBody::login("alice", "pass").unwrap()).unwrap(),
+            Command::new("A", CommandBody::login("alice", "Pa²²W0rD").unwrap()).unwrap(),
+            Command::new(
+                "A",
+                CommandBody::login(
+                    AString::from(Literal::unvalidated_non_sync(b"alice".as_ref())),
+                    "password",
+                )
+                .unwrap(),
+            )
+            .unwrap(),
+            Command::new(
+                "A",
+                CommandBody::authenticate_with_ir(
+                    AuthMechanism::Plain,
+                    b"\x00alice\x00pass".as_ref(),
+                ),
+            )
+            .unwrap(),
+        ];
+
+        for cmd in commands {
+            assert_eq!(
+                CommandCodec::default().encoded_len(&cmd),
+                CommandCodec::default().encode(&cmd).dump().len()
+            );
+        }
+
+        // Literal lengths with a different number of digits.
+        for length in [0, 9, 10, 99, 100, 12345] {
+            let rsp = Response::Data(Data::Fetch {
+                seq: NonZeroU32::new(1).unwrap(),
+                items: Vec1::from(MessageDataItem::BodyExt {
+                    section: None,
+                    origin: None,
+                    data: NString::from(Literal::unvalidated(vec![b'A'; length])),
+                }),
+            });
+
+            assert_eq!(
+                ResponseCodec::default().encoded_len(&rsp),
+                ResponseCodec::default().encode(&rsp).dump().len()
+            );
+        }
+
+        let rsp = Response::Status(Status::ok(None, None, "...").unwrap());
+        assert_eq!(
+            ResponseCodec::default().encoded_len(&rsp),
+            ResponseCodec::default().encode(&rsp).dump().len()
+        );
+
+        let greeting = Greeting::ok(None, "Hello, World!").unwrap();
+        assert_eq!(
+            GreetingCodec::default().encoded_len(&greeting),
+            GreetingCodec::default().encode(&greeting).dump().len()
+        );
+
+        assert_eq!(IdleDoneCodec::default().encoded_len(&IdleDone), 6);
+    }
+
+    #[test]
+    fn test_redacted_display() {
+        let tests = [
+            (
+                Command::new("A", CommandBody::login("alice", "pa ss").unwrap()).unwrap(),
+                "A LOGIN alice ***\r\n",
+            ),
+            (
+                Command::new("A", CommandBody::login("alice", "Pa²²W0rD").unwrap()).unwrap(),
+                "A LOGIN alice ***\r\n",
+            ),
+            (
+                Command::new(
+                    "A",
+                    CommandBody::login(
+                        AString::from(Literal::unvalidated_non_sync(b"alice".as_ref())),
+                        "pass",
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+                "A LOGIN {5+}\r\nalice ***\r\n",
+            ),
+            (
+                Command::new(
+                    "A",
+                    CommandBody::authenticate_with_ir(
+                        AuthMechanism::Plain,
+                        b"\x00alice\x00pass".as_ref(),
+                    ),
+                )
+                .unwrap(),
+                "A AUTHENTICATE PLAIN ***\r\n",
+            ),
+            (
+                Command::new("A", CommandBody::authenticate(AuthMechanism::Plain)).unwrap(),
+                "A AUTHENTICATE PLAIN\r\n",
+            ),
+            (
+                Command::new("A", CommandBody::select("INBOX").unwrap()).unwrap(),
+                "A SELECT INBOX\r\n",
+            ),
+        ];
+
+        for (cmd, expected) in tests {
+            let got = CommandCodec::default().redacted_display(&cmd);
+
+            assert_eq!(got, expected);
+            assert!(!got.contains("pass"));
+            assert!(!got.contains("Pa"));
+            assert!(!got.contains(&encode_b64(b"\x00alice\x00pass")));
+        }
+
+        let codec = AuthenticateDataCodec::default();
+        assert_eq!(
+            codec.redacted_display(&AuthenticateData::Continue(b"secret".to_vec().into())),
+            "***\r\n"
+        );
+        assert_eq!(codec.redacted_display(&AuthenticateData::Cancel), "*\r\n");
+
+        // Secrets are still encoded as usual.
+        let cmd = Command::new("A", CommandBody::login("alice", "pass").unwrap()).unwrap();
+        assert_eq!(
+            CommandCodec::default().encode(&cmd).dump(),
+            b"A LOGIN alice pass\r\n"
+        );
+    }
+
+    #[test]
+    fn test_redacted_display_default() {
+        struct CustomCodec;
+
+        impl Encoder for CustomCodec {
+            type Message<'a> = Command<'a>;
+
+            fn encode(&self, message: &Self::Message<'_>) -> Encoded {
+                CommandCodec::default().encode(message)
+            }
+        }
+
+        let cmd = Command::new("A", CommandBody::login("alice", "pass").unwrap()).unwrap();
+
+        assert_eq!(CustomCodec.redacted_display(&cmd), "***");
+    }
+
+    #[test]
+    fn test_encode_stats() {
+        let tests = [
+            (
+                Command::new("A", CommandBody::login("alice", "pass").unwrap()).unwrap(),
+                0,
+            ),
+            (
+                Command::new("A", CommandBody::login("alice", "Pa²²W0rD").unwrap()).unwrap(),
+                1,
+            ),
+            (
+                Command::new(
+                    "A",
+                    CommandBody::login(
+                        AString::from(Literal::unvalidated_non_sync(b"alice".as_ref())),
+                        Literal::unvalidated(b"\xCA\xFE".as_ref()),
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+                2,
+            ),
+        ];
+
+        for (cmd, literal_count) in tests {
+            let stats = CommandCodec::default().encode_stats(&cmd);
+            let encoded = CommandCodec::default().encode(&cmd);
+
+            assert_eq!(stats.literal_count, literal_count);
+            assert_eq!(
+                stats.literal_count,
+                encoded
+                    .clone()
+                    .filter(|fragment| matches!(fragment, Fragment::Literal { .. }))
+                    .count()
+            );
+            assert_eq!(stats.byte_len, encoded.dump().len());
+            assert_eq!(stats.byte_len, CommandCodec::default().encoded_len(&cmd));
+        }
+
+        let rsp = Response::Status(Status::ok(None, None, "...").unwrap());
+        assert_eq!(
+            ResponseCodec::default().encode_stats(&rsp),
+            EncodeStats {
+                byte_len: 10,
+                literal_count: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_encode_to_string() {
+        let cmd = Command::new("A", CommandBody::login("alice", "pass").unwrap()).unwrap();
+        assert_eq!(
+            CommandCodec::default().encode_to_string(&cmd).unwrap(),
+            "A LOGIN alice pass\r\n"
+        );
+
+        let cmd = Command::new("A", CommandBody::login("alice", "Pa²²W0rD").unwrap()).unwrap();
+        assert_eq!(
+            CommandCodec::default().encode_to_string(&cmd).unwrap(),
+            "A LOGIN alice {10}\r\nPa²²W0rD\r\n"
+        );
+
+        let cmd = Command::new(
+            "A",
+            CommandBody::login("alice", b"\xCA\xFE".as_ref()).unwrap(),
+        )
+        .unwrap();
+        let err = CommandCodec::default().encode_to_string(&cmd).unwrap_err();
+        assert_eq!(err.into_bytes(), b"A LOGIN alice {2}\r\n\xCA\xFE\r\n");
+    }
+
+    #[test]
+    fn test_encoded_display() {
+        let cmd = Command::new("A", CommandBody::login("alice", "Pa²²W0rD").unwrap()).unwrap();
+        assert_eq!(
+            CommandCodec::default().encode(&cmd).to_string(),
+            "A LOGIN alice {10}\r\nPa²²W0rD\r\n"
+        );
+
+        // Binary literals are replaced by a placeholder.
+        let cmd = Command::new(
+            "A",
+            CommandBody::login("alice", b"\xCA\xFE".as_ref()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            CommandCodec::default().encode(&cmd).to_string(),
+            "A LOGIN alice {2}\r\n{2 bytes}\r\n"
+        );
+
+        let cmd = Command::new(
+            "A",
+            CommandBody::login("alice", Literal::try_from(b"a\x07b".as_ref()).unwrap()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            CommandCodec::default().encode(&cmd).to_string(),
+            "A LOGIN alice {3}\r\n{3 bytes}\r\n"
+        );
+
+        let rsp = Response::Data(Data::Exists(42));
+        assert_eq!(
+            ResponseCodec::default().encode(&rsp).to_string(),
+            "* 42 EXISTS\r\n"
+        );
+
+        let greeting = Greeting::ok(None, "Hello").unwrap();
+        assert_eq!(
+            GreetingCodec::default().encode(&greeting).to_string(),
+            "* OK Hello\r\n"
+        );
+    }
+
     fn kat_encoder<'a, E, M, F>(tests: &'a [(M, F)])
     where
         E: Encoder<Message<'a> = M> + Default,
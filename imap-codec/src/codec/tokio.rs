@@ -0,0 +1,243 @@
+//! # Support for [`tokio_util::codec`].
+//!
+//! [`ImapCommandCodec`] and [`ImapResponseCodec`] implement [`Decoder`](TokioDecoder) and
+//! [`Encoder`](TokioEncoder) to be used with, e.g., [`Framed`](tokio_util::codec::Framed).
+//! Decoding yields `Ok(None)` until a full message, including all literal data, was received.
+//!
+//! ## Literals
+//!
+//! A decoder can't drive the IMAP literal handshake. When a client announces a synchronizing
+//! literal (`{42}\r\n`), it waits for a command continuation request (`+ ...`) before it sends
+//! the literal data. [`ImapCommandCodec`] never sends such a request and waits for the data
+//! forever. Thus, ...
+//!
+//! * a server should only use [`ImapCommandCodec`] with clients that use non-synchronizing
+//!   literals (`{42+}\r\n`, LITERAL+) or send the whole command at once, and
+//! * a client should only encode commands with non-synchronizing literals (or no literals at all).
+//!
+//! Responses are not affected: A client must accept literal data in responses right away, so
+//! [`ImapResponseCodec`] works as-is.
+//!
+//! [`ImapClient`] and [`ImapServer`] handle synchronizing literals and should be preferred for
+//! real connections.
+
+mod client;
+mod server;
+
+use std::io::Error as IoError;
+
+use bounded_static::IntoBoundedStatic;
+use bytes::{Buf, BytesMut};
+use imap_types::{
+    command::Command,
+    core::LiteralMode,
+    response::{Greeting, Response},
+};
+use thiserror::Error;
+use tokio_util::codec::{Decoder as TokioDecoder, Encoder as TokioEncoder};
+
+pub use self::{
+    client::ImapClient,
+    server::{ClientMessage, ClientMessageDecodeError, ImapServer},
+};
+use crate::{
+    decode::{trailing_literal_prefix, CommandDecodeError, Decoder, ResponseDecodeError},
+    encode::Encoder,
+    CommandCodec, GreetingCodec, ResponseCodec,
+};
+
+/// Tokio codec for commands.
+#[derive(Debug, Default)]
+pub struct ImapCommandCodec {
+    /// Underlying codec, e.g., to configure limits.
+    pub codec: CommandCodec,
+    /// Number of bytes required before decoding is attempted again.
+    required: usize,
+}
+
+impl ImapCommandCodec {
+    /// Create a tokio codec from a (configured) command codec.
+    pub fn new(codec: CommandCodec) -> Self {
+        Self { codec, required: 0 }
+    }
+}
+
+/// Tokio codec for responses.
+#[derive(Debug, Default)]
+pub struct ImapResponseCodec {
+    /// Underlying codec, e.g., to configure limits.
+    pub codec: ResponseCodec,
+    /// Number of bytes required before decoding is attempted again.
+    required: usize,
+}
+
+impl ImapResponseCodec {
+    /// Create a tokio codec from a (configured) response codec.
+    pub fn new(codec: ResponseCodec) -> Self {
+        Self { codec, required: 0 }
+    }
+}
+
+/// Error of [`ImapCommandCodec`], [`ImapResponseCodec`], [`ImapClient`], and [`ImapServer`].
+#[derive(Debug, Error)]
+pub enum TokioCodecError<E> {
+    /// Reading from or writing to the underlying stream failed.
+    #[error(transparent)]
+    Io(#[from] IoError),
+    /// Decoding failed.
+    ///
+    /// The input up to (and including) the next line ending was discarded. However, when the
+    /// peer may already send the rest of the offending message, e.g., the data of a too long
+    /// non-synchronizing literal, nothing was discarded and decoding can't continue.
+    #[error("decoding failed: {0:?}")]
+    Decode(E),
+}
+
+impl TokioDecoder for ImapCommandCodec {
+    type Item = Command<'static>;
+    type Error = TokioCodecError<CommandDecodeError<'static>>;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if src.len() < self.required {
+            return Ok(None);
+        }
+
+        match self.codec.decode(src) {
+            Ok((remaining, command)) => {
+                let consumed = src.len() - remaining.len();
+                let command = command.into_static();
+
+                src.advance(consumed);
+                self.required = 0;
+
+                Ok(Some(command))
+            }
+            Err(CommandDecodeError::Incomplete) => Ok(None),
+            Err(CommandDecodeError::LiteralFound { length, .. }) => {
+                self.required = src.len().saturating_add(length as usize);
+                src.reserve(length as usize);
+
+                Ok(None)
+            }
+            // The client may already send the rest of the command, so we can't recover.
+            Err(
+                error @ (CommandDecodeError::LiteralTooLong { .. }
+                | CommandDecodeError::MessageTooLong),
+            ) if trailing_literal_prefix(src, self.codec.allow_lf_line_endings)
+                != Some(LiteralMode::Sync) =>
+            {
+                Err(TokioCodecError::Decode(error.into_static()))
+            }
+            Err(
+                error @ (CommandDecodeError::LiteralTooLong { .. }
+                | CommandDecodeError::MessageTooLong),
+            ) => {
+                let error = error.into_static();
+
+                // The client waits for a response and won't send more of this command.
+                src.clear();
+                self.required = 0;
+
+                Err(TokioCodecError::Decode(error))
+            }
+            Err(error) => {
+                let error = error.into_static();
+
+                discard_line(src);
+                self.required = 0;
+
+                Err(TokioCodecError::Decode(error))
+            }
+        }
+    }
+}
+
+impl<'a> TokioEncoder<&Command<'a>> for ImapCommandCodec {
+    type Error = TokioCodecError<CommandDecodeError<'static>>;
+
+    /// Encode a command, including all literal data.
+    ///
+    /// Note: Synchronizing literals are not awaited. See the [module documentation](self).
+    fn encode(&mut self, item: &Command<'a>, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        dst.extend_from_slice(&self.codec.encode(item).dump());
+
+        Ok(())
+    }
+}
+
+impl TokioDecoder for ImapResponseCodec {
+    type Item = Response<'static>;
+    type Error = TokioCodecError<ResponseDecodeError>;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if src.len() < self.required {
+            return Ok(None);
+        }
+
+        match self.codec.decode(src) {
+            Ok((remaining, response)) => {
+                let consumed = src.len() - remaining.len();
+                let response = response.into_static();
+
+                src.advance(consumed);
+                self.required = 0;
+
+                Ok(Some(response))
+            }
+            Err(ResponseDecodeError::Incomplete) => Ok(None),
+            Err(ResponseDecodeError::LiteralFound { length }) => {
+                self.required = src.len().saturating_add(length as usize);
+                src.reserve(length as usize);
+
+                Ok(None)
+            }
+            // The server sends literal data right away, so we can't recover.
+            Err(
+                error @ (ResponseDecodeError::LiteralTooLong { .. }
+                | ResponseDecodeError::MessageTooLong),
+            ) => Err(TokioCodecError::Decode(error)),
+            Err(error) => {
+                discard_line(src);
+                self.required = 0;
+
+                Err(TokioCodecError::Decode(error))
+            }
+        }
+    }
+}
+
+impl<'a> TokioEncoder<&Response<'a>> for ImapResponseCodec {
+    type Error = TokioCodecError<ResponseDecodeError>;
+
+    fn encode(&mut self, item: &Response<'a>, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        dst.extend_from_slice(&self.codec.encode(item).dump());
+
+        Ok(())
+    }
+}
+
+impl<'a> TokioEncoder<&Greeting<'a>> for ImapResponseCodec {
+    type Error = TokioCodecError<ResponseDecodeError>;
+
+    /// Encode a greeting.
+    ///
+    /// Note: An `OK` or `BYE` greeting is decoded as an (untagged status) [`Response`].
+    fn encode(&mut self, item: &Greeting<'a>, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let codec = GreetingCodec {
+            allow_lf_line_endings: self.codec.allow_lf_line_endings,
+        };
+        dst.extend_from_slice(&codec.encode(item).dump());
+
+        Ok(())
+    }
+}
+
+/// Discard `src` up to (and including) the next `\n`, or everything when there is none.
+fn discard_line(src: &mut BytesMut) {
+    let length = src
+        .iter()
+        .position(|byte| *byte == b'\n')
+        .map_or(src.len(), |position| position + 1);
+
+    src.advance(length);
+}
@@ -0,0 +1,307 @@
+use std::{
+    collections::VecDeque,
+    io::{Error as IoError, ErrorKind},
+    pin::Pin,
+    task::{ready, Context, Poll, Waker},
+};
+
+use bounded_static::IntoBoundedStatic;
+use bytes::BytesMut;
+use futures_core::Stream;
+use futures_sink::Sink;
+use imap_types::{
+    auth::AuthenticateData,
+    command::Command,
+    core::{LiteralMode, Tag},
+    extensions::idle::IdleDone,
+    response::{Response, Status, Tagged},
+};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_util::codec::{Decoder as TokioDecoder, Encoder as TokioEncoder, Framed};
+
+use super::{ImapResponseCodec, TokioCodecError};
+use crate::{
+    decode::ResponseDecodeError,
+    encode::{Encoder, Fragment},
+    AuthenticateDataCodec, CommandCodec, IdleDoneCodec,
+};
+
+type Error = TokioCodecError<ResponseDecodeError>;
+
+/// An IMAP client connection that handles synchronizing literals.
+///
+/// Sending a [`Command`] writes it up to the first synchronizing literal and pauses until the
+/// server sends a command continuation request (`+ ...`). This request is consumed. When the
+/// server rejects the command instead, the rest of the command is dropped and the tagged status
+/// is yielded as usual.
+///
+/// All (other) [`Response`]s are yielded from the [`Stream`] implementation. Responses that arrive
+/// while a command is sent are buffered, so sending doesn't require to poll the stream
+/// concurrently.
+///
+/// [`AuthenticateData`] (during `AUTHENTICATE`) and [`IdleDone`] (during `IDLE`) are sent with
+/// their own [`Sink`] implementations.
+///
+/// Note: Commands are sent one after another. Pipelining is possible but a command is only
+/// started when the previous one was sent completely.
+#[derive(Debug)]
+pub struct ImapClient<S> {
+    framed: Framed<S, ClientCodec>,
+    command_codec: CommandCodec,
+    /// Remaining fragments of the command that is currently sent.
+    fragments: VecDeque<Fragment>,
+    /// Tag of the command that is currently sent.
+    tag: Option<Tag<'static>>,
+    /// State of the synchronizing literal at the front of `fragments`.
+    literal: LiteralState,
+    /// Responses that were received while waiting for a command continuation request.
+    responses: VecDeque<Response<'static>>,
+    sink_waker: Option<Waker>,
+    stream_waker: Option<Waker>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LiteralState {
+    /// Nothing to wait for.
+    Idle,
+    /// Waiting for a command continuation request.
+    Waiting,
+    /// The server agreed to receive the literal.
+    Accepted,
+}
+
+impl<S> ImapClient<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    /// Create a client on top of `stream`.
+    pub fn new(stream: S) -> Self {
+        Self::with_codec(stream, ImapResponseCodec::default())
+    }
+
+    /// Create a client on top of `stream` using a (configured) response codec.
+    pub fn with_codec(stream: S, codec: ImapResponseCodec) -> Self {
+        Self::with_codecs(stream, CommandCodec::default(), codec)
+    }
+
+    /// Create a client on top of `stream` using a (configured) command and response codec.
+    pub fn with_codecs(
+        stream: S,
+        command_codec: CommandCodec,
+        response_codec: ImapResponseCodec,
+    ) -> Self {
+        Self {
+            framed: Framed::new(stream, ClientCodec(response_codec)),
+            command_codec,
+            fragments: VecDeque::new(),
+            tag: None,
+            literal: LiteralState::Idle,
+            responses: VecDeque::new(),
+            sink_waker: None,
+            stream_waker: None,
+        }
+    }
+
+    /// Consume the client and return the underlying stream.
+    ///
+    /// Note: Buffered data is lost.
+    pub fn into_inner(self) -> S {
+        self.framed.into_inner()
+    }
+
+    /// Handle a response received while (maybe) waiting for a command continuation request.
+    ///
+    /// Returns the response unless it was consumed.
+    fn handle(&mut self, response: Response<'static>) -> Option<Response<'static>> {
+        if self.literal != LiteralState::Waiting {
+            return Some(response);
+        }
+
+        match response {
+            Response::CommandContinuationRequest(_) => {
+                self.literal = LiteralState::Accepted;
+                wake(&mut self.sink_waker);
+                None
+            }
+            Response::Status(Status::Tagged(Tagged { ref tag, .. }))
+                if Some(tag) == self.tag.as_ref() =>
+            {
+                // The server rejected the command.
+                self.fragments.clear();
+                self.tag = None;
+                self.literal = LiteralState::Idle;
+                wake(&mut self.sink_waker);
+                Some(response)
+            }
+            response => Some(response),
+        }
+    }
+
+    /// Send the remaining fragments of the current command.
+    fn poll_send(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        loop {
+            if self.literal == LiteralState::Waiting {
+                self.sink_waker = Some(cx.waker().clone());
+
+                match ready!(Pin::new(&mut self.framed).poll_next(cx)) {
+                    Some(Ok(response)) => {
+                        if let Some(response) = self.handle(response) {
+                            self.responses.push_back(response);
+                            wake(&mut self.stream_waker);
+                        }
+                    }
+                    Some(Err(error)) => return Poll::Ready(Err(error)),
+                    None => {
+                        return Poll::Ready(Err(IoError::from(ErrorKind::UnexpectedEof).into()))
+                    }
+                }
+
+                continue;
+            }
+
+            let Some(fragment) = self.fragments.front() else {
+                self.tag = None;
+                return Poll::Ready(Ok(()));
+            };
+
+            if let Fragment::Literal {
+                mode: LiteralMode::Sync,
+                ..
+            } = fragment
+            {
+                if self.literal == LiteralState::Idle {
+                    // The literal announcement must reach the server before we wait.
+                    ready!(Pin::new(&mut self.framed).poll_flush(cx))?;
+                    self.literal = LiteralState::Waiting;
+                    continue;
+                }
+            }
+
+            ready!(Pin::new(&mut self.framed).poll_ready(cx))?;
+
+            let data = match self.fragments.pop_front() {
+                Some(Fragment::Line { data } | Fragment::Literal { data, .. }) => data,
+                None => unreachable!(),
+            };
+            self.literal = LiteralState::Idle;
+
+            Pin::new(&mut self.framed).start_send(data)?;
+        }
+    }
+}
+
+macro_rules! impl_sink {
+    (<$($lifetime:lifetime)?> $item:ty, |$this:ident, $message:ident| $start:block) => {
+        impl<$($lifetime,)? S> Sink<$item> for ImapClient<S>
+        where
+            S: AsyncRead + AsyncWrite + Unpin,
+        {
+            type Error = Error;
+
+            fn poll_ready(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Result<(), Self::Error>> {
+                self.get_mut().poll_send(cx)
+            }
+
+            fn start_send(self: Pin<&mut Self>, $message: $item) -> Result<(), Self::Error> {
+                let $this = self.get_mut();
+
+                $start
+
+                Ok(())
+            }
+
+            fn poll_flush(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Result<(), Self::Error>> {
+                let this = self.get_mut();
+
+                ready!(this.poll_send(cx))?;
+                Pin::new(&mut this.framed).poll_flush(cx)
+            }
+
+            fn poll_close(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Result<(), Self::Error>> {
+                let this = self.get_mut();
+
+                ready!(this.poll_send(cx))?;
+                Pin::new(&mut this.framed).poll_close(cx)
+            }
+        }
+    };
+}
+
+impl_sink!(<'a> Command<'a>, |this, command| {
+    this.fragments = this.command_codec.encode(&command).collect();
+    this.tag = Some(command.tag.into_static());
+});
+impl_sink!(<> AuthenticateData, |this, data| {
+    this.fragments = AuthenticateDataCodec::new().encode(&data).collect();
+    this.tag = None;
+});
+impl_sink!(<> IdleDone, |this, done| {
+    this.fragments = IdleDoneCodec::new().encode(&done).collect();
+    this.tag = None;
+});
+
+impl<S> Stream for ImapClient<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    type Item = Result<Response<'static>, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(response) = this.responses.pop_front() {
+                return Poll::Ready(Some(Ok(response)));
+            }
+
+            this.stream_waker = Some(cx.waker().clone());
+
+            match ready!(Pin::new(&mut this.framed).poll_next(cx)) {
+                Some(Ok(response)) => {
+                    if let Some(response) = this.handle(response) {
+                        return Poll::Ready(Some(Ok(response)));
+                    }
+                }
+                other => return Poll::Ready(other),
+            }
+        }
+    }
+}
+
+/// Decodes responses and writes (already encoded) command fragments.
+#[derive(Debug)]
+struct ClientCodec(ImapResponseCodec);
+
+impl TokioDecoder for ClientCodec {
+    type Item = Response<'static>;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        self.0.decode(src)
+    }
+}
+
+impl TokioEncoder<Vec<u8>> for ClientCodec {
+    type Error = Error;
+
+    fn encode(&mut self, item: Vec<u8>, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        dst.extend_from_slice(&item);
+
+        Ok(())
+    }
+}
+
+fn wake(waker: &mut Option<Waker>) {
+    if let Some(waker) = waker.take() {
+        waker.wake();
+    }
+}
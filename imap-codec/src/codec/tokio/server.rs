@@ -0,0 +1,402 @@
+use std::{
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use bounded_static::IntoBoundedStatic;
+use bytes::{Buf, BytesMut};
+use futures_core::Stream;
+use futures_sink::Sink;
+use imap_types::{
+    auth::AuthenticateData,
+    command::Command,
+    core::LiteralMode,
+    extensions::idle::IdleDone,
+    response::{CommandContinuationRequest, Greeting, Response},
+};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_util::codec::{Decoder as TokioDecoder, Encoder as TokioEncoder, Framed};
+
+use super::{discard_line, TokioCodecError};
+use crate::{
+    decode::{
+        trailing_literal_prefix, AuthenticateDataDecodeError, CommandDecodeError, Decoder,
+        IdleDoneDecodeError,
+    },
+    encode::Encoder,
+    AuthenticateDataCodec, CommandCodec, GreetingCodec, IdleDoneCodec, ResponseCodec,
+};
+
+type Error = TokioCodecError<ClientMessageDecodeError<'static>>;
+
+/// Message received by [`ImapServer`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ClientMessage<'a> {
+    /// A command.
+    Command(Command<'a>),
+    /// A line of a SASL exchange, see [`ImapServer::expect_authenticate_data`].
+    AuthenticateData(AuthenticateData),
+    /// The end of IDLE, see [`ImapServer::expect_idle_done`].
+    IdleDone(IdleDone),
+}
+
+/// Error during [`ClientMessage`] decoding.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ClientMessageDecodeError<'a> {
+    /// Decoding a command failed.
+    Command(CommandDecodeError<'a>),
+    /// Decoding a line of a SASL exchange failed.
+    AuthenticateData(AuthenticateDataDecodeError),
+    /// Decoding the end of IDLE failed.
+    IdleDone(IdleDoneDecodeError),
+}
+
+/// An IMAP server connection that handles synchronizing literals.
+///
+/// When a client announces a synchronizing literal, a command continuation request
+/// (`+ Ready for literal data`) is sent automatically. All [`Command`]s are yielded from the
+/// [`Stream`] implementation. [`Response`]s and [`Greeting`]s are sent with the [`Sink`]
+/// implementations.
+///
+/// During `AUTHENTICATE` and `IDLE`, the client sends lines that aren't commands. Call
+/// [`ImapServer::expect_authenticate_data`] or [`ImapServer::expect_idle_done`] before the stream
+/// is polled for such a line.
+///
+/// Decoding errors are yielded but don't end the stream. The offending line was discarded, so the
+/// server can, e.g., respond with `BAD` and continue. This includes
+/// [`CommandDecodeError::LiteralTooLong`] and [`CommandDecodeError::MessageTooLong`] for a
+/// synchronizing literal that wasn't sent yet: The client won't send the literal data and waits
+/// for a (tagged) response instead. Otherwise, these errors end the stream because the client may
+/// already send the rest of the command.
+#[derive(Debug)]
+pub struct ImapServer<S> {
+    framed: Framed<S, ServerCodec>,
+    response_codec: ResponseCodec,
+    /// Whether a command continuation request must be sent (and flushed).
+    continuation: Continuation,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Continuation {
+    None,
+    Send,
+    Flush,
+}
+
+impl<S> ImapServer<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    /// Create a server on top of `stream`.
+    pub fn new(stream: S) -> Self {
+        Self::with_codec(stream, CommandCodec::default())
+    }
+
+    /// Create a server on top of `stream` using a (configured) command codec.
+    pub fn with_codec(stream: S, codec: CommandCodec) -> Self {
+        Self::with_codecs(stream, codec, ResponseCodec::default())
+    }
+
+    /// Create a server on top of `stream` using a (configured) command and response codec.
+    pub fn with_codecs(
+        stream: S,
+        command_codec: CommandCodec,
+        response_codec: ResponseCodec,
+    ) -> Self {
+        Self {
+            framed: Framed::new(
+                stream,
+                ServerCodec {
+                    codec: command_codec,
+                    expected: Expected::Command,
+                    required: 0,
+                    continued: None,
+                },
+            ),
+            response_codec,
+            continuation: Continuation::None,
+        }
+    }
+
+    /// Decode the next line as [`AuthenticateData`], e.g., after a SASL challenge was sent.
+    ///
+    /// This only affects the next line. Afterwards, commands are decoded again.
+    pub fn expect_authenticate_data(&mut self) {
+        self.framed.codec_mut().expect(Expected::AuthenticateData);
+    }
+
+    /// Decode the next line as [`IdleDone`], i.e., after `IDLE` was accepted.
+    ///
+    /// This only affects the next line. Afterwards, commands are decoded again.
+    pub fn expect_idle_done(&mut self) {
+        self.framed.codec_mut().expect(Expected::IdleDone);
+    }
+
+    /// Consume the server and return the underlying stream.
+    ///
+    /// Note: Buffered data is lost.
+    pub fn into_inner(self) -> S {
+        self.framed.into_inner()
+    }
+}
+
+impl<S> Stream for ImapServer<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    type Item = Result<ClientMessage<'static>, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if this.continuation == Continuation::Send {
+                ready!(Pin::new(&mut this.framed).poll_ready(cx))?;
+
+                let continuation = Response::CommandContinuationRequest(
+                    CommandContinuationRequest::basic(None, "Ready for literal data").unwrap(),
+                );
+                let data = this.response_codec.encode(&continuation).dump();
+                Pin::new(&mut this.framed).start_send(data)?;
+                this.continuation = Continuation::Flush;
+            }
+
+            if this.continuation == Continuation::Flush {
+                ready!(Pin::new(&mut this.framed).poll_flush(cx))?;
+                this.continuation = Continuation::None;
+            }
+
+            match ready!(Pin::new(&mut this.framed).poll_next(cx)) {
+                Some(Ok(Event::Message(message))) => return Poll::Ready(Some(Ok(message))),
+                Some(Ok(Event::Continue)) => this.continuation = Continuation::Send,
+                Some(Ok(Event::Failed(error))) => {
+                    return Poll::Ready(Some(Err(TokioCodecError::Decode(error))))
+                }
+                Some(Err(error)) => return Poll::Ready(Some(Err(error))),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+macro_rules! impl_sink {
+    ($item:ident, |$this:ident| $codec:expr) => {
+        impl<'a, S> Sink<$item<'a>> for ImapServer<S>
+        where
+            S: AsyncRead + AsyncWrite + Unpin,
+        {
+            type Error = Error;
+
+            fn poll_ready(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Result<(), Self::Error>> {
+                Pin::new(&mut self.get_mut().framed).poll_ready(cx)
+            }
+
+            fn start_send(self: Pin<&mut Self>, item: $item<'a>) -> Result<(), Self::Error> {
+                let $this = self.get_mut();
+                let data = $codec.encode(&item).dump();
+                Pin::new(&mut $this.framed).start_send(data)
+            }
+
+            fn poll_flush(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Result<(), Self::Error>> {
+                Pin::new(&mut self.get_mut().framed).poll_flush(cx)
+            }
+
+            fn poll_close(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Result<(), Self::Error>> {
+                Pin::new(&mut self.get_mut().framed).poll_close(cx)
+            }
+        }
+    };
+}
+
+impl_sink!(Response, |this| this.response_codec);
+impl_sink!(Greeting, |this| GreetingCodec::new());
+
+#[derive(Debug)]
+enum Event {
+    Message(ClientMessage<'static>),
+    /// The client announced a synchronizing literal and waits for a continuation request.
+    Continue,
+    /// Decoding failed but the connection can be used further.
+    Failed(ClientMessageDecodeError<'static>),
+}
+
+/// What the next line is decoded as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expected {
+    Command,
+    AuthenticateData,
+    IdleDone,
+}
+
+/// Decodes client messages and writes (already encoded) responses.
+#[derive(Debug)]
+struct ServerCodec {
+    codec: CommandCodec,
+    expected: Expected,
+    /// Number of bytes required before decoding is attempted again.
+    required: usize,
+    /// End of the literal prefix for which a continuation request was already sent.
+    ///
+    /// A literal of length zero doesn't require more data, so it would be found again.
+    continued: Option<usize>,
+}
+
+impl ServerCodec {
+    fn expect(&mut self, expected: Expected) {
+        self.expected = expected;
+        self.required = 0;
+        self.continued = None;
+    }
+
+    /// Handle the outcome of decoding a single line, i.e., [`AuthenticateData`] or [`IdleDone`].
+    fn line<M, E>(
+        &mut self,
+        src: &mut BytesMut,
+        result: Result<(usize, M), E>,
+        incomplete: E,
+        message: fn(M) -> ClientMessage<'static>,
+        error: fn(E) -> ClientMessageDecodeError<'static>,
+    ) -> Option<Event>
+    where
+        E: PartialEq,
+    {
+        let event = match result {
+            Ok((remaining, decoded)) => {
+                src.advance(src.len() - remaining);
+                Event::Message(message(decoded))
+            }
+            Err(failure) if failure == incomplete => return None,
+            Err(failure) => {
+                discard_line(src);
+                Event::Failed(error(failure))
+            }
+        };
+
+        self.expected = Expected::Command;
+
+        Some(event)
+    }
+}
+
+impl TokioDecoder for ServerCodec {
+    type Item = Event;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match self.expected {
+            Expected::Command => {}
+            Expected::AuthenticateData => {
+                let result = AuthenticateDataCodec::new()
+                    .decode(src)
+                    .map(|(remaining, data)| (remaining.len(), data));
+
+                return Ok(self.line(
+                    src,
+                    result,
+                    AuthenticateDataDecodeError::Incomplete,
+                    ClientMessage::AuthenticateData,
+                    ClientMessageDecodeError::AuthenticateData,
+                ));
+            }
+            Expected::IdleDone => {
+                let result = IdleDoneCodec::new()
+                    .decode(src)
+                    .map(|(remaining, done)| (remaining.len(), done));
+
+                return Ok(self.line(
+                    src,
+                    result,
+                    IdleDoneDecodeError::Incomplete,
+                    ClientMessage::IdleDone,
+                    ClientMessageDecodeError::IdleDone,
+                ));
+            }
+        }
+
+        if src.len() < self.required {
+            return Ok(None);
+        }
+
+        match self.codec.decode(src) {
+            Ok((remaining, command)) => {
+                let consumed = src.len() - remaining.len();
+                let command = command.into_static();
+
+                src.advance(consumed);
+                self.required = 0;
+                self.continued = None;
+
+                Ok(Some(Event::Message(ClientMessage::Command(command))))
+            }
+            Err(CommandDecodeError::Incomplete) => Ok(None),
+            Err(CommandDecodeError::LiteralFound { length, mode, .. }) => {
+                self.required = src.len().saturating_add(length as usize);
+                src.reserve(length as usize);
+
+                match mode {
+                    LiteralMode::Sync if self.continued != Some(src.len()) => {
+                        self.continued = Some(src.len());
+                        Ok(Some(Event::Continue))
+                    }
+                    _ => Ok(None),
+                }
+            }
+            // The client may already send the rest of the command, so we can't recover.
+            Err(
+                error @ (CommandDecodeError::LiteralTooLong { .. }
+                | CommandDecodeError::MessageTooLong),
+            ) if trailing_literal_prefix(src, self.codec.allow_lf_line_endings)
+                != Some(LiteralMode::Sync) =>
+            {
+                Err(TokioCodecError::Decode(ClientMessageDecodeError::Command(
+                    error.into_static(),
+                )))
+            }
+            Err(
+                error @ (CommandDecodeError::LiteralTooLong { .. }
+                | CommandDecodeError::MessageTooLong),
+            ) => {
+                let error = error.into_static();
+
+                // The client waits for a response and won't send more of this command.
+                src.clear();
+                self.required = 0;
+                self.continued = None;
+
+                Ok(Some(Event::Failed(ClientMessageDecodeError::Command(
+                    error,
+                ))))
+            }
+            Err(error) => {
+                let error = error.into_static();
+
+                discard_line(src);
+                self.required = 0;
+                self.continued = None;
+
+                Ok(Some(Event::Failed(ClientMessageDecodeError::Command(
+                    error,
+                ))))
+            }
+        }
+    }
+}
+
+impl TokioEncoder<Vec<u8>> for ServerCodec {
+    type Error = Error;
+
+    fn encode(&mut self, item: Vec<u8>, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        dst.extend_from_slice(&item);
+
+        Ok(())
+    }
+}
@@ -1,9 +1,5 @@
 use std::borrow::Cow;
 
-#[cfg(not(feature = "quirk_crlf_relaxed"))]
-use abnf_core::streaming::crlf;
-#[cfg(feature = "quirk_crlf_relaxed")]
-use abnf_core::streaming::crlf_relaxed as crlf;
 use abnf_core::streaming::sp;
 use imap_types::{
     auth::AuthMechanism,
@@ -11,25 +7,37 @@ use imap_types::{
     core::AString,
     fetch::{Macro, MacroOrMessageDataItemNames},
     flag::{Flag, StoreResponse, StoreType},
+    mailbox::Mailbox,
     secret::Secret,
+    sequence::{SeqNoOrUidSet, SequenceSet},
 };
 use nom::{
     branch::alt,
     bytes::streaming::{tag, tag_no_case},
     combinator::{map, opt, value},
     multi::{separated_list0, separated_list1},
-    sequence::{delimited, preceded, terminated, tuple},
+    sequence::{delimited, preceded, separated_pair, terminated, tuple},
 };
 
+#[cfg(feature = "ext_context")]
+use crate::extensions::context::{esearch, esort};
+#[cfg(feature = "ext_gmail")]
+use crate::extensions::gmail::store_att_gmail_labels;
 #[cfg(feature = "ext_id")]
 use crate::extensions::id::id;
+#[cfg(feature = "ext_metadata")]
+use crate::extensions::metadata::{getmetadata, setmetadata};
+#[cfg(feature = "ext_notify")]
+use crate::extensions::notify::notify;
 #[cfg(feature = "ext_sort_thread")]
 use crate::extensions::sort::sort;
 #[cfg(feature = "ext_sort_thread")]
 use crate::extensions::thread::thread;
+#[cfg(feature = "ext_urlauth")]
+use crate::extensions::urlauth::urlauth_command;
 use crate::{
     auth::auth_type,
-    core::{astring, base64, literal, tag_imap},
+    core::{astring, base64, line_ending, literal, tag_imap, with_allow_lf, x_extension},
     datetime::date_time,
     decode::{IMAPErrorKind, IMAPResult},
     extensions::{
@@ -53,33 +61,49 @@ use crate::{
 ///                     command-nonauth /
 ///                     command-select
 ///                   ) CRLF`
-pub(crate) fn command(input: &[u8]) -> IMAPResult<&[u8], Command> {
-    let mut parser_tag = terminated(tag_imap, sp);
-    let mut parser_body = terminated(
-        alt((command_any, command_auth, command_nonauth, command_select)),
-        crlf,
-    );
-
-    let (remaining, obtained_tag) = parser_tag(input)?;
-
-    match parser_body(remaining) {
-        Ok((remaining, body)) => Ok((
-            remaining,
-            Command {
-                tag: obtained_tag,
-                body,
-            },
-        )),
-        Err(mut error) => {
-            // If we got an `IMAPErrorKind::Literal`, we fill in the missing `tag`.
-            if let nom::Err::Error(ref mut err) | nom::Err::Failure(ref mut err) = error {
-                if let IMAPErrorKind::Literal { ref mut tag, .. } = err.kind {
-                    *tag = Some(obtained_tag);
+///
+/// Note: A bare `LF` is accepted as line ending when `allow_lf` is set.
+pub(crate) fn command(allow_lf: bool) -> impl Fn(&[u8]) -> IMAPResult<&[u8], Command> {
+    move |input: &[u8]| {
+        with_allow_lf(allow_lf, || {
+            let mut parser_tag = terminated(tag_imap, sp);
+            let mut parser_body = terminated(
+                alt((
+                    command_any,
+                    command_auth,
+                    command_nonauth,
+                    command_select,
+                    // Note: Tried last, i.e., only when no other command matched.
+                    map(x_extension, |(name, args)| CommandBody::Extension {
+                        name,
+                        args,
+                    }),
+                )),
+                line_ending(allow_lf),
+            );
+
+            let (remaining, obtained_tag) = parser_tag(input)?;
+
+            match parser_body(remaining) {
+                Ok((remaining, body)) => Ok((
+                    remaining,
+                    Command {
+                        tag: obtained_tag,
+                        body,
+                    },
+                )),
+                Err(mut error) => {
+                    // If we got an `IMAPErrorKind::Literal`, we fill in the missing `tag`.
+                    if let nom::Err::Error(ref mut err) | nom::Err::Failure(ref mut err) = error {
+                        if let IMAPErrorKind::Literal { ref mut tag, .. } = err.kind {
+                            *tag = Some(obtained_tag);
+                        }
+                    }
+
+                    Err(error)
                 }
             }
-
-            Err(error)
-        }
+        })
     }
 }
 
@@ -99,7 +123,7 @@ pub(crate) fn command_any(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
         value(CommandBody::Capability, tag_no_case(b"CAPABILITY")),
         value(CommandBody::Logout, tag_no_case(b"LOGOUT")),
         value(CommandBody::Noop, tag_no_case(b"NOOP")),
-        // x-command = "X" atom <experimental command arguments>
+        // Note: `x-command = "X" atom <experimental command arguments>` is handled in `command`.
         #[cfg(feature = "ext_id")]
         map(id, |parameters| CommandBody::Id { parameters }),
     ))(input)
@@ -142,6 +166,14 @@ pub(crate) fn command_auth(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
         getquota,
         getquotaroot,
         setquota,
+        #[cfg(feature = "ext_metadata")]
+        getmetadata,
+        #[cfg(feature = "ext_metadata")]
+        setmetadata,
+        #[cfg(feature = "ext_notify")]
+        notify,
+        #[cfg(feature = "ext_urlauth")]
+        urlauth_command,
     ))(input)
 }
 
@@ -163,7 +195,7 @@ pub(crate) fn append(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
         remaining,
         CommandBody::Append {
             mailbox,
-            flags: flags.unwrap_or_default(),
+            flags,
             date,
             message,
         },
@@ -402,7 +434,11 @@ pub(crate) fn command_select(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
         fetch,
         store,
         uid,
+        #[cfg(feature = "ext_context")]
+        esearch,
         search,
+        #[cfg(feature = "ext_context")]
+        esort,
         #[cfg(feature = "ext_sort_thread")]
         sort,
         #[cfg(feature = "ext_sort_thread")]
@@ -414,20 +450,26 @@ pub(crate) fn command_select(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
 
 /// `copy = "COPY" SP sequence-set SP mailbox`
 pub(crate) fn copy(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
-    let mut parser = tuple((tag_no_case(b"COPY"), sp, sequence_set, sp, mailbox));
+    let mut parser = preceded(tag_no_case(b"COPY "), copy_args);
 
-    let (remaining, (_, _, sequence_set, _, mailbox)) = parser(input)?;
+    let (remaining, (sequence_set, mailbox)) = parser(input)?;
 
     Ok((
         remaining,
         CommandBody::Copy {
-            sequence_set,
+            sequence_set: SeqNoOrUidSet::new(sequence_set, false),
             mailbox,
-            uid: false,
         },
     ))
 }
 
+/// `sequence-set SP mailbox`
+///
+/// Note: Arguments shared by COPY and MOVE (RFC 6851).
+pub(crate) fn copy_args(input: &[u8]) -> IMAPResult<&[u8], (SequenceSet, Mailbox)> {
+    separated_pair(sequence_set, sp, mailbox)(input)
+}
+
 /// `fetch = "FETCH" SP sequence-set SP ("ALL" /
 ///                                      "FULL" /
 ///                                      "FAST" /
@@ -466,27 +508,46 @@ pub(crate) fn fetch(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
     Ok((
         remaining,
         CommandBody::Fetch {
-            sequence_set,
+            sequence_set: SeqNoOrUidSet::new(sequence_set, false),
             macro_or_item_names,
-            uid: false,
         },
     ))
 }
 
 /// `store = "STORE" SP sequence-set SP store-att-flags`
+///
+/// With `ext_gmail`: `store =/ "STORE" SP sequence-set SP store-att-gmail-labels`
 pub(crate) fn store(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
-    let mut parser = tuple((tag_no_case(b"STORE"), sp, sequence_set, sp, store_att_flags));
+    let mut parser = tuple((tag_no_case(b"STORE"), sp, sequence_set, sp));
+
+    let (remaining, (_, _, sequence_set, _)) = parser(input)?;
+
+    #[cfg(feature = "ext_gmail")]
+    match store_att_gmail_labels(remaining) {
+        Ok((remaining, (kind, response, labels))) => {
+            return Ok((
+                remaining,
+                CommandBody::StoreGmailLabels {
+                    sequence_set: SeqNoOrUidSet::new(sequence_set, false),
+                    kind,
+                    response,
+                    labels,
+                },
+            ));
+        }
+        Err(nom::Err::Error(_)) => {}
+        Err(error) => return Err(error),
+    }
 
-    let (remaining, (_, _, sequence_set, _, (kind, response, flags))) = parser(input)?;
+    let (remaining, (kind, response, flags)) = store_att_flags(remaining)?;
 
     Ok((
         remaining,
         CommandBody::Store {
-            sequence_set,
+            sequence_set: SeqNoOrUidSet::new(sequence_set, false),
             kind,
             response,
             flags,
-            uid: false,
         },
     ))
 }
@@ -529,17 +590,44 @@ pub(crate) fn uid(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
     let mut parser = tuple((
         tag_no_case(b"UID"),
         sp,
-        alt((copy, fetch, search, store, r#move)),
+        alt((
+            copy,
+            fetch,
+            #[cfg(feature = "ext_context")]
+            esearch,
+            search,
+            store,
+            r#move,
+        )),
     ));
 
     let (remaining, (_, _, mut cmd)) = parser(input)?;
 
     match cmd {
-        CommandBody::Copy { ref mut uid, .. }
-        | CommandBody::Fetch { ref mut uid, .. }
-        | CommandBody::Search { ref mut uid, .. }
-        | CommandBody::Store { ref mut uid, .. }
-        | CommandBody::Move { ref mut uid, .. } => *uid = true,
+        CommandBody::Copy {
+            ref mut sequence_set,
+            ..
+        }
+        | CommandBody::Fetch {
+            ref mut sequence_set,
+            ..
+        }
+        | CommandBody::Store {
+            ref mut sequence_set,
+            ..
+        }
+        | CommandBody::Move {
+            ref mut sequence_set,
+            ..
+        } => *sequence_set = SeqNoOrUidSet::new(sequence_set.as_ref().clone(), true),
+        #[cfg(feature = "ext_gmail")]
+        CommandBody::StoreGmailLabels {
+            ref mut sequence_set,
+            ..
+        } => *sequence_set = SeqNoOrUidSet::new(sequence_set.as_ref().clone(), true),
+        CommandBody::Search { ref mut uid, .. } => *uid = true,
+        #[cfg(feature = "ext_context")]
+        CommandBody::ESearch { ref mut uid, .. } => *uid = true,
         _ => unreachable!(),
     }
 
@@ -551,12 +639,18 @@ mod tests {
     use std::num::NonZeroU32;
 
     use imap_types::{
-        core::Tag,
+        core::{AString, ExtensionArguments, ExtensionName, Quoted, Tag},
+        datetime::DateTime,
         fetch::{MessageDataItemName, Section},
     };
 
     use super::*;
-    use crate::{encode::Encoder, CommandCodec};
+    use crate::{
+        decode::{CommandDecodeError, Decoder},
+        encode::Encoder,
+        testing::kat_inverse_command,
+        CommandCodec,
+    };
 
     #[test]
     fn test_parse_fetch() {
@@ -620,6 +714,161 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_kat_inverse_command_extension() {
+        let tests = [
+            (
+                b"A XLIST \"\" \"*\"\r\n".as_ref(),
+                CommandBody::Extension {
+                    name: ExtensionName::try_from("XLIST").unwrap(),
+                    args: ExtensionArguments::try_from(b"\"\" \"*\"".as_ref()).unwrap(),
+                },
+            ),
+            (
+                b"A X-VENDOR  (a \xc3\xa4\r\n",
+                CommandBody::Extension {
+                    name: ExtensionName::try_from("X-VENDOR").unwrap(),
+                    args: ExtensionArguments::try_from(b" (a \xc3\xa4".as_ref()).unwrap(),
+                },
+            ),
+            (
+                b"A XNOARGS\r\n",
+                CommandBody::Extension {
+                    name: ExtensionName::try_from("XNOARGS").unwrap(),
+                    args: ExtensionArguments::default(),
+                },
+            ),
+        ];
+
+        for (test, expected) in tests {
+            let expected = Command::new("A", expected).unwrap();
+            assert!(test[2..].starts_with(expected.name().as_bytes()));
+            kat_inverse_command(&[(test, b"", expected.clone())]);
+            // The arguments must round-trip byte-exact.
+            assert_eq!(CommandCodec::default().encode(&expected).dump(), test);
+        }
+
+        // Known commands are not parsed as extensions ...
+        assert!(CommandCodec::default().decode(b"A NOOP\r\n").is_ok());
+        assert!(CommandCodec::default().decode(b"A NOOP x\r\n").is_err());
+        // ... and neither are unknown commands not starting with "X".
+        assert!(CommandCodec::default().decode(b"A FOO bar\r\n").is_err());
+        // Literals are not supported.
+        assert_eq!(
+            CommandCodec::default().decode(b"A XFOO {5}\r\n"),
+            Err(CommandDecodeError::Failed)
+        );
+        assert_eq!(
+            CommandCodec::default().decode(b"A XFOO a {5+}\r\nB NOOP\r\n"),
+            Err(CommandDecodeError::Failed)
+        );
+    }
+
+    #[test]
+    fn test_kat_inverse_command_store() {
+        kat_inverse_command(&[
+            (
+                b"A STORE 1:3 +FLAGS.SILENT (\\Deleted)\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::store(
+                        "1:3",
+                        StoreType::Add,
+                        StoreResponse::Silent,
+                        vec![Flag::Deleted],
+                        false,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A store 1 -flags.silent \\Seen \\Draft\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::store(
+                        1,
+                        StoreType::Remove,
+                        StoreResponse::Silent,
+                        vec![Flag::Seen, Flag::Draft],
+                        false,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A UID STORE 1:* FLAGS ()\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::store(
+                        "1:*",
+                        StoreType::Replace,
+                        StoreResponse::Answer,
+                        vec![],
+                        true,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_command_append() {
+        let date = DateTime::try_from(
+            chrono::DateTime::parse_from_rfc2822("Mon, 7 Feb 1994 22:43:04 -0800").unwrap(),
+        )
+        .unwrap();
+
+        kat_inverse_command(&[
+            (
+                b"A APPEND INBOX (\\Seen) \"07-Feb-1994 22:43:04 -0800\" {12}\r\nHello World!\r\n"
+                    .as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::append(
+                        "INBOX",
+                        Some(vec![Flag::Seen]),
+                        Some(date.clone()),
+                        b"Hello World!".as_ref(),
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            // An empty flag list is distinct from no flag list.
+            (
+                b"A APPEND INBOX () {1}\r\nA\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::append("INBOX", Some(vec![]), None, b"A".as_ref()).unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A APPEND INBOX {1}\r\nA\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::append("INBOX", None, None, b"A".as_ref()).unwrap(),
+                )
+                .unwrap(),
+            ),
+        ]);
+
+        // The date must follow the flags.
+        assert!(CommandCodec::default()
+            .decode(b"A APPEND INBOX \"07-Feb-1994 22:43:04 -0800\" (\\Seen) {1}\r\nA\r\n")
+            .is_err());
+    }
+
     #[test]
     fn test_that_empty_ir_is_encoded_correctly() {
         let command = Command::new(
@@ -635,4 +884,84 @@ mod tests {
 
         assert_eq!(buffer, b"A AUTHENTICATE PLAIN =\r\n")
     }
+
+    #[test]
+    fn test_kat_inverse_command_no_arguments() {
+        kat_inverse_command(&[
+            (
+                b"A CAPABILITY\r\n".as_ref(),
+                b"".as_ref(),
+                CommandBody::capability().tag("A").unwrap(),
+            ),
+            (b"A NOOP\r\n", b"", Command::noop("A").unwrap()),
+            (
+                b"A LOGOUT\r\n",
+                b"",
+                CommandBody::logout().tag("A").unwrap(),
+            ),
+            #[cfg(feature = "starttls")]
+            (
+                b"A STARTTLS\r\n",
+                b"",
+                CommandBody::starttls().tag("A").unwrap(),
+            ),
+            (b"A CHECK\r\n", b"", CommandBody::check().tag("A").unwrap()),
+            (b"A CLOSE\r\n", b"", CommandBody::close().tag("A").unwrap()),
+            (
+                b"A EXPUNGE\r\n",
+                b"",
+                CommandBody::expunge().tag("A").unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_command_mailbox_management() {
+        let quoted = |value| Mailbox::from(AString::from(Quoted::try_from(value).unwrap()));
+
+        kat_inverse_command(&[
+            (
+                b"A RENAME \"Old Name\" \"New/Name\"\r\n".as_ref(),
+                b"".as_ref(),
+                CommandBody::rename(quoted("Old Name"), quoted("New/Name"))
+                    .unwrap()
+                    .tag("A")
+                    .unwrap(),
+            ),
+            (
+                b"A RENAME INBOX \"Old Mail\"\r\n",
+                b"",
+                CommandBody::rename("inbox", "Old Mail")
+                    .unwrap()
+                    .tag("A")
+                    .unwrap(),
+            ),
+            (
+                b"A CREATE foo/\r\n",
+                b"",
+                CommandBody::create("foo/").unwrap().tag("A").unwrap(),
+            ),
+            (
+                b"A DELETE foo/bar\r\n",
+                b"",
+                CommandBody::delete("foo/bar").unwrap().tag("A").unwrap(),
+            ),
+            (
+                b"A SUBSCRIBE \"foo bar\"\r\n",
+                b"",
+                CommandBody::subscribe("foo bar").unwrap().tag("A").unwrap(),
+            ),
+            (
+                b"A UNSUBSCRIBE INBOX\r\n",
+                b"",
+                CommandBody::unsubscribe("INBOX").unwrap().tag("A").unwrap(),
+            ),
+        ]);
+
+        // A trailing delimiter makes it a different mailbox than INBOX.
+        assert!(matches!(
+            Mailbox::try_from("inbox/").unwrap(),
+            Mailbox::Other(_)
+        ));
+    }
 }
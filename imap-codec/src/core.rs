@@ -1,15 +1,13 @@
-use std::{borrow::Cow, num::NonZeroU32, str::from_utf8};
-
-#[cfg(not(feature = "quirk_crlf_relaxed"))]
-use abnf_core::streaming::crlf;
-#[cfg(feature = "quirk_crlf_relaxed")]
-use abnf_core::streaming::crlf_relaxed as crlf;
-use abnf_core::{is_alpha, is_digit, streaming::dquote};
-use base64::{engine::general_purpose::STANDARD as _base64, Engine};
+use std::{borrow::Cow, cell::Cell, num::NonZeroU32, str::from_utf8};
+
+use abnf_core::{
+    is_alpha, is_digit,
+    streaming::{dquote, sp},
+};
 use imap_types::{
     core::{
-        AString, Atom, AtomExt, Charset, IString, Literal, LiteralMode, NString, Quoted,
-        QuotedChar, Tag, Text,
+        AString, Atom, AtomExt, Charset, ExtensionArguments, ExtensionName, IString, Literal,
+        LiteralMode, NString, Quoted, QuotedChar, Tag, Text,
     },
     utils::{
         indicators::{is_astring_char, is_atom_char, is_quoted_specials, is_text_char},
@@ -20,11 +18,67 @@ use nom::{
     branch::alt,
     bytes::streaming::{escaped, tag, tag_no_case, take, take_while, take_while1, take_while_m_n},
     character::streaming::{char, digit1, one_of},
-    combinator::{map, map_res, opt, recognize},
-    sequence::{delimited, terminated, tuple},
+    combinator::{map, map_res, opt, recognize, verify},
+    sequence::{delimited, preceded, terminated, tuple},
 };
 
-use crate::decode::{IMAPErrorKind, IMAPParseError, IMAPResult};
+use crate::{
+    base64::decode_b64,
+    decode::{IMAPErrorKind, IMAPParseError, IMAPResult},
+};
+
+// ----- line ending -----
+
+/// `CRLF = CR LF`
+///
+/// Note: With `quirk_crlf_relaxed`, a bare `LF` is accepted, too.
+pub(crate) fn crlf(input: &[u8]) -> IMAPResult<&[u8], &[u8]> {
+    #[cfg(not(feature = "quirk_crlf_relaxed"))]
+    return recognize(abnf_core::streaming::crlf)(input);
+
+    #[cfg(feature = "quirk_crlf_relaxed")]
+    return recognize(abnf_core::streaming::crlf_relaxed)(input);
+}
+
+/// Line ending of a command or response, i.e., [`crlf`] or a bare `LF` when `allow_lf` is set.
+pub(crate) fn line_ending(allow_lf: bool) -> impl Fn(&[u8]) -> IMAPResult<&[u8], &[u8]> {
+    move |input: &[u8]| {
+        if allow_lf {
+            recognize(abnf_core::streaming::crlf_relaxed)(input)
+        } else {
+            crlf(input)
+        }
+    }
+}
+
+thread_local! {
+    /// Whether the message currently parsed accepts bare `LF` line endings (see [`with_allow_lf`]).
+    static ALLOW_LF: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run `parser` and accept bare `LF` line endings in nested parsers, e.g., after a literal prefix.
+///
+/// Top-level parsers, e.g., `command`, pass `allow_lf` this way because literals are nested too
+/// deeply to pass it explicitly.
+pub(crate) fn with_allow_lf<T>(allow_lf: bool, parser: impl FnOnce() -> T) -> T {
+    // Restores the previous value, even when `parser` panics.
+    struct Restore(bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            ALLOW_LF.with(|cell| cell.set(self.0));
+        }
+    }
+
+    let _restore = Restore(ALLOW_LF.with(|cell| cell.replace(allow_lf)));
+
+    parser()
+}
+
+/// Line ending of a nested element, e.g., a literal prefix (see [`with_allow_lf`]).
+fn nested_line_ending(input: &[u8]) -> IMAPResult<&[u8], &[u8]> {
+    line_ending(ALLOW_LF.with(Cell::get))(input)
+}
 
 // ----- number -----
 
@@ -140,6 +194,8 @@ pub(crate) fn is_any_text_char_except_quoted_specials(byte: u8) -> bool {
 /// literal8 = <defined in RFC 4466>
 /// ```
 /// -- <https://datatracker.ietf.org/doc/html/rfc7888#section-8>
+///
+/// Note: A bare `LF` is accepted after the prefix when the message accepts it (see [`with_allow_lf`]).
 pub(crate) fn literal(input: &[u8]) -> IMAPResult<&[u8], Literal> {
     let (remaining, (length, mode)) = terminated(
         delimited(
@@ -152,7 +208,7 @@ pub(crate) fn literal(input: &[u8]) -> IMAPResult<&[u8], Literal> {
             )),
             tag(b"}"),
         ),
-        crlf,
+        nested_line_ending,
     )(input)?;
 
     // Signal that an continuation request could be required.
@@ -257,7 +313,7 @@ pub(crate) fn base64(input: &[u8]) -> IMAPResult<&[u8], Vec<u8>> {
             take_while(is_base64_char),
             opt(alt((tag("=="), tag("=")))),
         ))),
-        |input| _base64.decode(input),
+        decode_b64,
     )(input)
 }
 
@@ -289,11 +345,129 @@ pub(crate) fn tag_imap(input: &[u8]) -> IMAPResult<&[u8], Tag> {
     })(input)
 }
 
+// ----- extension -----
+
+/// `"X" atom [SP 1*<any byte except NUL, CR, and LF>]`
+///
+/// Name and raw arguments of an experimental or vendor-specific command or response, e.g.,
+/// `x-command = "X" atom <experimental command arguments>`.
+///
+/// Note: Literals are not supported. Arguments ending with a literal prefix, e.g., `{5}`, are
+/// rejected (instead of misinterpreting the literal data as the next message).
+pub(crate) fn x_extension(input: &[u8]) -> IMAPResult<&[u8], (ExtensionName, ExtensionArguments)> {
+    let mut parser = tuple((
+        map(
+            verify(atom, |atom: &Atom| {
+                ExtensionName::validate(atom.inner()).is_ok()
+            }),
+            |atom| ExtensionName::unvalidated(atom.into_inner()),
+        ),
+        opt(preceded(
+            sp,
+            verify(
+                take_while1(|b| !matches!(b, b'\x00' | b'\r' | b'\n')),
+                |args: &[u8]| ExtensionArguments::validate(args).is_ok(),
+            ),
+        )),
+    ));
+
+    let (remaining, (name, args)) = parser(input)?;
+
+    Ok((
+        remaining,
+        (
+            name,
+            ExtensionArguments::unvalidated(args.unwrap_or_default()),
+        ),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::encode::{EncodeContext, EncodeIntoContext};
 
+    /// Yields all byte strings up to length 2 and more strings up to length 4 from "interesting" bytes.
+    fn agreement_inputs() -> Vec<Vec<u8>> {
+        let mut inputs = vec![vec![]];
+
+        for a in 0..=255u8 {
+            inputs.push(vec![a]);
+
+            for b in 0..=255u8 {
+                inputs.push(vec![a, b]);
+            }
+        }
+
+        let interesting = b"a1 \"\\+]([{%*\r\n\x00\x01\x7f\x80\xff";
+
+        for a in interesting {
+            for b in interesting {
+                for c in interesting {
+                    inputs.push(vec![*a, *b, *c]);
+
+                    for d in interesting {
+                        inputs.push(vec![*a, *b, *c, *d]);
+                    }
+                }
+            }
+        }
+
+        inputs
+    }
+
+    #[test]
+    fn test_validator_parser_agreement() {
+        for input in agreement_inputs() {
+            // `atom`
+            {
+                let wire = [input.as_slice(), b" "].concat();
+                let parsed = matches!(atom(&wire), Ok((b" ", _)));
+                assert_eq!(
+                    Atom::try_from(input.as_slice()).is_ok(),
+                    parsed,
+                    "{input:?}"
+                );
+            }
+
+            // `tag`
+            {
+                let wire = [input.as_slice(), b" "].concat();
+                let parsed = matches!(tag_imap(&wire), Ok((b" ", _)));
+                assert_eq!(Tag::try_from(input.as_slice()).is_ok(), parsed, "{input:?}");
+            }
+
+            // `text`
+            {
+                let wire = [input.as_slice(), b"\r\n"].concat();
+                let parsed = matches!(text(&wire), Ok((b"\r\n", _)));
+                assert_eq!(
+                    Text::try_from(input.as_slice()).is_ok(),
+                    parsed,
+                    "{input:?}"
+                );
+            }
+
+            // `quoted`
+            {
+                let mut wire = vec![b'"'];
+                for byte in input.iter() {
+                    if matches!(byte, b'"' | b'\\') {
+                        wire.push(b'\\');
+                    }
+                    wire.push(*byte);
+                }
+                wire.extend_from_slice(b"\" ");
+
+                let parsed = match quoted(&wire) {
+                    Ok((b" ", quoted)) => Some(quoted),
+                    _ => None,
+                };
+                assert_eq!(Quoted::try_from(input.as_slice()).ok(), parsed, "{input:?}");
+            }
+        }
+    }
+
     #[test]
     fn test_atom() {
         assert!(atom(b" ").is_err());
@@ -342,6 +516,35 @@ mod tests {
         assert!(matches!(quoted(br#"\"#), Err(nom::Err::Error(_))));
     }
 
+    #[test]
+    fn test_quoted_escape_roundtrip() {
+        let tests = [
+            ("", b"\"\"".as_ref()),
+            ("\\", br#""\\""#),
+            ("\"", br#""\"""#),
+            (r#"a\"b\\c"#, br#""a\\\"b\\\\c""#),
+            (r#"\\\"""#, br#""\\\\\\\"\"""#),
+        ];
+
+        for (test, expected) in tests {
+            // The logical (unescaped) value is stored ...
+            let value = Quoted::try_from(test).unwrap();
+            assert_eq!(value.inner(), test);
+
+            // ... escaped during encoding ...
+            let mut ctx = EncodeContext::new();
+            IString::Quoted(value.clone()).encode_ctx(&mut ctx).unwrap();
+            let encoded = ctx.dump();
+            assert_eq!(encoded, expected);
+
+            // ... and unescaped during parsing.
+            let input = [encoded.as_slice(), b")"].concat();
+            let (rem, got) = quoted(&input).unwrap();
+            assert_eq!(rem, b")");
+            assert_eq!(got, value);
+        }
+    }
+
     #[test]
     fn test_quoted_char() {
         let (rem, val) = quoted_char(b"\\\"xxx").unwrap();
@@ -377,6 +580,57 @@ mod tests {
         let (rem, val) = literal(b"{3}\r\n123xxx").unwrap();
         assert_eq!(rem, b"xxx");
         assert_eq!(val, Literal::try_from(b"123".as_slice()).unwrap());
+
+        // An empty literal is not an empty quoted string.
+        let (rem, val) = literal(b"{0}\r\n)").unwrap();
+        assert_eq!(rem, b")");
+        assert_eq!(val, Literal::try_from(b"".as_slice()).unwrap());
+        assert_ne!(
+            IString::Literal(val),
+            IString::Quoted(Quoted::try_from("").unwrap())
+        );
+
+        // A bare `LF` after the prefix is only accepted when allowed.
+        #[cfg(not(feature = "quirk_crlf_relaxed"))]
+        assert!(literal(b"{3}\n123").is_err());
+        assert!(with_allow_lf(true, || literal(b"{3}\n123")).is_ok());
+    }
+
+    #[test]
+    fn test_literal_and_quoted_borrow_from_input() {
+        let input = b"{5}\r\nhello)";
+        let (_, val) = literal(input).unwrap();
+        assert!(
+            matches!(val.into_inner(), Cow::Borrowed(data) if data.as_ptr() == input[5..].as_ptr())
+        );
+
+        let (_, val) = quoted(b"\"hello\")").unwrap();
+        assert!(matches!(val.into_inner(), Cow::Borrowed("hello")));
+
+        // Escaped characters require an allocation.
+        let (_, val) = quoted(b"\"he\\\"llo\")").unwrap();
+        assert!(matches!(val.into_inner(), Cow::Owned(data) if data == "he\"llo"));
+    }
+
+    #[test]
+    fn test_encode_empty_literal() {
+        let tests = [
+            (
+                IString::Literal(Literal::try_from(b"".as_slice()).unwrap()),
+                b"{0}\r\n".as_ref(),
+            ),
+            (
+                IString::Literal(Literal::unvalidated_non_sync(b"".as_slice())),
+                b"{0+}\r\n",
+            ),
+            (IString::Quoted(Quoted::try_from("").unwrap()), b"\"\""),
+        ];
+
+        for (test, expected) in tests {
+            let mut ctx = EncodeContext::new();
+            test.encode_ctx(&mut ctx).unwrap();
+            assert_eq!(ctx.dump(), expected);
+        }
     }
 
     #[test]
@@ -428,9 +682,9 @@ mod tests {
 
     #[test]
     fn test_base64() {
-        _base64.decode(b"AA==").unwrap();
-        // Note: "pad bits MUST be set to zero by conforming encoders" [RFC 4648, sec. 3.5].
-        //_base64.decode(b"aa==").unwrap();
-        _base64.decode(b"aQ==").unwrap();
+        assert_eq!(base64(b"AA== ").unwrap(), (b" ".as_ref(), vec![0]));
+        assert_eq!(base64(b"aQ==\r\n").unwrap().1, b"i");
+        assert_eq!(base64(b"YWJj\r\n").unwrap().1, b"abc");
+        assert!(base64(b"aQ\r\n").is_err());
     }
 }
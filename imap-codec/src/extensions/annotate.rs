@@ -0,0 +1,399 @@
+//! IMAP ANNOTATE Extension
+
+// Additional changes:
+//
+// fetch-att       =/ fetch-annotate-att
+// msg-att-dynamic =/ "ANNOTATION" SP
+//                    ( "(" entry-att *(SP entry-att) ")" /
+//                      "(" entry *(SP entry) ")" )
+// resp-text-code  =/ "ANNOTATE" SP "TOOBIG" /
+//                    "ANNOTATE" SP "TOOMANY"
+// search-key      =/ att-search
+
+use std::io::Write;
+
+use abnf_core::streaming::sp;
+use imap_types::{
+    core::Vec1,
+    extensions::annotate::{AnnotateCode, AttributeValue, EntryAnnotations, MessageAnnotations},
+    fetch::{MessageDataItem, MessageDataItemName},
+    search::SearchKey,
+};
+use nom::{
+    branch::alt,
+    bytes::streaming::{tag, tag_no_case},
+    combinator::{map, value},
+    multi::separated_list1,
+    sequence::{delimited, preceded, separated_pair, tuple},
+};
+
+use crate::{
+    core::{astring, nstring},
+    decode::IMAPResult,
+    encode::{utils::join_serializable, EncodeContext, EncodeIntoContext},
+    mailbox::list_mailbox,
+};
+
+/// ```abnf
+/// fetch-annotate-att = "ANNOTATION" SP "(" entries SP attribs ")"
+///
+/// entries = entry-match / "(" entry-match *(SP entry-match) ")"
+///
+/// attribs = attrib-match / "(" attrib-match *(SP attrib-match) ")"
+///
+/// entry-match = list-mailbox ; May contain "*" or "%"
+///
+/// attrib-match = astring ; May contain "*" or "%"
+/// ```
+pub(crate) fn fetch_annotate_att(input: &[u8]) -> IMAPResult<&[u8], MessageDataItemName> {
+    let mut parser = tuple((
+        tag_no_case(b"ANNOTATION "),
+        delimited(
+            tag(b"("),
+            separated_pair(
+                alt((
+                    map(
+                        delimited(tag(b"("), separated_list1(sp, list_mailbox), tag(b")")),
+                        Vec1::unvalidated,
+                    ),
+                    map(list_mailbox, Vec1::from),
+                )),
+                sp,
+                alt((
+                    map(
+                        delimited(tag(b"("), separated_list1(sp, astring), tag(b")")),
+                        Vec1::unvalidated,
+                    ),
+                    map(astring, Vec1::from),
+                )),
+            ),
+            tag(b")"),
+        ),
+    ));
+
+    let (remaining, (_, (entries, attributes))) = parser(input)?;
+
+    Ok((
+        remaining,
+        MessageDataItemName::Annotation {
+            entries,
+            attributes,
+        },
+    ))
+}
+
+/// ```abnf
+/// msg-att-annotate = "ANNOTATION" SP
+///                    ( "(" entry-att *(SP entry-att) ")" /
+///                      "(" entry *(SP entry) ")" )
+/// ```
+pub(crate) fn msg_att_annotate(input: &[u8]) -> IMAPResult<&[u8], MessageDataItem> {
+    map(
+        preceded(
+            tag_no_case(b"ANNOTATION "),
+            alt((
+                map(
+                    delimited(tag(b"("), separated_list1(sp, entry_att), tag(b")")),
+                    |entries| MessageAnnotations::WithValues(Vec1::unvalidated(entries)),
+                ),
+                map(
+                    delimited(tag(b"("), separated_list1(sp, astring), tag(b")")),
+                    |entries| MessageAnnotations::WithoutValues(Vec1::unvalidated(entries)),
+                ),
+            )),
+        ),
+        MessageDataItem::Annotation,
+    )(input)
+}
+
+/// ```abnf
+/// entry-att = entry SP "(" att-value *(SP att-value) ")"
+///
+/// entry = astring ; MUST NOT contain "*" or "%"
+/// ```
+fn entry_att(input: &[u8]) -> IMAPResult<&[u8], EntryAnnotations> {
+    let mut parser = separated_pair(
+        astring,
+        sp,
+        delimited(tag(b"("), separated_list1(sp, att_value), tag(b")")),
+    );
+
+    let (remaining, (entry, values)) = parser(input)?;
+
+    Ok((
+        remaining,
+        EntryAnnotations {
+            entry,
+            values: Vec1::unvalidated(values),
+        },
+    ))
+}
+
+/// ```abnf
+/// att-value = attrib SP value
+///
+/// attrib = astring ; MUST NOT contain "*" or "%"
+///
+/// value = nstring / literal8
+/// ```
+///
+/// Note: `literal8` is not supported (yet).
+fn att_value(input: &[u8]) -> IMAPResult<&[u8], AttributeValue> {
+    map(
+        separated_pair(astring, sp, nstring),
+        |(attribute, value)| AttributeValue { attribute, value },
+    )(input)
+}
+
+/// ```abnf
+/// att-search = "ANNOTATION" SP entry-match SP attrib-match SP value
+/// ```
+pub(crate) fn att_search(input: &[u8]) -> IMAPResult<&[u8], SearchKey> {
+    let mut parser = tuple((
+        tag_no_case(b"ANNOTATION "),
+        list_mailbox,
+        sp,
+        astring,
+        sp,
+        nstring,
+    ));
+
+    let (remaining, (_, entry, _, attribute, _, value)) = parser(input)?;
+
+    Ok((
+        remaining,
+        SearchKey::Annotation {
+            entry,
+            attribute,
+            value,
+        },
+    ))
+}
+
+/// ```abnf
+/// "ANNOTATE" SP ("TOOBIG" / "TOOMANY")
+/// ```
+pub(crate) fn annotate_code(input: &[u8]) -> IMAPResult<&[u8], AnnotateCode> {
+    preceded(
+        tag_no_case(b"ANNOTATE "),
+        alt((
+            value(AnnotateCode::TooBig, tag_no_case(b"TOOBIG")),
+            value(AnnotateCode::TooMany, tag_no_case(b"TOOMANY")),
+        )),
+    )(input)
+}
+
+/// Encodes a single element as is and multiple elements as a parenthesized list.
+pub(crate) fn encode_one_or_list<I: EncodeIntoContext>(
+    elements: &Vec1<I>,
+    ctx: &mut EncodeContext,
+) -> std::io::Result<()> {
+    match elements.as_ref() {
+        [element] => element.encode_ctx(ctx),
+        elements => {
+            ctx.write_all(b"(")?;
+            join_serializable(elements, b" ", ctx)?;
+            ctx.write_all(b")")
+        }
+    }
+}
+
+impl<'a> EncodeIntoContext for MessageAnnotations<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        ctx.write_all(b"(")?;
+        match self {
+            Self::WithValues(entries) => join_serializable(entries.as_ref(), b" ", ctx)?,
+            Self::WithoutValues(entries) => join_serializable(entries.as_ref(), b" ", ctx)?,
+        }
+        ctx.write_all(b")")
+    }
+}
+
+impl<'a> EncodeIntoContext for EntryAnnotations<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        self.entry.encode_ctx(ctx)?;
+        ctx.write_all(b" (")?;
+        join_serializable(self.values.as_ref(), b" ", ctx)?;
+        ctx.write_all(b")")
+    }
+}
+
+impl<'a> EncodeIntoContext for AttributeValue<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        self.attribute.encode_ctx(ctx)?;
+        ctx.write_all(b" ")?;
+        self.value.encode_ctx(ctx)
+    }
+}
+
+impl EncodeIntoContext for AnnotateCode {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        ctx.write_all(b"ANNOTATE ")?;
+
+        match self {
+            Self::TooBig => ctx.write_all(b"TOOBIG"),
+            Self::TooMany => ctx.write_all(b"TOOMANY"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imap_types::{
+        command::{Command, CommandBody},
+        core::{AString, IString, NString, Quoted, Tag},
+        mailbox::ListMailbox,
+        response::{Code, Data, Response, Status},
+    };
+
+    use super::*;
+    use crate::testing::{kat_inverse_command, kat_inverse_response};
+
+    #[test]
+    fn test_kat_inverse_command_annotate() {
+        kat_inverse_command(&[
+            (
+                b"A FETCH 1 (ANNOTATION (/comment (value.priv)))\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::fetch(
+                        "1",
+                        vec![MessageDataItemName::Annotation {
+                            entries: Vec1::from(ListMailbox::try_from("/comment").unwrap()),
+                            attributes: Vec1::from(AString::try_from("value.priv").unwrap()),
+                        }],
+                        false,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A FETCH 1 (ANNOTATION ((/comment /altsubject/*) (value.priv \"value.*\")) UID)\r\n"
+                    .as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::fetch(
+                        "1",
+                        vec![
+                            MessageDataItemName::Annotation {
+                                entries: Vec1::try_from(vec![
+                                    ListMailbox::try_from("/comment").unwrap(),
+                                    ListMailbox::try_from("/altsubject/*").unwrap(),
+                                ])
+                                .unwrap(),
+                                attributes: Vec1::try_from(vec![
+                                    AString::try_from("value.priv").unwrap(),
+                                    AString::try_from("value.*").unwrap(),
+                                ])
+                                .unwrap(),
+                            },
+                            MessageDataItemName::Uid,
+                        ],
+                        false,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A UID SEARCH ANNOTATION /comment/% \"value.*\" \"IMAP4\"\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::search(
+                        None,
+                        Vec1::from(SearchKey::Annotation {
+                            entry: ListMailbox::try_from("/comment/%").unwrap(),
+                            attribute: AString::try_from("value.*").unwrap(),
+                            value: NString(Some(IString::Quoted(
+                                Quoted::try_from("IMAP4").unwrap(),
+                            ))),
+                        }),
+                        true,
+                    ),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_annotate() {
+        kat_inverse_response(&[
+            (
+                b"* 1 FETCH (ANNOTATION (/comment (value.priv \"My comment\" value.shared NIL)))\r\n"
+                    .as_ref(),
+                b"".as_ref(),
+                Response::Data(
+                    Data::fetch(
+                        1,
+                        vec![MessageDataItem::Annotation(MessageAnnotations::WithValues(
+                            Vec1::from(EntryAnnotations {
+                                entry: AString::try_from("/comment").unwrap(),
+                                values: Vec1::try_from(vec![
+                                    AttributeValue {
+                                        attribute: AString::try_from("value.priv").unwrap(),
+                                        value: NString::try_from("My comment").unwrap(),
+                                    },
+                                    AttributeValue {
+                                        attribute: AString::try_from("value.shared").unwrap(),
+                                        value: NString(None),
+                                    },
+                                ])
+                                .unwrap(),
+                            }),
+                        ))],
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"* 1 FETCH (UID 7 ANNOTATION (/comment /altsubject))\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(
+                    Data::fetch(
+                        1,
+                        vec![
+                            MessageDataItem::Uid(7.try_into().unwrap()),
+                            MessageDataItem::Annotation(MessageAnnotations::WithoutValues(
+                                Vec1::try_from(vec![
+                                    AString::try_from("/comment").unwrap(),
+                                    AString::try_from("/altsubject").unwrap(),
+                                ])
+                                .unwrap(),
+                            )),
+                        ],
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"A NO [ANNOTATE TOOBIG] Annotation too big\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Status(
+                    Status::no(
+                        Some(Tag::try_from("A").unwrap()),
+                        Some(Code::Annotate(AnnotateCode::TooBig)),
+                        "Annotation too big",
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"A NO [ANNOTATE TOOMANY] Too many annotations\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Status(
+                    Status::no(
+                        Some(Tag::try_from("A").unwrap()),
+                        Some(Code::Annotate(AnnotateCode::TooMany)),
+                        "Too many annotations",
+                    )
+                    .unwrap(),
+                ),
+            ),
+        ]);
+    }
+}
@@ -0,0 +1,538 @@
+//! IMAP ESEARCH, CONTEXT, and ESORT Extensions
+
+// Additional changes:
+//
+// search        =/ "SEARCH" search-return-opts [SP "CHARSET" SP charset] 1*(SP search-key)
+// sort          =/ "SORT" search-return-opts SP sort-criteria SP search-criteria
+// response-data =/ "*" SP esearch-response CRLF
+
+use std::io::Write;
+
+use abnf_core::streaming::{dquote, sp};
+use imap_types::{
+    command::CommandBody,
+    core::{Tag, Vec1},
+    extensions::context::{PartialRange, SearchReturnData, SearchReturnOption},
+    response::Data,
+    sequence::SequenceSet,
+};
+use nom::{
+    branch::alt,
+    bytes::streaming::{tag, tag_no_case},
+    combinator::{map, opt, value},
+    multi::{many0, separated_list0, separated_list1},
+    sequence::{delimited, preceded, separated_pair, tuple},
+};
+
+use crate::{
+    core::{charset, nil, number, nz_number, tag_imap},
+    decode::IMAPResult,
+    encode::{EncodeContext, EncodeIntoContext},
+    extensions::sort::sort_criteria,
+    search::{search_criteria, search_key},
+    sequence::sequence_set,
+};
+
+/// ```abnf
+/// search =/ "SEARCH" search-return-opts [SP "CHARSET" SP charset] 1*(SP search-key)
+/// ```
+pub(crate) fn esearch(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = tuple((
+        tag_no_case(b"SEARCH"),
+        search_return_opts,
+        opt(preceded(tuple((sp, tag_no_case(b"CHARSET"), sp)), charset)),
+        sp,
+        map(separated_list1(sp, search_key(9)), Vec1::unvalidated),
+    ));
+
+    let (remaining, (_, return_options, charset, _, criteria)) = parser(input)?;
+
+    Ok((
+        remaining,
+        CommandBody::ESearch {
+            return_options,
+            charset,
+            criteria,
+            uid: false,
+        },
+    ))
+}
+
+/// ```abnf
+/// sort =/ ["UID" SP] "SORT" search-return-opts SP sort-criteria SP search-criteria
+/// ```
+pub(crate) fn esort(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = tuple((
+        map(opt(tag_no_case("UID ")), |thing| thing.is_some()),
+        tag_no_case("SORT"),
+        search_return_opts,
+        sp,
+        sort_criteria,
+        sp,
+        search_criteria,
+    ));
+
+    let (remaining, (uid, _, return_options, _, sort_criteria, _, (charset, search_criteria))) =
+        parser(input)?;
+
+    Ok((
+        remaining,
+        CommandBody::ESort {
+            return_options,
+            sort_criteria,
+            charset,
+            search_criteria,
+            uid,
+        },
+    ))
+}
+
+/// ```abnf
+/// search-return-opts = SP "RETURN" SP "(" [search-return-opt *(SP search-return-opt)] ")"
+/// ```
+fn search_return_opts(input: &[u8]) -> IMAPResult<&[u8], Vec<SearchReturnOption>> {
+    delimited(
+        tag_no_case(b" RETURN ("),
+        separated_list0(sp, search_return_opt),
+        tag(b")"),
+    )(input)
+}
+
+/// ```abnf
+/// search-return-opt = "MIN" / "MAX" / "ALL" / "COUNT" /
+///                     "UPDATE" / "CONTEXT" /
+///                     "PARTIAL" SP partial-range
+/// ```
+fn search_return_opt(input: &[u8]) -> IMAPResult<&[u8], SearchReturnOption> {
+    alt((
+        value(SearchReturnOption::Min, tag_no_case(b"MIN")),
+        value(SearchReturnOption::Max, tag_no_case(b"MAX")),
+        value(SearchReturnOption::All, tag_no_case(b"ALL")),
+        value(SearchReturnOption::Count, tag_no_case(b"COUNT")),
+        value(SearchReturnOption::Update, tag_no_case(b"UPDATE")),
+        value(SearchReturnOption::Context, tag_no_case(b"CONTEXT")),
+        map(
+            preceded(tag_no_case(b"PARTIAL "), partial_range),
+            SearchReturnOption::Partial,
+        ),
+    ))(input)
+}
+
+/// ```abnf
+/// partial-range = nz-number ":" nz-number
+/// ```
+fn partial_range(input: &[u8]) -> IMAPResult<&[u8], PartialRange> {
+    map(
+        separated_pair(nz_number, tag(b":"), nz_number),
+        |(first, last)| PartialRange { first, last },
+    )(input)
+}
+
+/// ```abnf
+/// esearch-response = "ESEARCH" [search-correlator] [SP "UID"] *(SP search-return-data)
+/// ```
+///
+/// Note: Starts after "*" SP.
+pub(crate) fn esearch_response(input: &[u8]) -> IMAPResult<&[u8], Data> {
+    let mut parser = tuple((
+        tag_no_case(b"ESEARCH"),
+        opt(search_correlator),
+        map(opt(tag_no_case(b" UID")), |thing| thing.is_some()),
+        many0(preceded(sp, search_return_data)),
+    ));
+
+    let (remaining, (_, correlator, uid, data)) = parser(input)?;
+
+    Ok((
+        remaining,
+        Data::ESearch {
+            correlator,
+            uid,
+            data,
+        },
+    ))
+}
+
+/// ```abnf
+/// search-correlator = SP "(" "TAG" SP tag-string ")"
+///
+/// tag-string = string
+/// ```
+///
+/// Note: Only quoted tags are supported.
+fn search_correlator(input: &[u8]) -> IMAPResult<&[u8], Tag> {
+    delimited(
+        tag_no_case(b" (TAG "),
+        delimited(dquote, tag_imap, dquote),
+        tag(b")"),
+    )(input)
+}
+
+/// ```abnf
+/// search-return-data = "MIN" SP nz-number /
+///                      "MAX" SP nz-number /
+///                      "ALL" SP sequence-set /
+///                      "COUNT" SP number /
+///                      ret-data-partial /
+///                      ret-data-addto /
+///                      ret-data-removefrom
+///
+/// ret-data-partial = "PARTIAL" SP "(" partial-range SP partial-results ")"
+///
+/// partial-results = sequence-set / "NIL"
+///
+/// ret-data-addto = "ADDTO" SP "(" context-position SP sequence-set
+///                  *(SP context-position SP sequence-set) ")"
+///
+/// ret-data-removefrom = "REMOVEFROM" SP "(" context-position SP sequence-set
+///                       *(SP context-position SP sequence-set) ")"
+/// ```
+fn search_return_data(input: &[u8]) -> IMAPResult<&[u8], SearchReturnData> {
+    alt((
+        map(
+            preceded(tag_no_case(b"MIN "), nz_number),
+            SearchReturnData::Min,
+        ),
+        map(
+            preceded(tag_no_case(b"MAX "), nz_number),
+            SearchReturnData::Max,
+        ),
+        map(
+            preceded(tag_no_case(b"ALL "), sequence_set),
+            SearchReturnData::All,
+        ),
+        map(
+            preceded(tag_no_case(b"COUNT "), number),
+            SearchReturnData::Count,
+        ),
+        map(
+            delimited(
+                tag_no_case(b"PARTIAL ("),
+                separated_pair(
+                    partial_range,
+                    sp,
+                    alt((value(None, nil), map(sequence_set, Some))),
+                ),
+                tag(b")"),
+            ),
+            |(range, results)| SearchReturnData::Partial { range, results },
+        ),
+        map(
+            preceded(tag_no_case(b"ADDTO "), context_updates),
+            SearchReturnData::AddTo,
+        ),
+        map(
+            preceded(tag_no_case(b"REMOVEFROM "), context_updates),
+            SearchReturnData::RemoveFrom,
+        ),
+    ))(input)
+}
+
+/// ```abnf
+/// "(" context-position SP sequence-set *(SP context-position SP sequence-set) ")"
+///
+/// context-position = number
+/// ```
+fn context_updates(input: &[u8]) -> IMAPResult<&[u8], Vec1<(u32, SequenceSet)>> {
+    delimited(
+        tag(b"("),
+        map(
+            separated_list1(sp, separated_pair(number, sp, sequence_set)),
+            Vec1::unvalidated,
+        ),
+        tag(b")"),
+    )(input)
+}
+
+/// Encode ` RETURN (...)`.
+pub(crate) fn encode_search_return_opts(
+    return_options: &[SearchReturnOption],
+    ctx: &mut EncodeContext,
+) -> std::io::Result<()> {
+    ctx.write_all(b" RETURN (")?;
+
+    for (index, option) in return_options.iter().enumerate() {
+        if index > 0 {
+            ctx.write_all(b" ")?;
+        }
+        option.encode_ctx(ctx)?;
+    }
+
+    ctx.write_all(b")")
+}
+
+impl EncodeIntoContext for SearchReturnOption {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        match self {
+            Self::Min => ctx.write_all(b"MIN"),
+            Self::Max => ctx.write_all(b"MAX"),
+            Self::All => ctx.write_all(b"ALL"),
+            Self::Count => ctx.write_all(b"COUNT"),
+            Self::Update => ctx.write_all(b"UPDATE"),
+            Self::Context => ctx.write_all(b"CONTEXT"),
+            Self::Partial(range) => {
+                ctx.write_all(b"PARTIAL ")?;
+                range.encode_ctx(ctx)
+            }
+        }
+    }
+}
+
+impl EncodeIntoContext for PartialRange {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        write!(ctx, "{}:{}", self.first, self.last)
+    }
+}
+
+impl EncodeIntoContext for SearchReturnData {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        match self {
+            Self::Min(min) => write!(ctx, "MIN {min}"),
+            Self::Max(max) => write!(ctx, "MAX {max}"),
+            Self::All(sequence_set) => {
+                ctx.write_all(b"ALL ")?;
+                sequence_set.encode_ctx(ctx)
+            }
+            Self::Count(count) => write!(ctx, "COUNT {count}"),
+            Self::Partial { range, results } => {
+                ctx.write_all(b"PARTIAL (")?;
+                range.encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                match results {
+                    Some(sequence_set) => sequence_set.encode_ctx(ctx)?,
+                    None => ctx.write_all(b"NIL")?,
+                }
+                ctx.write_all(b")")
+            }
+            Self::AddTo(updates) => {
+                ctx.write_all(b"ADDTO ")?;
+                encode_context_updates(updates.as_ref(), ctx)
+            }
+            Self::RemoveFrom(updates) => {
+                ctx.write_all(b"REMOVEFROM ")?;
+                encode_context_updates(updates.as_ref(), ctx)
+            }
+        }
+    }
+}
+
+fn encode_context_updates(
+    updates: &[(u32, SequenceSet)],
+    ctx: &mut EncodeContext,
+) -> std::io::Result<()> {
+    ctx.write_all(b"(")?;
+
+    for (index, (position, sequence_set)) in updates.iter().enumerate() {
+        if index > 0 {
+            ctx.write_all(b" ")?;
+        }
+        write!(ctx, "{position} ")?;
+        sequence_set.encode_ctx(ctx)?;
+    }
+
+    ctx.write_all(b")")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use imap_types::{
+        command::Command,
+        core::Charset,
+        extensions::sort::{SortCriterion, SortKey},
+        response::Response,
+        search::SearchKey,
+    };
+
+    use super::*;
+    use crate::testing::{kat_inverse_command, kat_inverse_response};
+
+    fn range(first: u32, last: u32) -> PartialRange {
+        PartialRange {
+            first: NonZeroU32::new(first).unwrap(),
+            last: NonZeroU32::new(last).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_kat_inverse_command_esearch_esort() {
+        kat_inverse_command(&[
+            (
+                b"A SEARCH RETURN (PARTIAL 1:100) UNDELETED\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::esearch(
+                        vec![SearchReturnOption::Partial(range(1, 100))],
+                        None,
+                        Vec1::from(SearchKey::Undeleted),
+                        false,
+                    ),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A UID SEARCH RETURN () CHARSET UTF-8 ALL\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::esearch(
+                        vec![],
+                        Some(Charset::try_from("UTF-8").unwrap()),
+                        Vec1::from(SearchKey::All),
+                        true,
+                    ),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A UID SORT RETURN (MIN COUNT UPDATE CONTEXT) (DATE) UTF-8 ALL\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::ESort {
+                        return_options: vec![
+                            SearchReturnOption::Min,
+                            SearchReturnOption::Count,
+                            SearchReturnOption::Update,
+                            SearchReturnOption::Context,
+                        ],
+                        sort_criteria: Vec1::from(SortCriterion {
+                            reverse: false,
+                            key: SortKey::Date,
+                        }),
+                        charset: Charset::try_from("UTF-8").unwrap(),
+                        search_criteria: Vec1::from(SearchKey::All),
+                        uid: true,
+                    },
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_esearch() {
+        kat_inverse_response(&[
+            (
+                b"* ESEARCH (TAG \"A\") PARTIAL (1:100 1:5,7)\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::ESearch {
+                    correlator: Some(Tag::try_from("A").unwrap()),
+                    uid: false,
+                    data: vec![SearchReturnData::Partial {
+                        range: range(1, 100),
+                        results: Some("1:5,7".try_into().unwrap()),
+                    }],
+                }),
+            ),
+            (
+                b"* ESEARCH (TAG \"A\") UID PARTIAL (101:200 NIL)\r\n",
+                b"",
+                Response::Data(Data::ESearch {
+                    correlator: Some(Tag::try_from("A").unwrap()),
+                    uid: true,
+                    data: vec![SearchReturnData::Partial {
+                        range: range(101, 200),
+                        results: None,
+                    }],
+                }),
+            ),
+            (
+                b"* ESEARCH (TAG \"B\") UID MIN 2 MAX 20 COUNT 3 ALL 2,10:20\r\n",
+                b"",
+                Response::Data(Data::ESearch {
+                    correlator: Some(Tag::try_from("B").unwrap()),
+                    uid: true,
+                    data: vec![
+                        SearchReturnData::Min(NonZeroU32::new(2).unwrap()),
+                        SearchReturnData::Max(NonZeroU32::new(20).unwrap()),
+                        SearchReturnData::Count(3),
+                        SearchReturnData::All("2,10:20".try_into().unwrap()),
+                    ],
+                }),
+            ),
+            (
+                b"* ESEARCH (TAG \"C\") UID ADDTO (0 32544 3 28)\r\n",
+                b"",
+                Response::Data(Data::ESearch {
+                    correlator: Some(Tag::try_from("C").unwrap()),
+                    uid: true,
+                    data: vec![SearchReturnData::AddTo(
+                        Vec1::try_from(vec![
+                            (0, "32544".try_into().unwrap()),
+                            (3, "28".try_into().unwrap()),
+                        ])
+                        .unwrap(),
+                    )],
+                }),
+            ),
+            (
+                b"* ESEARCH (TAG \"C\") UID REMOVEFROM (0 32544)\r\n",
+                b"",
+                Response::Data(Data::ESearch {
+                    correlator: Some(Tag::try_from("C").unwrap()),
+                    uid: true,
+                    data: vec![SearchReturnData::RemoveFrom(Vec1::from((
+                        0,
+                        "32544".try_into().unwrap(),
+                    )))],
+                }),
+            ),
+            (
+                b"* ESEARCH\r\n",
+                b"",
+                Response::Data(Data::ESearch {
+                    correlator: None,
+                    uid: false,
+                    data: vec![],
+                }),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_esearch_order() {
+        let count = || SearchReturnData::Count(5);
+        let min = || SearchReturnData::Min(NonZeroU32::new(1).unwrap());
+        let max = || SearchReturnData::Max(NonZeroU32::new(9).unwrap());
+        let all = || SearchReturnData::All("1,5:9".try_into().unwrap());
+
+        // The same logical result in different orders. The order is preserved.
+        let tests = [
+            (
+                b"* ESEARCH (TAG \"A1\") UID COUNT 5 MIN 1\r\n".as_ref(),
+                vec![count(), min()],
+            ),
+            (
+                b"* ESEARCH (TAG \"A1\") UID MIN 1 COUNT 5\r\n",
+                vec![min(), count()],
+            ),
+            (
+                b"* ESEARCH (TAG \"A1\") UID MIN 1 MAX 9 COUNT 5 ALL 1,5:9\r\n",
+                vec![min(), max(), count(), all()],
+            ),
+            (
+                b"* ESEARCH (TAG \"A1\") UID ALL 1,5:9 COUNT 5 MAX 9 MIN 1\r\n",
+                vec![all(), count(), max(), min()],
+            ),
+            (
+                b"* ESEARCH (TAG \"A1\") UID COUNT 5 ALL 1,5:9 MIN 1 MAX 9\r\n",
+                vec![count(), all(), min(), max()],
+            ),
+        ];
+
+        for (input, data) in tests {
+            kat_inverse_response(&[(
+                input,
+                b"",
+                Response::Data(Data::ESearch {
+                    correlator: Some(Tag::try_from("A1").unwrap()),
+                    uid: true,
+                    data,
+                }),
+            )]);
+        }
+    }
+}
@@ -78,10 +78,11 @@ mod tests {
         command::Command,
         core::Atom,
         extensions::enable::{CapabilityEnable, Utf8Kind},
+        response::Response,
     };
 
     use super::*;
-    use crate::testing::kat_inverse_command;
+    use crate::testing::{kat_inverse_command, kat_inverse_response};
 
     #[test]
     fn test_parse_enable() {
@@ -127,4 +128,34 @@ mod tests {
             ),
         ]);
     }
+
+    #[test]
+    fn test_kat_inverse_response_enabled() {
+        kat_inverse_response(&[
+            (
+                b"* ENABLED\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Enabled {
+                    capabilities: vec![],
+                }),
+            ),
+            (
+                b"* ENABLED CONDSTORE X-GOOD-IDEA\r\n?",
+                b"?",
+                Response::Data(Data::Enabled {
+                    capabilities: vec![
+                        CapabilityEnable::from(Atom::try_from("CONDSTORE").unwrap()),
+                        CapabilityEnable::from(Atom::try_from("X-GOOD-IDEA").unwrap()),
+                    ],
+                }),
+            ),
+            (
+                b"* ENABLED UTF8=ACCEPT\r\n",
+                b"",
+                Response::Data(Data::Enabled {
+                    capabilities: vec![CapabilityEnable::Utf8(Utf8Kind::Accept)],
+                }),
+            ),
+        ]);
+    }
 }
@@ -0,0 +1,294 @@
+//! Gmail IMAP Extensions
+
+// Additional changes:
+//
+// fetch-att      =/ "X-GM-LABELS" / "X-GM-MSGID" / "X-GM-THRID"
+// msg-att        =/ "X-GM-LABELS" SP gmail-labels /
+//                   "X-GM-MSGID" SP number64 /
+//                   "X-GM-THRID" SP number64
+// search-key     =/ "X-GM-RAW" SP astring
+// store          =/ "STORE" SP sequence-set SP store-att-gmail-labels
+
+use std::io::Write;
+
+use abnf_core::streaming::sp;
+use imap_types::{
+    extensions::gmail::GmailLabel,
+    fetch::{MessageDataItem, MessageDataItemName},
+    flag::{StoreResponse, StoreType},
+};
+use nom::{
+    branch::alt,
+    bytes::streaming::{tag, tag_no_case},
+    combinator::{map, opt, value},
+    multi::separated_list1,
+    sequence::{delimited, preceded, tuple},
+};
+
+use crate::{
+    core::{astring, atom, number64},
+    decode::IMAPResult,
+    encode::{EncodeContext, EncodeIntoContext},
+};
+
+/// ```abnf
+/// gmail-label = "\" atom / astring
+/// ```
+pub(crate) fn gmail_label(input: &[u8]) -> IMAPResult<&[u8], GmailLabel> {
+    alt((
+        map(preceded(tag(b"\\"), atom), GmailLabel::System),
+        map(astring, GmailLabel::Other),
+    ))(input)
+}
+
+/// ```abnf
+/// gmail-labels = "(" [gmail-label *(SP gmail-label)] ")"
+/// ```
+pub(crate) fn gmail_labels(input: &[u8]) -> IMAPResult<&[u8], Vec<GmailLabel>> {
+    map(
+        delimited(tag(b"("), opt(separated_list1(sp, gmail_label)), tag(b")")),
+        Option::unwrap_or_default,
+    )(input)
+}
+
+/// ```abnf
+/// fetch-att =/ "X-GM-LABELS" / "X-GM-MSGID" / "X-GM-THRID"
+/// ```
+pub(crate) fn fetch_att_gmail(input: &[u8]) -> IMAPResult<&[u8], MessageDataItemName> {
+    alt((
+        value(
+            MessageDataItemName::GmailLabels,
+            tag_no_case(b"X-GM-LABELS"),
+        ),
+        value(MessageDataItemName::GmailMsgId, tag_no_case(b"X-GM-MSGID")),
+        value(MessageDataItemName::GmailThrId, tag_no_case(b"X-GM-THRID")),
+    ))(input)
+}
+
+/// ```abnf
+/// msg-att-gmail = "X-GM-LABELS" SP gmail-labels /
+///                 "X-GM-MSGID" SP number64 /
+///                 "X-GM-THRID" SP number64
+/// ```
+pub(crate) fn msg_att_gmail(input: &[u8]) -> IMAPResult<&[u8], MessageDataItem> {
+    alt((
+        map(
+            preceded(tuple((tag_no_case(b"X-GM-LABELS"), sp)), gmail_labels),
+            MessageDataItem::GmailLabels,
+        ),
+        map(
+            preceded(tuple((tag_no_case(b"X-GM-MSGID"), sp)), number64),
+            MessageDataItem::GmailMsgId,
+        ),
+        map(
+            preceded(tuple((tag_no_case(b"X-GM-THRID"), sp)), number64),
+            MessageDataItem::GmailThrId,
+        ),
+    ))(input)
+}
+
+/// ```abnf
+/// store-att-gmail-labels = (["+" / "-"] "X-GM-LABELS" [".SILENT"]) SP
+///                          (gmail-labels / (gmail-label *(SP gmail-label)))
+/// ```
+pub(crate) fn store_att_gmail_labels(
+    input: &[u8],
+) -> IMAPResult<&[u8], (StoreType, StoreResponse, Vec<GmailLabel>)> {
+    let mut parser = tuple((
+        map(
+            opt(alt((
+                value(StoreType::Add, tag(b"+")),
+                value(StoreType::Remove, tag(b"-")),
+            ))),
+            |kind| kind.unwrap_or(StoreType::Replace),
+        ),
+        tag_no_case(b"X-GM-LABELS"),
+        map(opt(tag_no_case(b".SILENT")), |silent| match silent {
+            Some(_) => StoreResponse::Silent,
+            None => StoreResponse::Answer,
+        }),
+        sp,
+        alt((gmail_labels, separated_list1(sp, gmail_label))),
+    ));
+
+    let (remaining, (kind, _, response, _, labels)) = parser(input)?;
+
+    Ok((remaining, (kind, response, labels)))
+}
+
+impl<'a> EncodeIntoContext for GmailLabel<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        match self {
+            Self::System(atom) => {
+                ctx.write_all(b"\\")?;
+                atom.encode_ctx(ctx)
+            }
+            Self::Other(astring) => astring.encode_ctx(ctx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use imap_types::{
+        command::{Command, CommandBody},
+        core::{AString, Quoted, Vec1},
+        fetch::{Macro, MacroOrMessageDataItemNames},
+        response::{Data, Response},
+        search::SearchKey,
+    };
+
+    use super::*;
+    use crate::testing::{kat_inverse_command, kat_inverse_response};
+
+    #[test]
+    fn test_kat_inverse_command_gmail() {
+        kat_inverse_command(&[
+            (
+                b"A FETCH 1:* (X-GM-LABELS X-GM-MSGID X-GM-THRID)\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::fetch(
+                        "1:*",
+                        vec![
+                            MessageDataItemName::GmailLabels,
+                            MessageDataItemName::GmailMsgId,
+                            MessageDataItemName::GmailThrId,
+                        ],
+                        false,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A UID FETCH 1 (FLAGS X-GM-LABELS)\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::fetch(
+                        "1",
+                        MacroOrMessageDataItemNames::MessageDataItemNames(vec![
+                            MessageDataItemName::Flags,
+                            MessageDataItemName::GmailLabels,
+                        ]),
+                        true,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A FETCH 1 FAST\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new("A", CommandBody::fetch("1", Macro::Fast, false).unwrap()).unwrap(),
+            ),
+            (
+                b"A STORE 1 +X-GM-LABELS (\\Inbox \"Work\")\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::store_gmail_labels(
+                        "1",
+                        StoreType::Add,
+                        StoreResponse::Answer,
+                        vec![
+                            GmailLabel::try_from("\\Inbox").unwrap(),
+                            GmailLabel::Other(AString::from(Quoted::try_from("Work").unwrap())),
+                        ],
+                        false,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A UID STORE 1:2 -X-GM-LABELS.SILENT \\Starred\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::store_gmail_labels(
+                        "1:2",
+                        StoreType::Remove,
+                        StoreResponse::Silent,
+                        vec![GmailLabel::try_from("\\Starred").unwrap()],
+                        true,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A STORE 1 X-GM-LABELS ()\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::store_gmail_labels(
+                        "1",
+                        StoreType::Replace,
+                        StoreResponse::Answer,
+                        vec![],
+                        false,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A SEARCH X-GM-RAW \"has:attachment in:unread\"\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::search(
+                        None,
+                        Vec1::from(SearchKey::GmailRaw(
+                            AString::try_from("has:attachment in:unread").unwrap(),
+                        )),
+                        false,
+                    ),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_gmail() {
+        kat_inverse_response(&[
+            (
+                b"* 1 FETCH (X-GM-THRID 1278455344230334865 X-GM-LABELS (\\Inbox \"Work\"))\r\n"
+                    .as_ref(),
+                b"".as_ref(),
+                Response::Data(
+                    Data::fetch(
+                        1,
+                        vec![
+                            MessageDataItem::GmailThrId(1278455344230334865),
+                            MessageDataItem::GmailLabels(vec![
+                                GmailLabel::try_from("\\Inbox").unwrap(),
+                                GmailLabel::Other(AString::from(Quoted::try_from("Work").unwrap())),
+                            ]),
+                        ],
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"* 2 FETCH (UID 7 X-GM-MSGID 1278455344230334866 X-GM-LABELS ())\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Fetch {
+                    seq: NonZeroU32::new(2).unwrap(),
+                    items: Vec1::try_from(vec![
+                        MessageDataItem::Uid(NonZeroU32::new(7).unwrap()),
+                        MessageDataItem::GmailMsgId(1278455344230334866),
+                        MessageDataItem::GmailLabels(vec![]),
+                    ])
+                    .unwrap(),
+                }),
+            ),
+        ]);
+    }
+}
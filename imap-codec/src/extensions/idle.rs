@@ -2,6 +2,11 @@
 //!
 //! This extension enables the [`CommandBody::Idle`](crate::command::CommandBody#variant.Idle) variant.
 //! No additional types are used.
+//!
+//! The IDLE handshake is decoded with [`IdleAcceptCodec`](crate::IdleAcceptCodec) (server's `+ idling`)
+//! and [`IdleDoneCodec`](crate::IdleDoneCodec) (client's `DONE`).
+//! Any other server response, e.g., a tagged `NO`, must be decoded with
+//! [`ResponseCodec`](crate::ResponseCodec).
 
 // Additional changes:
 //
@@ -9,16 +14,16 @@
 
 use std::io::Write;
 
-#[cfg(not(feature = "quirk_crlf_relaxed"))]
-use abnf_core::streaming::crlf;
-#[cfg(feature = "quirk_crlf_relaxed")]
-use abnf_core::streaming::crlf_relaxed as crlf;
-use imap_types::{command::CommandBody, extensions::idle::IdleDone};
+use imap_types::{
+    command::CommandBody, extensions::idle::IdleDone, response::CommandContinuationRequest,
+};
 use nom::{bytes::streaming::tag_no_case, combinator::value, sequence::tuple};
 
 use crate::{
+    core::crlf,
     decode::IMAPResult,
     encode::{EncodeContext, EncodeIntoContext},
+    response::continue_req,
 };
 
 /// `idle = "IDLE" CRLF "DONE"` (edited)
@@ -54,6 +59,15 @@ pub(crate) fn idle_done(input: &[u8]) -> IMAPResult<&[u8], IdleDone> {
     value(IdleDone, tuple((tag_no_case("DONE"), crlf)))(input)
 }
 
+/// `continue-req = "+" SP (resp-text / base64) CRLF`
+///
+/// Note: This parser must be executed *instead* of the response parser
+/// when the client waits for the server to accept IDLE, e.g., `+ idling`.
+/// Any continuation request accepts IDLE, regardless of its text.
+pub(crate) fn idle_accept(input: &[u8]) -> IMAPResult<&[u8], CommandContinuationRequest> {
+    continue_req(false)(input)
+}
+
 impl EncodeIntoContext for IdleDone {
     fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
         ctx.write_all(b"DONE\r\n")
@@ -62,13 +76,18 @@ impl EncodeIntoContext for IdleDone {
 
 #[cfg(test)]
 mod tests {
-    use imap_types::command::{Command, CommandBody};
+    use imap_types::{
+        command::{Command, CommandBody},
+        core::Tag,
+        response::{Code, Data, Response, Status},
+    };
 
     use super::*;
     use crate::{
-        decode::{Decoder, IdleDoneDecodeError},
-        testing::kat_inverse_command,
-        IdleDoneCodec,
+        decode::{Decoder, IdleAcceptDecodeError, IdleDoneDecodeError},
+        encode::Encoder,
+        testing::{kat_inverse_command, kat_inverse_idle_accept},
+        IdleAcceptCodec, IdleDoneCodec, ResponseCodec,
     };
 
     #[test]
@@ -114,4 +133,100 @@ mod tests {
             assert_eq!(expected, got);
         }
     }
+
+    #[test]
+    fn test_kat_inverse_idle_accept() {
+        kat_inverse_idle_accept(&[
+            (
+                b"+ idling\r\n".as_ref(),
+                b"".as_ref(),
+                CommandContinuationRequest::idling(),
+            ),
+            (
+                b"+ [ALERT] idling\r\n?",
+                b"?",
+                CommandContinuationRequest::basic(Some(Code::Alert), "idling").unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_encode_idle_accept() {
+        assert_eq!(
+            IdleAcceptCodec::default()
+                .encode(&CommandContinuationRequest::idling())
+                .dump(),
+            b"+ idling\r\n"
+        );
+    }
+
+    #[test]
+    fn test_decode_idle_accept() {
+        let tests = [
+            // Ok
+            (
+                b"+ idling\r\n".as_ref(),
+                Ok((b"".as_ref(), CommandContinuationRequest::idling())),
+            ),
+            (
+                b"+ Waiting for DONE\r\n".as_ref(),
+                Ok((
+                    b"".as_ref(),
+                    CommandContinuationRequest::basic(None, "Waiting for DONE").unwrap(),
+                )),
+            ),
+            // Incomplete
+            (b"+".as_ref(), Err(IdleAcceptDecodeError::Incomplete)),
+            (b"+ idling".as_ref(), Err(IdleAcceptDecodeError::Incomplete)),
+            (
+                b"+ idling\r".as_ref(),
+                Err(IdleAcceptDecodeError::Incomplete),
+            ),
+            // Failed
+            (
+                b"A NO idle\r\n".as_ref(),
+                Err(IdleAcceptDecodeError::Failed),
+            ),
+            (
+                b"* OK idle\r\n".as_ref(),
+                Err(IdleAcceptDecodeError::Failed),
+            ),
+        ];
+
+        for (test, expected) in tests {
+            let got = IdleAcceptCodec::default().decode(test);
+
+            dbg!((std::str::from_utf8(test).unwrap(), &expected, &got));
+
+            assert_eq!(expected, got);
+        }
+    }
+
+    #[test]
+    fn test_decode_idle_accept_fallback() {
+        let tests = [
+            (
+                b"A NO idle\r\n".as_ref(),
+                Response::Status(
+                    Status::no(Some(Tag::try_from("A").unwrap()), None, "idle").unwrap(),
+                ),
+            ),
+            (
+                b"* OK idle\r\n".as_ref(),
+                Response::Status(Status::ok(None, None, "idle").unwrap()),
+            ),
+            (b"* 3 EXISTS\r\n".as_ref(), Response::Data(Data::Exists(3))),
+        ];
+
+        for (test, expected) in tests {
+            assert_eq!(
+                IdleAcceptCodec::default().decode(test),
+                Err(IdleAcceptDecodeError::Failed)
+            );
+
+            let (rem, got) = ResponseCodec::default().decode(test).unwrap();
+            assert!(rem.is_empty());
+            assert_eq!(expected, got);
+        }
+    }
 }
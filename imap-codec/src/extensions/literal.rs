@@ -2,11 +2,10 @@
 mod tests {
     use imap_types::{
         command::{Command, CommandBody},
-        core::{Literal, Vec1},
-        response::{Capability, Code, Greeting},
+        core::Literal,
     };
 
-    use crate::testing::{kat_inverse_command, kat_inverse_greeting};
+    use crate::testing::kat_inverse_command;
 
     #[test]
     fn test_kat_inverse_command_login_literal_plus() {
@@ -66,14 +65,24 @@ mod tests {
         ]);
     }
 
+    #[cfg(feature = "quirk_missing_imap4rev1")]
     #[test]
     fn test_kat_inverse_greeting_capability_literal_plus() {
+        use imap_types::{
+            core::Vec1,
+            response::{Capabilities, Capability, Code, Greeting},
+        };
+
+        use crate::testing::kat_inverse_greeting;
+
         kat_inverse_greeting(&[
             (
                 b"* OK [CAPABILITY LITERAL+] ...\r\n".as_ref(),
                 b"".as_ref(),
                 Greeting::ok(
-                    Some(Code::Capability(Vec1::from(Capability::LiteralPlus))),
+                    Some(Code::Capability(Capabilities::unvalidated(Vec1::from(
+                        Capability::LiteralPlus,
+                    )))),
                     "...",
                 )
                 .unwrap(),
@@ -82,11 +91,30 @@ mod tests {
                 b"* OK [CAPABILITY LITERAL-] ...\r\n?",
                 b"?",
                 Greeting::ok(
-                    Some(Code::Capability(Vec1::from(Capability::LiteralMinus))),
+                    Some(Code::Capability(Capabilities::unvalidated(Vec1::from(
+                        Capability::LiteralMinus,
+                    )))),
                     "...",
                 )
                 .unwrap(),
             ),
         ]);
     }
+
+    #[cfg(not(feature = "quirk_missing_imap4rev1"))]
+    #[test]
+    fn test_decode_greeting_capability_literal_plus_without_imap4rev1() {
+        use imap_types::response::Code;
+
+        use crate::{decode::Decoder, GreetingCodec};
+
+        // Without `IMAP4rev1`, the code is not recognized as a CAPABILITY code.
+        for test in [
+            b"* OK [CAPABILITY LITERAL+] ...\r\n".as_ref(),
+            b"* OK [CAPABILITY LITERAL-] ...\r\n",
+        ] {
+            let (_, greeting) = GreetingCodec::default().decode(test).unwrap();
+            assert!(matches!(greeting.code, Some(Code::Other(_))));
+        }
+    }
 }
@@ -0,0 +1,509 @@
+//! The IMAP METADATA Extension
+
+// Additional changes:
+//
+// command-auth   =/ setmetadata / getmetadata
+// response-data  =/ "*" SP metadata-resp CRLF
+// resp-text-code =/ "METADATA" SP ("LONGENTRIES" SP number /
+//                                  "MAXSIZE" SP number /
+//                                  "TOOMANY" / "NOPRIVATE")
+
+use std::io::Write;
+
+use abnf_core::streaming::sp;
+use imap_types::{
+    command::CommandBody,
+    core::Vec1,
+    extensions::metadata::{
+        Depth, EntryValue, GetMetadataOption, MetadataCode, MetadataEntry, MetadataResponse,
+    },
+    response::Data,
+};
+use nom::{
+    branch::alt,
+    bytes::streaming::{tag, tag_no_case},
+    combinator::{map, map_opt, opt, value},
+    multi::separated_list1,
+    sequence::{delimited, preceded, separated_pair, terminated, tuple},
+};
+
+use crate::{
+    core::{astring, nstring, number},
+    decode::IMAPResult,
+    encode::{utils::join_serializable, EncodeContext, EncodeIntoContext},
+    mailbox::mailbox,
+};
+
+/// ```abnf
+/// getmetadata = "GETMETADATA" [SP getmetadata-options] SP mailbox SP entries
+///
+/// getmetadata-options = "(" getmetadata-option *(SP getmetadata-option) ")"
+/// ```
+pub(crate) fn getmetadata(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = tuple((
+        tag_no_case("GETMETADATA "),
+        opt(terminated(
+            delimited(tag("("), separated_list1(sp, getmetadata_option), tag(")")),
+            sp,
+        )),
+        mailbox,
+        sp,
+        entries,
+    ));
+
+    let (remaining, (_, options, mailbox, _, entries)) = parser(input)?;
+
+    Ok((
+        remaining,
+        CommandBody::GetMetadata {
+            options: options.unwrap_or_default(),
+            mailbox,
+            entries,
+        },
+    ))
+}
+
+/// ```abnf
+/// getmetadata-option = "MAXSIZE" SP number / "DEPTH" SP ("0" / "1" / "infinity")
+/// ```
+pub(crate) fn getmetadata_option(input: &[u8]) -> IMAPResult<&[u8], GetMetadataOption> {
+    alt((
+        map(
+            preceded(tag_no_case("MAXSIZE "), number),
+            GetMetadataOption::MaxSize,
+        ),
+        map(
+            preceded(
+                tag_no_case("DEPTH "),
+                alt((
+                    value(Depth::Zero, tag("0")),
+                    value(Depth::One, tag("1")),
+                    value(Depth::Infinity, tag_no_case("infinity")),
+                )),
+            ),
+            GetMetadataOption::Depth,
+        ),
+    ))(input)
+}
+
+/// ```abnf
+/// entries = entry / "(" entry *(SP entry) ")"
+/// ```
+pub(crate) fn entries(input: &[u8]) -> IMAPResult<&[u8], Vec1<MetadataEntry>> {
+    alt((
+        map(
+            delimited(tag("("), separated_list1(sp, entry), tag(")")),
+            Vec1::unvalidated,
+        ),
+        map(entry, Vec1::from),
+    ))(input)
+}
+
+/// ```abnf
+/// entry = astring
+/// ```
+///
+/// Note: Slash-separated path that must start with `/shared` or `/private`.
+pub(crate) fn entry(input: &[u8]) -> IMAPResult<&[u8], MetadataEntry> {
+    map_opt(astring, |astring| MetadataEntry::try_from(astring).ok())(input)
+}
+
+/// ```abnf
+/// setmetadata = "SETMETADATA" SP mailbox SP entry-values
+/// ```
+pub(crate) fn setmetadata(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = tuple((tag_no_case("SETMETADATA "), mailbox, sp, entry_values));
+
+    let (remaining, (_, mailbox, _, entry_values)) = parser(input)?;
+
+    Ok((
+        remaining,
+        CommandBody::SetMetadata {
+            mailbox,
+            entry_values,
+        },
+    ))
+}
+
+/// ```abnf
+/// entry-values = "(" entry-value *(SP entry-value) ")"
+///
+/// entry-value = entry SP value
+///
+/// value = nstring / literal8
+/// ```
+///
+/// Note: `literal8` is not supported (yet).
+pub(crate) fn entry_values(input: &[u8]) -> IMAPResult<&[u8], Vec1<EntryValue>> {
+    map(
+        delimited(
+            tag("("),
+            separated_list1(
+                sp,
+                map(separated_pair(entry, sp, nstring), |(entry, value)| {
+                    EntryValue { entry, value }
+                }),
+            ),
+            tag(")"),
+        ),
+        Vec1::unvalidated,
+    )(input)
+}
+
+/// ```abnf
+/// metadata-resp = "METADATA" SP mailbox SP (entry-values / entry-list)
+///
+/// entry-list = entry *(SP entry)
+/// ```
+pub(crate) fn metadata_resp(input: &[u8]) -> IMAPResult<&[u8], Data> {
+    let mut parser = tuple((
+        tag_no_case("METADATA "),
+        mailbox,
+        sp,
+        alt((
+            map(entry_values, MetadataResponse::WithValues),
+            map(separated_list1(sp, entry), |entries| {
+                MetadataResponse::WithoutValues(Vec1::unvalidated(entries))
+            }),
+        )),
+    ));
+
+    let (remaining, (_, mailbox, _, items)) = parser(input)?;
+
+    Ok((remaining, Data::Metadata { mailbox, items }))
+}
+
+/// ```abnf
+/// "METADATA" SP ("LONGENTRIES" SP number /
+///                "MAXSIZE" SP number /
+///                "TOOMANY" /
+///                "NOPRIVATE")
+/// ```
+pub(crate) fn metadata_code(input: &[u8]) -> IMAPResult<&[u8], MetadataCode> {
+    preceded(
+        tag_no_case("METADATA "),
+        alt((
+            map(
+                preceded(tag_no_case("LONGENTRIES "), number),
+                MetadataCode::LongEntries,
+            ),
+            map(
+                preceded(tag_no_case("MAXSIZE "), number),
+                MetadataCode::MaxSize,
+            ),
+            value(MetadataCode::TooMany, tag_no_case("TOOMANY")),
+            value(MetadataCode::NoPrivate, tag_no_case("NOPRIVATE")),
+        )),
+    )(input)
+}
+
+impl EncodeIntoContext for GetMetadataOption {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        match self {
+            Self::MaxSize(size) => write!(ctx, "MAXSIZE {size}"),
+            Self::Depth(depth) => write!(ctx, "DEPTH {depth}"),
+        }
+    }
+}
+
+impl<'a> EncodeIntoContext for MetadataEntry<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        self.inner().encode_ctx(ctx)
+    }
+}
+
+impl<'a> EncodeIntoContext for Vec1<MetadataEntry<'a>> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        match self.as_ref() {
+            [entry] => entry.encode_ctx(ctx),
+            entries => {
+                ctx.write_all(b"(")?;
+                join_serializable(entries, b" ", ctx)?;
+                ctx.write_all(b")")
+            }
+        }
+    }
+}
+
+impl<'a> EncodeIntoContext for EntryValue<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        self.entry.encode_ctx(ctx)?;
+        ctx.write_all(b" ")?;
+        self.value.encode_ctx(ctx)
+    }
+}
+
+impl<'a> EncodeIntoContext for Vec1<EntryValue<'a>> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        ctx.write_all(b"(")?;
+        join_serializable(self.as_ref(), b" ", ctx)?;
+        ctx.write_all(b")")
+    }
+}
+
+impl<'a> EncodeIntoContext for MetadataResponse<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        match self {
+            Self::WithValues(entry_values) => entry_values.encode_ctx(ctx),
+            Self::WithoutValues(entries) => join_serializable(entries.as_ref(), b" ", ctx),
+        }
+    }
+}
+
+impl EncodeIntoContext for MetadataCode {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        ctx.write_all(b"METADATA ")?;
+
+        match self {
+            Self::LongEntries(size) => write!(ctx, "LONGENTRIES {size}"),
+            Self::MaxSize(size) => write!(ctx, "MAXSIZE {size}"),
+            Self::TooMany => ctx.write_all(b"TOOMANY"),
+            Self::NoPrivate => ctx.write_all(b"NOPRIVATE"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imap_types::{
+        command::Command,
+        core::NString,
+        response::{Code, Response, Status},
+    };
+
+    use super::*;
+    use crate::testing::{kat_inverse_command, kat_inverse_response};
+
+    fn entry_value(entry: &'static str, value: Option<&'static str>) -> EntryValue<'static> {
+        EntryValue {
+            entry: MetadataEntry::try_from(entry).unwrap(),
+            value: NString(value.map(|value| value.try_into().unwrap())),
+        }
+    }
+
+    #[test]
+    fn test_kat_inverse_command_getmetadata() {
+        kat_inverse_command(&[
+            (
+                b"A GETMETADATA \"\" /shared/comment\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::get_metadata(
+                        vec![],
+                        "",
+                        MetadataEntry::try_from("/shared/comment").unwrap(),
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A GETMETADATA (DEPTH infinity MAXSIZE 1024) INBOX (/shared/comment /private/comment)\r\n?",
+                b"?",
+                Command::new(
+                    "A",
+                    CommandBody::get_metadata(
+                        vec![
+                            GetMetadataOption::Depth(Depth::Infinity),
+                            GetMetadataOption::MaxSize(1024),
+                        ],
+                        "INBOX",
+                        vec![
+                            MetadataEntry::try_from("/shared/comment").unwrap(),
+                            MetadataEntry::try_from("/private/comment").unwrap(),
+                        ],
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A GETMETADATA (MAXSIZE 1024) INBOX /private/comment\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::get_metadata(
+                        vec![GetMetadataOption::MaxSize(1024)],
+                        "INBOX",
+                        MetadataEntry::try_from("/private/comment").unwrap(),
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A GETMETADATA (DEPTH 0) INBOX /private/vendor\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::get_metadata(
+                        vec![GetMetadataOption::Depth(Depth::Zero)],
+                        "INBOX",
+                        MetadataEntry::try_from("/private/vendor").unwrap(),
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A GETMETADATA (DEPTH 1) INBOX /private/vendor\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::get_metadata(
+                        vec![GetMetadataOption::Depth(Depth::One)],
+                        "INBOX",
+                        MetadataEntry::try_from("/private/vendor").unwrap(),
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_command_setmetadata() {
+        kat_inverse_command(&[
+            (
+                b"A SETMETADATA INBOX (/private/comment \"My comment\")\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::set_metadata(
+                        "INBOX",
+                        vec![entry_value("/private/comment", Some("My comment"))],
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A SETMETADATA \"\" (/shared/comment NIL /shared/admin \"mailto:admin@example.org\")\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::set_metadata(
+                        "",
+                        vec![
+                            entry_value("/shared/comment", None),
+                            entry_value("/shared/admin", Some("mailto:admin@example.org")),
+                        ],
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_getmetadata_invalid_entry() {
+        assert!(getmetadata(b"GETMETADATA INBOX /public/comment\r\n").is_err());
+        assert!(getmetadata(b"GETMETADATA (DEPTH 2) INBOX /shared/comment\r\n").is_err());
+    }
+
+    #[test]
+    fn test_kat_inverse_response_metadata() {
+        kat_inverse_response(&[
+            (
+                b"* METADATA INBOX (/private/comment \"My comment\" /shared/comment NIL)\r\n"
+                    .as_ref(),
+                b"".as_ref(),
+                Response::Data(
+                    Data::metadata(
+                        "INBOX",
+                        MetadataResponse::WithValues(
+                            vec![
+                                entry_value("/private/comment", Some("My comment")),
+                                entry_value("/shared/comment", None),
+                            ]
+                            .try_into()
+                            .unwrap(),
+                        ),
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"* METADATA \"\" /shared/comment /shared/admin\r\n",
+                b"",
+                Response::Data(
+                    Data::metadata(
+                        "",
+                        MetadataResponse::WithoutValues(
+                            vec![
+                                MetadataEntry::try_from("/shared/comment").unwrap(),
+                                MetadataEntry::try_from("/shared/admin").unwrap(),
+                            ]
+                            .try_into()
+                            .unwrap(),
+                        ),
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"A OK [METADATA LONGENTRIES 2199] GETMETADATA complete\r\n",
+                b"",
+                Response::Status(
+                    Status::ok(
+                        Some("A".try_into().unwrap()),
+                        Some(Code::Metadata(MetadataCode::LongEntries(2199))),
+                        "GETMETADATA complete",
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"A OK [METADATA LONGENTRIES 2048] GETMETADATA complete\r\n",
+                b"",
+                Response::Status(
+                    Status::ok(
+                        Some("A".try_into().unwrap()),
+                        Some(Code::Metadata(MetadataCode::LongEntries(2048))),
+                        "GETMETADATA complete",
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"A NO [METADATA MAXSIZE 1024] Annotation too large\r\n",
+                b"",
+                Response::Status(
+                    Status::no(
+                        Some("A".try_into().unwrap()),
+                        Some(Code::Metadata(MetadataCode::MaxSize(1024))),
+                        "Annotation too large",
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"A NO [METADATA TOOMANY] Too many annotations\r\n",
+                b"",
+                Response::Status(
+                    Status::no(
+                        Some("A".try_into().unwrap()),
+                        Some(Code::Metadata(MetadataCode::TooMany)),
+                        "Too many annotations",
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"A NO [METADATA NOPRIVATE] Private annotations not supported\r\n",
+                b"",
+                Response::Status(
+                    Status::no(
+                        Some("A".try_into().unwrap()),
+                        Some(Code::Metadata(MetadataCode::NoPrivate)),
+                        "Private annotations not supported",
+                    )
+                    .unwrap(),
+                ),
+            ),
+        ]);
+    }
+}
@@ -1,32 +1,34 @@
 //! IMAP - MOVE Extension
 
-use abnf_core::streaming::sp;
-use imap_types::command::CommandBody;
-use nom::{bytes::streaming::tag_no_case, sequence::tuple};
+use imap_types::{command::CommandBody, sequence::SeqNoOrUidSet};
+use nom::{bytes::streaming::tag_no_case, sequence::preceded};
 
-use crate::{decode::IMAPResult, mailbox::mailbox, sequence::sequence_set};
+use crate::{command::copy_args, decode::IMAPResult};
 
 /// ```abnf
 /// move = "MOVE" SP sequence-set SP mailbox
 /// ```
 pub(crate) fn r#move(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
-    let mut parser = tuple((tag_no_case(b"MOVE"), sp, sequence_set, sp, mailbox));
+    let mut parser = preceded(tag_no_case(b"MOVE "), copy_args);
 
-    let (remaining, (_, _, sequence_set, _, mailbox)) = parser(input)?;
+    let (remaining, (sequence_set, mailbox)) = parser(input)?;
 
     Ok((
         remaining,
         CommandBody::Move {
-            sequence_set,
+            sequence_set: SeqNoOrUidSet::new(sequence_set, false),
             mailbox,
-            uid: false,
         },
     ))
 }
 
 #[cfg(test)]
 mod tests {
-    use imap_types::command::{Command, CommandBody};
+    use imap_types::{
+        command::{Command, CommandBody},
+        core::{AString, IString, Quoted},
+        mailbox::Mailbox,
+    };
 
     use crate::testing::kat_inverse_command;
 
@@ -48,6 +50,38 @@ mod tests {
                 b"??",
                 Command::new("A", CommandBody::r#move("1:*", "test", false).unwrap()).unwrap(),
             ),
+            (
+                b"A UID MOVE 1:5 \"Archive\"\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::r#move(
+                        "1:5",
+                        Mailbox::from(AString::String(IString::Quoted(
+                            Quoted::try_from("Archive").unwrap(),
+                        ))),
+                        true,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A UID COPY 1:5 \"Archive\"\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::copy(
+                        "1:5",
+                        Mailbox::from(AString::String(IString::Quoted(
+                            Quoted::try_from("Archive").unwrap(),
+                        ))),
+                        true,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
         ]);
     }
 }
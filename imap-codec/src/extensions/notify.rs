@@ -0,0 +1,387 @@
+//! The IMAP NOTIFY Extension
+
+// Additional changes:
+//
+// command-auth   =/ notify
+// resp-text-code =/ "NOTIFICATIONOVERFLOW" / unsupported-events-code
+
+use std::io::Write;
+
+use abnf_core::streaming::sp;
+use imap_types::{
+    command::CommandBody,
+    core::Vec1,
+    extensions::notify::{Event, EventGroup, FilterMailboxes, NotifySet},
+    mailbox::Mailbox,
+};
+use nom::{
+    branch::alt,
+    bytes::streaming::{tag, tag_no_case},
+    combinator::{map, opt, value},
+    multi::separated_list1,
+    sequence::{delimited, preceded, tuple},
+};
+
+use crate::{
+    core::atom,
+    decode::IMAPResult,
+    encode::{utils::join_serializable, EncodeContext, EncodeIntoContext},
+    fetch::fetch_att,
+    mailbox::mailbox,
+};
+
+/// ```abnf
+/// notify = "NOTIFY" SP (notify-set / notify-none)
+///
+/// notify-set = "SET" [status-indicator] SP event-groups
+///
+/// status-indicator = SP "STATUS"
+///
+/// notify-none = "NONE"
+/// ```
+pub(crate) fn notify(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = preceded(
+        tag_no_case("NOTIFY "),
+        alt((
+            map(
+                tuple((
+                    tag_no_case("SET"),
+                    map(opt(tag_no_case(" STATUS")), |status| status.is_some()),
+                    sp,
+                    separated_list1(sp, event_group),
+                )),
+                |(_, status, _, event_groups)| {
+                    Some(NotifySet {
+                        status,
+                        event_groups: Vec1::unvalidated(event_groups),
+                    })
+                },
+            ),
+            value(None, tag_no_case("NONE")),
+        )),
+    );
+
+    let (remaining, set) = parser(input)?;
+
+    Ok((remaining, CommandBody::Notify { set }))
+}
+
+/// ```abnf
+/// event-group = "(" filter-mailboxes SP events ")"
+///
+/// events = ( "(" event *(SP event) ")" ) / "NONE"
+/// ```
+pub(crate) fn event_group(input: &[u8]) -> IMAPResult<&[u8], EventGroup> {
+    let mut parser = delimited(
+        tag("("),
+        tuple((
+            filter_mailboxes,
+            sp,
+            alt((map(events, Some), value(None, tag_no_case("NONE")))),
+        )),
+        tag(")"),
+    );
+
+    let (remaining, (filter, _, events)) = parser(input)?;
+
+    Ok((remaining, EventGroup { filter, events }))
+}
+
+/// ```abnf
+/// filter-mailboxes = filter-mailboxes-selected / filter-mailboxes-other
+///
+/// filter-mailboxes-selected = "selected" / "selected-delayed"
+///
+/// filter-mailboxes-other = "inboxes" / "personal" / "subscribed" /
+///                          ( "subtree" SP one-or-more-mailbox ) /
+///                          ( "mailboxes" SP one-or-more-mailbox )
+/// ```
+pub(crate) fn filter_mailboxes(input: &[u8]) -> IMAPResult<&[u8], FilterMailboxes> {
+    alt((
+        value(
+            FilterMailboxes::SelectedDelayed,
+            tag_no_case("SELECTED-DELAYED"),
+        ),
+        value(FilterMailboxes::Selected, tag_no_case("SELECTED")),
+        value(FilterMailboxes::Inboxes, tag_no_case("INBOXES")),
+        value(FilterMailboxes::Personal, tag_no_case("PERSONAL")),
+        value(FilterMailboxes::Subscribed, tag_no_case("SUBSCRIBED")),
+        map(
+            preceded(tag_no_case("SUBTREE "), one_or_more_mailbox),
+            FilterMailboxes::Subtree,
+        ),
+        map(
+            preceded(tag_no_case("MAILBOXES "), one_or_more_mailbox),
+            FilterMailboxes::Mailboxes,
+        ),
+    ))(input)
+}
+
+/// ```abnf
+/// one-or-more-mailbox = mailbox / many-mailboxes
+///
+/// many-mailboxes = "(" mailbox *(SP mailbox) ")"
+/// ```
+pub(crate) fn one_or_more_mailbox(input: &[u8]) -> IMAPResult<&[u8], Vec1<Mailbox>> {
+    alt((
+        map(
+            delimited(tag("("), separated_list1(sp, mailbox), tag(")")),
+            Vec1::unvalidated,
+        ),
+        map(mailbox, Vec1::from),
+    ))(input)
+}
+
+/// `"(" event *(SP event) ")"`
+pub(crate) fn events(input: &[u8]) -> IMAPResult<&[u8], Vec1<Event>> {
+    map(
+        delimited(tag("("), separated_list1(sp, event), tag(")")),
+        Vec1::unvalidated,
+    )(input)
+}
+
+/// ```abnf
+/// event = message-event / mailbox-event / event-ext
+///
+/// message-event = ( "MessageNew" [SP "(" fetch-att *(SP fetch-att) ")" ] ) /
+///                 "MessageExpunge" / "FlagChange" / "AnnotationChange"
+///
+/// mailbox-event = "MailboxName" / "SubscriptionChange" /
+///                 "MailboxMetadataChange" / "ServerMetadataChange"
+///
+/// event-ext = atom
+/// ```
+pub(crate) fn event(input: &[u8]) -> IMAPResult<&[u8], Event> {
+    let (remaining, event) = map(atom, Event::from)(input)?;
+
+    match event {
+        Event::MessageNew { .. } => map(
+            opt(preceded(
+                sp,
+                delimited(tag("("), separated_list1(sp, fetch_att), tag(")")),
+            )),
+            |fetch_attributes| Event::MessageNew {
+                fetch_attributes: fetch_attributes.map(Vec1::unvalidated),
+            },
+        )(remaining),
+        _ => Ok((remaining, event)),
+    }
+}
+
+impl<'a> EncodeIntoContext for NotifySet<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        ctx.write_all(b"SET")?;
+
+        if self.status {
+            ctx.write_all(b" STATUS")?;
+        }
+
+        ctx.write_all(b" ")?;
+        join_serializable(self.event_groups.as_ref(), b" ", ctx)
+    }
+}
+
+impl<'a> EncodeIntoContext for EventGroup<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        ctx.write_all(b"(")?;
+        self.filter.encode_ctx(ctx)?;
+        ctx.write_all(b" ")?;
+
+        match &self.events {
+            Some(events) => {
+                ctx.write_all(b"(")?;
+                join_serializable(events.as_ref(), b" ", ctx)?;
+                ctx.write_all(b")")?;
+            }
+            None => ctx.write_all(b"NONE")?,
+        }
+
+        ctx.write_all(b")")
+    }
+}
+
+impl<'a> EncodeIntoContext for FilterMailboxes<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        let (prefix, mailboxes) = match self {
+            Self::Selected => return ctx.write_all(b"SELECTED"),
+            Self::SelectedDelayed => return ctx.write_all(b"SELECTED-DELAYED"),
+            Self::Inboxes => return ctx.write_all(b"INBOXES"),
+            Self::Personal => return ctx.write_all(b"PERSONAL"),
+            Self::Subscribed => return ctx.write_all(b"SUBSCRIBED"),
+            Self::Subtree(mailboxes) => (b"SUBTREE ".as_ref(), mailboxes),
+            Self::Mailboxes(mailboxes) => (b"MAILBOXES ".as_ref(), mailboxes),
+        };
+
+        ctx.write_all(prefix)?;
+
+        match mailboxes.as_ref() {
+            [mailbox] => mailbox.encode_ctx(ctx),
+            mailboxes => {
+                ctx.write_all(b"(")?;
+                join_serializable(mailboxes, b" ", ctx)?;
+                ctx.write_all(b")")
+            }
+        }
+    }
+}
+
+impl<'a> EncodeIntoContext for Event<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        ctx.write_all(self.name().as_bytes())?;
+
+        if let Event::MessageNew {
+            fetch_attributes: Some(fetch_attributes),
+        } = self
+        {
+            ctx.write_all(b" (")?;
+            join_serializable(fetch_attributes.as_ref(), b" ", ctx)?;
+            ctx.write_all(b")")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imap_types::{
+        command::{Command, CommandBody},
+        core::Atom,
+        fetch::MessageDataItemName,
+        response::{Code, Response, Status},
+    };
+
+    use super::*;
+    use crate::testing::{kat_inverse_command, kat_inverse_response};
+
+    #[test]
+    fn test_kat_inverse_command_notify() {
+        kat_inverse_command(&[
+            (
+                b"A NOTIFY SET (SELECTED (MessageNew MessageExpunge))\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::notify_set(
+                        false,
+                        vec![EventGroup {
+                            filter: FilterMailboxes::Selected,
+                            events: Some(
+                                vec![
+                                    Event::MessageNew {
+                                        fetch_attributes: None,
+                                    },
+                                    Event::MessageExpunge,
+                                ]
+                                .try_into()
+                                .unwrap(),
+                            ),
+                        }],
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A NOTIFY NONE\r\n?",
+                b"?",
+                Command::new("A", CommandBody::notify_none()).unwrap(),
+            ),
+            (
+                b"A NOTIFY SET STATUS (SELECTED-DELAYED (MessageNew (UID FLAGS) FlagChange)) (SUBTREE (Lists Archive) (MailboxName)) (MAILBOXES INBOX NONE) (PERSONAL (SubscriptionChange X-Foo))\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::notify_set(
+                        true,
+                        vec![
+                            EventGroup {
+                                filter: FilterMailboxes::SelectedDelayed,
+                                events: Some(
+                                    vec![
+                                        Event::MessageNew {
+                                            fetch_attributes: Some(
+                                                vec![
+                                                    MessageDataItemName::Uid,
+                                                    MessageDataItemName::Flags,
+                                                ]
+                                                .try_into()
+                                                .unwrap(),
+                                            ),
+                                        },
+                                        Event::FlagChange,
+                                    ]
+                                    .try_into()
+                                    .unwrap(),
+                                ),
+                            },
+                            EventGroup {
+                                filter: FilterMailboxes::Subtree(
+                                    vec![
+                                        Mailbox::try_from("Lists").unwrap(),
+                                        Mailbox::try_from("Archive").unwrap(),
+                                    ]
+                                    .try_into()
+                                    .unwrap(),
+                                ),
+                                events: Some(Vec1::from(Event::MailboxName)),
+                            },
+                            EventGroup {
+                                filter: FilterMailboxes::Mailboxes(Vec1::from(Mailbox::Inbox)),
+                                events: None,
+                            },
+                            EventGroup {
+                                filter: FilterMailboxes::Personal,
+                                events: Some(
+                                    vec![
+                                        Event::SubscriptionChange,
+                                        Event::from(Atom::try_from("X-Foo").unwrap()),
+                                    ]
+                                    .try_into()
+                                    .unwrap(),
+                                ),
+                            },
+                        ],
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_notify() {
+        kat_inverse_response(&[
+            (
+                b"* OK [NOTIFICATIONOVERFLOW] ...\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Status(
+                    Status::ok(None, Some(Code::NotificationOverflow), "...").unwrap(),
+                ),
+            ),
+            (
+                b"A NO [BADEVENT (MessageNew MessageExpunge FlagChange)] ...\r\n",
+                b"",
+                Response::Status(
+                    Status::no(
+                        Some("A".try_into().unwrap()),
+                        Some(Code::BadEvent(
+                            vec![
+                                Event::MessageNew {
+                                    fetch_attributes: None,
+                                },
+                                Event::MessageExpunge,
+                                Event::FlagChange,
+                            ]
+                            .try_into()
+                            .unwrap(),
+                        )),
+                        "...",
+                    )
+                    .unwrap(),
+                ),
+            ),
+        ]);
+    }
+}
@@ -218,7 +218,7 @@ mod tests {
         core::{IString, Tag},
         extensions::quota::{QuotaGet, QuotaSet, Resource},
         mailbox::Mailbox,
-        response::{Capability, Code, Response, Status},
+        response::{Code, Response, Status},
         status::{StatusDataItem, StatusDataItemName},
     };
 
@@ -417,6 +417,65 @@ mod tests {
         )]);
     }
 
+    #[cfg(feature = "quirk_missing_imap4rev1")]
+    #[test]
+    fn test_kat_inverse_response_data_capability_quota() {
+        use imap_types::response::{Capabilities, Capability};
+
+        kat_inverse_response(&[
+            (
+                b"* CAPABILITY QUOTA QUOTA=RES-STORAGE\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Capability(Capabilities::unvalidated(
+                    Vec1::try_from(vec![
+                        Capability::Quota,
+                        Capability::QuotaRes(Resource::Storage),
+                    ])
+                    .unwrap(),
+                ))),
+            ),
+            (
+                b"* CAPABILITY QUOTA QUOTA=RES-STORAGE QUOTA=RES-MESSAGE\r\n",
+                b"",
+                Response::Data(Data::Capability(Capabilities::unvalidated(
+                    Vec1::try_from(vec![
+                        Capability::Quota,
+                        Capability::QuotaRes(Resource::Storage),
+                        Capability::QuotaRes(Resource::Message),
+                    ])
+                    .unwrap(),
+                ))),
+            ),
+            (
+                b"* CAPABILITY QUOTA QUOTASET QUOTA=RES-STORAGE QUOTA=RES-MESSAGE\r\n",
+                b"",
+                Response::Data(Data::Capability(Capabilities::unvalidated(
+                    Vec1::try_from(vec![
+                        Capability::Quota,
+                        Capability::QuotaSet,
+                        Capability::QuotaRes(Resource::Storage),
+                        Capability::QuotaRes(Resource::Message),
+                    ])
+                    .unwrap(),
+                ))),
+            ),
+        ]);
+    }
+
+    #[cfg(not(feature = "quirk_missing_imap4rev1"))]
+    #[test]
+    fn test_decode_response_data_capability_quota_without_imap4rev1() {
+        use crate::{decode::Decoder, ResponseCodec};
+
+        for test in [
+            b"* CAPABILITY QUOTA QUOTA=RES-STORAGE\r\n".as_ref(),
+            b"* CAPABILITY QUOTA QUOTA=RES-STORAGE QUOTA=RES-MESSAGE\r\n",
+            b"* CAPABILITY QUOTA QUOTASET QUOTA=RES-STORAGE QUOTA=RES-MESSAGE\r\n",
+        ] {
+            assert!(ResponseCodec::default().decode(test).is_err());
+        }
+    }
+
     #[test]
     fn test_kat_inverse_response_data_quota() {
         kat_inverse_response(&[
@@ -451,42 +510,6 @@ mod tests {
                     .unwrap(),
                 ),
             ),
-            (
-                b"* CAPABILITY QUOTA QUOTA=RES-STORAGE\r\n",
-                b"",
-                Response::Data(
-                    Data::capability(vec![
-                        Capability::Quota,
-                        Capability::QuotaRes(Resource::Storage),
-                    ])
-                    .unwrap(),
-                ),
-            ),
-            (
-                b"* CAPABILITY QUOTA QUOTA=RES-STORAGE QUOTA=RES-MESSAGE\r\n",
-                b"",
-                Response::Data(
-                    Data::capability(vec![
-                        Capability::Quota,
-                        Capability::QuotaRes(Resource::Storage),
-                        Capability::QuotaRes(Resource::Message),
-                    ])
-                    .unwrap(),
-                ),
-            ),
-            (
-                b"* CAPABILITY QUOTA QUOTASET QUOTA=RES-STORAGE QUOTA=RES-MESSAGE\r\n",
-                b"",
-                Response::Data(
-                    Data::capability(vec![
-                        Capability::Quota,
-                        Capability::QuotaSet,
-                        Capability::QuotaRes(Resource::Storage),
-                        Capability::QuotaRes(Resource::Message),
-                    ])
-                    .unwrap(),
-                ),
-            ),
             (
                 b"* QUOTA \"!partition/sda4\" (STORAGE 104 10923847)\r\n",
                 b"",
@@ -0,0 +1,504 @@
+//! IMAP4 Login Referrals and IMAP4 Mailbox Referrals
+
+// Additional changes:
+//
+// resp-text-code =/ "REFERRAL" SP imapurl
+
+use std::{borrow::Cow, io::Write, str::from_utf8};
+
+use imap_types::{
+    auth::AuthMechanism,
+    extensions::referral::{ImapUrl, UrlAuth},
+};
+use nom::{
+    branch::alt,
+    bytes::streaming::{tag, tag_no_case, take_while1},
+    character::streaming::digit1,
+    combinator::{map, map_opt, map_res, opt, recognize},
+    sequence::{delimited, preceded, terminated, tuple},
+};
+
+#[cfg(feature = "ext_urlauth")]
+use crate::extensions::urlauth::{is_expire_char, urlauth_params};
+use crate::{
+    core::nz_number,
+    decode::IMAPResult,
+    encode::{EncodeContext, EncodeIntoContext},
+};
+
+/// ```abnf
+/// imapurl = "imap://" iserver "/" [ imailbox-ref [ iuid [ isection ] ] ]
+///
+/// iserver = [iuserinfo "@"] host [ ":" port ]
+///
+/// imailbox-ref = enc-mailbox [uidvalidity]
+///
+/// uidvalidity = ";UIDVALIDITY=" nz-number
+///
+/// iuid = "/;UID=" nz-number
+///
+/// isection = "/;SECTION=" enc-section
+/// ```
+///
+/// With the `ext_urlauth` feature, the URLAUTH parameters (see [`urlauth_params`]) are accepted, too.
+///
+/// Note: Search criteria and `;PARTIAL=` are not supported (yet).
+pub(crate) fn imap_url(input: &[u8]) -> IMAPResult<&[u8], ImapUrl> {
+    let mut parser = tuple((
+        tag_no_case(b"imap://"),
+        opt(terminated(iuserinfo, tag(b"@"))),
+        host,
+        opt(preceded(
+            tag(b":"),
+            map_res(
+                // # Safety
+                //
+                // `unwrap` is safe because `1*DIGIT` contains ASCII-only characters.
+                map(digit1, |val| from_utf8(val).unwrap()),
+                str::parse::<u16>,
+            ),
+        )),
+        tag(b"/"),
+        opt(tuple((
+            map_opt(enc_mailbox, percent_decode),
+            opt(preceded(tag_no_case(b";UIDVALIDITY="), nz_number)),
+            opt(tuple((
+                preceded(tag_no_case(b"/;UID="), nz_number),
+                opt(preceded(
+                    tag_no_case(b"/;SECTION="),
+                    map_opt(take_while1(is_bchar), percent_decode),
+                )),
+            ))),
+        ))),
+    ));
+
+    let (remaining, (_, userinfo, host, port, _, path)) = parser(input)?;
+
+    #[cfg(feature = "ext_urlauth")]
+    let (remaining, (expire, access, authorization)) = urlauth_params(remaining)?;
+
+    let (user, auth) = userinfo.unwrap_or_default();
+    let (mailbox, uid_validity, uid, section) = match path {
+        Some((mailbox, uid_validity, Some((uid, section)))) => {
+            (Some(mailbox), uid_validity, Some(uid), section)
+        }
+        Some((mailbox, uid_validity, None)) => (Some(mailbox), uid_validity, None, None),
+        None => (None, None, None, None),
+    };
+
+    Ok((
+        remaining,
+        ImapUrl {
+            user,
+            auth,
+            host,
+            port,
+            mailbox,
+            uid_validity,
+            uid,
+            section,
+            #[cfg(feature = "ext_urlauth")]
+            expire,
+            #[cfg(feature = "ext_urlauth")]
+            access,
+            #[cfg(feature = "ext_urlauth")]
+            authorization,
+        },
+    ))
+}
+
+/// ```abnf
+/// iuserinfo = enc-user [iauth] / [enc-user] iauth
+///
+/// enc-user = 1*achar
+/// ```
+#[allow(clippy::type_complexity)]
+fn iuserinfo(input: &[u8]) -> IMAPResult<&[u8], (Option<Cow<str>>, Option<UrlAuth>)> {
+    alt((
+        map(
+            tuple((map_opt(take_while1(is_achar), percent_decode), opt(iauth))),
+            |(user, auth)| (Some(user), auth),
+        ),
+        map(iauth, |auth| (None, Some(auth))),
+    ))(input)
+}
+
+/// ```abnf
+/// iauth = ";AUTH=" ( "*" / enc-auth-type )
+///
+/// enc-auth-type = 1*achar
+/// ```
+fn iauth(input: &[u8]) -> IMAPResult<&[u8], UrlAuth> {
+    preceded(
+        tag_no_case(b";AUTH="),
+        map_opt(take_while1(is_achar), |val: &[u8]| match val {
+            b"*" => Some(UrlAuth::Any),
+            _ => match percent_decode(val)? {
+                Cow::Borrowed(val) => AuthMechanism::try_from(val).ok(),
+                Cow::Owned(val) => AuthMechanism::try_from(val).ok(),
+            }
+            .map(UrlAuth::Mechanism),
+        }),
+    )(input)
+}
+
+/// ```abnf
+/// host = IP-literal / IPv4address / reg-name
+/// ```
+///
+/// Note: The host is not validated further.
+fn host(input: &[u8]) -> IMAPResult<&[u8], Cow<str>> {
+    alt((
+        map(
+            recognize(delimited(
+                tag(b"["),
+                take_while1(is_ip_literal_char),
+                tag(b"]"),
+            )),
+            // Safety: `is_ip_literal_char` only accepts ASCII characters.
+            |val| Cow::Borrowed(from_utf8(val).unwrap()),
+        ),
+        map_opt(take_while1(is_reg_name_char), percent_decode),
+    ))(input)
+}
+
+/// ```abnf
+/// enc-mailbox = 1*bchar
+/// ```
+///
+/// Note: A trailing "/" followed by ";" belongs to `iuid`, e.g., `INBOX/;UID=20`.
+fn enc_mailbox(input: &[u8]) -> IMAPResult<&[u8], &[u8]> {
+    let (remaining, mailbox) = take_while1(is_bchar)(input)?;
+
+    let (remaining, mailbox) =
+        if mailbox.len() > 1 && mailbox.ends_with(b"/") && remaining.starts_with(b";") {
+            let length = mailbox.len() - 1;
+            (&input[length..], &mailbox[..length])
+        } else {
+            (remaining, mailbox)
+        };
+
+    Ok((remaining, mailbox))
+}
+
+/// ```abnf
+/// unreserved = ALPHA / DIGIT / "-" / "." / "_" / "~"
+/// ```
+fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~')
+}
+
+/// ```abnf
+/// achar = uchar / "&" / "=" / "~"
+///
+/// uchar = unreserved / pct-encoded / sub-delims-sh
+///
+/// sub-delims-sh = "!" / "$" / "'" / "(" / ")" / "*" / "+" / ","
+/// ```
+///
+/// Note: Percent-encoded octets are not validated further.
+pub(crate) fn is_achar(byte: u8) -> bool {
+    is_unreserved(byte)
+        || matches!(
+            byte,
+            b'%' | b'!' | b'$' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b'&' | b'='
+        )
+}
+
+/// ```abnf
+/// bchar = achar / ":" / "@" / "/"
+/// ```
+fn is_bchar(byte: u8) -> bool {
+    is_achar(byte) || matches!(byte, b':' | b'@' | b'/')
+}
+
+/// ```abnf
+/// reg-name = *( unreserved / pct-encoded / sub-delims )
+///
+/// sub-delims = "!" / "$" / "&" / "'" / "(" / ")" / "*" / "+" / "," / ";" / "="
+/// ```
+fn is_reg_name_char(byte: u8) -> bool {
+    is_unreserved(byte)
+        || matches!(
+            byte,
+            b'%' | b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b'='
+        )
+}
+
+fn is_ip_literal_char(byte: u8) -> bool {
+    byte.is_ascii_hexdigit() || matches!(byte, b':' | b'.')
+}
+
+fn is_ip_literal(host: &str) -> bool {
+    match host
+        .strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+    {
+        Some(address) => !address.is_empty() && address.bytes().all(is_ip_literal_char),
+        None => false,
+    }
+}
+
+/// Percent-decode `value`, e.g., `My%20Folder` to `My Folder`.
+///
+/// Fails when a `%` is not followed by two hex digits or when the result is not UTF-8.
+pub(crate) fn percent_decode(value: &[u8]) -> Option<Cow<'_, str>> {
+    if !value.contains(&b'%') {
+        return from_utf8(value).ok().map(Cow::Borrowed);
+    }
+
+    fn hex_digit(byte: u8) -> Option<u8> {
+        char::from(byte).to_digit(16).map(|digit| digit as u8)
+    }
+
+    let mut decoded = Vec::with_capacity(value.len());
+    let mut bytes = value.iter().copied();
+
+    while let Some(byte) = bytes.next() {
+        if byte == b'%' {
+            let high = hex_digit(bytes.next()?)?;
+            let low = hex_digit(bytes.next()?)?;
+            decoded.push(high << 4 | low);
+        } else {
+            decoded.push(byte);
+        }
+    }
+
+    String::from_utf8(decoded).ok().map(Cow::Owned)
+}
+
+/// Write `value` and percent-encode every byte not accepted by `is_allowed` (and every `%`).
+///
+/// This is the inverse of [`percent_decode`].
+pub(crate) fn encode_percent(
+    ctx: &mut EncodeContext,
+    value: &str,
+    is_allowed: fn(u8) -> bool,
+) -> std::io::Result<()> {
+    for byte in value.bytes() {
+        if byte != b'%' && is_allowed(byte) {
+            ctx.write_all(&[byte])?;
+        } else {
+            write!(ctx, "%{byte:02X}")?;
+        }
+    }
+
+    Ok(())
+}
+
+impl<'a> EncodeIntoContext for ImapUrl<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        ctx.write_all(b"imap://")?;
+
+        if let Some(user) = &self.user {
+            encode_percent(ctx, user, is_achar)?;
+        }
+
+        if let Some(auth) = &self.auth {
+            ctx.write_all(b";AUTH=")?;
+            match auth {
+                UrlAuth::Any => ctx.write_all(b"*")?,
+                UrlAuth::Mechanism(mechanism) => encode_percent(ctx, mechanism.as_ref(), is_achar)?,
+            }
+        }
+
+        if self.user.is_some() || self.auth.is_some() {
+            ctx.write_all(b"@")?;
+        }
+
+        // Note: Only an IP-literal may contain brackets and colons.
+        if is_ip_literal(&self.host) {
+            ctx.write_all(self.host.as_bytes())?;
+        } else {
+            encode_percent(ctx, &self.host, is_reg_name_char)?;
+        }
+
+        if let Some(port) = self.port {
+            write!(ctx, ":{port}")?;
+        }
+
+        ctx.write_all(b"/")?;
+
+        // Note: Parameters are only meaningful (and only encoded) with their "parent".
+        if let Some(mailbox) = &self.mailbox {
+            // Note: A trailing "/" would be taken as the start of "/;UID=".
+            match mailbox.strip_suffix('/') {
+                Some(mailbox) => {
+                    encode_percent(ctx, mailbox, is_bchar)?;
+                    ctx.write_all(b"%2F")?;
+                }
+                None => encode_percent(ctx, mailbox, is_bchar)?,
+            }
+
+            if let Some(uid_validity) = self.uid_validity {
+                write!(ctx, ";UIDVALIDITY={uid_validity}")?;
+            }
+
+            if let Some(uid) = self.uid {
+                write!(ctx, "/;UID={uid}")?;
+
+                if let Some(section) = &self.section {
+                    ctx.write_all(b"/;SECTION=")?;
+                    encode_percent(ctx, section, is_bchar)?;
+                }
+            }
+        }
+
+        #[cfg(feature = "ext_urlauth")]
+        {
+            if let Some(expire) = &self.expire {
+                ctx.write_all(b";EXPIRE=")?;
+                encode_percent(ctx, expire, is_expire_char)?;
+            }
+
+            if let Some(access) = &self.access {
+                ctx.write_all(b";URLAUTH=")?;
+                access.encode_ctx(ctx)?;
+
+                if let Some(authorization) = &self.authorization {
+                    authorization.encode_ctx(ctx)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use super::*;
+    use crate::testing::{known_answer_test_encode, known_answer_test_parse};
+
+    #[test]
+    fn test_kat_imap_url() {
+        let tests = [
+            (
+                b"imap://user@example.com/INBOX;UIDVALIDITY=1/;UID=20]".as_ref(),
+                ImapUrl {
+                    user: Some("user".into()),
+                    mailbox: Some("INBOX".into()),
+                    uid_validity: NonZeroU32::new(1),
+                    uid: NonZeroU32::new(20),
+                    ..ImapUrl::new("example.com")
+                },
+            ),
+            (
+                b"imap://user;AUTH=*@SERVER2/]",
+                ImapUrl {
+                    user: Some("user".into()),
+                    auth: Some(UrlAuth::Any),
+                    ..ImapUrl::new("SERVER2")
+                },
+            ),
+            (
+                b"imap://;AUTH=GSSAPI@[::1]:1143/]",
+                ImapUrl {
+                    auth: Some(UrlAuth::Mechanism(
+                        AuthMechanism::try_from("GSSAPI").unwrap(),
+                    )),
+                    port: Some(1143),
+                    ..ImapUrl::new("[::1]")
+                },
+            ),
+            (
+                b"imap://example.com:143/foo/bar/;UID=7/;SECTION=1.2]",
+                ImapUrl {
+                    port: Some(143),
+                    mailbox: Some("foo/bar".into()),
+                    uid: NonZeroU32::new(7),
+                    section: Some("1.2".into()),
+                    ..ImapUrl::new("example.com")
+                },
+            ),
+            (
+                b"imap://example.com/foo%20bar]",
+                ImapUrl {
+                    mailbox: Some("foo bar".into()),
+                    ..ImapUrl::new("example.com")
+                },
+            ),
+            (
+                b"imap://a%40b;AUTH=X%2FY@ex%25ample.com/%C3%A4%25/;UID=1/;SECTION=1%20x]",
+                ImapUrl {
+                    user: Some("a@b".into()),
+                    auth: Some(UrlAuth::Mechanism(AuthMechanism::try_from("X/Y").unwrap())),
+                    mailbox: Some("\u{e4}%".into()),
+                    uid: NonZeroU32::new(1),
+                    section: Some("1 x".into()),
+                    ..ImapUrl::new("ex%ample.com")
+                },
+            ),
+            (
+                b"imap://example.com/foo%2F;UIDVALIDITY=1]",
+                ImapUrl {
+                    mailbox: Some("foo/".into()),
+                    uid_validity: NonZeroU32::new(1),
+                    ..ImapUrl::new("example.com")
+                },
+            ),
+        ];
+
+        for (test, expected) in tests {
+            known_answer_test_parse((test, b"]", expected.clone()), imap_url);
+            known_answer_test_encode((expected, &test[..test.len() - 1]));
+        }
+    }
+
+    #[test]
+    fn test_encode_imap_url_percent_encoded() {
+        let tests = [
+            (
+                ImapUrl {
+                    user: Some("a@b".into()),
+                    mailbox: Some("My Folder]\r\n".into()),
+                    uid: NonZeroU32::new(1),
+                    section: Some("1 \"x\"".into()),
+                    ..ImapUrl::new("example.com/x]")
+                },
+                b"imap://a%40b@example.com%2Fx%5D/My%20Folder%5D%0D%0A/;UID=1/;SECTION=1%20%22x%22"
+                    .as_ref(),
+            ),
+            (
+                ImapUrl {
+                    mailbox: Some("My Folder".into()),
+                    ..ImapUrl::new("[::1]")
+                },
+                b"imap://[::1]/My%20Folder",
+            ),
+            (
+                ImapUrl {
+                    mailbox: Some("foo%20bar".into()),
+                    ..ImapUrl::new("[::1]")
+                },
+                b"imap://[::1]/foo%2520bar",
+            ),
+            (ImapUrl::new("[::1] x"), b"imap://%5B%3A%3A1%5D%20x/"),
+        ];
+
+        for (url, expected) in tests {
+            known_answer_test_encode((url, expected));
+        }
+    }
+
+    #[test]
+    fn test_parse_imap_url_invalid() {
+        assert!(imap_url(b"http://example.com/]").is_err());
+        assert!(imap_url(b"imap://example.com]").is_err());
+        assert!(imap_url(b"imap://example.com:99999/]").is_err());
+        assert!(imap_url(b"imap://user@/]").is_err());
+
+        // Invalid percent-encoding (or non-UTF-8) is not taken as the mailbox.
+        for test in [
+            b"imap://example.com/foo%2]".as_ref(),
+            b"imap://example.com/foo%zz]",
+            b"imap://example.com/foo%FF]",
+        ] {
+            let (remaining, url) = imap_url(test).unwrap();
+            assert_eq!(url.mailbox, None);
+            assert_ne!(remaining, b"]");
+        }
+    }
+}
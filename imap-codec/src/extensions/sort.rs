@@ -1,20 +1,28 @@
 use std::io::Write;
+#[cfg(feature = "ext_condstore_qresync")]
+use std::num::NonZeroU64;
 
 use abnf_core::streaming::sp;
 use imap_types::{
     command::CommandBody,
     core::Vec1,
     extensions::sort::{SortCriterion, SortKey},
+    response::Data,
 };
+#[cfg(feature = "ext_condstore_qresync")]
+use nom::combinator::map_res;
 use nom::{
     branch::alt,
     bytes::streaming::{tag, tag_no_case},
     combinator::{map, opt, value},
-    multi::separated_list1,
-    sequence::{delimited, tuple},
+    multi::{many0, separated_list1},
+    sequence::{delimited, preceded, tuple},
 };
 
+#[cfg(feature = "ext_condstore_qresync")]
+use crate::core::number64;
 use crate::{
+    core::nz_number,
     decode::IMAPResult,
     encode::{EncodeContext, EncodeIntoContext},
     search::search_criteria,
@@ -87,6 +95,50 @@ pub(crate) fn sort_key(input: &[u8]) -> IMAPResult<&[u8], SortKey> {
     ))(input)
 }
 
+/// ```abnf
+/// sort-data = "SORT" *(SP nz-number)
+///
+/// ; RFC 7162
+/// sort-data =/ "SORT" [1*(SP nz-number) SP search-sort-mod-seq]
+/// ```
+///
+/// Note: Starts after "*" SP.
+pub(crate) fn sort_data(input: &[u8]) -> IMAPResult<&[u8], Data> {
+    let (remaining, ids) = preceded(tag_no_case(b"SORT"), many0(preceded(sp, nz_number)))(input)?;
+
+    // The trailer is optional, i.e., even with CONDSTORE a server may omit it. It requires at least
+    // one number.
+    #[cfg(feature = "ext_condstore_qresync")]
+    let (remaining, modseq) = if ids.is_empty() {
+        (remaining, None)
+    } else {
+        opt(preceded(sp, search_sort_mod_seq))(remaining)?
+    };
+
+    Ok((
+        remaining,
+        Data::Sort {
+            ids,
+            #[cfg(feature = "ext_condstore_qresync")]
+            modseq,
+        },
+    ))
+}
+
+/// ```abnf
+/// search-sort-mod-seq = "(" "MODSEQ" SP mod-sequence-value ")"
+///
+/// mod-sequence-value = 1*DIGIT
+/// ```
+#[cfg(feature = "ext_condstore_qresync")]
+fn search_sort_mod_seq(input: &[u8]) -> IMAPResult<&[u8], NonZeroU64> {
+    delimited(
+        tag_no_case(b"(MODSEQ "),
+        map_res(number64, NonZeroU64::try_from),
+        tag(b")"),
+    )(input)
+}
+
 impl EncodeIntoContext for SortCriterion {
     fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
         if self.reverse {
@@ -96,3 +148,137 @@ impl EncodeIntoContext for SortCriterion {
         ctx.write_all(self.key.as_ref().as_bytes())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use imap_types::{
+        command::Command,
+        core::Charset,
+        response::{Data, Response},
+        search::SearchKey,
+    };
+
+    use super::*;
+    use crate::testing::{kat_inverse_command, kat_inverse_response};
+    #[cfg(feature = "ext_condstore_qresync")]
+    use crate::{decode::Decoder, testing::known_answer_test_encode, ResponseCodec};
+
+    #[test]
+    fn test_kat_inverse_command_sort() {
+        kat_inverse_command(&[
+            (
+                b"A SORT (REVERSE DATE) UTF-8 ALL\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::Sort {
+                        sort_criteria: Vec1::from(SortCriterion {
+                            reverse: true,
+                            key: SortKey::Date,
+                        }),
+                        charset: Charset::try_from("UTF-8").unwrap(),
+                        search_criteria: Vec1::from(SearchKey::All),
+                        uid: false,
+                    },
+                )
+                .unwrap(),
+            ),
+            (
+                b"A UID SORT (SUBJECT ARRIVAL) US-ASCII UNSEEN FLAGGED\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::Sort {
+                        sort_criteria: vec![
+                            SortCriterion {
+                                reverse: false,
+                                key: SortKey::Subject,
+                            },
+                            SortCriterion {
+                                reverse: false,
+                                key: SortKey::Arrival,
+                            },
+                        ]
+                        .try_into()
+                        .unwrap(),
+                        charset: Charset::try_from("US-ASCII").unwrap(),
+                        search_criteria: vec![SearchKey::Unseen, SearchKey::Flagged]
+                            .try_into()
+                            .unwrap(),
+                        uid: true,
+                    },
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_sort() {
+        kat_inverse_response(&[
+            (
+                b"* SORT 2 84 882\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::sort(
+                    [2, 84, 882]
+                        .into_iter()
+                        .map(|n| NonZeroU32::new(n).unwrap())
+                        .collect(),
+                )),
+            ),
+            (b"* SORT\r\n", b"", Response::Data(Data::sort(vec![]))),
+        ]);
+    }
+
+    #[cfg(feature = "ext_condstore_qresync")]
+    #[test]
+    fn test_kat_inverse_response_sort_modseq() {
+        kat_inverse_response(&[
+            (
+                b"* SORT 2 84 882 (MODSEQ 12345)\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Sort {
+                    ids: [2, 84, 882]
+                        .into_iter()
+                        .map(|n| NonZeroU32::new(n).unwrap())
+                        .collect(),
+                    modseq: NonZeroU64::new(12345),
+                }),
+            ),
+            (
+                b"* SORT 1 (MODSEQ 9223372036854775807)\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Sort {
+                    ids: vec![NonZeroU32::new(1).unwrap()],
+                    modseq: NonZeroU64::new(9223372036854775807),
+                }),
+            ),
+            // The trailer is optional.
+            (
+                b"* SORT 1\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::sort(vec![NonZeroU32::new(1).unwrap()])),
+            ),
+        ]);
+
+        for test in [
+            b"* SORT 1 (MODSEQ 0)\r\n".as_ref(),
+            b"* SORT 1 (MODSEQ)\r\n",
+            b"* SORT 1 (MODSEQ 1 2)\r\n",
+            b"* SORT (MODSEQ 1)\r\n",
+        ] {
+            assert!(ResponseCodec::default().decode(test).is_err());
+        }
+
+        // Without numbers, the trailer is not encoded.
+        known_answer_test_encode((
+            Data::Sort {
+                ids: vec![],
+                modseq: NonZeroU64::new(1),
+            },
+            b"* SORT\r\n",
+        ));
+    }
+}
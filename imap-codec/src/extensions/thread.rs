@@ -4,7 +4,7 @@ use abnf_core::streaming::sp;
 use imap_types::{
     command::CommandBody,
     core::{Vec1, Vec2},
-    extensions::thread::{Thread, ThreadingAlgorithm, ThreadingAlgorithmOther},
+    extensions::thread::{Thread, ThreadingAlgorithm},
     response::Data,
 };
 use nom::{
@@ -30,17 +30,7 @@ impl<'a> EncodeIntoContext for Thread {
 
 impl<'a> EncodeIntoContext for ThreadingAlgorithm<'a> {
     fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
-        match self {
-            ThreadingAlgorithm::OrderedSubject => ctx.write_all(b"ORDEREDSUBJECT"),
-            ThreadingAlgorithm::References => ctx.write_all(b"REFERENCES"),
-            ThreadingAlgorithm::Other(other) => other.encode_ctx(ctx),
-        }
-    }
-}
-
-impl<'a> EncodeIntoContext for ThreadingAlgorithmOther<'a> {
-    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
-        ctx.write_all(self.as_ref().as_bytes())
+        ctx.write_all(self.to_string().as_bytes())
     }
 }
 
@@ -158,9 +148,21 @@ pub(crate) fn thread_list_limited<'a>(
 mod tests {
     use std::num::NonZeroU32;
 
-    use imap_types::core::{Vec1, Vec2};
+    use imap_types::{
+        command::{Command, CommandBody},
+        core::{Charset, Vec1, Vec2},
+        extensions::thread::ThreadMember,
+        response::{Data, Response},
+        search::SearchKey,
+    };
 
-    use super::{thread_list, Thread};
+    use super::{thread_list, Thread, ThreadingAlgorithm};
+    use crate::{
+        decode::Decoder,
+        encode::Encoder,
+        testing::{kat_inverse_command, kat_inverse_response},
+        ResponseCodec,
+    };
 
     #[test]
     fn test_thread_list() {
@@ -328,4 +330,129 @@ mod tests {
             assert!(rem.is_empty());
         }
     }
+
+    #[test]
+    fn test_kat_inverse_command_thread() {
+        kat_inverse_command(&[
+            (
+                b"A THREAD ORDEREDSUBJECT UTF-8 ALL\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::Thread {
+                        algorithm: ThreadingAlgorithm::OrderedSubject,
+                        charset: Charset::try_from("UTF-8").unwrap(),
+                        search_criteria: Vec1::from(SearchKey::All),
+                        uid: false,
+                    },
+                )
+                .unwrap(),
+            ),
+            (
+                b"A UID THREAD REFERENCES US-ASCII UNSEEN\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::Thread {
+                        algorithm: ThreadingAlgorithm::References,
+                        charset: Charset::try_from("US-ASCII").unwrap(),
+                        search_criteria: Vec1::from(SearchKey::Unseen),
+                        uid: true,
+                    },
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_thread() {
+        let members = |prefix: &[u32], answers: Option<Vec<Thread>>| Thread::Members {
+            prefix: prefix
+                .iter()
+                .map(|n| NonZeroU32::new(*n).unwrap())
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+            answers: answers.map(|answers| Vec2::try_from(answers).unwrap()),
+        };
+
+        kat_inverse_response(&[
+            (
+                b"* THREAD (1 2)(3 (4)(5))\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Thread(vec![
+                    members(&[1, 2], None),
+                    members(&[3], Some(vec![members(&[4], None), members(&[5], None)])),
+                ])),
+            ),
+            (b"* THREAD\r\n", b"", Response::Data(Data::Thread(vec![]))),
+        ]);
+    }
+
+    #[test]
+    fn test_thread_member() {
+        let n = |id| NonZeroU32::new(id);
+        let leaf = |id| ThreadMember {
+            id: n(id),
+            children: vec![],
+        };
+
+        let input = b"* THREAD (1 (2)(3))((1)(2))(4 5 (6 7)(8 ((9)(10))(11)))\r\n";
+        let (_, response) = ResponseCodec::default().decode(input).unwrap();
+        let Response::Data(Data::Thread(threads)) = response else {
+            unreachable!()
+        };
+
+        let members: Vec<_> = threads.into_iter().map(ThreadMember::from).collect();
+        assert_eq!(
+            members[0],
+            ThreadMember {
+                id: n(1),
+                children: vec![leaf(2), leaf(3)],
+            }
+        );
+        assert_eq!(
+            members[1],
+            ThreadMember {
+                id: None,
+                children: vec![leaf(1), leaf(2)],
+            }
+        );
+        assert_eq!(
+            members[2],
+            ThreadMember {
+                id: n(4),
+                children: vec![ThreadMember {
+                    id: n(5),
+                    children: vec![
+                        ThreadMember {
+                            id: n(6),
+                            children: vec![leaf(7)],
+                        },
+                        ThreadMember {
+                            id: n(8),
+                            children: vec![
+                                ThreadMember {
+                                    id: None,
+                                    children: vec![leaf(9), leaf(10)],
+                                },
+                                leaf(11),
+                            ],
+                        },
+                    ],
+                }],
+            }
+        );
+
+        let threads = members
+            .into_iter()
+            .map(Thread::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let output = ResponseCodec::default()
+            .encode(&Response::Data(Data::Thread(threads)))
+            .dump();
+        assert_eq!(output, input);
+    }
 }
@@ -1,15 +1,36 @@
 #[cfg(test)]
 mod tests {
-    use imap_types::command::{Command, CommandBody};
+    use imap_types::{
+        command::{Command, CommandBody},
+        response::{Capability, Data, Response},
+    };
 
-    use crate::testing::kat_inverse_command;
+    use crate::testing::{kat_inverse_command, kat_inverse_response};
 
     #[test]
     fn test_kat_inverse_command_unselect() {
-        kat_inverse_command(&[(
-            b"A UNSELECT\r\n".as_ref(),
+        kat_inverse_command(&[
+            (
+                b"A UNSELECT\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new("A", CommandBody::unselect()).unwrap(),
+            ),
+            (
+                b"A CLOSE\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new("A", CommandBody::Close).unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_unselect() {
+        kat_inverse_response(&[(
+            b"* CAPABILITY IMAP4rev1 UNSELECT\r\n".as_ref(),
             b"".as_ref(),
-            Command::new("A", CommandBody::unselect()).unwrap(),
+            Response::Data(
+                Data::capability(vec![Capability::Imap4Rev1, Capability::Unselect]).unwrap(),
+            ),
         )]);
     }
 }
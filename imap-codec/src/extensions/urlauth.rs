@@ -0,0 +1,435 @@
+//! IMAP URLAUTH Extension
+
+// Additional changes:
+//
+// command-auth  =/ genurlauth / resetkey / urlfetch
+// response-data =/ "*" SP (genurlauth-data / urlfetch-data) CRLF
+// imapurl       =/ imapurl [expire] [";URLAUTH=" access [":" mechanism ":" urlauth]]
+
+use std::{borrow::Cow, io::Write, str::from_utf8};
+
+use abnf_core::streaming::sp;
+use imap_types::{
+    command::CommandBody,
+    core::Vec1,
+    extensions::{
+        referral::ImapUrl,
+        urlauth::{UrlAuthAccess, UrlAuthMechanism, UrlAuthorization},
+    },
+    response::Data,
+};
+use nom::{
+    branch::alt,
+    bytes::streaming::{tag, tag_no_case, take_while1},
+    combinator::{map, map_opt, opt, value, verify},
+    multi::{many0, many1},
+    sequence::{delimited, preceded, separated_pair, tuple},
+};
+
+use crate::{
+    core::nstring,
+    decode::IMAPResult,
+    encode::{EncodeContext, EncodeIntoContext},
+    extensions::referral::{encode_percent, imap_url, is_achar, percent_decode},
+    mailbox::mailbox,
+};
+
+/// ```abnf
+/// command-auth =/ genurlauth / resetkey / urlfetch
+/// ```
+pub(crate) fn urlauth_command(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    alt((genurlauth, resetkey, urlfetch))(input)
+}
+
+/// ```abnf
+/// genurlauth = "GENURLAUTH" 1*(SP url-rump SP mechanism)
+/// ```
+pub(crate) fn genurlauth(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = preceded(
+        tag_no_case(b"GENURLAUTH"),
+        many1(preceded(sp, separated_pair(url_astring, sp, mechanism))),
+    );
+
+    let (remaining, urls) = parser(input)?;
+
+    Ok((
+        remaining,
+        CommandBody::GenUrlAuth {
+            urls: Vec1::unvalidated(urls),
+        },
+    ))
+}
+
+/// ```abnf
+/// resetkey = "RESETKEY" [SP mailbox *(SP mechanism)]
+/// ```
+pub(crate) fn resetkey(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = preceded(
+        tag_no_case(b"RESETKEY"),
+        opt(preceded(
+            sp,
+            tuple((mailbox, many0(preceded(sp, mechanism)))),
+        )),
+    );
+
+    let (remaining, mailbox_and_mechanisms) = parser(input)?;
+
+    Ok((
+        remaining,
+        CommandBody::ResetKey {
+            mailbox_and_mechanisms,
+        },
+    ))
+}
+
+/// ```abnf
+/// urlfetch = "URLFETCH" 1*(SP url-full)
+/// ```
+pub(crate) fn urlfetch(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = preceded(tag_no_case(b"URLFETCH"), many1(preceded(sp, url_astring)));
+
+    let (remaining, urls) = parser(input)?;
+
+    Ok((
+        remaining,
+        CommandBody::UrlFetch {
+            urls: Vec1::unvalidated(urls),
+        },
+    ))
+}
+
+/// ```abnf
+/// genurlauth-data = "*" SP "GENURLAUTH" 1*(SP url-full)
+/// ```
+///
+/// Note: Starts after "*" SP.
+pub(crate) fn genurlauth_data(input: &[u8]) -> IMAPResult<&[u8], Data> {
+    let mut parser = preceded(tag_no_case(b"GENURLAUTH"), many1(preceded(sp, url_astring)));
+
+    let (remaining, urls) = parser(input)?;
+
+    Ok((
+        remaining,
+        Data::GenUrlAuth {
+            urls: Vec1::unvalidated(urls),
+        },
+    ))
+}
+
+/// ```abnf
+/// urlfetch-data = "*" SP "URLFETCH" 1*(SP url-full SP nstring)
+/// ```
+///
+/// Note: Starts after "*" SP.
+pub(crate) fn urlfetch_data(input: &[u8]) -> IMAPResult<&[u8], Data> {
+    let mut parser = preceded(
+        tag_no_case(b"URLFETCH"),
+        many1(preceded(sp, separated_pair(url_astring, sp, nstring))),
+    );
+
+    let (remaining, items) = parser(input)?;
+
+    Ok((
+        remaining,
+        Data::UrlFetch {
+            items: Vec1::unvalidated(items),
+        },
+    ))
+}
+
+/// ```abnf
+/// url-full = astring
+///
+/// url-rump = astring
+/// ```
+///
+/// Note: Literals are not supported.
+fn url_astring(input: &[u8]) -> IMAPResult<&[u8], ImapUrl> {
+    alt((delimited(tag(b"\""), imap_url, tag(b"\"")), imap_url))(input)
+}
+
+/// ```abnf
+/// [expire] [";URLAUTH=" access [":" mechanism ":" urlauth]]
+///
+/// expire = ";EXPIRE=" date-time
+/// ```
+///
+/// Note: The date-time is not validated further.
+#[allow(clippy::type_complexity)]
+pub(crate) fn urlauth_params(
+    input: &[u8],
+) -> IMAPResult<
+    &[u8],
+    (
+        Option<Cow<str>>,
+        Option<UrlAuthAccess>,
+        Option<UrlAuthorization>,
+    ),
+> {
+    let mut parser = tuple((
+        opt(preceded(
+            tag_no_case(b";EXPIRE="),
+            map_opt(
+                take_while1(|byte| byte == b'%' || is_expire_char(byte)),
+                percent_decode,
+            ),
+        )),
+        opt(tuple((
+            preceded(tag_no_case(b";URLAUTH="), access),
+            opt(urlauth_full),
+        ))),
+    ));
+
+    let (remaining, (expire, urlauth)) = parser(input)?;
+
+    let (access, authorization) = match urlauth {
+        Some((access, authorization)) => (Some(access), authorization),
+        None => (None, None),
+    };
+
+    Ok((remaining, (expire, access, authorization)))
+}
+
+/// Characters of a date-time, e.g., `2024-05-01T12:00:00+02:00`.
+pub(crate) fn is_expire_char(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b':' | b'.' | b'+')
+}
+
+/// ```abnf
+/// access = ("submit+" enc-user) / ("user+" enc-user) / "authuser" / "anonymous"
+/// ```
+fn access(input: &[u8]) -> IMAPResult<&[u8], UrlAuthAccess> {
+    alt((
+        map(
+            preceded(tag_no_case(b"submit+"), enc_user),
+            UrlAuthAccess::Submit,
+        ),
+        map(
+            preceded(tag_no_case(b"user+"), enc_user),
+            UrlAuthAccess::User,
+        ),
+        value(UrlAuthAccess::AuthUser, tag_no_case(b"authuser")),
+        value(UrlAuthAccess::Anonymous, tag_no_case(b"anonymous")),
+    ))(input)
+}
+
+/// ```abnf
+/// enc-user = 1*achar
+/// ```
+fn enc_user(input: &[u8]) -> IMAPResult<&[u8], Cow<str>> {
+    map_opt(take_while1(is_achar), percent_decode)(input)
+}
+
+/// ```abnf
+/// urlauth-full = ":" mechanism ":" urlauth
+///
+/// urlauth = 32*HEXDIG
+/// ```
+fn urlauth_full(input: &[u8]) -> IMAPResult<&[u8], UrlAuthorization> {
+    let mut parser = tuple((
+        preceded(tag(b":"), mechanism),
+        preceded(
+            tag(b":"),
+            verify(
+                take_while1(|byte: u8| byte.is_ascii_hexdigit()),
+                |val: &[u8]| val.len() >= 32,
+            ),
+        ),
+    ));
+
+    let (remaining, (mechanism, token)) = parser(input)?;
+
+    Ok((
+        remaining,
+        UrlAuthorization {
+            mechanism,
+            // Safety: Only ASCII hex digits are accepted.
+            token: from_utf8(token).unwrap().into(),
+        },
+    ))
+}
+
+/// ```abnf
+/// mechanism = "INTERNAL" / 1*(ALPHA / DIGIT / "-" / ".")
+/// ```
+fn mechanism(input: &[u8]) -> IMAPResult<&[u8], UrlAuthMechanism> {
+    map(
+        take_while1(|byte: u8| byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.')),
+        |val: &[u8]| {
+            // Safety: Only letters, digits, "-", and "." are accepted, which is a valid mechanism.
+            UrlAuthMechanism::try_from(from_utf8(val).unwrap()).unwrap()
+        },
+    )(input)
+}
+
+/// Encode a URL as a quoted string.
+pub(crate) fn encode_url_quoted(url: &ImapUrl, ctx: &mut EncodeContext) -> std::io::Result<()> {
+    ctx.write_all(b"\"")?;
+    url.encode_ctx(ctx)?;
+    ctx.write_all(b"\"")
+}
+
+impl<'a> EncodeIntoContext for UrlAuthAccess<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        match self {
+            Self::Submit(user) => {
+                ctx.write_all(b"submit+")?;
+                encode_percent(ctx, user, is_achar)
+            }
+            Self::User(user) => {
+                ctx.write_all(b"user+")?;
+                encode_percent(ctx, user, is_achar)
+            }
+            Self::AuthUser => ctx.write_all(b"authuser"),
+            Self::Anonymous => ctx.write_all(b"anonymous"),
+        }
+    }
+}
+
+impl<'a> EncodeIntoContext for UrlAuthMechanism<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        match self {
+            Self::Internal => ctx.write_all(b"INTERNAL"),
+            Self::Other(_) => ctx.write_all(self.as_ref().as_bytes()),
+        }
+    }
+}
+
+impl<'a> EncodeIntoContext for UrlAuthorization<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        ctx.write_all(b":")?;
+        self.mechanism.encode_ctx(ctx)?;
+        ctx.write_all(b":")?;
+        encode_percent(ctx, &self.token, |byte| byte.is_ascii_hexdigit())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use imap_types::{
+        command::Command,
+        core::{Literal, NString},
+        mailbox::Mailbox,
+        response::Response,
+    };
+
+    use super::*;
+    use crate::testing::{kat_inverse_command, kat_inverse_response};
+
+    fn url_rump() -> ImapUrl<'static> {
+        ImapUrl {
+            user: Some("joe".into()),
+            mailbox: Some("INBOX".into()),
+            uid: NonZeroU32::new(20),
+            section: Some("1.2".into()),
+            access: Some(UrlAuthAccess::Submit("fred".into())),
+            ..ImapUrl::new("example.com")
+        }
+    }
+
+    fn url_full() -> ImapUrl<'static> {
+        ImapUrl {
+            authorization: Some(UrlAuthorization {
+                mechanism: UrlAuthMechanism::Internal,
+                token: "91354a473744909de610943775f92038".into(),
+            }),
+            ..url_rump()
+        }
+    }
+
+    #[test]
+    fn test_kat_inverse_command_urlauth() {
+        kat_inverse_command(&[
+            (
+                b"A GENURLAUTH \"imap://joe@example.com/INBOX/;UID=20/;SECTION=1.2;URLAUTH=submit+fred\" INTERNAL\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::gen_url_auth(vec![(url_rump(), UrlAuthMechanism::Internal)])
+                        .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A URLFETCH \"imap://joe@example.com/INBOX/;UID=20/;SECTION=1.2;URLAUTH=submit+fred:INTERNAL:91354a473744909de610943775f92038\"\r\n",
+                b"",
+                Command::new("A", CommandBody::url_fetch(vec![url_full()]).unwrap()).unwrap(),
+            ),
+            (
+                b"A RESETKEY\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::ResetKey {
+                        mailbox_and_mechanisms: None,
+                    },
+                )
+                .unwrap(),
+            ),
+            (
+                b"A RESETKEY INBOX INTERNAL X-FOO.1\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::ResetKey {
+                        mailbox_and_mechanisms: Some((
+                            Mailbox::Inbox,
+                            vec![
+                                UrlAuthMechanism::Internal,
+                                UrlAuthMechanism::try_from("X-FOO.1").unwrap(),
+                            ],
+                        )),
+                    },
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_urlauth() {
+        kat_inverse_response(&[
+            (
+                b"* GENURLAUTH \"imap://joe@example.com/INBOX/;UID=20/;SECTION=1.2;URLAUTH=submit+fred:INTERNAL:91354a473744909de610943775f92038\"\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::GenUrlAuth {
+                    urls: Vec1::from(url_full()),
+                }),
+            ),
+            (
+                b"* URLFETCH \"imap://joe@example.com/INBOX/;UID=20/;SECTION=1.2;URLAUTH=submit+fred:INTERNAL:91354a473744909de610943775f92038\" {5}\r\nHello\r\n",
+                b"",
+                Response::Data(
+                    Data::url_fetch(vec![(
+                        url_full(),
+                        NString(Some(Literal::try_from("Hello").unwrap().into())),
+                    )])
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"* URLFETCH \"imap://example.com/INBOX/;UID=1;EXPIRE=2024-05-01T12:00:00Z;URLAUTH=anonymous:INTERNAL:00000000000000000000000000000000\" NIL\r\n",
+                b"",
+                Response::Data(
+                    Data::url_fetch(vec![(
+                        ImapUrl {
+                            mailbox: Some("INBOX".into()),
+                            uid: NonZeroU32::new(1),
+                            expire: Some("2024-05-01T12:00:00Z".into()),
+                            access: Some(UrlAuthAccess::Anonymous),
+                            authorization: Some(UrlAuthorization {
+                                mechanism: UrlAuthMechanism::Internal,
+                                token: "00000000000000000000000000000000".into(),
+                            }),
+                            ..ImapUrl::new("example.com")
+                        },
+                        NString(None),
+                    )])
+                    .unwrap(),
+                ),
+            ),
+        ]);
+    }
+}
@@ -13,6 +13,10 @@ use nom::{
     sequence::{delimited, tuple},
 };
 
+#[cfg(feature = "ext_annotate")]
+use crate::extensions::annotate::{fetch_annotate_att, msg_att_annotate};
+#[cfg(feature = "ext_gmail")]
+use crate::extensions::gmail::{fetch_att_gmail, msg_att_gmail};
 use crate::{
     body::body,
     core::{astring, nstring, number, nz_number},
@@ -83,6 +87,10 @@ pub(crate) fn fetch_att(input: &[u8]) -> IMAPResult<&[u8], MessageDataItemName>
         value(MessageDataItemName::Rfc822Size, tag_no_case(b"RFC822.SIZE")),
         value(MessageDataItemName::Rfc822Text, tag_no_case(b"RFC822.TEXT")),
         value(MessageDataItemName::Rfc822, tag_no_case(b"RFC822")),
+        #[cfg(feature = "ext_gmail")]
+        fetch_att_gmail,
+        #[cfg(feature = "ext_annotate")]
+        fetch_annotate_att,
     ))(input)
 }
 
@@ -93,7 +101,17 @@ pub(crate) fn msg_att(input: &[u8]) -> IMAPResult<&[u8], Vec1<MessageDataItem>>
     delimited(
         tag(b"("),
         map(
-            separated_list1(sp, alt((msg_att_dynamic, msg_att_static))),
+            separated_list1(
+                sp,
+                alt((
+                    msg_att_dynamic,
+                    msg_att_static,
+                    #[cfg(feature = "ext_gmail")]
+                    msg_att_gmail,
+                    #[cfg(feature = "ext_annotate")]
+                    msg_att_annotate,
+                )),
+            ),
             Vec1::unvalidated,
         ),
         tag(b")"),
@@ -280,13 +298,16 @@ pub(crate) fn header_fld_name(input: &[u8]) -> IMAPResult<&[u8], AString> {
 mod tests {
     use imap_types::{
         body::{BasicFields, Body, BodyStructure, SpecificFields},
-        core::{IString, NString},
+        command::{Command, CommandBody},
+        core::{IString, Literal, NString},
         datetime::DateTime,
         envelope::Envelope,
+        fetch::{MacroOrMessageDataItemNames, MessageDataItemKey},
+        response::{Data, Response},
     };
 
     use super::*;
-    use crate::testing::known_answer_test_encode;
+    use crate::testing::{kat_inverse_command, kat_inverse_response, known_answer_test_encode};
 
     #[test]
     fn test_encode_message_data_item_name() {
@@ -469,4 +490,147 @@ mod tests {
             known_answer_test_encode(test)
         }
     }
+
+    #[test]
+    fn test_kat_inverse_rfc822() {
+        kat_inverse_command(&[(
+            b"A FETCH 1 (RFC822 RFC822.HEADER RFC822.TEXT RFC822.SIZE)\r\n".as_ref(),
+            b"".as_ref(),
+            Command::new(
+                "A",
+                CommandBody::fetch(
+                    "1",
+                    vec![
+                        MessageDataItemName::Rfc822,
+                        MessageDataItemName::Rfc822Header,
+                        MessageDataItemName::Rfc822Text,
+                        MessageDataItemName::Rfc822Size,
+                    ],
+                    false,
+                )
+                .unwrap(),
+            )
+            .unwrap(),
+        )]);
+
+        kat_inverse_response(&[(
+            b"* 1 FETCH (RFC822 {4}\r\nA\r\nB RFC822.HEADER \"A: b\" RFC822.TEXT NIL RFC822.SIZE 5)\r\n"
+                .as_ref(),
+            b"".as_ref(),
+            Response::Data(Data::Fetch {
+                seq: NonZeroU32::try_from(1).unwrap(),
+                items: Vec1::try_from(vec![
+                    MessageDataItem::Rfc822(NString(Some(IString::Literal(
+                        Literal::try_from(b"A\r\nB".as_ref()).unwrap(),
+                    )))),
+                    MessageDataItem::Rfc822Header(NString::try_from("A: b").unwrap()),
+                    MessageDataItem::Rfc822Text(NString(None)),
+                    MessageDataItem::Rfc822Size(5),
+                ])
+                .unwrap(),
+            }),
+        )]);
+    }
+
+    #[test]
+    fn test_collect_message_data_item_names() {
+        let item_names: MacroOrMessageDataItemNames =
+            [MessageDataItemName::Flags, MessageDataItemName::Uid]
+                .into_iter()
+                .collect();
+
+        kat_inverse_command(&[(
+            b"A FETCH 1 (FLAGS UID)\r\n".as_ref(),
+            b"".as_ref(),
+            Command::new("A", CommandBody::fetch("1", item_names, false).unwrap()).unwrap(),
+        )]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_fetch_order() {
+        let items = Vec1::try_from(vec![
+            MessageDataItem::Uid(NonZeroU32::try_from(7).unwrap()),
+            MessageDataItem::Rfc822Size(42),
+            MessageDataItem::Flags(vec![]),
+            MessageDataItem::BodyExt {
+                section: Some(Section::Header(None)),
+                origin: None,
+                data: NString(None),
+            },
+        ])
+        .unwrap();
+
+        let data = Data::Fetch {
+            seq: NonZeroU32::try_from(1).unwrap(),
+            items,
+        };
+
+        kat_inverse_response(&[(
+            b"* 1 FETCH (UID 7 RFC822.SIZE 42 FLAGS () BODY[HEADER] NIL)\r\n".as_ref(),
+            b"".as_ref(),
+            Response::Data(data.clone()),
+        )]);
+
+        assert_eq!(
+            data.fetch_item(&MessageDataItemName::Flags),
+            Some(&MessageDataItem::Flags(vec![]))
+        );
+        assert_eq!(
+            data.fetch_item(&MessageDataItemName::Rfc822Size),
+            Some(&MessageDataItem::Rfc822Size(42))
+        );
+        assert!(data
+            .fetch_item(&MessageDataItemName::BodyExt {
+                section: Some(Section::Header(None)),
+                partial: None,
+                peek: true,
+            })
+            .is_some());
+        assert_eq!(data.fetch_item(&MessageDataItemName::Envelope), None);
+        assert_eq!(
+            Data::Exists(1).fetch_item(&MessageDataItemName::Flags),
+            None
+        );
+        assert_eq!(
+            data.fetch_item(&MessageDataItemName::BodyExt {
+                section: Some(Section::Text(None)),
+                partial: None,
+                peek: false,
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_response_key() {
+        let tests = [
+            (b"UID ".as_ref(), b"(UID 7)".as_ref()),
+            (b"FLAGS ", b"(FLAGS (\\Seen))"),
+            (b"RFC822.SIZE ", b"(RFC822.SIZE 42)"),
+            (b"BODY.PEEK[HEADER] ", b"(BODY[HEADER] NIL)"),
+            (b"BODY[1.2.MIME] ", b"(BODY[1.2.MIME] NIL)"),
+            (b"BODY[]<0.100> ", b"(BODY[]<0> NIL)"),
+            (b"BODY.PEEK[TEXT]<42.1> ", b"(BODY[TEXT]<42> NIL)"),
+        ];
+
+        for (name, items) in tests {
+            let (_, name) = fetch_att(name).unwrap();
+            let (_, items) = msg_att(items).unwrap();
+            let item = items.as_ref().first().unwrap();
+            assert_eq!(name.response_key(), item.key());
+            assert!(item.answers(&name));
+        }
+
+        let (_, name) = fetch_att(b"BODY.PEEK[HEADER]<0.100> ").unwrap();
+        assert_eq!(
+            name.response_key(),
+            MessageDataItemKey::BodyExt {
+                section: Some(Section::Header(None)),
+                origin: Some(0),
+            }
+        );
+
+        let (_, items) = msg_att(b"(BODY[HEADER] NIL)").unwrap();
+        assert_ne!(name.response_key(), items.as_ref()[0].key());
+    }
 }
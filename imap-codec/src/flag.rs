@@ -154,6 +154,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_flag_recent() {
+        let recent = Flag::system(Atom::try_from("Recent").unwrap());
+
+        let (_, got) = flag(b"\\Recent)").unwrap();
+        assert_eq!(got, recent);
+
+        let (_, got) = flag_list(b"(\\Seen \\Recent)").unwrap();
+        assert_eq!(got, vec![Flag::Seen, recent]);
+
+        let (_, got) = flag_fetch(b"\\Recent)").unwrap();
+        assert_eq!(got, FlagFetch::Recent);
+    }
+
     #[test]
     fn test_parse_flag_perm() {
         let tests = [
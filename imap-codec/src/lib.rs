@@ -19,15 +19,17 @@
 //! **Note:** Decoding IMAP traces is more elaborate than it seems on a first glance.
 //! Please consult the [`decode`](`crate::decode`) module documentation to learn how to handle real-world decoding.
 //!
+//! The codecs are the stable parsing API of imap-codec.
+//! The underlying nom parsers are private on purpose: their module layout follows the IMAP grammar and
+//! their error type exposes parser internals, so both may change with any release.
+//! Use the codecs, e.g., [`GreetingCodec`](crate::GreetingCodec) instead of a `greeting` parser.
+//!
 //! ### Example
 //!
 //! ```rust
 //! // Run 'cargo add imap-types' in your project's root directory.
 //! use imap_codec::{decode::Decoder, GreetingCodec};
-//! use imap_types::{
-//!     core::Text,
-//!     response::{Code, Greeting, GreetingKind},
-//! };
+//! use imap_types::response::{Code, Greeting};
 //!
 //! let (remaining, greeting) = GreetingCodec::default()
 //!     .decode(b"* OK [ALERT] Hello, World!\r\n<remaining>")
@@ -35,11 +37,7 @@
 //!
 //! assert_eq!(
 //!     greeting,
-//!     Greeting {
-//!         kind: GreetingKind::Ok,
-//!         code: Some(Code::Alert),
-//!         text: Text::try_from("Hello, World!").unwrap(),
-//!     }
+//!     Greeting::ok(Some(Code::Alert), "Hello, World!").unwrap()
 //! );
 //! assert_eq!(remaining, &b"<remaining>"[..])
 //! ```
@@ -56,16 +54,9 @@
 //! ```rust
 //! // Run 'cargo add imap-types' in your project's root directory.
 //! use imap_codec::{encode::Encoder, GreetingCodec};
-//! use imap_types::{
-//!     core::Text,
-//!     response::{Code, Greeting, GreetingKind},
-//! };
+//! use imap_types::response::{Code, Greeting};
 //!
-//! let greeting = Greeting {
-//!     kind: GreetingKind::Ok,
-//!     code: Some(Code::Alert),
-//!     text: Text::try_from("Hello, World!").unwrap(),
-//! };
+//! let greeting = Greeting::ok(Some(Code::Alert), "Hello, World!").unwrap();
 //!
 //! let bytes = GreetingCodec::default().encode(&greeting).dump();
 //!
@@ -78,11 +69,13 @@
 //!
 //! In addition, imap-codec defines the following features:
 //!
-//! | Feature               | Description                    | Enabled by default |
-//! |-----------------------|--------------------------------|--------------------|
-//! | quirk_crlf_relaxed    | Make `\r` in `\r\n` optional.  | No                 |
-//! | quirk_rectify_numbers | Rectify (invalid) numbers.     | No                 |
-//! | quirk_missing_text    | Rectify missing `text` element.| No                 |
+//! | Feature                 | Description                                                          | Enabled by default |
+//! |-------------------------|----------------------------------------------------------------------|--------------------|
+//! | quirk_crlf_relaxed      | Make `\r` in `\r\n` optional.                                        | No                 |
+//! | quirk_rectify_numbers   | Rectify (invalid) numbers.                                           | No                 |
+//! | quirk_missing_text      | Rectify missing `text` element.                                      | No                 |
+//! | quirk_missing_imap4rev1 | Accept CAPABILITY without `IMAP4rev1`.                               | No                 |
+//! | tokio                   | Support for tokio (`tokio_util::codec`, `ImapClient`, `ImapServer`). | No                 |
 //!
 //! ## Quirks
 //!
@@ -93,6 +86,9 @@
 //! imap-codec can't otherwise access their emails, we may add a `quirk_` feature to quickly resolve the problem.
 //! Of course, imap-codec should never violate the IMAP standard itself. So, we need to do this carefully.
 //!
+//! Note: Bare `\n` line endings can also be accepted per codec (instead of globally via `quirk_crlf_relaxed`)
+//! by setting `allow_lf_line_endings`, e.g., [`CommandCodec::allow_lf_line_endings`](crate::CommandCodec::allow_lf_line_endings).
+//!
 //! [imap-types]: https://docs.rs/imap-types/latest/imap_types
 //! [imap-types features]: https://docs.rs/imap-types/latest/imap_types/#features
 //! [IMAP4rev1]: https://tools.ietf.org/html/rfc3501
@@ -113,6 +109,7 @@ pub struct ReadmeDoctestsRoot;
 pub struct ReadmeDoctests;
 
 mod auth;
+mod base64;
 mod body;
 mod codec;
 mod command;
@@ -133,3 +130,35 @@ mod testing;
 pub use codec::*;
 // Re-export.
 pub use imap_types;
+
+// Features of imap-types that add enum variants must also be enabled in imap-codec. The enums are
+// `#[non_exhaustive]`, so a mismatch would otherwise only be detected when encoding a variant.
+macro_rules! assert_feature_forwarded {
+    ($feature:literal, $flag:ident) => {
+        #[cfg(not(feature = $feature))]
+        const _: () = assert!(
+            !imap_types::features::$flag,
+            concat!(
+                "`imap-types/",
+                $feature,
+                "` is enabled, but `imap-codec/",
+                $feature,
+                "` is not"
+            )
+        );
+    };
+}
+
+assert_feature_forwarded!("starttls", STARTTLS);
+assert_feature_forwarded!("ext_annotate", EXT_ANNOTATE);
+assert_feature_forwarded!("ext_appendlimit", EXT_APPENDLIMIT);
+assert_feature_forwarded!("ext_condstore_qresync", EXT_CONDSTORE_QRESYNC);
+assert_feature_forwarded!("ext_context", EXT_CONTEXT);
+assert_feature_forwarded!("ext_gmail", EXT_GMAIL);
+assert_feature_forwarded!("ext_login_referrals", EXT_LOGIN_REFERRALS);
+assert_feature_forwarded!("ext_mailbox_referrals", EXT_MAILBOX_REFERRALS);
+assert_feature_forwarded!("ext_id", EXT_ID);
+assert_feature_forwarded!("ext_metadata", EXT_METADATA);
+assert_feature_forwarded!("ext_notify", EXT_NOTIFY);
+assert_feature_forwarded!("ext_sort_thread", EXT_SORT_THREAD);
+assert_feature_forwarded!("ext_urlauth", EXT_URLAUTH);
@@ -15,7 +15,7 @@ use nom::{
 };
 
 #[cfg(feature = "ext_sort_thread")]
-use crate::extensions::thread::thread_data;
+use crate::extensions::{sort::sort_data, thread::thread_data};
 use crate::{
     core::{astring, nil, number, nz_number, quoted_char, string},
     decode::IMAPResult,
@@ -88,10 +88,7 @@ pub(crate) fn mailbox_data(input: &[u8]) -> IMAPResult<&[u8], Data> {
             |(_, nums)| Data::Search(nums),
         ),
         #[cfg(feature = "ext_sort_thread")]
-        map(
-            preceded(tag_no_case(b"SORT"), many0(preceded(sp, nz_number))),
-            Data::Sort,
-        ),
+        sort_data,
         #[cfg(feature = "ext_sort_thread")]
         thread_data,
         map(
@@ -145,7 +142,19 @@ pub(crate) fn mailbox_list(
 
 #[cfg(test)]
 mod tests {
+    use imap_types::{
+        core::{AString, Literal, Quoted, QuotedChar},
+        flag::FlagNameAttribute,
+        response::{Data, Response},
+        status::StatusDataItem,
+    };
+
     use super::*;
+    use crate::{
+        decode::{Decoder, ResponseDecodeError},
+        testing::kat_inverse_response,
+        ResponseCodec,
+    };
 
     #[test]
     fn test_mailbox() {
@@ -155,4 +164,98 @@ mod tests {
         assert!(mailbox(b"inbox.sent ").is_ok());
         assert!(mailbox(b"aaa").is_err());
     }
+
+    #[test]
+    fn test_mailbox_inbox_case_insensitive() {
+        for test in [
+            b"InBoX ".as_ref(),
+            b"inbox ",
+            b"\"INBOX\" ",
+            b"{5}\r\niNbOx ",
+        ] {
+            let (rem, got) = mailbox(test).unwrap();
+            assert_eq!(rem, b" ");
+            assert_eq!(got, Mailbox::Inbox);
+        }
+
+        let (_, got) = mailbox(b"inbox.sent ").unwrap();
+        assert_ne!(got, Mailbox::Inbox);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_lsub() {
+        kat_inverse_response(&[
+            (
+                b"* LSUB () \"/\" \"INBOX/Archive\"\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Lsub {
+                    items: vec![],
+                    delimiter: Some(QuotedChar::try_from('/').unwrap()),
+                    mailbox: Mailbox::from(AString::from(
+                        Quoted::try_from("INBOX/Archive").unwrap(),
+                    )),
+                }),
+            ),
+            (
+                b"* LSUB (\\Noselect) \"/\" Lists\r\n",
+                b"",
+                Response::Data(Data::Lsub {
+                    items: vec![FlagNameAttribute::Noselect],
+                    delimiter: Some(QuotedChar::try_from('/').unwrap()),
+                    mailbox: Mailbox::try_from("Lists").unwrap(),
+                }),
+            ),
+            (
+                b"* LIST () \"/\" \"INBOX/Archive\"\r\n",
+                b"",
+                Response::Data(Data::List {
+                    items: vec![],
+                    delimiter: Some(QuotedChar::try_from('/').unwrap()),
+                    mailbox: Mailbox::from(AString::from(
+                        Quoted::try_from("INBOX/Archive").unwrap(),
+                    )),
+                }),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_literal_mailbox() {
+        kat_inverse_response(&[
+            (
+                b"* LIST () \"/\" {11}\r\nfoo\r\n\"bar\"/\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::List {
+                    items: vec![],
+                    delimiter: Some(QuotedChar::try_from('/').unwrap()),
+                    mailbox: Mailbox::from(AString::from(
+                        Literal::try_from("foo\r\n\"bar\"/").unwrap(),
+                    )),
+                }),
+            ),
+            (
+                b"* LSUB () NIL {7}\r\nfoo bar\r\n",
+                b"",
+                Response::Data(Data::Lsub {
+                    items: vec![],
+                    delimiter: None,
+                    mailbox: Mailbox::from(AString::from(Literal::try_from("foo bar").unwrap())),
+                }),
+            ),
+            (
+                b"* STATUS {7}\r\nfoo bar (MESSAGES 1)\r\n",
+                b"",
+                Response::Data(Data::Status {
+                    mailbox: Mailbox::from(AString::from(Literal::try_from("foo bar").unwrap())),
+                    items: vec![StatusDataItem::Messages(1)].into(),
+                }),
+            ),
+        ]);
+
+        // A client must be told that it has to wait for the literal data.
+        assert_eq!(
+            ResponseCodec::default().decode(b"* LIST () \"/\" {11}\r\n"),
+            Err(ResponseDecodeError::LiteralFound { length: 11 })
+        );
+    }
 }
@@ -1,30 +1,44 @@
-#[cfg(not(feature = "quirk_crlf_relaxed"))]
-use abnf_core::streaming::crlf;
-#[cfg(feature = "quirk_crlf_relaxed")]
-use abnf_core::streaming::crlf_relaxed as crlf;
 use abnf_core::streaming::sp;
-use base64::{engine::general_purpose::STANDARD as _base64, Engine};
+#[cfg(feature = "quirk_missing_imap4rev1")]
+use imap_types::core::Vec1;
 use imap_types::{
-    core::{Text, Vec1},
+    core::Text,
     response::{
-        Bye, Capability, Code, CodeOther, CommandContinuationRequest, Data, Greeting, GreetingKind,
-        Response, Status, StatusBody, StatusKind, Tagged,
+        Bye, Capabilities, Capability, Code, CodeOther, CommandContinuationRequest, Data, Greeting,
+        GreetingKind, Response, Status, StatusBody, StatusKind, Tagged,
     },
 };
+#[cfg(not(feature = "quirk_missing_imap4rev1"))]
+use nom::combinator::map_opt;
 #[cfg(feature = "quirk_missing_text")]
 use nom::combinator::peek;
 use nom::{
     branch::alt,
     bytes::streaming::{tag, tag_no_case, take_until, take_while},
-    combinator::{map, map_res, opt, value},
+    combinator::{map, map_res, opt, value, verify},
     multi::separated_list1,
     sequence::{delimited, preceded, terminated, tuple},
 };
 
+#[cfg(feature = "ext_annotate")]
+use crate::extensions::annotate::annotate_code;
+#[cfg(feature = "ext_context")]
+use crate::extensions::context::esearch_response;
 #[cfg(feature = "ext_id")]
 use crate::extensions::id::id_response;
+#[cfg(feature = "ext_metadata")]
+use crate::extensions::metadata::{metadata_code, metadata_resp};
+#[cfg(feature = "ext_notify")]
+use crate::extensions::notify::events;
+#[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
+use crate::extensions::referral::imap_url;
+#[cfg(feature = "ext_urlauth")]
+use crate::extensions::urlauth::{genurlauth_data, urlfetch_data};
+#[cfg(feature = "ext_condstore_qresync")]
+use crate::sequence::sequence_set;
 use crate::{
-    core::{atom, charset, nz_number, tag_imap, text},
+    base64::decode_b64,
+    core::{atom, charset, line_ending, nz_number, tag_imap, text, with_allow_lf, x_extension},
     decode::IMAPResult,
     extensions::enable::enable_data,
     fetch::msg_att,
@@ -35,20 +49,22 @@ use crate::{
 // ----- greeting -----
 
 /// `greeting = "*" SP (resp-cond-auth / resp-cond-bye) CRLF`
-pub(crate) fn greeting(input: &[u8]) -> IMAPResult<&[u8], Greeting> {
-    let mut parser = tuple((
-        tag(b"*"),
-        sp,
-        alt((
-            resp_cond_auth,
-            map(resp_cond_bye, |resp_text| (GreetingKind::Bye, resp_text)),
-        )),
-        crlf,
-    ));
+pub(crate) fn greeting(allow_lf: bool) -> impl Fn(&[u8]) -> IMAPResult<&[u8], Greeting> {
+    move |input: &[u8]| {
+        let mut parser = tuple((
+            tag(b"*"),
+            sp,
+            alt((
+                resp_cond_auth,
+                map(resp_cond_bye, |resp_text| (GreetingKind::Bye, resp_text)),
+            )),
+            line_ending(allow_lf),
+        ));
 
-    let (remaining, (_, _, (kind, (code, text)), _)) = parser(input)?;
+        let (remaining, (_, _, (kind, (code, text)), _)) = parser(input)?;
 
-    Ok((remaining, Greeting { kind, code, text }))
+        Ok((remaining, Greeting { kind, code, text }))
+    }
 }
 
 /// `resp-cond-auth = ("OK" / "PREAUTH") SP resp-text`
@@ -87,7 +103,10 @@ pub(crate) fn resp_text(input: &[u8]) -> IMAPResult<&[u8], (Option<Code>, Text)>
                         terminated(resp_text_code, tag(b"]")),
                         map(
                             terminated(
-                                take_while(|b: u8| b != b']' && b != b'\r' && b != b'\n'),
+                                verify(
+                                    take_while(|b: u8| b != b']' && b != b'\r' && b != b'\n'),
+                                    |bytes: &[u8]| !is_uidvalidity_code(bytes),
+                                ),
                                 tag(b"]"),
                             ),
                             |bytes: &[u8]| Code::Other(CodeOther::unvalidated(bytes)),
@@ -101,7 +120,8 @@ pub(crate) fn resp_text(input: &[u8]) -> IMAPResult<&[u8], (Option<Code>, Text)>
             #[cfg(feature = "quirk_missing_text")]
             alt((
                 preceded(sp, text),
-                map(peek(crlf), |_| {
+                // Note: The line ending itself is checked later.
+                map(peek(line_ending(true)), |_| {
                     log::warn!("Rectified missing `text` to \"...\"");
 
                     Text::unvalidated("...")
@@ -113,6 +133,17 @@ pub(crate) fn resp_text(input: &[u8]) -> IMAPResult<&[u8], (Option<Code>, Text)>
     }
 }
 
+/// Whether `code` is a `UIDVALIDITY` response code.
+///
+/// An invalid `UIDVALIDITY`, e.g., `UIDVALIDITY 0`, must not fall back to [`Code::Other`] but is
+/// rejected. Other codes, e.g., `UIDNEXT 0` or `UNSEEN 0` (sent by some servers for empty
+/// mailboxes), are still passed through.
+fn is_uidvalidity_code(code: &[u8]) -> bool {
+    let name = code.split(|byte| *byte == b' ').next().unwrap_or_default();
+
+    name.eq_ignore_ascii_case(b"UIDVALIDITY")
+}
+
 /// `resp-text-code = "ALERT" /
 ///                   "BADCHARSET" [SP "(" charset *(SP charset) ")" ] /
 ///                   capability-data /
@@ -177,6 +208,26 @@ pub(crate) fn resp_text_code(input: &[u8]) -> IMAPResult<&[u8], Code> {
         value(Code::CompressionActive, tag_no_case(b"COMPRESSIONACTIVE")),
         value(Code::OverQuota, tag_no_case(b"OVERQUOTA")),
         value(Code::TooBig, tag_no_case(b"TOOBIG")),
+        // RFC 5465
+        #[cfg(feature = "ext_notify")]
+        value(
+            Code::NotificationOverflow,
+            tag_no_case(b"NOTIFICATIONOVERFLOW"),
+        ),
+        #[cfg(feature = "ext_notify")]
+        map(preceded(tag_no_case(b"BADEVENT "), events), Code::BadEvent),
+        // RFC 5464
+        #[cfg(feature = "ext_metadata")]
+        map(metadata_code, Code::Metadata),
+        // RFC 5257
+        #[cfg(feature = "ext_annotate")]
+        map(annotate_code, Code::Annotate),
+        // RFC 2221
+        #[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
+        map(
+            preceded(tuple((tag_no_case(b"REFERRAL"), sp)), imap_url),
+            |url| Code::Referral(Box::new(url)),
+        ),
     ))(input)
 }
 
@@ -184,16 +235,25 @@ pub(crate) fn resp_text_code(input: &[u8]) -> IMAPResult<&[u8], Code> {
 ///
 /// Servers MUST implement the STARTTLS, AUTH=PLAIN, and LOGINDISABLED capabilities
 /// Servers which offer RFC 1730 compatibility MUST list "IMAP4" as the first capability.
-pub(crate) fn capability_data(input: &[u8]) -> IMAPResult<&[u8], Vec1<Capability>> {
-    let mut parser = tuple((
+///
+/// Note: A later revision, e.g., "IMAP4rev2" (RFC 9051), is accepted instead of "IMAP4rev1". A list
+/// without any revision is only accepted with `quirk_missing_imap4rev1`.
+pub(crate) fn capability_data(input: &[u8]) -> IMAPResult<&[u8], Capabilities> {
+    let parser = tuple((
         tag_no_case("CAPABILITY"),
         sp,
         separated_list1(sp, capability),
     ));
 
-    let (rem, (_, _, caps)) = parser(input)?;
+    #[cfg(not(feature = "quirk_missing_imap4rev1"))]
+    let mut parser = map_opt(parser, |(_, _, caps)| Capabilities::try_from(caps).ok());
+
+    #[cfg(feature = "quirk_missing_imap4rev1")]
+    let mut parser = map(parser, |(_, _, caps)| {
+        Capabilities::unvalidated(Vec1::unvalidated(caps))
+    });
 
-    Ok((rem, Vec1::unvalidated(caps)))
+    parser(input)
 }
 
 /// `capability = ("AUTH=" auth-type) /
@@ -215,63 +275,85 @@ pub(crate) fn resp_cond_bye(input: &[u8]) -> IMAPResult<&[u8], (Option<Code>, Te
 // ----- response -----
 
 /// `response = *(continue-req / response-data) response-done`
-pub(crate) fn response(input: &[u8]) -> IMAPResult<&[u8], Response> {
-    // Divert from standard here for better usability.
-    // response_data already contains the bye response, thus
-    // response_done could also be response_tagged.
-    //
-    // However, I will keep it as it is for now.
-    alt((
-        map(continue_req, Response::CommandContinuationRequest),
-        response_data,
-        map(response_done, Response::Status),
-    ))(input)
+pub(crate) fn response(allow_lf: bool) -> impl Fn(&[u8]) -> IMAPResult<&[u8], Response> {
+    move |input: &[u8]| {
+        // Divert from standard here for better usability.
+        // response_data already contains the bye response, thus
+        // response_done could also be response_tagged.
+        //
+        // However, I will keep it as it is for now.
+        with_allow_lf(allow_lf, || {
+            alt((
+                map(continue_req(allow_lf), Response::CommandContinuationRequest),
+                response_data(allow_lf),
+                map(response_done(allow_lf), Response::Status),
+            ))(input)
+        })
+    }
 }
 
 /// `continue-req = "+" SP (resp-text / base64) CRLF`
-pub(crate) fn continue_req(input: &[u8]) -> IMAPResult<&[u8], CommandContinuationRequest> {
-    // We can't map the output of `resp_text` directly to `Continue::basic()` because we might end
-    // up with a subset of `Text` that is valid base64 and will panic on `unwrap()`. Thus, we first
-    // let the parsing finish and only later map to `Continue`.
-
-    // A helper struct to postpone the unification to `Continue` in the `alt` combinator below.
-    enum Either<A, B> {
-        Base64(A),
-        Basic(B),
-    }
+///
+/// Note: The payload is tried as `base64` first and falls back to `resp-text`. Thus, an empty
+/// payload, i.e., `+ \r\n`, is an empty `base64` blob, e.g., an empty SASL challenge. With
+/// `quirk_missing_text`, `+\r\n` (without the space) is accepted as well.
+pub(crate) fn continue_req(
+    allow_lf: bool,
+) -> impl Fn(&[u8]) -> IMAPResult<&[u8], CommandContinuationRequest> {
+    move |input: &[u8]| {
+        #[cfg(feature = "quirk_missing_text")]
+        {
+            let empty: IMAPResult<&[u8], _> = tuple((tag(b"+"), line_ending(allow_lf)))(input);
 
-    let mut parser = tuple((
-        tag(b"+ "),
-        alt((
-            #[cfg(not(feature = "quirk_crlf_relaxed"))]
-            map(
-                map_res(take_until("\r\n"), |input| _base64.decode(input)),
-                Either::Base64,
-            ),
-            #[cfg(feature = "quirk_crlf_relaxed")]
-            map(
-                map_res(take_until("\n"), |input: &[u8]| {
-                    if !input.is_empty() && input[input.len().saturating_sub(1)] == b'\r' {
-                        _base64.decode(&input[..input.len().saturating_sub(1)])
-                    } else {
-                        _base64.decode(input)
-                    }
-                }),
-                Either::Base64,
-            ),
-            map(resp_text, Either::Basic),
-        )),
-        crlf,
-    ));
+            if let Ok((remaining, _)) = empty {
+                log::warn!("Rectified missing `SP` in empty continuation request");
 
-    let (remaining, (_, either, _)) = parser(input)?;
+                return Ok((remaining, CommandContinuationRequest::base64(b"".as_ref())));
+            }
+        }
 
-    let continue_request = match either {
-        Either::Base64(data) => CommandContinuationRequest::base64(data),
-        Either::Basic((code, text)) => CommandContinuationRequest::basic(code, text).unwrap(),
-    };
+        // We can't map the output of `resp_text` directly to `Continue::basic()` because we might end
+        // up with a subset of `Text` that is valid base64 and will panic on `unwrap()`. Thus, we first
+        // let the parsing finish and only later map to `Continue`.
 
-    Ok((remaining, continue_request))
+        // A helper struct to postpone the unification to `Continue` in the `alt` combinator below.
+        enum Either<A, B> {
+            Base64(A),
+            Basic(B),
+        }
+
+        let mut parser = tuple((
+            tag(b"+ "),
+            alt((
+                map(
+                    map_res(
+                        |input| {
+                            if allow_lf || cfg!(feature = "quirk_crlf_relaxed") {
+                                map(take_until("\n"), |line: &[u8]| {
+                                    line.strip_suffix(b"\r").unwrap_or(line)
+                                })(input)
+                            } else {
+                                take_until("\r\n")(input)
+                            }
+                        },
+                        decode_b64,
+                    ),
+                    Either::Base64,
+                ),
+                map(resp_text, Either::Basic),
+            )),
+            line_ending(allow_lf),
+        ));
+
+        let (remaining, (_, either, _)) = parser(input)?;
+
+        let continue_request = match either {
+            Either::Base64(data) => CommandContinuationRequest::base64(data),
+            Either::Basic((code, text)) => CommandContinuationRequest::basic(code, text).unwrap(),
+        };
+
+        Ok((remaining, continue_request))
+    }
 }
 
 /// ```abnf
@@ -282,36 +364,53 @@ pub(crate) fn continue_req(input: &[u8]) -> IMAPResult<&[u8], CommandContinuatio
 ///                    message-data /
 ///                    capability-data /
 ///                    id_response ; (See RFC 2971)
+///                    metadata-resp ; (See RFC 5464)
 ///                  ) CRLF
 /// ```
-pub(crate) fn response_data(input: &[u8]) -> IMAPResult<&[u8], Response> {
-    let mut parser = tuple((
-        tag(b"*"),
-        sp,
-        alt((
-            map(resp_cond_state, |(kind, code, text)| {
-                Response::Status(Status::Untagged(StatusBody { kind, code, text }))
-            }),
-            map(resp_cond_bye, |(code, text)| {
-                Response::Status(Status::Bye(Bye { code, text }))
-            }),
-            map(mailbox_data, Response::Data),
-            map(message_data, Response::Data),
-            map(capability_data, |caps| {
-                Response::Data(Data::Capability(caps))
-            }),
-            map(enable_data, Response::Data),
-            #[cfg(feature = "ext_id")]
-            map(id_response, |parameters| {
-                Response::Data(Data::Id { parameters })
-            }),
-        )),
-        crlf,
-    ));
+pub(crate) fn response_data(allow_lf: bool) -> impl Fn(&[u8]) -> IMAPResult<&[u8], Response> {
+    move |input: &[u8]| {
+        let mut parser = tuple((
+            tag(b"*"),
+            sp,
+            alt((
+                map(resp_cond_state, |(kind, code, text)| {
+                    Response::Status(Status::Untagged(StatusBody { kind, code, text }))
+                }),
+                map(resp_cond_bye, |(code, text)| {
+                    Response::Status(Status::Bye(Bye { code, text }))
+                }),
+                map(mailbox_data, Response::Data),
+                map(message_data, Response::Data),
+                #[cfg(feature = "ext_condstore_qresync")]
+                map(expunged_resp, Response::Data),
+                map(capability_data, |caps| {
+                    Response::Data(Data::Capability(caps))
+                }),
+                map(enable_data, Response::Data),
+                #[cfg(feature = "ext_context")]
+                map(esearch_response, Response::Data),
+                #[cfg(feature = "ext_id")]
+                map(id_response, |parameters| {
+                    Response::Data(Data::Id { parameters })
+                }),
+                #[cfg(feature = "ext_metadata")]
+                map(metadata_resp, Response::Data),
+                #[cfg(feature = "ext_urlauth")]
+                map(genurlauth_data, Response::Data),
+                #[cfg(feature = "ext_urlauth")]
+                map(urlfetch_data, Response::Data),
+                // Note: Tried last, i.e., only when no other response matched.
+                map(x_extension, |(name, args)| {
+                    Response::Data(Data::Extension { name, args })
+                }),
+            )),
+            line_ending(allow_lf),
+        ));
 
-    let (remaining, (_, _, response, _)) = parser(input)?;
+        let (remaining, (_, _, response, _)) = parser(input)?;
 
-    Ok((remaining, response))
+        Ok((remaining, response))
+    }
 }
 
 /// `resp-cond-state = ("OK" / "NO" / "BAD") SP resp-text`
@@ -334,34 +433,63 @@ pub(crate) fn resp_cond_state(input: &[u8]) -> IMAPResult<&[u8], (StatusKind, Op
 }
 
 /// `response-done = response-tagged / response-fatal`
-pub(crate) fn response_done(input: &[u8]) -> IMAPResult<&[u8], Status> {
-    alt((response_tagged, response_fatal))(input)
+pub(crate) fn response_done(allow_lf: bool) -> impl Fn(&[u8]) -> IMAPResult<&[u8], Status> {
+    move |input: &[u8]| alt((response_tagged(allow_lf), response_fatal(allow_lf)))(input)
 }
 
 /// `response-tagged = tag SP resp-cond-state CRLF`
-pub(crate) fn response_tagged(input: &[u8]) -> IMAPResult<&[u8], Status> {
-    let mut parser = tuple((tag_imap, sp, resp_cond_state, crlf));
+pub(crate) fn response_tagged(allow_lf: bool) -> impl Fn(&[u8]) -> IMAPResult<&[u8], Status> {
+    move |input: &[u8]| {
+        let mut parser = tuple((tag_imap, sp, resp_cond_state, line_ending(allow_lf)));
 
-    let (remaining, (tag, _, (kind, code, text), _)) = parser(input)?;
+        let (remaining, (tag, _, (kind, code, text), _)) = parser(input)?;
 
-    Ok((
-        remaining,
-        Status::Tagged(Tagged {
-            tag,
-            body: StatusBody { kind, code, text },
-        }),
-    ))
+        Ok((
+            remaining,
+            Status::Tagged(Tagged {
+                tag,
+                body: StatusBody { kind, code, text },
+            }),
+        ))
+    }
 }
 
 /// `response-fatal = "*" SP resp-cond-bye CRLF`
 ///
 /// Server closes connection immediately
-pub(crate) fn response_fatal(input: &[u8]) -> IMAPResult<&[u8], Status> {
-    let mut parser = tuple((tag(b"*"), sp, resp_cond_bye, crlf));
+pub(crate) fn response_fatal(allow_lf: bool) -> impl Fn(&[u8]) -> IMAPResult<&[u8], Status> {
+    move |input: &[u8]| {
+        let mut parser = tuple((tag(b"*"), sp, resp_cond_bye, line_ending(allow_lf)));
 
-    let (remaining, (_, _, (code, text), _)) = parser(input)?;
+        let (remaining, (_, _, (code, text), _)) = parser(input)?;
 
-    Ok((remaining, Status::Bye(Bye { code, text })))
+        Ok((remaining, Status::Bye(Bye { code, text })))
+    }
+}
+
+/// ```abnf
+/// expunged-resp = "VANISHED" [SP "(EARLIER)"] SP known-uids
+///
+/// known-uids = sequence-set
+/// ```
+#[cfg(feature = "ext_condstore_qresync")]
+pub(crate) fn expunged_resp(input: &[u8]) -> IMAPResult<&[u8], Data> {
+    let mut parser = tuple((
+        tag_no_case(b"VANISHED"),
+        map(opt(tag_no_case(b" (EARLIER)")), |earlier| earlier.is_some()),
+        sp,
+        sequence_set,
+    ));
+
+    let (remaining, (_, earlier, _, known_uids)) = parser(input)?;
+
+    Ok((
+        remaining,
+        Data::Vanished {
+            earlier,
+            known_uids,
+        },
+    ))
 }
 
 /// `message-data = nz-number SP ("EXPUNGE" / ("FETCH" SP msg-att))`
@@ -382,16 +510,23 @@ mod tests {
     use std::num::NonZeroU32;
 
     use imap_types::{
+        auth::AuthMechanism,
         body::{
             BasicFields, Body, BodyExtension, BodyStructure, Disposition, Language, Location,
             SinglePartExtensionData, SpecificFields,
         },
-        core::{IString, NString, QuotedChar, Tag},
-        flag::FlagNameAttribute,
+        core::{ExtensionArguments, ExtensionName, IString, NString, QuotedChar, Tag, Vec1},
+        fetch::MessageDataItem,
+        flag::{Flag, FlagFetch, FlagNameAttribute},
     };
 
     use super::*;
-    use crate::testing::{kat_inverse_greeting, kat_inverse_response, known_answer_test_encode};
+    use crate::{
+        decode::Decoder,
+        encode::Encoder,
+        testing::{kat_inverse_greeting, kat_inverse_response, known_answer_test_encode},
+        GreetingCodec, ResponseCodec,
+    };
 
     #[test]
     fn test_kat_inverse_greeting() {
@@ -424,16 +559,60 @@ mod tests {
                 b"".as_ref(),
                 Greeting::new(GreetingKind::PreAuth, Some(Code::Alert), "hello").unwrap(),
             ),
+            (
+                b"* OK [CAPABILITY IMAP4rev1 AUTH=PLAIN] Ready\r\n".as_ref(),
+                b"".as_ref(),
+                Greeting::ok(
+                    Some(
+                        Code::capability(vec![
+                            Capability::Imap4Rev1,
+                            Capability::Auth(AuthMechanism::Plain),
+                        ])
+                        .unwrap(),
+                    ),
+                    "Ready",
+                )
+                .unwrap(),
+            ),
         ]);
     }
 
+    #[test]
+    fn test_greeting_capability_code() {
+        // The code uses the same parser as the CAPABILITY response.
+        let (_, greeting) = GreetingCodec::default()
+            .decode(b"* OK [CAPABILITY IMAP4rev1 STARTTLS AUTH=PLAIN] Ready\r\n")
+            .unwrap();
+        let (_, response) = ResponseCodec::default()
+            .decode(b"* CAPABILITY IMAP4rev1 STARTTLS AUTH=PLAIN\r\n")
+            .unwrap();
+
+        match (greeting.code, response) {
+            (Some(Code::Capability(from_code)), Response::Data(Data::Capability(from_data))) => {
+                assert_eq!(from_code, from_data)
+            }
+            unexpected => panic!("Unexpected: {unexpected:?}"),
+        }
+
+        // Without IMAP4rev1, this is not a (valid) CAPABILITY code.
+        #[cfg(not(feature = "quirk_missing_imap4rev1"))]
+        {
+            let (_, greeting) = GreetingCodec::default()
+                .decode(b"* OK [CAPABILITY STARTTLS] Ready\r\n")
+                .unwrap();
+            assert!(!matches!(greeting.code, Some(Code::Capability(_))));
+        }
+    }
+
     #[test]
     fn test_kat_inverse_response_data() {
         kat_inverse_response(&[
             (
                 b"* CAPABILITY IMAP4REV1\r\n".as_ref(),
                 b"".as_ref(),
-                Response::Data(Data::Capability(Vec1::from(Capability::Imap4Rev1))),
+                Response::Data(Data::Capability(
+                    Capabilities::try_from(Capability::Imap4Rev1).unwrap(),
+                )),
             ),
             (
                 b"* LIST (\\Noselect) \"/\" bbb\r\n",
@@ -468,6 +647,336 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_kat_inverse_response_data_message_status() {
+        kat_inverse_response(&[
+            (
+                b"* 0 EXISTS\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Exists(0)),
+            ),
+            (b"* 0 RECENT\r\n", b"", Response::Data(Data::Recent(0))),
+            (
+                b"* 4294967295 EXISTS\r\n",
+                b"",
+                Response::Data(Data::Exists(u32::MAX)),
+            ),
+            (
+                b"* 1 EXPUNGE\r\n",
+                b"",
+                Response::Data(Data::Expunge(NonZeroU32::new(1).unwrap())),
+            ),
+            (
+                b"* 3 FETCH (UID 42 FLAGS (\\Seen) RFC822.SIZE 1024)\r\n",
+                b"",
+                Response::Data(Data::Fetch {
+                    seq: NonZeroU32::new(3).unwrap(),
+                    items: Vec1::try_from(vec![
+                        MessageDataItem::Uid(NonZeroU32::new(42).unwrap()),
+                        MessageDataItem::Flags(vec![FlagFetch::Flag(Flag::Seen)]),
+                        MessageDataItem::Rfc822Size(1024),
+                    ])
+                    .unwrap(),
+                }),
+            ),
+        ]);
+
+        // EXPUNGE and FETCH refer to a message, so there is no message number 0.
+        for test in [
+            b"* 0 EXPUNGE\r\n".as_ref(),
+            b"* 0 FETCH (UID 42)\r\n".as_ref(),
+        ] {
+            assert!(ResponseCodec::default().decode(test).is_err());
+        }
+    }
+
+    #[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
+    #[test]
+    fn test_kat_inverse_response_referral() {
+        use imap_types::{
+            auth::AuthMechanism,
+            extensions::referral::{ImapUrl, UrlAuth},
+        };
+
+        kat_inverse_response(&[
+            (
+                b"A1 NO [REFERRAL imap://user;AUTH=*@SERVER2/] Remote Server\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Status(
+                    Status::no(
+                        Some(Tag::try_from("A1").unwrap()),
+                        Some(Code::Referral(Box::new(ImapUrl {
+                            user: Some("user".into()),
+                            auth: Some(UrlAuth::Any),
+                            ..ImapUrl::new("SERVER2")
+                        }))),
+                        "Remote Server",
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"* OK [REFERRAL imap://user;AUTH=GSSAPI@SERVER2/] Server not accepting logins\r\n",
+                b"",
+                Response::Status(
+                    Status::ok(
+                        None,
+                        Some(Code::Referral(Box::new(ImapUrl {
+                            user: Some("user".into()),
+                            auth: Some(UrlAuth::Mechanism(
+                                AuthMechanism::try_from("GSSAPI").unwrap(),
+                            )),
+                            ..ImapUrl::new("SERVER2")
+                        }))),
+                        "Server not accepting logins",
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"* NO [REFERRAL imap://user@example.com/INBOX;UIDVALIDITY=1/;UID=20] Moved\r\n",
+                b"",
+                Response::Status(
+                    Status::no(
+                        None,
+                        Some(Code::Referral(Box::new(ImapUrl {
+                            user: Some("user".into()),
+                            mailbox: Some("INBOX".into()),
+                            uid_validity: 1.try_into().ok(),
+                            uid: 20.try_into().ok(),
+                            ..ImapUrl::new("example.com")
+                        }))),
+                        "Moved",
+                    )
+                    .unwrap(),
+                ),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_capability_data_imap4rev1() {
+        assert!(capability_data(b"CAPABILITY IDLE IMAP4rev1\r\n").is_ok());
+
+        #[cfg(not(feature = "quirk_missing_imap4rev1"))]
+        assert!(capability_data(b"CAPABILITY IDLE\r\n").is_err());
+        #[cfg(feature = "quirk_missing_imap4rev1")]
+        assert!(capability_data(b"CAPABILITY IDLE\r\n").is_ok());
+    }
+
+    #[test]
+    fn test_decode_capability_imap4rev2_only() {
+        let expected = Capabilities::try_from(vec![
+            Capability::try_from("IMAP4rev2").unwrap(),
+            Capability::Idle,
+        ])
+        .unwrap();
+
+        let (_, response) = ResponseCodec::default()
+            .decode(b"* CAPABILITY IMAP4rev2 IDLE\r\n")
+            .unwrap();
+        assert_eq!(response, Response::Data(Data::Capability(expected)));
+
+        let (_, greeting) = GreetingCodec::default()
+            .decode(b"* OK [CAPABILITY IMAP4rev2 AUTH=PLAIN] ready\r\n")
+            .unwrap();
+        match greeting.code {
+            Some(Code::Capability(capabilities)) => {
+                assert!(capabilities.supports_auth(&AuthMechanism::Plain))
+            }
+            unexpected => panic!("Unexpected: {unexpected:?}"),
+        }
+    }
+
+    #[cfg(feature = "ext_condstore_qresync")]
+    #[test]
+    fn test_kat_inverse_response_data_vanished() {
+        use imap_types::sequence::SequenceSet;
+
+        kat_inverse_response(&[
+            (
+                b"* 5 EXPUNGE\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Expunge(NonZeroU32::new(5).unwrap())),
+            ),
+            (
+                b"* VANISHED 5\r\n",
+                b"",
+                Response::Data(Data::Vanished {
+                    earlier: false,
+                    known_uids: SequenceSet::try_from(5).unwrap(),
+                }),
+            ),
+            (
+                b"* VANISHED (EARLIER) 41,43:116,118\r\n",
+                b"",
+                Response::Data(Data::Vanished {
+                    earlier: true,
+                    known_uids: SequenceSet::try_from("41,43:116,118").unwrap(),
+                }),
+            ),
+        ]);
+
+        for test in [b"* 5 EXPUNGE\r\n".as_ref(), b"* VANISHED 5\r\n"] {
+            let (_, Response::Data(data)) = response(false)(test).unwrap() else {
+                panic!("expected data");
+            };
+            assert!(data.is_expunge_like());
+        }
+
+        assert!(!Data::Exists(5).is_expunge_like());
+    }
+
+    #[test]
+    fn test_kat_inverse_response_data_idle() {
+        let capabilities = vec![Capability::Imap4Rev1, Capability::Idle, Capability::Move];
+
+        kat_inverse_response(&[(
+            b"* CAPABILITY IMAP4rev1 IDLE MOVE\r\n".as_ref(),
+            b"".as_ref(),
+            Response::Data(Data::capability(capabilities.clone()).unwrap()),
+        )]);
+
+        let capabilities = Capabilities::try_from(capabilities).unwrap();
+        assert!(capabilities.supports_idle());
+    }
+
+    #[cfg(feature = "starttls")]
+    #[test]
+    fn test_kat_inverse_response_data_starttls() {
+        let capabilities = vec![
+            Capability::Imap4Rev1,
+            Capability::StartTls,
+            Capability::LoginDisabled,
+        ];
+
+        kat_inverse_response(&[(
+            b"* CAPABILITY IMAP4rev1 STARTTLS LOGINDISABLED\r\n".as_ref(),
+            b"".as_ref(),
+            Response::Data(Data::capability(capabilities.clone()).unwrap()),
+        )]);
+
+        let capabilities = Capabilities::try_from(capabilities).unwrap();
+        assert!(capabilities.contains(&Capability::StartTls));
+        assert!(!capabilities.supports_login());
+    }
+
+    #[cfg(feature = "ext_appendlimit")]
+    #[test]
+    fn test_kat_inverse_response_data_appendlimit() {
+        use imap_types::{mailbox::Mailbox, status::StatusDataItem};
+
+        kat_inverse_response(&[
+            (
+                b"* CAPABILITY IMAP4REV1 APPENDLIMIT=35651584\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(
+                    Data::capability(vec![
+                        Capability::Imap4Rev1,
+                        Capability::AppendLimit(Some(35651584)),
+                    ])
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"* CAPABILITY IMAP4REV1 APPENDLIMIT\r\n",
+                b"",
+                Response::Data(
+                    Data::capability(vec![Capability::Imap4Rev1, Capability::AppendLimit(None)])
+                        .unwrap(),
+                ),
+            ),
+            (
+                b"* STATUS INBOX (APPENDLIMIT 257890)\r\n",
+                b"",
+                Response::Data(Data::Status {
+                    mailbox: Mailbox::Inbox,
+                    items: vec![StatusDataItem::AppendLimit(Some(257890))].into(),
+                }),
+            ),
+            (
+                b"* STATUS INBOX (APPENDLIMIT NIL)\r\n",
+                b"",
+                Response::Data(Data::Status {
+                    mailbox: Mailbox::Inbox,
+                    items: vec![StatusDataItem::AppendLimit(None)].into(),
+                }),
+            ),
+            // RFC 7889 reuses the TOOBIG response code of RFC 4469.
+            (
+                b"A NO [TOOBIG] Message too large\r\n",
+                b"",
+                Response::Status(
+                    Status::no(
+                        Some(Tag::try_from("A").unwrap()),
+                        Some(Code::TooBig),
+                        "Message too large",
+                    )
+                    .unwrap(),
+                ),
+            ),
+        ]);
+
+        // Non-canonical values can't be encoded losslessly and are kept as-is.
+        assert!(matches!(
+            Capability::try_from("APPENDLIMIT=01").unwrap(),
+            Capability::Other(_)
+        ));
+    }
+
+    #[test]
+    fn test_kat_inverse_response_code_alert_parse_trycreate_capability() {
+        kat_inverse_response(&[
+            (
+                b"* OK [ALERT] System going down\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Status(Status::ok(None, Some(Code::Alert), "System going down").unwrap()),
+            ),
+            (
+                b"* BAD [PARSE] Invalid header\r\n",
+                b"",
+                Response::Status(Status::bad(None, Some(Code::Parse), "Invalid header").unwrap()),
+            ),
+            (
+                b"A NO [TRYCREATE] No such mailbox\r\n",
+                b"",
+                Response::Status(
+                    Status::no(
+                        Some(Tag::try_from("A").unwrap()),
+                        Some(Code::TryCreate),
+                        "No such mailbox",
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"A OK [CAPABILITY IMAP4rev1 IDLE AUTH=PLAIN] Logged in\r\n",
+                b"",
+                Response::Status(
+                    Status::ok(
+                        Some(Tag::try_from("A").unwrap()),
+                        Some(
+                            Code::capability(vec![
+                                Capability::Imap4Rev1,
+                                Capability::Idle,
+                                Capability::Auth(AuthMechanism::Plain),
+                            ])
+                            .unwrap(),
+                        ),
+                        "Logged in",
+                    )
+                    .unwrap(),
+                ),
+            ),
+        ]);
+
+        let (_, response) = response(false)(b"* OK [ALERT] System going down\r\n").unwrap();
+        let Response::Status(status) = response else {
+            panic!("expected status, got {response:?}");
+        };
+        assert_eq!(status.alert_text().unwrap().as_ref(), "System going down");
+    }
+
     #[test]
     fn test_kat_inverse_response_status() {
         kat_inverse_response(&[
@@ -569,6 +1078,98 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_kat_inverse_bye() {
+        // At connection startup, BYE means "connection refused" ...
+        kat_inverse_greeting(&[(
+            b"* BYE [ALERT] Connection refused\r\n".as_ref(),
+            b"".as_ref(),
+            Greeting::bye(Some(Code::Alert), "Connection refused").unwrap(),
+        )]);
+
+        // ... and later, it means "session ends".
+        kat_inverse_response(&[(
+            b"* BYE Server shutting down\r\n".as_ref(),
+            b"".as_ref(),
+            Response::Status(Status::bye(None, "Server shutting down").unwrap()),
+        )]);
+
+        let (_, response) = ResponseCodec::default()
+            .decode(b"* BYE Server shutting down\r\n")
+            .unwrap();
+        assert!(response.is_bye());
+
+        let (_, response) = ResponseCodec::default()
+            .decode(b"* OK Still there\r\n")
+            .unwrap();
+        assert!(!response.is_bye());
+    }
+
+    #[test]
+    fn test_kat_inverse_response_continue() {
+        kat_inverse_response(&[
+            (
+                b"+ \r\n".as_ref(),
+                b"".as_ref(),
+                Response::CommandContinuationRequest(CommandContinuationRequest::base64(
+                    b"".as_ref(),
+                )),
+            ),
+            (
+                b"+ YQ==\r\n?",
+                b"?",
+                Response::CommandContinuationRequest(CommandContinuationRequest::base64(
+                    b"a".as_ref(),
+                )),
+            ),
+            (
+                b"+ hello\r\n",
+                b"",
+                Response::CommandContinuationRequest(
+                    CommandContinuationRequest::basic(None, "hello").unwrap(),
+                ),
+            ),
+            (
+                b"+ [READ-WRITE] hello\r\n",
+                b"",
+                Response::CommandContinuationRequest(
+                    CommandContinuationRequest::basic(Some(Code::ReadWrite), "hello").unwrap(),
+                ),
+            ),
+            // RFC 2195
+            (
+                b"+ PDE4OTYuNjk3MTcwOTUyQHBvc3RvZmZpY2UucmVzdG9uLm1jaS5uZXQ+\r\n",
+                b"",
+                Response::CommandContinuationRequest(CommandContinuationRequest::base64(
+                    b"<1896.697170952@postoffice.reston.mci.net>".as_ref(),
+                )),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_continue_req_empty() {
+        assert_eq!(
+            continue_req(false)(b"+ \r\n").unwrap(),
+            (
+                b"".as_ref(),
+                CommandContinuationRequest::base64(b"".as_ref())
+            )
+        );
+
+        #[cfg(feature = "quirk_missing_text")]
+        assert_eq!(
+            continue_req(false)(b"+\r\n").unwrap(),
+            (
+                b"".as_ref(),
+                CommandContinuationRequest::base64(b"".as_ref())
+            )
+        );
+
+        #[cfg(not(feature = "quirk_missing_text"))]
+        assert!(continue_req(false)(b"+\r\n").is_err());
+    }
+
     /*
     // TODO(#184)
     #[test]
@@ -680,8 +1281,169 @@ mod tests {
         ];
 
         for test in tests {
-            assert!(response(test).is_err());
+            assert!(response(false)(test).is_err());
+        }
+    }
+
+    #[test]
+    fn test_parse_resp_text_code_nz_number() {
+        assert_eq!(
+            resp_text(b"[UIDVALIDITY 3857529045] UIDs valid\r\n")
+                .unwrap()
+                .1
+                 .0,
+            Some(Code::uidvalidity(3857529045).unwrap())
+        );
+
+        for test in [
+            b"[UIDVALIDITY 0] UIDs valid\r\n".as_ref(),
+            b"[uidvalidity 0] UIDs valid\r\n",
+            b"[UIDVALIDITY] UIDs valid\r\n",
+        ] {
+            assert!(resp_text(test).is_err());
+        }
+
+        assert!(response(false)(b"* OK [UIDVALIDITY 0] UIDs valid\r\n").is_err());
+        assert!(Code::uidvalidity(0).is_err());
+
+        // Some servers send these for empty mailboxes, so they don't fail the whole response.
+        for (test, expected) in [
+            (
+                b"[uidnext 0] Predicted next UID\r\n".as_ref(),
+                b"uidnext 0".as_ref(),
+            ),
+            (b"[UNSEEN 0] Message 0 is first unseen\r\n", b"UNSEEN 0"),
+        ] {
+            assert_eq!(
+                resp_text(test).unwrap().1 .0,
+                Some(Code::Other(CodeOther::unvalidated(expected)))
+            );
         }
+        assert!(response(false)(b"* OK [UIDNEXT 0] Predicted next UID\r\n").is_ok());
+
+        // Unknown codes are still passed through.
+        assert_eq!(
+            resp_text(b"[X-UIDVALIDITY 0] ...\r\n").unwrap().1 .0,
+            Some(Code::Other(CodeOther::unvalidated(
+                b"X-UIDVALIDITY 0".as_ref()
+            )))
+        );
+    }
+
+    #[test]
+    fn test_code_other() {
+        let tests = [
+            (
+                b"* OK [XVENDOR FOO BAR] ...\r\n".as_ref(),
+                b"XVENDOR".as_ref(),
+                Some(b"FOO BAR".as_ref()),
+            ),
+            // Arguments are kept verbatim.
+            (
+                b"* OK [XVENDOR FOO  (BAR)] ...\r\n",
+                b"XVENDOR",
+                Some(b"FOO  (BAR)"),
+            ),
+            // Known prefix, unknown suffix.
+            (
+                b"* OK [READ-WRITE-LATER] ...\r\n",
+                b"READ-WRITE-LATER",
+                None,
+            ),
+            (b"* OK [UIDNEXTX 5] ...\r\n", b"UIDNEXTX", Some(b"5")),
+        ];
+
+        for (test, name, arguments) in tests {
+            let (rem, got) = response(false)(test).unwrap();
+            assert!(rem.is_empty());
+
+            let Response::Status(Status::Untagged(StatusBody {
+                code: Some(Code::Other(ref other)),
+                ..
+            })) = got
+            else {
+                panic!("expected `Code::Other`, got {got:?}");
+            };
+            assert_eq!(other.name(), name);
+            assert_eq!(other.arguments(), arguments);
+
+            assert_eq!(ResponseCodec::default().encode(&got).dump(), test);
+        }
+    }
+
+    #[test]
+    fn test_kat_inverse_response_unknown_extension() {
+        kat_inverse_response(&[
+            (
+                b"* OK [X-VENDOR-THING foo] ...\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Status(
+                    Status::ok(
+                        None,
+                        Some(Code::Other(CodeOther::unvalidated(
+                            b"X-VENDOR-THING foo".as_ref(),
+                        ))),
+                        "...",
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"* CAPABILITY IMAP4REV1 X-VENDOR-THING\r\n",
+                b"",
+                Response::Data(
+                    Data::capability(vec![
+                        Capability::Imap4Rev1,
+                        Capability::try_from("X-VENDOR-THING").unwrap(),
+                    ])
+                    .unwrap(),
+                ),
+            ),
+        ]);
+
+        assert!(matches!(
+            Capability::try_from("X-VENDOR-THING").unwrap(),
+            Capability::Other(_)
+        ));
+    }
+
+    #[test]
+    fn test_kat_inverse_response_data_extension() {
+        let tests = [
+            (
+                b"* XLIST (\\Inbox) \"/\" \"INBOX\"\r\n".as_ref(),
+                Data::Extension {
+                    name: ExtensionName::try_from("XLIST").unwrap(),
+                    args: ExtensionArguments::try_from(b"(\\Inbox) \"/\" \"INBOX\"".as_ref())
+                        .unwrap(),
+                },
+            ),
+            (
+                b"* X-GM-EXT-1  \xc3\xa4 (\r\n",
+                Data::Extension {
+                    name: ExtensionName::try_from("X-GM-EXT-1").unwrap(),
+                    args: ExtensionArguments::try_from(b" \xc3\xa4 (".as_ref()).unwrap(),
+                },
+            ),
+            (
+                b"* XNOARGS\r\n",
+                Data::Extension {
+                    name: ExtensionName::try_from("XNOARGS").unwrap(),
+                    args: ExtensionArguments::default(),
+                },
+            ),
+        ];
+
+        for (test, expected) in tests {
+            kat_inverse_response(&[(test, b"", Response::Data(expected.clone()))]);
+            // The arguments must round-trip byte-exact.
+            known_answer_test_encode((Response::Data(expected), test));
+        }
+
+        // Unknown responses not starting with "X" are still rejected.
+        assert!(ResponseCodec::default().decode(b"* FOO bar\r\n").is_err());
+        // Literals are not supported.
+        assert!(ResponseCodec::default().decode(b"* XFOO {5}\r\n").is_err());
     }
 
     #[test]
@@ -12,6 +12,8 @@ use nom::{
     sequence::{delimited, tuple},
 };
 
+#[cfg(feature = "ext_annotate")]
+use crate::extensions::annotate::att_search;
 use crate::{
     core::{astring, atom, charset, number},
     datetime::date,
@@ -211,6 +213,13 @@ fn search_key_limited<'a>(
                 |(_, _, val)| SearchKey::Uid(val),
             ),
             value(SearchKey::Undraft, tag_no_case(b"UNDRAFT")),
+            #[cfg(feature = "ext_gmail")]
+            map(
+                tuple((tag_no_case(b"X-GM-RAW"), sp, astring)),
+                |(_, _, val)| SearchKey::GmailRaw(val),
+            ),
+            #[cfg(feature = "ext_annotate")]
+            att_search,
             map(sequence_set, SearchKey::SequenceSet),
             map(
                 delimited(tag(b"("), separated_list1(sp, search_key), tag(b")")),
@@ -240,13 +249,16 @@ pub(crate) fn search_criteria(input: &[u8]) -> IMAPResult<&[u8], (Charset, Vec1<
 #[cfg(test)]
 mod tests {
     use imap_types::{
-        core::{AString, Atom},
+        command::Command,
+        core::{AString, Atom, Charset, IString, Literal, Quoted},
         datetime::NaiveDate,
+        flag::Flag,
+        response::{Code, Response, Status},
         sequence::{Sequence, SequenceSet},
     };
 
     use super::*;
-    use crate::testing::known_answer_test_encode;
+    use crate::testing::{kat_inverse_command, kat_inverse_response, known_answer_test_encode};
 
     #[test]
     fn test_parse_search() {
@@ -433,4 +445,249 @@ mod tests {
             known_answer_test_encode(test);
         }
     }
+
+    #[test]
+    fn test_kat_inverse_command_search_combinators() {
+        let from = SearchKey::From(AString::from(Quoted::try_from("a").unwrap()));
+
+        kat_inverse_command(&[
+            (
+                b"A SEARCH OR (FROM \"a\") (NOT (SEEN))\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::search(
+                        None,
+                        Vec1::from(SearchKey::or(
+                            SearchKey::and(from.clone()).unwrap(),
+                            SearchKey::and(SearchKey::not(
+                                SearchKey::and(SearchKey::Seen).unwrap(),
+                            ))
+                            .unwrap(),
+                        )),
+                        false,
+                    ),
+                )
+                .unwrap(),
+            ),
+            // Top-level keys are ANDed implicitly, i.e., without parentheses.
+            (
+                b"A SEARCH FROM \"a\" NOT SEEN KEYWORD $Forwarded\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::search(
+                        None,
+                        SearchKey::and(vec![
+                            from,
+                            SearchKey::not(SearchKey::try_from(Flag::Seen).unwrap()),
+                            SearchKey::try_from(Flag::Keyword(
+                                Atom::try_from("$Forwarded").unwrap(),
+                            ))
+                            .unwrap(),
+                        ])
+                        .unwrap(),
+                        false,
+                    ),
+                )
+                .unwrap(),
+            ),
+        ]);
+
+        assert!(SearchKey::try_from(Flag::system(Atom::try_from("Foo").unwrap())).is_err());
+    }
+
+    #[test]
+    fn test_kat_inverse_search_sequence_set() {
+        let search = |criteria: Vec<SearchKey<'static>>, uid: bool| {
+            Command::new(
+                "A",
+                CommandBody::search(None, Vec1::try_from(criteria).unwrap(), uid),
+            )
+            .unwrap()
+        };
+
+        kat_inverse_command(&[
+            // A leading digit (or `*`) starts a bare sequence set, which is encoded without keyword.
+            (
+                b"A SEARCH 1,3,5:* UNDELETED\r\n".as_ref(),
+                b"".as_ref(),
+                search(
+                    vec![
+                        SearchKey::SequenceSet(SequenceSet::try_from("1,3,5:*").unwrap()),
+                        SearchKey::Undeleted,
+                    ],
+                    false,
+                ),
+            ),
+            (
+                b"A SEARCH UNSEEN *:100\r\n",
+                b"",
+                search(
+                    vec![
+                        SearchKey::Unseen,
+                        SearchKey::SequenceSet(SequenceSet::try_from("*:100").unwrap()),
+                    ],
+                    false,
+                ),
+            ),
+            (
+                b"A UID SEARCH UID 1:100 NOT 42\r\n",
+                b"",
+                search(
+                    vec![
+                        SearchKey::Uid(SequenceSet::try_from("1:100").unwrap()),
+                        SearchKey::not(SearchKey::SequenceSet(SequenceSet::try_from(42).unwrap())),
+                    ],
+                    true,
+                ),
+            ),
+        ]);
+
+        // `UNDELETED` is a key, not a (broken) sequence set.
+        assert_eq!(
+            search_key(9)(b"UNDELETED ").unwrap().1,
+            SearchKey::Undeleted
+        );
+        // A broken sequence set is not mistaken for a key, i.e., the command fails later.
+        assert_eq!(
+            search_key(9)(b"1:UNDELETED ").unwrap(),
+            (
+                b":UNDELETED ".as_ref(),
+                SearchKey::SequenceSet(SequenceSet::try_from(1).unwrap())
+            )
+        );
+    }
+
+    #[test]
+    fn test_kat_inverse_command_search_builder() {
+        kat_inverse_command(&[
+            (
+                b"A SEARCH UNSEEN\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new("A", CommandBody::search(None, SearchKey::Unseen, false)).unwrap(),
+            ),
+            // Multiple keys end up at the top level ...
+            (
+                b"A UID SEARCH UID 1:100 UNSEEN NOT DELETED\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::search(
+                        None,
+                        SearchKey::and(vec![
+                            SearchKey::uid(SequenceSet::try_from("1:100").unwrap()),
+                            SearchKey::Unseen,
+                            SearchKey::not(SearchKey::Deleted),
+                        ])
+                        .unwrap(),
+                        true,
+                    ),
+                )
+                .unwrap(),
+            ),
+            // ... but nested keys are kept in parentheses.
+            (
+                b"A SEARCH (SEEN FLAGGED)\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::search(
+                        None,
+                        Vec1::from(
+                            SearchKey::and(vec![SearchKey::Seen, SearchKey::Flagged]).unwrap(),
+                        ),
+                        false,
+                    ),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_search_header() {
+        let header = |field: AString<'static>, value: AString<'static>| {
+            Command::new(
+                "A",
+                CommandBody::search(None, Vec1::from(SearchKey::Header(field, value)), false),
+            )
+            .unwrap()
+        };
+
+        kat_inverse_command(&[
+            // Note: `<` and `@` are atom chars, so no quoting is required.
+            (
+                b"A SEARCH HEADER Message-ID <abc@x>\r\n".as_ref(),
+                b"".as_ref(),
+                header(
+                    AString::try_from("Message-ID").unwrap(),
+                    AString::try_from("<abc@x>").unwrap(),
+                ),
+            ),
+            (
+                b"A SEARCH HEADER \"Message-ID\" \"<abc@x> (1)\"\r\n",
+                b"",
+                header(
+                    AString::from(Quoted::try_from("Message-ID").unwrap()),
+                    AString::from(Quoted::try_from("<abc@x> (1)").unwrap()),
+                ),
+            ),
+            (
+                b"A SEARCH HEADER X-Foo {4}\r\na\r\nb\r\n",
+                b"",
+                header(
+                    AString::try_from("X-Foo").unwrap(),
+                    AString::from(Literal::try_from(b"a\r\nb".as_ref()).unwrap()),
+                ),
+            ),
+        ]);
+
+        // A value with specials is quoted, and a value with CR/LF is sent as literal.
+        assert!(matches!(
+            AString::try_from("<abc@x> (1)").unwrap(),
+            AString::String(IString::Quoted(_))
+        ));
+        assert!(matches!(
+            AString::try_from("a\r\nb").unwrap(),
+            AString::String(IString::Literal(_))
+        ));
+    }
+
+    #[test]
+    fn test_kat_inverse_search_charset() {
+        kat_inverse_command(&[(
+            "A SEARCH CHARSET UTF-8 TEXT {8}\r\nrésumé\r\n".as_bytes(),
+            b"".as_ref(),
+            Command::new(
+                "A",
+                CommandBody::search(
+                    Some(Charset::try_from("UTF-8").unwrap()),
+                    Vec1::from(SearchKey::Text(AString::from(
+                        Literal::try_from("résumé").unwrap(),
+                    ))),
+                    false,
+                ),
+            )
+            .unwrap(),
+        )]);
+
+        kat_inverse_response(&[(
+            b"A NO [BADCHARSET (UTF-8 \"US-ASCII\")] ...\r\n".as_ref(),
+            b"".as_ref(),
+            Response::Status(
+                Status::no(
+                    Some("A".try_into().unwrap()),
+                    Some(Code::BadCharset {
+                        allowed: vec![
+                            Charset::try_from("UTF-8").unwrap(),
+                            Charset::from(Quoted::try_from("US-ASCII").unwrap()),
+                        ],
+                    }),
+                    "...",
+                )
+                .unwrap(),
+            ),
+        )]);
+    }
 }
@@ -83,6 +83,8 @@ pub(crate) fn seq_number(input: &[u8]) -> IMAPResult<&[u8], SeqOrUid> {
 
 #[cfg(test)]
 mod tests {
+    use std::num::NonZeroU32;
+
     use super::*;
     use crate::encode::{EncodeContext, EncodeIntoContext};
 
@@ -109,6 +111,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_encode_sequence_set_from_uids() {
+        let uids = [1, 2, 3, 5].map(|uid| NonZeroU32::new(uid).unwrap());
+
+        let mut ctx = EncodeContext::new();
+        SequenceSet::from_uids(uids)
+            .unwrap()
+            .encode_ctx(&mut ctx)
+            .unwrap();
+
+        assert_eq!(ctx.dump(), b"1:3,5");
+    }
+
     #[test]
     fn test_parse_sequence_set() {
         let (rem, val) = sequence_set(b"1:*?").unwrap();
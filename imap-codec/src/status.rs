@@ -8,6 +8,8 @@ use nom::{
     sequence::tuple,
 };
 
+#[cfg(feature = "ext_appendlimit")]
+use crate::core::nil;
 use crate::{
     core::{number, number64, nz_number},
     decode::IMAPResult,
@@ -35,6 +37,8 @@ pub(crate) fn status_att(input: &[u8]) -> IMAPResult<&[u8], StatusDataItemName>
             StatusDataItemName::HighestModSeq,
             tag_no_case(b"HIGHESTMODSEQ"),
         ),
+        #[cfg(feature = "ext_appendlimit")]
+        value(StatusDataItemName::AppendLimit, tag_no_case(b"APPENDLIMIT")),
     ))(input)
 }
 
@@ -51,6 +55,10 @@ pub(crate) fn status_att_list(input: &[u8]) -> IMAPResult<&[u8], Vec<StatusDataI
 ///                    ("UIDVALIDITY" SP nz-number) /
 ///                    ("UNSEEN" SP number)`
 ///
+/// ```abnf
+/// status-att-val =/ "APPENDLIMIT" SP (number / nil) ; RFC 7889
+/// ```
+///
 /// Note: See errata id: 261
 fn status_att_val(input: &[u8]) -> IMAPResult<&[u8], StatusDataItem> {
     alt((
@@ -82,6 +90,15 @@ fn status_att_val(input: &[u8]) -> IMAPResult<&[u8], StatusDataItem> {
             tuple((tag_no_case(b"DELETED"), sp, number)),
             |(_, _, num)| StatusDataItem::Deleted(num),
         ),
+        #[cfg(feature = "ext_appendlimit")]
+        map(
+            tuple((
+                tag_no_case(b"APPENDLIMIT"),
+                sp,
+                alt((map(number, Some), value(None, nil))),
+            )),
+            |(_, _, limit)| StatusDataItem::AppendLimit(limit),
+        ),
     ))(input)
 }
 
@@ -89,8 +106,14 @@ fn status_att_val(input: &[u8]) -> IMAPResult<&[u8], StatusDataItem> {
 mod tests {
     use std::num::NonZeroU32;
 
+    use imap_types::response::{Data, Response};
+
     use super::*;
-    use crate::testing::known_answer_test_encode;
+    use crate::{
+        decode::Decoder,
+        testing::{kat_inverse_response, known_answer_test_encode},
+        ResponseCodec,
+    };
 
     #[test]
     fn test_encode_status_data_item_name() {
@@ -134,4 +157,44 @@ mod tests {
             known_answer_test_encode(test);
         }
     }
+
+    #[test]
+    fn test_kat_inverse_response_status() {
+        kat_inverse_response(&[
+            (
+                b"* STATUS INBOX (MESSAGES 0 UNSEEN 0)\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Status {
+                    mailbox: "INBOX".try_into().unwrap(),
+                    items: vec![StatusDataItem::Messages(0), StatusDataItem::Unseen(0)].into(),
+                }),
+            ),
+            // The order is preserved.
+            (
+                b"* STATUS INBOX (UNSEEN 0 UIDVALIDITY 1 RECENT 0 UIDNEXT 7 MESSAGES 0)\r\n",
+                b"",
+                Response::Data(Data::Status {
+                    mailbox: "INBOX".try_into().unwrap(),
+                    items: vec![
+                        StatusDataItem::Unseen(0),
+                        StatusDataItem::UidValidity(NonZeroU32::new(1).unwrap()),
+                        StatusDataItem::Recent(0),
+                        StatusDataItem::UidNext(NonZeroU32::new(7).unwrap()),
+                        StatusDataItem::Messages(0),
+                    ]
+                    .into(),
+                }),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_status_zero_uid() {
+        for input in [
+            b"* STATUS INBOX (UIDNEXT 0)\r\n".as_ref(),
+            b"* STATUS INBOX (MESSAGES 0 UIDVALIDITY 0)\r\n",
+        ] {
+            assert!(ResponseCodec::default().decode(input).is_err());
+        }
+    }
 }
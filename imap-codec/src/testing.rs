@@ -4,14 +4,15 @@ use imap_types::{
     auth::AuthenticateData,
     command::Command,
     extensions::idle::IdleDone,
-    response::{Greeting, Response},
+    response::{CommandContinuationRequest, Greeting, Response},
     utils::escape_byte_string,
 };
 
 use crate::{
     decode::{Decoder, IMAPResult},
     encode::{EncodeContext, EncodeIntoContext},
-    AuthenticateDataCodec, CommandCodec, GreetingCodec, IdleDoneCodec, ResponseCodec,
+    AuthenticateDataCodec, CommandCodec, GreetingCodec, IdleAcceptCodec, IdleDoneCodec,
+    ResponseCodec,
 };
 
 pub(crate) fn known_answer_test_encode(
@@ -83,6 +84,7 @@ impl_kat_inverse! {kat_inverse_response, ResponseCodec, Response}
 //impl_kat_inverse! {kat_inverse_continue, ContinueCodec, Continue}
 impl_kat_inverse! {kat_inverse_authenticate_data, AuthenticateDataCodec, AuthenticateData}
 impl_kat_inverse! {kat_inverse_done, IdleDoneCodec, IdleDone}
+impl_kat_inverse! {kat_inverse_idle_accept, IdleAcceptCodec, CommandContinuationRequest}
 
 #[cfg(test)]
 mod tests {
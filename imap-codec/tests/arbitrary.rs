@@ -0,0 +1,111 @@
+//! Messages generated by `Arbitrary` must survive an encode/decode round-trip.
+//!
+//! This is what differential fuzzing relies on: Every generated message is valid and encodable.
+
+use arbitrary::{Arbitrary, Error, Unstructured};
+use imap_codec::{
+    decode::Decoder,
+    encode::Encoder,
+    imap_types::{
+        auth::AuthenticateData,
+        command::Command,
+        response::{Greeting, Response},
+        utils::escape_byte_string,
+    },
+    AuthenticateDataCodec, CommandCodec, GreetingCodec, ResponseCodec,
+};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+macro_rules! impl_test_to_bytes_and_back {
+    ($codec:ty, $object:ty) => {
+        impl_test_to_bytes_and_back!($codec, $object, |_| {})
+    };
+    ($codec:ty, $object:ty, $normalize:expr) => {{
+        let mut rng = SmallRng::seed_from_u64(1337);
+        let mut data = [0u8; 1024];
+        let mut count = 0;
+
+        while count < 5_000 {
+            rng.try_fill(&mut data[..]).unwrap();
+            let mut u = Unstructured::new(&data);
+
+            let mut object = match <$object>::arbitrary(&mut u) {
+                Ok(object) => object,
+                Err(Error::NotEnoughData | Error::IncorrectFormat) => continue,
+                Err(error) => panic!("{error}"),
+            };
+            count += 1;
+
+            let codec = <$codec>::default();
+            let encoded = codec.encode(&object).dump();
+            $normalize(&mut object);
+
+            match codec.decode(&encoded) {
+                Ok((rem, decoded)) => {
+                    assert!(rem.is_empty(), "{}", escape_byte_string(&encoded));
+                    assert_eq!(object, decoded, "{}", escape_byte_string(&encoded));
+                }
+                Err(error) => panic!(
+                    "Failed to decode {}: {error:?}\n{object:#?}",
+                    escape_byte_string(&encoded)
+                ),
+            }
+        }
+    }};
+}
+
+#[test]
+fn test_arbitrary_command_to_bytes_and_back() {
+    impl_test_to_bytes_and_back!(CommandCodec, Command, |_command: &mut Command| {
+        // Note: `ID ()` is encoded as `ID NIL`.
+        #[cfg(all(feature = "ext_id", feature = "quirk_id_empty_to_nil"))]
+        {
+            use imap_codec::imap_types::command::CommandBody;
+
+            if let CommandBody::Id { parameters } = &mut _command.body {
+                if matches!(parameters, Some(parameters) if parameters.is_empty()) {
+                    *parameters = None;
+                }
+            }
+        }
+    });
+}
+
+#[test]
+fn test_arbitrary_response_to_bytes_and_back() {
+    impl_test_to_bytes_and_back!(ResponseCodec, Response, |_response: &mut Response| {
+        // Note: The `(MODSEQ n)` trailer of `* SORT` requires at least one number.
+        #[cfg(all(feature = "ext_sort_thread", feature = "ext_condstore_qresync"))]
+        {
+            use imap_codec::imap_types::response::Data;
+
+            if let Response::Data(Data::Sort { ids, modseq }) = _response {
+                if ids.is_empty() {
+                    *modseq = None;
+                }
+            }
+        }
+
+        // Note: `* ID ()` is encoded as `* ID NIL`.
+        #[cfg(all(feature = "ext_id", feature = "quirk_id_empty_to_nil"))]
+        {
+            use imap_codec::imap_types::response::Data;
+
+            if let Response::Data(Data::Id { parameters }) = _response {
+                if matches!(parameters, Some(parameters) if parameters.is_empty()) {
+                    *parameters = None;
+                }
+            }
+        }
+    });
+}
+
+#[test]
+fn test_arbitrary_greeting_to_bytes_and_back() {
+    impl_test_to_bytes_and_back!(GreetingCodec, Greeting);
+}
+
+#[test]
+fn test_arbitrary_authenticate_data_to_bytes_and_back() {
+    impl_test_to_bytes_and_back!(AuthenticateDataCodec, AuthenticateData);
+}
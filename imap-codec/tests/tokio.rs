@@ -0,0 +1,440 @@
+use std::num::NonZeroU32;
+
+use futures::{SinkExt, StreamExt};
+use imap_codec::{
+    decode::{AuthenticateDataDecodeError, CommandDecodeError},
+    imap_types::{
+        auth::{AuthMechanism, AuthenticateData},
+        command::{Command, CommandBody},
+        core::{AString, Literal, NString, Vec1},
+        extensions::idle::IdleDone,
+        fetch::{MessageDataItem, MessageDataItemName},
+        response::{CommandContinuationRequest, Data, Greeting, GreetingKind, Response, Status},
+    },
+    tokio::{
+        ClientMessage, ClientMessageDecodeError, ImapClient, ImapCommandCodec, ImapResponseCodec,
+        ImapServer, TokioCodecError,
+    },
+    CommandCodec,
+};
+use tokio::{
+    io::{duplex, split, AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+use tokio_util::codec::{Decoder, FramedRead, FramedWrite};
+
+#[tokio::test]
+async fn test_tokio_duplex() {
+    // A small buffer forces partial reads.
+    let (client, server) = duplex(16);
+
+    let (client_read, client_write) = split(client);
+    let mut client_read = FramedRead::new(client_read, ImapResponseCodec::default());
+    let mut client_write = FramedWrite::new(client_write, ImapCommandCodec::default());
+
+    let (server_read, server_write) = split(server);
+    let mut server_read = FramedRead::new(server_read, ImapCommandCodec::default());
+    let mut server_write = FramedWrite::new(server_write, ImapResponseCodec::default());
+
+    let login = Command::new(
+        "A1",
+        CommandBody::login(
+            AString::from(Literal::try_from_non_sync("alice").unwrap()),
+            "password",
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let fetch = Command::new(
+        "A2",
+        CommandBody::fetch(
+            "1",
+            vec![MessageDataItemName::BodyExt {
+                section: None,
+                partial: None,
+                peek: false,
+            }],
+            false,
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    let greeting = Greeting::new(GreetingKind::Ok, None, "ready").unwrap();
+    let body = b"Subject: Hello\r\n\r\nThis literal is longer than the buffer.\r\n".as_ref();
+    let responses = [
+        Response::Status(Status::ok(Some("A1".try_into().unwrap()), None, "logged in").unwrap()),
+        Response::Data(Data::Fetch {
+            seq: NonZeroU32::new(1).unwrap(),
+            items: Vec1::from(MessageDataItem::BodyExt {
+                section: None,
+                origin: None,
+                data: NString::from(Literal::try_from(body).unwrap()),
+            }),
+        }),
+        Response::Status(Status::ok(Some("A2".try_into().unwrap()), None, "done").unwrap()),
+    ];
+
+    let server = async {
+        server_write.send(&greeting).await.unwrap();
+
+        assert_eq!(server_read.next().await.unwrap().unwrap(), login);
+        server_write.send(&responses[0]).await.unwrap();
+
+        assert_eq!(server_read.next().await.unwrap().unwrap(), fetch);
+        server_write.send(&responses[1]).await.unwrap();
+        server_write.send(&responses[2]).await.unwrap();
+    };
+
+    let client = async {
+        assert_eq!(
+            client_read.next().await.unwrap().unwrap(),
+            Response::Status(Status::ok(None, None, "ready").unwrap())
+        );
+
+        client_write.send(&login).await.unwrap();
+        assert_eq!(client_read.next().await.unwrap().unwrap(), responses[0]);
+
+        client_write.send(&fetch).await.unwrap();
+        assert_eq!(client_read.next().await.unwrap().unwrap(), responses[1]);
+        assert_eq!(client_read.next().await.unwrap().unwrap(), responses[2]);
+    };
+
+    tokio::join!(server, client);
+}
+
+#[tokio::test]
+async fn test_tokio_decode_failed() {
+    let (mut client, server) = duplex(64);
+    let mut server = FramedRead::new(server, ImapCommandCodec::default());
+
+    client
+        .write_all(b"A1 INVALID\r\nA2 NOOP\r\n")
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        server.next().await.unwrap(),
+        Err(TokioCodecError::Decode(_))
+    ));
+
+    // The invalid line was discarded, so the next command is decoded on a fresh codec.
+    let mut codec = ImapCommandCodec::default();
+    let mut buffer = server.read_buffer().clone();
+    assert_eq!(
+        codec.decode(&mut buffer).unwrap(),
+        Some(Command::new("A2", CommandBody::Noop).unwrap())
+    );
+}
+
+#[tokio::test]
+async fn test_tokio_decode_literal_too_long() {
+    let mut codec = CommandCodec::default();
+    codec.max_literal_length = 4;
+
+    // The client waits for a response, so the announcement is discarded.
+    let (mut client, server) = duplex(64);
+    let mut server = FramedRead::new(server, ImapCommandCodec::new(codec));
+
+    client.write_all(b"A1 LOGIN {5}\r\n").await.unwrap();
+    assert!(matches!(
+        server.next().await.unwrap(),
+        Err(TokioCodecError::Decode(
+            CommandDecodeError::LiteralTooLong { .. }
+        ))
+    ));
+    assert!(server.read_buffer().is_empty());
+
+    // The client already sends the literal data, so nothing is discarded.
+    let mut codec = CommandCodec::default();
+    codec.max_literal_length = 4;
+
+    let (mut client, server) = duplex(64);
+    let mut server = FramedRead::new(server, ImapCommandCodec::new(codec));
+
+    client
+        .write_all(b"A1 LOGIN {13+}\r\nA2 DELETE x\r\n x\r\n")
+        .await
+        .unwrap();
+    assert!(matches!(
+        server.next().await.unwrap(),
+        Err(TokioCodecError::Decode(
+            CommandDecodeError::LiteralTooLong { .. }
+        ))
+    ));
+    assert!(server.read_buffer().starts_with(b"A1 LOGIN {13+}\r\n"));
+}
+
+#[tokio::test]
+async fn test_tokio_client_server_sync_literal() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    // A password that can't be sent as quoted string requires a (synchronizing) literal.
+    let login = Command::new(
+        "A1",
+        CommandBody::login(
+            "alice",
+            Literal::try_from(b"pass\"word\\".as_ref()).unwrap(),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let ok = Response::Status(Status::ok(Some("A1".try_into().unwrap()), None, "welcome").unwrap());
+
+    let server = async {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut server = ImapServer::new(stream);
+
+        server
+            .send(Greeting::new(GreetingKind::Ok, None, "ready").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(
+            server.next().await.unwrap().unwrap(),
+            ClientMessage::Command(login.clone())
+        );
+        server.send(ok.clone()).await.unwrap();
+    };
+
+    let client = async {
+        let mut client = ImapClient::new(TcpStream::connect(address).await.unwrap());
+
+        assert_eq!(
+            client.next().await.unwrap().unwrap(),
+            Response::Status(Status::ok(None, None, "ready").unwrap())
+        );
+        client.send(login.clone()).await.unwrap();
+        assert_eq!(client.next().await.unwrap().unwrap(), ok);
+    };
+
+    tokio::join!(server, client);
+}
+
+#[tokio::test]
+async fn test_tokio_client_sync_literal_rejected() {
+    let (client, server) = duplex(64);
+    let mut client = ImapClient::new(client);
+    let mut server = BufReader::new(server);
+
+    let login = Command::new(
+        "A1",
+        CommandBody::login("alice", Literal::try_from("secret").unwrap()).unwrap(),
+    )
+    .unwrap();
+
+    let server = async {
+        let mut line = String::new();
+        server.read_line(&mut line).await.unwrap();
+        assert_eq!(line, "A1 LOGIN alice {6}\r\n");
+
+        server
+            .write_all(b"* OK still there\r\nA1 NO no literals, please\r\n")
+            .await
+            .unwrap();
+
+        // The literal data must not be sent.
+        server.into_inner()
+    };
+
+    let client = async {
+        client.send(login).await.unwrap();
+
+        assert_eq!(
+            client.next().await.unwrap().unwrap(),
+            Response::Status(Status::ok(None, None, "still there").unwrap())
+        );
+        assert_eq!(
+            client.next().await.unwrap().unwrap(),
+            Response::Status(
+                Status::no(Some("A1".try_into().unwrap()), None, "no literals, please").unwrap()
+            )
+        );
+
+        client
+            .send(Command::new("A2", CommandBody::Noop).unwrap())
+            .await
+            .unwrap();
+    };
+
+    let (mut server, ()) = tokio::join!(server, client);
+
+    let mut line = String::new();
+    BufReader::new(&mut server)
+        .read_line(&mut line)
+        .await
+        .unwrap();
+    assert_eq!(line, "A2 NOOP\r\n");
+}
+
+#[tokio::test]
+async fn test_tokio_server_empty_sync_literal() {
+    let (client, server) = duplex(64);
+    let mut server = ImapServer::new(server);
+    let mut client = BufReader::new(client);
+
+    client.write_all(b"A1 LOGIN alice {0}\r\n").await.unwrap();
+
+    let server = async {
+        assert_eq!(
+            server.next().await.unwrap().unwrap(),
+            ClientMessage::Command(
+                Command::new(
+                    "A1",
+                    CommandBody::login("alice", Literal::try_from("").unwrap()).unwrap()
+                )
+                .unwrap()
+            )
+        );
+        server
+            .send(Response::Status(
+                Status::ok(Some("A1".try_into().unwrap()), None, "done").unwrap(),
+            ))
+            .await
+            .unwrap();
+    };
+
+    let client = async {
+        let mut line = String::new();
+        client.read_line(&mut line).await.unwrap();
+        assert_eq!(line, "+ Ready for literal data\r\n");
+
+        client.write_all(b"\r\n").await.unwrap();
+
+        // Only a single continuation request is sent.
+        let mut line = String::new();
+        client.read_line(&mut line).await.unwrap();
+        assert_eq!(line, "A1 OK done\r\n");
+    };
+
+    tokio::join!(server, client);
+}
+
+#[tokio::test]
+async fn test_tokio_client_server_authenticate() {
+    let (client, server) = duplex(64);
+    let mut client = ImapClient::new(client);
+    let mut server = ImapServer::new(server);
+
+    let authenticate = Command::new("A1", CommandBody::authenticate(AuthMechanism::Plain)).unwrap();
+    let data = AuthenticateData::Continue(b"\x00alice\x00password".to_vec().into());
+    let challenge =
+        Response::CommandContinuationRequest(CommandContinuationRequest::base64(b"?".as_ref()));
+    let bad =
+        Response::Status(Status::bad(Some("A1".try_into().unwrap()), None, "cancelled").unwrap());
+
+    let server = async {
+        assert_eq!(
+            server.next().await.unwrap().unwrap(),
+            ClientMessage::Command(authenticate.clone())
+        );
+        server.expect_authenticate_data();
+        server.send(challenge.clone()).await.unwrap();
+        assert_eq!(
+            server.next().await.unwrap().unwrap(),
+            ClientMessage::AuthenticateData(data.clone())
+        );
+        server.expect_authenticate_data();
+        server.send(challenge.clone()).await.unwrap();
+        assert_eq!(
+            server.next().await.unwrap().unwrap(),
+            ClientMessage::AuthenticateData(AuthenticateData::Cancel)
+        );
+        server.send(bad.clone()).await.unwrap();
+
+        // Commands are decoded again.
+        assert_eq!(
+            server.next().await.unwrap().unwrap(),
+            ClientMessage::Command(Command::new("A2", CommandBody::Noop).unwrap())
+        );
+    };
+
+    let client = async {
+        client.send(authenticate.clone()).await.unwrap();
+        assert_eq!(client.next().await.unwrap().unwrap(), challenge);
+        client.send(data.clone()).await.unwrap();
+        assert_eq!(client.next().await.unwrap().unwrap(), challenge);
+        client.send(AuthenticateData::Cancel).await.unwrap();
+        assert_eq!(client.next().await.unwrap().unwrap(), bad);
+        client
+            .send(Command::new("A2", CommandBody::Noop).unwrap())
+            .await
+            .unwrap();
+    };
+
+    tokio::join!(server, client);
+}
+
+#[tokio::test]
+async fn test_tokio_server_authenticate_data_bad_base64() {
+    let (mut client, server) = duplex(64);
+    let mut server = ImapServer::new(server);
+
+    client
+        .write_all(b"A1 AUTHENTICATE PLAIN\r\n")
+        .await
+        .unwrap();
+    assert!(matches!(
+        server.next().await.unwrap().unwrap(),
+        ClientMessage::Command(_)
+    ));
+
+    server.expect_authenticate_data();
+    client.write_all(b"A\r\nA2 NOOP\r\n").await.unwrap();
+    assert!(matches!(
+        server.next().await.unwrap(),
+        Err(TokioCodecError::Decode(
+            ClientMessageDecodeError::AuthenticateData(AuthenticateDataDecodeError::BadBase64)
+        ))
+    ));
+
+    // The offending line was discarded and commands are decoded again.
+    assert_eq!(
+        server.next().await.unwrap().unwrap(),
+        ClientMessage::Command(Command::new("A2", CommandBody::Noop).unwrap())
+    );
+}
+
+#[tokio::test]
+async fn test_tokio_client_server_idle() {
+    let (client, server) = duplex(64);
+    let mut client = ImapClient::new(client);
+    let mut server = ImapServer::new(server);
+
+    let idle = Command::new("A1", CommandBody::Idle).unwrap();
+    let idling = Response::CommandContinuationRequest(CommandContinuationRequest::idling());
+    let ok = Response::Status(Status::ok(Some("A1".try_into().unwrap()), None, "done").unwrap());
+
+    let server = async {
+        assert_eq!(
+            server.next().await.unwrap().unwrap(),
+            ClientMessage::Command(idle.clone())
+        );
+        server.expect_idle_done();
+        server.send(idling.clone()).await.unwrap();
+        assert_eq!(
+            server.next().await.unwrap().unwrap(),
+            ClientMessage::IdleDone(IdleDone)
+        );
+        server.send(ok.clone()).await.unwrap();
+        assert_eq!(
+            server.next().await.unwrap().unwrap(),
+            ClientMessage::Command(Command::new("A2", CommandBody::Noop).unwrap())
+        );
+    };
+
+    let client = async {
+        client.send(idle.clone()).await.unwrap();
+        assert_eq!(client.next().await.unwrap().unwrap(), idling);
+        // Pipelined with the next command.
+        client.feed(IdleDone).await.unwrap();
+        client
+            .feed(Command::new("A2", CommandBody::Noop).unwrap())
+            .await
+            .unwrap();
+        SinkExt::<Command>::flush(&mut client).await.unwrap();
+        assert_eq!(client.next().await.unwrap().unwrap(), ok);
+    };
+
+    tokio::join!(server, client);
+}
@@ -1227,6 +1227,30 @@ S: a1 OK Conditional Store enabled
     test_lines_of_trace(trace);
 }
 
+#[test]
+fn test_transcript_from_rfc9208() {
+    let trace = br##"C: G0001 GETQUOTA "!partition/sda4"
+S: * QUOTA "!partition/sda4" (STORAGE 104 10923847)
+S: G0001 OK Getquota complete
+C: G0002 GETQUOTAROOT INBOX
+S: * QUOTAROOT INBOX "#user/alice" "!partition/sda4"
+S: * QUOTA "#user/alice" (MESSAGE 42 1000)
+S: * QUOTA "!partition/sda4" (STORAGE 104 10923847)
+S: G0002 OK Getquotaroot complete
+C: G0003 GETQUOTAROOT comp.mail.mime
+S: * QUOTAROOT comp.mail.mime
+S: G0003 OK Getquotaroot complete
+C: S0000 GETQUOTA "#user/alice"
+S: * QUOTA "#user/alice" (STORAGE 54 111 MESSAGE 42 1000)
+S: S0000 OK Getquota completed
+C: S0001 SETQUOTA "#user/alice" (STORAGE 510)
+S: * QUOTA "#user/alice" (STORAGE 58 512)
+S: S0001 OK Rounded quota
+"##;
+
+    test_lines_of_trace(trace);
+}
+
 #[test]
 fn test_response_status_ok() {
     let trace = br#"S: * OK IMAP4rev1 server ready
@@ -1,10 +1,30 @@
+use alloc::{boxed::Box, string::String, vec::Vec};
+#[cfg(any(
+    feature = "ext_login_referrals",
+    feature = "ext_mailbox_referrals",
+    feature = "ext_urlauth"
+))]
+use core::num::NonZeroU32;
+
 use arbitrary::{Arbitrary, Unstructured};
 use chrono::{FixedOffset, TimeZone};
 
+#[cfg(feature = "ext_metadata")]
+use crate::extensions::metadata::MetadataEntry;
+#[cfg(feature = "ext_notify")]
+use crate::extensions::notify::Event;
+#[cfg(any(
+    feature = "ext_login_referrals",
+    feature = "ext_mailbox_referrals",
+    feature = "ext_urlauth"
+))]
+use crate::extensions::referral::{ImapUrl, UrlAuth};
 #[cfg(feature = "ext_sort_thread")]
 use crate::extensions::sort::SortAlgorithm;
 #[cfg(feature = "ext_sort_thread")]
 use crate::extensions::thread::ThreadingAlgorithm;
+#[cfg(feature = "ext_urlauth")]
+use crate::extensions::urlauth::{UrlAuthAccess, UrlAuthMechanism, UrlAuthorization};
 use crate::{
     auth::AuthMechanism,
     body::{
@@ -12,8 +32,8 @@ use crate::{
         SinglePartExtensionData, SpecificFields,
     },
     core::{
-        AString, Atom, AtomExt, IString, Literal, LiteralMode, NString, Quoted, QuotedChar, Tag,
-        Text, Vec1, Vec2,
+        AString, Atom, AtomExt, ExtensionArguments, ExtensionName, IString, Literal, LiteralMode,
+        NString, Quoted, QuotedChar, Tag, Text, Vec1, Vec2,
     },
     datetime::{DateTime, NaiveDate},
     envelope::Envelope,
@@ -21,11 +41,13 @@ use crate::{
     flag::{Flag, FlagNameAttribute},
     mailbox::{ListCharString, Mailbox, MailboxOther},
     response::{
-        Bye, Capability, Code, CodeOther, CommandContinuationRequestBasic, Greeting, GreetingKind,
-        Status, StatusBody, StatusKind, Tagged,
+        Bye, Capabilities, Capability, Code, CodeOther, CommandContinuationRequest,
+        CommandContinuationRequestBasic, Greeting, GreetingKind, Status, StatusBody, StatusKind,
+        Tagged,
     },
     search::SearchKey,
     sequence::SequenceSet,
+    utils::indicators::{is_astring_char, is_atom_char, is_text_char},
 };
 
 macro_rules! implement_tryfrom {
@@ -57,11 +79,41 @@ macro_rules! implement_tryfrom_t {
     };
 }
 
-implement_tryfrom! { Atom<'a>, &str }
-implement_tryfrom! { AtomExt<'a>, &str }
-implement_tryfrom! { Quoted<'a>, &str }
-implement_tryfrom! { Tag<'a>, &str }
-implement_tryfrom! { Text<'a>, &str }
+/// Generates a string with at least `min` characters, all of which satisfy `is_valid`.
+///
+/// Drawing characters from the allowed alphabet (instead of rejecting invalid input) produces a
+/// valid instance for (almost) every input and keeps the fuzzer productive.
+fn arbitrary_string(
+    u: &mut Unstructured,
+    min: usize,
+    is_valid: fn(u8) -> bool,
+) -> arbitrary::Result<String> {
+    let alphabet: Vec<char> = (0x00u8..=0x7f)
+        .filter(|byte| is_valid(*byte))
+        .map(char::from)
+        .collect();
+
+    let len = u.arbitrary_len::<u8>()?.max(min);
+
+    (0..len).map(|_| u.choose(&alphabet).copied()).collect()
+}
+
+macro_rules! implement_valid_string {
+    ($target:ty, $min:expr, $is_valid:expr) => {
+        impl<'a> Arbitrary<'a> for $target {
+            fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+                // Safety: `arbitrary_string` only yields valid values.
+                Ok(<$target>::try_from(arbitrary_string(u, $min, $is_valid)?).unwrap())
+            }
+        }
+    };
+}
+
+implement_valid_string! { Atom<'a>, 1, is_atom_char }
+implement_valid_string! { AtomExt<'a>, 1, is_astring_char }
+implement_valid_string! { Quoted<'a>, 0, is_text_char }
+implement_valid_string! { Tag<'a>, 1, |byte| is_astring_char(byte) && byte != b'+' }
+implement_valid_string! { Text<'a>, 1, is_text_char }
 implement_tryfrom! { ListCharString<'a>, &str }
 implement_tryfrom! { QuotedChar, char }
 implement_tryfrom! { Mailbox<'a>, &str }
@@ -72,6 +124,10 @@ implement_tryfrom! { MailboxOther<'a>, AString<'a> }
 implement_tryfrom! { CapabilityEnable<'a>, &str }
 implement_tryfrom! { Resource<'a>, &str }
 implement_tryfrom! { AuthMechanism<'a>, &str }
+#[cfg(feature = "ext_metadata")]
+implement_tryfrom! { MetadataEntry<'a>, AString<'a> }
+#[cfg(feature = "ext_notify")]
+implement_tryfrom! { Event<'a>, Atom<'a> }
 #[cfg(feature = "ext_sort_thread")]
 implement_tryfrom! { SortAlgorithm<'a>, Atom<'a> }
 implement_tryfrom_t! { Vec1<T>, Vec<T> }
@@ -79,6 +135,20 @@ implement_tryfrom_t! { Vec2<T>, Vec<T> }
 #[cfg(feature = "ext_sort_thread")]
 implement_tryfrom! { ThreadingAlgorithm<'a>, Atom<'a> }
 
+impl<'a> Arbitrary<'a> for Capabilities<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut capabilities = Vec::<Capability>::arbitrary(u)?;
+
+        // A set must contain `IMAP4rev1` (or another IMAP revision).
+        if Capabilities::validate(&capabilities).is_err() {
+            let at = u.int_in_range(0..=capabilities.len())?;
+            capabilities.insert(at, Capability::Imap4Rev1);
+        }
+
+        Ok(Capabilities::try_from(capabilities).unwrap())
+    }
+}
+
 impl<'a> Arbitrary<'a> for CommandContinuationRequestBasic<'a> {
     fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
         Self::new(Option::<Code>::arbitrary(u)?, Text::arbitrary(u)?)
@@ -86,6 +156,15 @@ impl<'a> Arbitrary<'a> for CommandContinuationRequestBasic<'a> {
     }
 }
 
+impl<'a> Arbitrary<'a> for CommandContinuationRequest<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(match bool::arbitrary(u)? {
+            true => Self::Basic(CommandContinuationRequestBasic::arbitrary(u)?),
+            false => Self::Base64(Vec::<u8>::arbitrary(u)?.into()),
+        })
+    }
+}
+
 // TODO(#301): This is due to the `Code`/`Text` ambiguity.
 impl<'a> Arbitrary<'a> for Greeting<'a> {
     fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
@@ -177,6 +256,23 @@ impl<'a> Arbitrary<'a> for Literal<'a> {
     }
 }
 
+impl<'a> Arbitrary<'a> for ExtensionName<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut name = String::from("X");
+        name.push_str(&arbitrary_string(u, 1, is_atom_char)?);
+
+        // Safety: `arbitrary_string` only yields valid atoms.
+        Ok(ExtensionName::try_from(name).unwrap())
+    }
+}
+
+impl<'a> Arbitrary<'a> for ExtensionArguments<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        ExtensionArguments::try_from(<&[u8]>::arbitrary(u)?)
+            .map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
 impl<'a> Arbitrary<'a> for CodeOther<'a> {
     fn arbitrary(_: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
         // `CodeOther` is a fallback and should usually not be created.
@@ -471,6 +567,108 @@ impl<'a> Arbitrary<'a> for NaiveDate {
     }
 }
 
+#[cfg(any(
+    feature = "ext_login_referrals",
+    feature = "ext_mailbox_referrals",
+    feature = "ext_urlauth"
+))]
+impl<'a> Arbitrary<'a> for UrlAuth<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(match bool::arbitrary(u)? {
+            true => Self::Any,
+            false => {
+                let mechanism = arbitrary_string(u, 1, is_atom_char)?;
+
+                // Safety: `arbitrary_string` only yields valid atoms.
+                Self::Mechanism(AuthMechanism::from(Atom::try_from(mechanism).unwrap()))
+            }
+        })
+    }
+}
+
+// Note: Only URLs that survive an encode/decode round trip are generated, e.g., the UID requires a
+//       mailbox. Textual components are arbitrary because they are percent-encoded.
+#[cfg(any(
+    feature = "ext_login_referrals",
+    feature = "ext_mailbox_referrals",
+    feature = "ext_urlauth"
+))]
+impl<'a> Arbitrary<'a> for ImapUrl<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut url = ImapUrl::new(arbitrary_string(u, 1, |_| true)?);
+
+        if bool::arbitrary(u)? {
+            url.user = Some(arbitrary_string(u, 1, |_| true)?.into());
+        }
+        url.auth = Option::<UrlAuth>::arbitrary(u)?;
+        url.port = Option::<u16>::arbitrary(u)?;
+
+        if bool::arbitrary(u)? {
+            url.mailbox = Some(arbitrary_string(u, 1, |_| true)?.into());
+            url.uid_validity = Option::<NonZeroU32>::arbitrary(u)?;
+            url.uid = Option::<NonZeroU32>::arbitrary(u)?;
+
+            if url.uid.is_some() && bool::arbitrary(u)? {
+                url.section = Some(arbitrary_string(u, 1, |_| true)?.into());
+            }
+        }
+
+        #[cfg(feature = "ext_urlauth")]
+        {
+            if bool::arbitrary(u)? {
+                url.expire = Some(arbitrary_string(u, 1, |_| true)?.into());
+            }
+            url.access = Option::<UrlAuthAccess>::arbitrary(u)?;
+
+            if url.access.is_some() {
+                url.authorization = Option::<UrlAuthorization>::arbitrary(u)?;
+            }
+        }
+
+        Ok(url)
+    }
+}
+
+#[cfg(feature = "ext_urlauth")]
+impl<'a> Arbitrary<'a> for UrlAuthAccess<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(match u.int_in_range(0u8..=3)? {
+            0 => Self::Submit(arbitrary_string(u, 1, |_| true)?.into()),
+            1 => Self::User(arbitrary_string(u, 1, |_| true)?.into()),
+            2 => Self::AuthUser,
+            3 => Self::Anonymous,
+            _ => unreachable!(),
+        })
+    }
+}
+
+#[cfg(feature = "ext_urlauth")]
+impl<'a> Arbitrary<'a> for UrlAuthMechanism<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(match bool::arbitrary(u)? {
+            true => Self::Internal,
+            false => {
+                let mechanism = arbitrary_string(u, 1, |byte| {
+                    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.')
+                })?;
+
+                // Safety: `arbitrary_string` only yields valid mechanisms.
+                Self::try_from(Atom::try_from(mechanism).unwrap()).unwrap()
+            }
+        })
+    }
+}
+
+#[cfg(feature = "ext_urlauth")]
+impl<'a> Arbitrary<'a> for UrlAuthorization<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            mechanism: UrlAuthMechanism::arbitrary(u)?,
+            token: arbitrary_string(u, 32, |byte| byte.is_ascii_hexdigit())?.into(),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use arbitrary::{Arbitrary, Error, Unstructured};
@@ -480,6 +678,7 @@ mod tests {
 
     use crate::{
         command::Command,
+        core::{Atom, AtomExt, Quoted, Tag, Text},
         response::{Greeting, Response},
     };
 
@@ -528,6 +727,28 @@ mod tests {
         };
     }
 
+    #[test]
+    fn test_arbitrary_core_is_valid() {
+        let mut rng = SmallRng::seed_from_u64(1337);
+        let mut data = [0u8; 256];
+
+        for _ in 0..1_000 {
+            rng.try_fill(&mut data).unwrap();
+            let mut u = Unstructured::new(&data);
+
+            let atom = Atom::arbitrary(&mut u).unwrap();
+            assert!(Atom::validate(atom.inner()).is_ok());
+            let atom_ext = AtomExt::arbitrary(&mut u).unwrap();
+            assert!(AtomExt::validate(atom_ext.inner()).is_ok());
+            let quoted = Quoted::arbitrary(&mut u).unwrap();
+            assert!(Quoted::validate(quoted.inner()).is_ok());
+            let tag = Tag::arbitrary(&mut u).unwrap();
+            assert!(Tag::validate(tag.inner()).is_ok());
+            let text = Text::arbitrary(&mut u).unwrap();
+            assert!(Text::validate(text.inner()).is_ok());
+        }
+    }
+
     #[test]
     fn test_arbitrary_greeting() {
         impl_test_arbitrary! {Greeting};
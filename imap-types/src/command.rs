@@ -2,7 +2,7 @@
 //!
 //! See <https://tools.ietf.org/html/rfc3501#section-6>.
 
-use std::borrow::Cow;
+use alloc::{borrow::Cow, format, string::String, vec::Vec};
 
 #[cfg(feature = "arbitrary")]
 use arbitrary::Arbitrary;
@@ -13,22 +13,36 @@ use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "ext_id")]
 use crate::core::{IString, NString};
+#[cfg(feature = "ext_context")]
+use crate::extensions::context::SearchReturnOption;
+#[cfg(feature = "ext_gmail")]
+use crate::extensions::gmail::GmailLabel;
+#[cfg(feature = "ext_metadata")]
+use crate::extensions::metadata::{EntryValue, GetMetadataOption, MetadataEntry};
+#[cfg(feature = "ext_notify")]
+use crate::extensions::notify::NotifySet;
 #[cfg(feature = "ext_sort_thread")]
 use crate::extensions::sort::SortCriterion;
 #[cfg(feature = "ext_sort_thread")]
 use crate::extensions::thread::ThreadingAlgorithm;
+#[cfg(feature = "ext_urlauth")]
+use crate::extensions::{referral::ImapUrl, urlauth::UrlAuthMechanism};
 use crate::{
     auth::AuthMechanism,
-    command::error::{AppendError, CopyError, ListError, LoginError, RenameError},
-    core::{AString, Charset, Literal, Tag, Vec1},
+    command::error::{
+        AppendError, CommandLoginError, CopyError, ListError, LoginError, RenameError,
+        SemanticError, StoreError,
+    },
+    core::{AString, Charset, ExtensionArguments, ExtensionName, Literal, Tag, Vec1},
     datetime::DateTime,
     extensions::{compress::CompressionAlgorithm, enable::CapabilityEnable, quota::QuotaSet},
     fetch::MacroOrMessageDataItemNames,
-    flag::{Flag, StoreResponse, StoreType},
+    flag::{error::FlagError, Flag, StoreResponse, StoreType},
     mailbox::{ListMailbox, Mailbox},
     search::SearchKey,
     secret::Secret,
-    sequence::SequenceSet,
+    sequence::{SeqNoOrUidSet, SequenceKind, SequenceSet, TypedSequenceSet},
+    state::State,
     status::StatusDataItemName,
 };
 
@@ -45,6 +59,14 @@ pub struct Command<'a> {
 }
 
 impl<'a> Command<'a> {
+    /// Render this command for diagnostics, e.g., logging.
+    ///
+    /// Shows one field per line and summarizes literals as `<N bytes>`. The output is not IMAP
+    /// and must not be parsed.
+    pub fn debug_render(&self) -> String {
+        crate::utils::summarize_literals(&format!("{:#?}", self))
+    }
+
     /// Create a new command.
     pub fn new<T>(tag: T, body: CommandBody<'a>) -> Result<Self, T::Error>
     where
@@ -56,10 +78,84 @@ impl<'a> Command<'a> {
         })
     }
 
+    /// Create a new LOGIN command.
+    ///
+    /// See [`CommandBody::login`] for how the username and password are represented.
+    #[allow(clippy::type_complexity)]
+    pub fn login<T, U, P>(
+        tag: T,
+        username: U,
+        password: P,
+    ) -> Result<Self, CommandLoginError<T::Error, U::Error, P::Error>>
+    where
+        T: TryInto<Tag<'a>>,
+        U: TryInto<AString<'a>>,
+        P: TryInto<AString<'a>>,
+    {
+        Ok(Self {
+            tag: tag.try_into().map_err(CommandLoginError::Tag)?,
+            body: CommandBody::login(username, password).map_err(CommandLoginError::Login)?,
+        })
+    }
+
+    /// Create a new NOOP command.
+    ///
+    /// NOOP is typically used to poll for updates. The server may send any number of untagged
+    /// responses, e.g., EXISTS or EXPUNGE, before it completes the command:
+    ///
+    /// ```rust
+    /// use imap_types::{
+    ///     command::Command,
+    ///     response::{Data, Response, Status},
+    /// };
+    ///
+    /// let command = Command::noop("A1").unwrap();
+    ///
+    /// // Responses as received from the server.
+    /// let responses = [
+    ///     Response::Data(Data::Exists(23)),
+    ///     Response::Data(Data::Recent(1)),
+    ///     Response::Status(Status::ok(Some(command.tag.clone()), None, "NOOP completed").unwrap()),
+    /// ];
+    ///
+    /// let mut updates = Vec::new();
+    ///
+    /// for response in responses {
+    ///     match response {
+    ///         Response::Status(Status::Tagged(tagged)) if tagged.tag == command.tag => break,
+    ///         Response::Data(data) => updates.push(data),
+    ///         _ => {}
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(updates, [Data::Exists(23), Data::Recent(1)]);
+    /// ```
+    pub fn noop<T>(tag: T) -> Result<Self, T::Error>
+    where
+        T: TryInto<Tag<'a>>,
+    {
+        Self::new(tag, CommandBody::noop())
+    }
+
+    /// Get the command tag.
+    pub fn tag(&self) -> &Tag<'a> {
+        &self.tag
+    }
+
+    /// Get the command body.
+    pub fn body(&self) -> &CommandBody<'a> {
+        &self.body
+    }
+
     /// Get the command name.
-    pub fn name(&self) -> &'static str {
+    pub fn name(&self) -> &str {
         self.body.name()
     }
+
+    /// Check the command for semantic errors (see [`CommandBody::validate`]).
+    pub fn validate(&self) -> Result<(), SemanticError> {
+        self.body.validate()
+    }
 }
 
 /// Command body.
@@ -69,6 +165,7 @@ impl<'a> Command<'a> {
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum CommandBody<'a> {
     // ----- Any State (see https://tools.ietf.org/html/rfc3501#section-6.1) -----
     /// ### 6.1.1.  CAPABILITY Command
@@ -395,6 +492,10 @@ pub enum CommandBody<'a> {
     /// Unselect a mailbox.
     ///
     /// This should bring the client back to the AUTHENTICATED state.
+    ///
+    /// Unlike [`CommandBody::Close`], messages with the `\Deleted` flag are *not* removed.
+    /// Requires the `UNSELECT` capability (RFC 3691), see
+    /// [`Capabilities::supports_unselect`](crate::response::Capabilities::supports_unselect).
     Unselect,
 
     /// 6.3.2.  EXAMINE Command
@@ -886,7 +987,9 @@ pub enum CommandBody<'a> {
         /// Mailbox.
         mailbox: Mailbox<'a>,
         /// Flags.
-        flags: Vec<Flag<'a>>,
+        ///
+        /// Note: `Some(vec![])`, i.e., `()`, is legal and distinct from `None`, i.e., no flag list.
+        flags: Option<Vec<Flag<'a>>>,
         /// Datetime.
         date: Option<DateTime>,
         /// Message to append.
@@ -1022,6 +1125,27 @@ pub enum CommandBody<'a> {
         uid: bool,
     },
 
+    #[cfg(feature = "ext_context")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_context")))]
+    /// SEARCH command with `RETURN` options (RFC 4731, RFC 5267).
+    ///
+    /// Data:
+    /// * untagged responses: ESEARCH
+    ///
+    /// ```imap
+    /// SEARCH RETURN (<return options>) [CHARSET <charset>] <criteria>
+    /// ```
+    ESearch {
+        /// Return options, where no options means `ALL`.
+        return_options: Vec<SearchReturnOption>,
+        /// Charset.
+        charset: Option<Charset<'a>>,
+        /// Criteria.
+        criteria: Vec1<SearchKey<'a>>,
+        /// Use UID variant.
+        uid: bool,
+    },
+
     #[cfg(feature = "ext_sort_thread")]
     /// SORT command.
     ///
@@ -1045,6 +1169,29 @@ pub enum CommandBody<'a> {
         uid: bool,
     },
 
+    #[cfg(feature = "ext_context")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_context")))]
+    /// SORT command with `RETURN` options (RFC 5267).
+    ///
+    /// Data:
+    /// * untagged responses: ESEARCH
+    ///
+    /// ```imap
+    /// SORT RETURN (<return options>) (<sort criteria>) <charset> <criteria>
+    /// ```
+    ESort {
+        /// Return options, where no options means `ALL`.
+        return_options: Vec<SearchReturnOption>,
+        /// Sort criteria.
+        sort_criteria: Vec1<SortCriterion>,
+        /// Charset.
+        charset: Charset<'a>,
+        /// Search criteria.
+        search_criteria: Vec1<SearchKey<'a>>,
+        /// Use UID variant.
+        uid: bool,
+    },
+
     #[cfg(feature = "ext_sort_thread")]
     /// THREAD command.
     ///
@@ -1094,11 +1241,9 @@ pub enum CommandBody<'a> {
     ///   safely ignore the newly transmitted envelope.
     Fetch {
         /// Set of messages.
-        sequence_set: SequenceSet,
+        sequence_set: SeqNoOrUidSet,
         /// Message data items (or a macro).
         macro_or_item_names: MacroOrMessageDataItemNames<'a>,
-        /// Use UID variant.
-        uid: bool,
     },
 
     /// ### 6.4.6.  STORE Command
@@ -1153,15 +1298,31 @@ pub enum CommandBody<'a> {
     ///    Equivalent to -FLAGS, but without returning a new value.
     Store {
         /// Set of messages.
-        sequence_set: SequenceSet,
+        sequence_set: SeqNoOrUidSet,
         /// Kind of storage, i.e., replace, add, or remove.
         kind: StoreType,
         /// Kind of response, i.e., answer or silent.
         response: StoreResponse,
         /// Flags.
-        flags: Vec<Flag<'a>>, // FIXME(misuse): must not accept "\*" or "\Recent"
-        /// Use UID variant.
-        uid: bool,
+        flags: Vec<Flag<'a>>,
+    },
+
+    #[cfg(feature = "ext_gmail")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_gmail")))]
+    /// STORE command for Gmail labels.
+    ///
+    /// ```imap
+    /// STORE <sequence set> [+|-]X-GM-LABELS[.SILENT] (<labels>)
+    /// ```
+    StoreGmailLabels {
+        /// Set of messages.
+        sequence_set: SeqNoOrUidSet,
+        /// Kind of storage, i.e., replace, add, or remove.
+        kind: StoreType,
+        /// Kind of response, i.e., answer or silent.
+        response: StoreResponse,
+        /// Labels.
+        labels: Vec<GmailLabel<'a>>,
     },
 
     /// 6.4.7.  COPY Command
@@ -1192,11 +1353,9 @@ pub enum CommandBody<'a> {
     /// before the COPY attempt.
     Copy {
         /// Set of messages.
-        sequence_set: SequenceSet,
+        sequence_set: SeqNoOrUidSet,
         /// Destination mailbox.
         mailbox: Mailbox<'a>,
-        /// Use UID variant.
-        uid: bool,
     },
 
     /// The UID mechanism was inlined into copy, fetch, store, and search.
@@ -1408,11 +1567,9 @@ pub enum CommandBody<'a> {
     /// MOVE command.
     Move {
         /// Set of messages.
-        sequence_set: SequenceSet,
+        sequence_set: SeqNoOrUidSet,
         /// Destination mailbox.
         mailbox: Mailbox<'a>,
-        /// Use UID variant.
-        uid: bool,
     },
 
     #[cfg(feature = "ext_id")]
@@ -1421,6 +1578,114 @@ pub enum CommandBody<'a> {
         /// Parameters.
         parameters: Option<Vec<(IString<'a>, NString<'a>)>>,
     },
+
+    #[cfg(feature = "ext_metadata")]
+    /// GETMETADATA command.
+    ///
+    /// Retrieves server (empty mailbox name) or mailbox annotations.
+    ///
+    /// Result:
+    /// * OK - GETMETADATA completed
+    /// * NO - GETMETADATA failure: can't access annotations
+    /// * BAD - command unknown or arguments invalid
+    GetMetadata {
+        /// Options, i.e., `MAXSIZE` and `DEPTH`.
+        options: Vec<GetMetadataOption>,
+        /// Mailbox name, or the empty string for server annotations.
+        mailbox: Mailbox<'a>,
+        /// Requested entries.
+        entries: Vec1<MetadataEntry<'a>>,
+    },
+
+    #[cfg(feature = "ext_metadata")]
+    /// SETMETADATA command.
+    ///
+    /// Sets (or removes, when the value is `NIL`) server or mailbox annotations.
+    ///
+    /// Result:
+    /// * OK - SETMETADATA completed
+    /// * NO - SETMETADATA failure, e.g., with `[METADATA TOOMANY]`
+    /// * BAD - command unknown or arguments invalid
+    SetMetadata {
+        /// Mailbox name, or the empty string for server annotations.
+        mailbox: Mailbox<'a>,
+        /// Entries and their (new) values.
+        entry_values: Vec1<EntryValue<'a>>,
+    },
+
+    #[cfg(feature = "ext_notify")]
+    /// NOTIFY command.
+    ///
+    /// Requests unsolicited notifications about events in (possibly unselected) mailboxes.
+    ///
+    /// Result:
+    /// * OK - NOTIFY completed
+    /// * NO - NOTIFY failed, e.g., with `[BADEVENT (...)]`
+    /// * BAD - command unknown or arguments invalid
+    Notify {
+        /// Event groups to monitor, or `None` for `NOTIFY NONE`.
+        set: Option<NotifySet<'a>>,
+    },
+
+    #[cfg(feature = "ext_urlauth")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_urlauth")))]
+    /// GENURLAUTH command.
+    ///
+    /// Requests URLAUTH-authorized URLs, i.e., URLs that carry a token generated by `mechanism`.
+    ///
+    /// Result:
+    /// * OK - GENURLAUTH completed
+    /// * NO - GENURLAUTH failure: can't generate a URLAUTH
+    /// * BAD - command unknown or arguments invalid
+    GenUrlAuth {
+        /// URL "rumps", i.e., URLs with an access identifier, and the mechanism to use.
+        urls: Vec1<(ImapUrl<'a>, UrlAuthMechanism<'a>)>,
+    },
+
+    #[cfg(feature = "ext_urlauth")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_urlauth")))]
+    /// RESETKEY command.
+    ///
+    /// Invalidates the URLAUTHs of a mailbox (or all mailboxes).
+    ///
+    /// Result:
+    /// * OK - RESETKEY completed
+    /// * NO - RESETKEY failure: can't reset the key
+    /// * BAD - command unknown or arguments invalid
+    ResetKey {
+        /// Mailbox and mechanisms to reset, or `None` for all mailboxes.
+        ///
+        /// An empty list of mechanisms resets all mechanisms of the mailbox.
+        mailbox_and_mechanisms: Option<(Mailbox<'a>, Vec<UrlAuthMechanism<'a>>)>,
+    },
+
+    #[cfg(feature = "ext_urlauth")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_urlauth")))]
+    /// URLFETCH command.
+    ///
+    /// Fetches the data referenced by URLAUTH-authorized URLs.
+    ///
+    /// Result:
+    /// * OK - URLFETCH completed
+    /// * NO - URLFETCH failed
+    /// * BAD - command unknown or arguments invalid
+    UrlFetch {
+        /// URLAUTH-authorized URLs.
+        urls: Vec1<ImapUrl<'a>>,
+    },
+
+    /// Experimental or vendor-specific command, e.g., `XLIST`.
+    ///
+    /// This is only used for commands that aren't supported otherwise. The arguments are kept
+    /// as-is to allow a lossless round-trip.
+    ///
+    /// Note: The `name` starts with "X" (see [`ExtensionName`]).
+    Extension {
+        /// Name of the command.
+        name: ExtensionName<'a>,
+        /// Raw arguments (excluding the space following the name).
+        args: ExtensionArguments<'a>,
+    },
 }
 
 impl<'a> CommandBody<'a> {
@@ -1437,6 +1702,28 @@ impl<'a> CommandBody<'a> {
 
     // ----- Constructors -----
 
+    /// Construct a CAPABILITY command.
+    pub fn capability() -> Self {
+        CommandBody::Capability
+    }
+
+    /// Construct a NOOP command.
+    pub fn noop() -> Self {
+        CommandBody::Noop
+    }
+
+    /// Construct a LOGOUT command.
+    pub fn logout() -> Self {
+        CommandBody::Logout
+    }
+
+    /// Construct a STARTTLS command.
+    #[cfg(feature = "starttls")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "starttls")))]
+    pub fn starttls() -> Self {
+        CommandBody::StartTLS
+    }
+
     /// Construct an AUTHENTICATE command.
     pub fn authenticate(mechanism: AuthMechanism<'a>) -> Self {
         CommandBody::Authenticate {
@@ -1459,6 +1746,13 @@ impl<'a> CommandBody<'a> {
     }
 
     /// Construct a LOGIN command.
+    ///
+    /// The username and password are converted into the most compact valid representation, i.e.,
+    /// an atom is preferred over a quoted string, and a quoted string is preferred over a literal.
+    /// Thus, credentials containing, e.g., whitespace or quotes, are supported as well.
+    ///
+    /// Note: The conversion only fails when a value can't be represented at all, e.g., because it
+    /// contains a NUL byte.
     pub fn login<U, P>(username: U, password: P) -> Result<Self, LoginError<U::Error, P::Error>>
     where
         U: TryInto<AString<'a>>,
@@ -1491,6 +1785,9 @@ impl<'a> CommandBody<'a> {
     }
 
     /// Construct a CREATE command.
+    ///
+    /// A trailing hierarchy delimiter, e.g., `foo/`, is kept as is. It tells the server that
+    /// mailboxes will be created under this name. See [`CommandBody::Create`].
     pub fn create<M>(mailbox: M) -> Result<Self, M::Error>
     where
         M: TryInto<Mailbox<'a>>,
@@ -1511,6 +1808,9 @@ impl<'a> CommandBody<'a> {
     }
 
     /// Construct a RENAME command.
+    ///
+    /// Renaming INBOX is special: It moves all messages in INBOX to `new_mailbox` and leaves INBOX
+    /// (empty) in place. See [`CommandBody::Rename`].
     pub fn rename<F, T>(mailbox: F, new_mailbox: T) -> Result<Self, RenameError<F::Error, T::Error>>
     where
         F: TryInto<Mailbox<'a>>,
@@ -1587,9 +1887,13 @@ impl<'a> CommandBody<'a> {
     }
 
     /// Construct an APPEND command.
+    ///
+    /// The arguments are in the order of the grammar, i.e., `mailbox [flag-list] [date-time] literal`.
+    ///
+    /// Fails when `flags` contains `\Recent`, which can't be set by the client.
     pub fn append<M, D>(
         mailbox: M,
-        flags: Vec<Flag<'a>>,
+        flags: Option<Vec<Flag<'a>>>,
         date: Option<DateTime>,
         message: D,
     ) -> Result<Self, AppendError<M::Error, D::Error>>
@@ -1597,6 +1901,10 @@ impl<'a> CommandBody<'a> {
         M: TryInto<Mailbox<'a>>,
         D: TryInto<Literal<'a>>,
     {
+        if let Some(flags) = &flags {
+            check_flags(flags).map_err(AppendError::Flag)?;
+        }
+
         Ok(CommandBody::Append {
             mailbox: mailbox.try_into().map_err(AppendError::Mailbox)?,
             flags,
@@ -1605,8 +1913,35 @@ impl<'a> CommandBody<'a> {
         })
     }
 
-    /// Construct a SEARCH command.
-    pub fn search(charset: Option<Charset<'a>>, criteria: Vec1<SearchKey<'a>>, uid: bool) -> Self {
+    /// Construct a CHECK command.
+    pub fn check() -> Self {
+        CommandBody::Check
+    }
+
+    /// Construct a CLOSE command.
+    pub fn close() -> Self {
+        CommandBody::Close
+    }
+
+    /// Construct an EXPUNGE command.
+    pub fn expunge() -> Self {
+        CommandBody::Expunge
+    }
+
+    /// Construct a SEARCH or UID SEARCH command.
+    ///
+    /// The `criteria` can be a single key or multiple keys, e.g., a [`Vec1`] of keys or a
+    /// [`SearchKey::And`]. Multiple keys are placed at the top level, where they are ANDed
+    /// implicitly, i.e., without parentheses.
+    pub fn search<K>(charset: Option<Charset<'a>>, criteria: K, uid: bool) -> Self
+    where
+        K: Into<SearchKey<'a>>,
+    {
+        let criteria = match criteria.into() {
+            SearchKey::And(keys) => keys,
+            key => Vec1::from(key),
+        };
+
         CommandBody::Search {
             charset,
             criteria,
@@ -1623,31 +1958,32 @@ impl<'a> CommandBody<'a> {
         let sequence_set = sequence_set.try_into()?;
 
         Ok(CommandBody::Fetch {
-            sequence_set,
+            sequence_set: SeqNoOrUidSet::new(sequence_set, uid),
             macro_or_item_names: macro_or_item_names.into(),
-            uid,
         })
     }
 
     /// Construct a STORE command.
+    ///
+    /// Fails when `flags` contains `\Recent`, which can't be set by the client.
     pub fn store<S>(
         sequence_set: S,
         kind: StoreType,
         response: StoreResponse,
         flags: Vec<Flag<'a>>,
         uid: bool,
-    ) -> Result<Self, S::Error>
+    ) -> Result<Self, StoreError<S::Error>>
     where
         S: TryInto<SequenceSet>,
     {
-        let sequence_set = sequence_set.try_into()?;
+        let sequence_set = sequence_set.try_into().map_err(StoreError::Sequence)?;
+        check_flags(&flags).map_err(StoreError::Flag)?;
 
         Ok(CommandBody::Store {
-            sequence_set,
+            sequence_set: SeqNoOrUidSet::new(sequence_set, uid),
             kind,
             response,
             flags,
-            uid,
         })
     }
 
@@ -1661,15 +1997,62 @@ impl<'a> CommandBody<'a> {
         S: TryInto<SequenceSet>,
         M: TryInto<Mailbox<'a>>,
     {
+        let sequence_set = sequence_set.try_into().map_err(CopyError::Sequence)?;
+
         Ok(CommandBody::Copy {
-            sequence_set: sequence_set.try_into().map_err(CopyError::Sequence)?,
+            sequence_set: SeqNoOrUidSet::new(sequence_set, uid),
             mailbox: mailbox.try_into().map_err(CopyError::Mailbox)?,
-            uid,
+        })
+    }
+
+    /// Construct a FETCH or UID FETCH command depending on the kind of `sequence_set`.
+    pub fn fetch_typed<K, I>(sequence_set: TypedSequenceSet<K>, macro_or_item_names: I) -> Self
+    where
+        K: SequenceKind,
+        I: Into<MacroOrMessageDataItemNames<'a>>,
+    {
+        CommandBody::Fetch {
+            sequence_set: sequence_set.into(),
+            macro_or_item_names: macro_or_item_names.into(),
+        }
+    }
+
+    /// Construct a STORE or UID STORE command depending on the kind of `sequence_set`.
+    ///
+    /// Fails when `flags` contains `\Recent`, which can't be set by the client.
+    pub fn store_typed<K>(
+        sequence_set: TypedSequenceSet<K>,
+        kind: StoreType,
+        response: StoreResponse,
+        flags: Vec<Flag<'a>>,
+    ) -> Result<Self, FlagError>
+    where
+        K: SequenceKind,
+    {
+        check_flags(&flags)?;
+
+        Ok(CommandBody::Store {
+            sequence_set: sequence_set.into(),
+            kind,
+            response,
+            flags,
+        })
+    }
+
+    /// Construct a COPY or UID COPY command depending on the kind of `sequence_set`.
+    pub fn copy_typed<K, M>(sequence_set: TypedSequenceSet<K>, mailbox: M) -> Result<Self, M::Error>
+    where
+        K: SequenceKind,
+        M: TryInto<Mailbox<'a>>,
+    {
+        Ok(CommandBody::Copy {
+            sequence_set: sequence_set.into(),
+            mailbox: mailbox.try_into()?,
         })
     }
 
     /// Get the name of the command.
-    pub fn name(&self) -> &'static str {
+    pub fn name(&self) -> &str {
         match self {
             Self::Capability => "CAPABILITY",
             Self::Noop => "NOOP",
@@ -1681,6 +2064,8 @@ impl<'a> CommandBody<'a> {
             Self::Select { .. } => "SELECT",
             #[cfg(feature = "ext_sort_thread")]
             Self::Sort { .. } => "SORT",
+            #[cfg(feature = "ext_context")]
+            Self::ESort { .. } => "SORT",
             #[cfg(feature = "ext_sort_thread")]
             Self::Thread { .. } => "THREAD",
             Self::Unselect => "UNSELECT",
@@ -1698,8 +2083,12 @@ impl<'a> CommandBody<'a> {
             Self::Close => "CLOSE",
             Self::Expunge => "EXPUNGE",
             Self::Search { .. } => "SEARCH",
+            #[cfg(feature = "ext_context")]
+            Self::ESearch { .. } => "SEARCH",
             Self::Fetch { .. } => "FETCH",
             Self::Store { .. } => "STORE",
+            #[cfg(feature = "ext_gmail")]
+            Self::StoreGmailLabels { .. } => "STORE",
             Self::Copy { .. } => "COPY",
             Self::Idle => "IDLE",
             Self::Enable { .. } => "ENABLE",
@@ -1710,14 +2099,174 @@ impl<'a> CommandBody<'a> {
             Self::Move { .. } => "MOVE",
             #[cfg(feature = "ext_id")]
             Self::Id { .. } => "ID",
+            #[cfg(feature = "ext_metadata")]
+            Self::GetMetadata { .. } => "GETMETADATA",
+            #[cfg(feature = "ext_metadata")]
+            Self::SetMetadata { .. } => "SETMETADATA",
+            #[cfg(feature = "ext_notify")]
+            Self::Notify { .. } => "NOTIFY",
+            #[cfg(feature = "ext_urlauth")]
+            Self::GenUrlAuth { .. } => "GENURLAUTH",
+            #[cfg(feature = "ext_urlauth")]
+            Self::ResetKey { .. } => "RESETKEY",
+            #[cfg(feature = "ext_urlauth")]
+            Self::UrlFetch { .. } => "URLFETCH",
+            Self::Extension { name, .. } => name.inner(),
+        }
+    }
+
+    /// Returns whether the command may be issued in `state` (see RFC 3501, section 6).
+    ///
+    /// Note: No command is valid in the [`State::Greeting`] and [`State::Logout`] states, nor while
+    /// idling. Unknown [`CommandBody::Extension`] commands are assumed to be valid in every other state.
+    pub fn is_valid_in(&self, state: &State) -> bool {
+        let (not_authenticated, authenticated, selected) = match state {
+            State::NotAuthenticated => (true, false, false),
+            State::Authenticated => (false, true, false),
+            State::Selected(_) => (false, true, true),
+            State::Greeting
+            | State::Logout
+            | State::IdleAuthenticated(_)
+            | State::IdleSelected(_, _) => return false,
+        };
+
+        match self {
+            // Any state
+            Self::Capability | Self::Noop | Self::Logout | Self::Extension { .. } => true,
+            #[cfg(feature = "ext_id")]
+            Self::Id { .. } => true,
+            // Not Authenticated state
+            #[cfg(feature = "starttls")]
+            Self::StartTLS => not_authenticated,
+            Self::Authenticate { .. } | Self::Login { .. } => not_authenticated,
+            // Authenticated state (also valid in Selected state)
+            Self::Select { .. }
+            | Self::Examine { .. }
+            | Self::Create { .. }
+            | Self::Delete { .. }
+            | Self::Rename { .. }
+            | Self::Subscribe { .. }
+            | Self::Unsubscribe { .. }
+            | Self::List { .. }
+            | Self::Lsub { .. }
+            | Self::Status { .. }
+            | Self::Append { .. }
+            | Self::Idle
+            | Self::Compress { .. }
+            | Self::GetQuota { .. }
+            | Self::GetQuotaRoot { .. }
+            | Self::SetQuota { .. } => authenticated,
+            #[cfg(feature = "ext_metadata")]
+            Self::GetMetadata { .. } | Self::SetMetadata { .. } => authenticated,
+            #[cfg(feature = "ext_notify")]
+            Self::Notify { .. } => authenticated,
+            #[cfg(feature = "ext_urlauth")]
+            Self::GenUrlAuth { .. } | Self::ResetKey { .. } | Self::UrlFetch { .. } => {
+                authenticated
+            }
+            // ENABLE is only valid in the Authenticated state (see RFC 5161, section 3.1).
+            Self::Enable { .. } => authenticated && !selected,
+            // Selected state
+            Self::Unselect
+            | Self::Check
+            | Self::Close
+            | Self::Expunge
+            | Self::Search { .. }
+            | Self::Fetch { .. }
+            | Self::Store { .. }
+            | Self::Copy { .. }
+            | Self::Move { .. } => selected,
+            #[cfg(feature = "ext_sort_thread")]
+            Self::Sort { .. } | Self::Thread { .. } => selected,
+            #[cfg(feature = "ext_context")]
+            Self::ESearch { .. } | Self::ESort { .. } => selected,
+            #[cfg(feature = "ext_gmail")]
+            Self::StoreGmailLabels { .. } => selected,
         }
     }
+
+    /// Check the command for semantic errors, i.e., for errors a server will reject although
+    /// the command is syntactically valid.
+    ///
+    /// This is meant for programmatically constructed commands and doesn't need to be called for
+    /// parsed ones. See [`SemanticError`] for the checks.
+    ///
+    /// Note: Many invalid commands, e.g., a SEARCH without keys or a STORE with an empty
+    /// sequence set, can't be constructed in the first place.
+    pub fn validate(&self) -> Result<(), SemanticError> {
+        match self {
+            Self::Create {
+                mailbox: Mailbox::Inbox,
+            } => Err(SemanticError::CreateInbox),
+            Self::Delete {
+                mailbox: Mailbox::Inbox,
+            } => Err(SemanticError::DeleteInbox),
+            Self::Rename {
+                to: Mailbox::Inbox, ..
+            } => Err(SemanticError::RenameToInbox),
+            Self::Rename { from, to } if from == to => Err(SemanticError::RenameToSelf),
+            Self::Status { item_names, .. } if item_names.is_empty() => {
+                Err(SemanticError::StatusWithoutItems)
+            }
+            #[cfg(feature = "ext_id")]
+            Self::Id {
+                parameters: Some(parameters),
+            } => {
+                if parameters.len() > 30 {
+                    return Err(SemanticError::IdTooManyFields);
+                }
+
+                for (i, (field, value)) in parameters.iter().enumerate() {
+                    if field.as_ref().len() > 30 {
+                        return Err(SemanticError::IdFieldTooLong);
+                    }
+
+                    if value.0.as_ref().map_or(0, |value| value.as_ref().len()) > 1024 {
+                        return Err(SemanticError::IdValueTooLong);
+                    }
+
+                    if parameters[..i]
+                        .iter()
+                        .any(|(other, _)| other.as_ref().eq_ignore_ascii_case(field.as_ref()))
+                    {
+                        return Err(SemanticError::IdDuplicateField);
+                    }
+                }
+
+                Ok(())
+            }
+            #[cfg(feature = "ext_urlauth")]
+            Self::GenUrlAuth { urls } => {
+                if urls
+                    .as_ref()
+                    .iter()
+                    .any(|(url, _)| url.access.is_none() || url.authorization.is_some())
+                {
+                    return Err(SemanticError::GenUrlAuthNotRump);
+                }
+
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Rejects `\Recent`, which can't be set by the client (see [`Flag::system`]).
+fn check_flags(flags: &[Flag]) -> Result<(), FlagError> {
+    if flags.iter().any(Flag::is_recent) {
+        return Err(FlagError::Recent);
+    }
+
+    Ok(())
 }
 
 /// Error-related types.
 pub mod error {
     use thiserror::Error;
 
+    use crate::flag::error::FlagError;
+
     #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
     pub enum LoginError<U, P> {
         #[error("Invalid username: {0}")]
@@ -1726,6 +2275,14 @@ pub mod error {
         Password(P),
     }
 
+    #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+    pub enum CommandLoginError<T, U, P> {
+        #[error("Invalid tag: {0}")]
+        Tag(T),
+        #[error(transparent)]
+        Login(LoginError<U, P>),
+    }
+
     #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
     pub enum RenameError<F, T> {
         #[error("Invalid (from) mailbox: {0}")]
@@ -1746,10 +2303,20 @@ pub mod error {
     pub enum AppendError<M, D> {
         #[error("Invalid mailbox: {0}")]
         Mailbox(M),
+        #[error("Invalid flag: {0}")]
+        Flag(FlagError),
         #[error("Invalid data: {0}")]
         Data(D),
     }
 
+    #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+    pub enum StoreError<S> {
+        #[error("Invalid sequence: {0}")]
+        Sequence(S),
+        #[error("Invalid flag: {0}")]
+        Flag(FlagError),
+    }
+
     #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
     pub enum CopyError<S, M> {
         #[error("Invalid sequence: {0}")]
@@ -1757,6 +2324,53 @@ pub mod error {
         #[error("Invalid mailbox: {0}")]
         Mailbox(M),
     }
+
+    /// A semantic error found by [`CommandBody::validate`](super::CommandBody::validate).
+    #[derive(Clone, Copy, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+    #[non_exhaustive]
+    pub enum SemanticError {
+        /// CREATE of INBOX, which always exists (RFC 3501, section 6.3.3).
+        #[error("Can't create INBOX")]
+        CreateInbox,
+        /// DELETE of INBOX (RFC 3501, section 6.3.4).
+        #[error("Can't delete INBOX")]
+        DeleteInbox,
+        /// RENAME to INBOX, which always exists (RFC 3501, section 6.3.5).
+        #[error("Can't rename to INBOX")]
+        RenameToInbox,
+        /// RENAME to the same mailbox, which already exists (RFC 3501, section 6.3.5).
+        #[error("Can't rename a mailbox to itself")]
+        RenameToSelf,
+        /// STATUS without status data items (RFC 3501, `status-att-list`).
+        #[error("STATUS requires at least one status data item")]
+        StatusWithoutItems,
+        /// ID with more than 30 field-value pairs (RFC 2971, section 3.3).
+        #[cfg(feature = "ext_id")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "ext_id")))]
+        #[error("ID must not have more than 30 fields")]
+        IdTooManyFields,
+        /// ID with a field longer than 30 octets (RFC 2971, section 3.3).
+        #[cfg(feature = "ext_id")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "ext_id")))]
+        #[error("ID field must not be longer than 30 octets")]
+        IdFieldTooLong,
+        /// ID with a value longer than 1024 octets (RFC 2971, section 3.3).
+        #[cfg(feature = "ext_id")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "ext_id")))]
+        #[error("ID value must not be longer than 1024 octets")]
+        IdValueTooLong,
+        /// ID with a (case-insensitively) repeated field (RFC 2971, section 3.3).
+        #[cfg(feature = "ext_id")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "ext_id")))]
+        #[error("ID field must not occur more than once")]
+        IdDuplicateField,
+        /// GENURLAUTH with a URL that is not a "rump", i.e., has no access identifier or
+        /// already has an authorization (RFC 4467, section 7.1).
+        #[cfg(feature = "ext_urlauth")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "ext_urlauth")))]
+        #[error("GENURLAUTH requires URLs with access identifier and without authorization")]
+        GenUrlAuthNotRump,
+    }
 }
 
 #[cfg(test)]
@@ -1777,7 +2391,7 @@ mod tests {
         mailbox::{ListMailbox, Mailbox},
         search::SearchKey,
         secret::Secret,
-        sequence::{SeqOrUid, Sequence, SequenceSet},
+        sequence::{SeqNo, SeqNoOrUidSet, SeqOrUid, Sequence, SequenceSet, TypedSequenceSet, Uid},
         status::StatusDataItemName,
     };
 
@@ -1834,7 +2448,7 @@ mod tests {
             CommandBody::status("inbox", vec![StatusDataItemName::Messages]).unwrap(),
             CommandBody::append(
                 "inbox",
-                vec![],
+                None,
                 Some(
                     DateTime::try_from(
                         ChronoDateTime::parse_from_rfc2822("Tue, 1 Jul 2003 10:52:37 +0200")
@@ -1847,7 +2461,7 @@ mod tests {
             .unwrap(),
             CommandBody::append(
                 "inbox",
-                vec![Flag::Keyword("test".try_into().unwrap())],
+                Some(vec![Flag::Keyword("test".try_into().unwrap())]),
                 Some(
                     DateTime::try_from(
                         ChronoDateTime::parse_from_rfc2822("Tue, 1 Jul 2003 10:52:37 +0200")
@@ -1960,6 +2574,103 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_conversion_login() {
+        let tests = [
+            (
+                CommandBody::login("alice", "password").unwrap(),
+                AString::Atom("alice".try_into().unwrap()),
+                AString::Atom("password".try_into().unwrap()),
+            ),
+            (
+                CommandBody::login("alice", "pass word").unwrap(),
+                AString::Atom("alice".try_into().unwrap()),
+                AString::String(IString::Quoted("pass word".try_into().unwrap())),
+            ),
+            (
+                CommandBody::login("alice", "pass\"word").unwrap(),
+                AString::Atom("alice".try_into().unwrap()),
+                AString::String(IString::Quoted("pass\"word".try_into().unwrap())),
+            ),
+            (
+                CommandBody::login("al ice", "pass²word").unwrap(),
+                AString::String(IString::Quoted("al ice".try_into().unwrap())),
+                AString::String(IString::Literal("pass²word".try_into().unwrap())),
+            ),
+        ];
+
+        for (test, expected_username, expected_password) in tests {
+            match test {
+                CommandBody::Login { username, password } => {
+                    assert_eq!(username, expected_username);
+                    assert_eq!(password.declassify(), &expected_password);
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        assert!(matches!(
+            CommandBody::login("alice\x00", "password"),
+            Err(LoginError::Username(_))
+        ));
+        assert!(matches!(
+            CommandBody::login("alice", "pass\x00word"),
+            Err(LoginError::Password(_))
+        ));
+    }
+
+    #[test]
+    fn test_command_login() {
+        assert_eq!(
+            Command::login("A1", "alice", "pass word").unwrap(),
+            Command {
+                tag: Tag::try_from("A1").unwrap(),
+                body: CommandBody::login("alice", "pass word").unwrap(),
+            }
+        );
+        assert!(matches!(
+            Command::login("A 1", "alice", "password"),
+            Err(CommandLoginError::Tag(_))
+        ));
+        assert!(matches!(
+            Command::login("A1", "alice", "pass\x00word"),
+            Err(CommandLoginError::Login(LoginError::Password(_)))
+        ));
+    }
+
+    #[test]
+    fn test_command_debug_render() {
+        let cmd = Command::new(
+            "A1",
+            CommandBody::append(
+                "INBOX",
+                None,
+                None,
+                Literal::try_from(b"From: alice\r\n\r\nHello!".as_ref()).unwrap(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let rendered = cmd.debug_render();
+        assert!(rendered.lines().count() > 1);
+        assert!(rendered.contains("<21 bytes>"));
+        assert!(!rendered.contains("Hello!"));
+
+        // The regular `Debug` output is unchanged.
+        assert!(format!("{:?}", cmd).contains("Hello!"));
+        assert!(format!("{:#?}", cmd).contains("Hello!"));
+    }
+
+    #[test]
+    fn test_command_accessors() {
+        let cmd = Command::new("A1", CommandBody::Noop).unwrap();
+
+        assert_eq!(cmd.tag(), &Tag::try_from("A1").unwrap());
+        assert_eq!(cmd.body(), &CommandBody::Noop);
+        assert_eq!(cmd.name(), "NOOP");
+    }
+
     #[test]
     fn test_command_body_name() {
         let tests = [
@@ -2052,7 +2763,7 @@ mod tests {
                     mailbox: Mailbox::Inbox,
                     date: None,
                     message: Literal::try_from("").unwrap(),
-                    flags: vec![],
+                    flags: None,
                 },
                 "APPEND",
             ),
@@ -2069,27 +2780,24 @@ mod tests {
             ),
             (
                 CommandBody::Fetch {
-                    sequence_set: SequenceSet::try_from(1u32).unwrap(),
+                    sequence_set: SeqNoOrUidSet::new(SequenceSet::try_from(1u32).unwrap(), true),
                     macro_or_item_names: MacroOrMessageDataItemNames::Macro(Macro::Full),
-                    uid: true,
                 },
                 "FETCH",
             ),
             (
                 CommandBody::Store {
-                    sequence_set: SequenceSet::try_from(1).unwrap(),
+                    sequence_set: SeqNoOrUidSet::new(SequenceSet::try_from(1).unwrap(), true),
                     flags: vec![],
                     response: StoreResponse::Silent,
                     kind: StoreType::Add,
-                    uid: true,
                 },
                 "STORE",
             ),
             (
                 CommandBody::Copy {
-                    sequence_set: SequenceSet::try_from(1).unwrap(),
+                    sequence_set: SeqNoOrUidSet::new(SequenceSet::try_from(1).unwrap(), true),
                     mailbox: Mailbox::Inbox,
-                    uid: true,
                 },
                 "COPY",
             ),
@@ -2127,9 +2835,8 @@ mod tests {
             ),
             (
                 CommandBody::Move {
-                    sequence_set: SequenceSet::try_from(1).unwrap(),
+                    sequence_set: SeqNoOrUidSet::new(SequenceSet::try_from(1).unwrap(), true),
                     mailbox: Mailbox::Inbox,
-                    uid: true,
                 },
                 "MOVE",
             ),
@@ -2139,4 +2846,192 @@ mod tests {
             assert_eq!(test.name(), expected);
         }
     }
+
+    #[test]
+    fn test_command_body_typed() {
+        let seq = TypedSequenceSet::from(SeqNo::try_from(1).unwrap());
+        let uid = TypedSequenceSet::from(Uid::try_from(1).unwrap());
+
+        let tests = [
+            (CommandBody::fetch_typed(seq.clone(), Macro::Fast), false),
+            (CommandBody::fetch_typed(uid.clone(), Macro::Fast), true),
+            (
+                CommandBody::store_typed(
+                    uid.clone(),
+                    StoreType::Add,
+                    StoreResponse::Silent,
+                    vec![Flag::Seen],
+                )
+                .unwrap(),
+                true,
+            ),
+            (
+                CommandBody::copy_typed(seq.clone(), "Archive").unwrap(),
+                false,
+            ),
+            (
+                CommandBody::copy_typed(uid.clone(), "Archive").unwrap(),
+                true,
+            ),
+            (CommandBody::move_typed(seq, "Archive").unwrap(), false),
+            (CommandBody::move_typed(uid, "Archive").unwrap(), true),
+        ];
+
+        for (test, expected) in tests {
+            match test {
+                CommandBody::Fetch { sequence_set, .. }
+                | CommandBody::Store { sequence_set, .. }
+                | CommandBody::Copy { sequence_set, .. }
+                | CommandBody::Move { sequence_set, .. } => {
+                    assert_eq!(sequence_set.is_uid(), expected)
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_command_body_recent() {
+        let recent = || vec![Flag::Seen, Flag::try_from("\\Recent").unwrap()];
+
+        assert_eq!(
+            CommandBody::store("1", StoreType::Add, StoreResponse::Answer, recent(), false),
+            Err(StoreError::Flag(FlagError::Recent))
+        );
+        assert_eq!(
+            CommandBody::store_typed(
+                TypedSequenceSet::from(Uid::try_from(1).unwrap()),
+                StoreType::Replace,
+                StoreResponse::Silent,
+                recent(),
+            ),
+            Err(FlagError::Recent)
+        );
+        assert_eq!(
+            CommandBody::append("INBOX", Some(recent()), None, b"A".as_ref()),
+            Err(AppendError::Flag(FlagError::Recent))
+        );
+    }
+
+    #[test]
+    fn test_command_validate() {
+        let tests = [
+            (
+                CommandBody::create("INBOX").unwrap(),
+                SemanticError::CreateInbox,
+            ),
+            (
+                CommandBody::delete("inbox").unwrap(),
+                SemanticError::DeleteInbox,
+            ),
+            (
+                CommandBody::rename("Archive", "Inbox").unwrap(),
+                SemanticError::RenameToInbox,
+            ),
+            (
+                CommandBody::rename("Archive", "Archive").unwrap(),
+                SemanticError::RenameToSelf,
+            ),
+            (
+                CommandBody::status("Archive", vec![]).unwrap(),
+                SemanticError::StatusWithoutItems,
+            ),
+        ];
+
+        for (test, expected) in tests {
+            let cmd = Command::new("A", test).unwrap();
+            assert_eq!(cmd.validate(), Err(expected));
+        }
+
+        let tests = [
+            CommandBody::create("Archive").unwrap(),
+            CommandBody::delete("Archive").unwrap(),
+            CommandBody::rename("INBOX", "Archive").unwrap(),
+            CommandBody::status("Archive", vec![StatusDataItemName::Messages]).unwrap(),
+            CommandBody::list("", "").unwrap(),
+            CommandBody::search(None, Vec1::from(SearchKey::All), false),
+        ];
+
+        for test in tests {
+            assert_eq!(test.validate(), Ok(()));
+        }
+    }
+
+    #[cfg(feature = "ext_id")]
+    #[test]
+    fn test_command_validate_id() {
+        let id = |parameters: &[(&str, Option<&str>)]| CommandBody::Id {
+            parameters: Some(
+                parameters
+                    .iter()
+                    .map(|(field, value)| {
+                        (
+                            IString::try_from(field.to_string()).unwrap(),
+                            NString(
+                                value.map(|value| IString::try_from(value.to_string()).unwrap()),
+                            ),
+                        )
+                    })
+                    .collect(),
+            ),
+        };
+
+        assert_eq!(CommandBody::Id { parameters: None }.validate(), Ok(()));
+        assert_eq!(
+            id(&[("name", Some("imap-codec")), ("version", None)]).validate(),
+            Ok(())
+        );
+        assert_eq!(
+            id(&[("name", Some("a")), ("NAME", Some("b"))]).validate(),
+            Err(SemanticError::IdDuplicateField)
+        );
+        assert_eq!(
+            id(&[(&"x".repeat(31), None)]).validate(),
+            Err(SemanticError::IdFieldTooLong)
+        );
+        assert_eq!(
+            id(&[("name", Some(&"x".repeat(1025)))]).validate(),
+            Err(SemanticError::IdValueTooLong)
+        );
+
+        let fields: Vec<_> = (0..31).map(|i| format!("field{i}")).collect();
+        let parameters: Vec<_> = fields.iter().map(|field| (field.as_str(), None)).collect();
+        assert_eq!(
+            id(&parameters).validate(),
+            Err(SemanticError::IdTooManyFields)
+        );
+    }
+
+    #[cfg(feature = "ext_urlauth")]
+    #[test]
+    fn test_command_validate_genurlauth() {
+        use crate::extensions::urlauth::{UrlAuthAccess, UrlAuthorization};
+
+        let rump = ImapUrl {
+            mailbox: Some("INBOX".into()),
+            access: Some(UrlAuthAccess::Anonymous),
+            ..ImapUrl::new("example.com")
+        };
+        let full = ImapUrl {
+            authorization: Some(UrlAuthorization {
+                mechanism: UrlAuthMechanism::Internal,
+                token: "00000000000000000000000000000000".into(),
+            }),
+            ..rump.clone()
+        };
+
+        let tests = [
+            (rump.clone(), Ok(())),
+            (
+                ImapUrl::new("example.com"),
+                Err(SemanticError::GenUrlAuthNotRump),
+            ),
+            (full, Err(SemanticError::GenUrlAuthNotRump)),
+        ];
+
+        for (url, expected) in tests {
+            let cmd = CommandBody::gen_url_auth(vec![(url, UrlAuthMechanism::Internal)]).unwrap();
+            assert_eq!(cmd.validate(), expected);
+        }
+    }
 }
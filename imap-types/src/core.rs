@@ -34,11 +34,16 @@
 //!             └───────┘ └──────┘
 //! ```
 
-use std::{
+use alloc::{
     borrow::Cow,
+    format,
+    string::String,
+    vec,
+    vec::{IntoIter, Vec},
+};
+use core::{
     fmt::{Debug, Display, Formatter},
-    str::from_utf8,
-    vec::IntoIter,
+    str::{from_utf8, Utf8Error},
 };
 
 #[cfg(feature = "arbitrary")]
@@ -48,8 +53,12 @@ use bounded_static::ToStatic;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::utils::indicators::{
-    is_any_text_char_except_quoted_specials, is_astring_char, is_atom_char, is_char8, is_text_char,
+use crate::utils::{
+    escape_quoted,
+    indicators::{
+        is_any_text_char_except_quoted_specials, is_astring_char, is_atom_char, is_char8,
+        is_text_char,
+    },
 };
 
 macro_rules! impl_try_from {
@@ -101,7 +110,7 @@ pub struct Atom<'a>(pub(crate) Cow<'a, str>);
 
 // We want a slightly more dense `Debug` implementation.
 impl<'a> Debug for Atom<'a> {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         write!(f, "Atom({:?})", self.0)
     }
 }
@@ -130,6 +139,14 @@ impl<'a> Atom<'a> {
         self.0.as_ref()
     }
 
+    /// Compares the atom with `other`, ignoring ASCII case.
+    ///
+    /// Many atoms, e.g., capabilities or keywords, are case-insensitive in IMAP. Note that the
+    /// derived `PartialEq` is case-sensitive.
+    pub fn eq_ignore_ascii_case(&self, other: &str) -> bool {
+        self.0.eq_ignore_ascii_case(other)
+    }
+
     /// Consumes the atom, returning the inner value.
     pub fn into_inner(self) -> Cow<'a, str> {
         self.0
@@ -157,14 +174,62 @@ impl<'a> Atom<'a> {
     }
 }
 
+impl Atom<'static> {
+    /// Constructs an atom from a static string, panicking when it is invalid.
+    ///
+    /// This is a `const fn`, i.e., an invalid atom is a compile error in a `const` context. Prefer
+    /// the [`atom!`](crate::atom) macro, which always enforces this.
+    pub const fn from_static(value: &'static str) -> Self {
+        let bytes = value.as_bytes();
+
+        assert!(!bytes.is_empty(), "atom must not be empty");
+
+        let mut index = 0;
+
+        while index < bytes.len() {
+            assert!(
+                is_atom_char(bytes[index]),
+                "atom must only contain ATOM-CHARs"
+            );
+            index += 1;
+        }
+
+        Self(Cow::Borrowed(value))
+    }
+}
+
+/// Constructs an [`Atom<'static>`](crate::core::Atom) that is validated at compile time.
+///
+/// ```rust
+/// use imap_types::{atom, core::Atom};
+///
+/// const FLAGS: Atom<'static> = atom!("FLAGS");
+///
+/// assert_eq!(FLAGS, Atom::try_from("FLAGS").unwrap());
+/// assert_eq!(atom!("UID").inner(), "UID");
+/// ```
+///
+/// An invalid atom doesn't compile:
+///
+/// ```rust,compile_fail
+/// let _ = imap_types::atom!("FLA GS");
+/// ```
+#[macro_export]
+macro_rules! atom {
+    ($value:expr) => {{
+        const ATOM: $crate::core::Atom<'static> = $crate::core::Atom::from_static($value);
+        ATOM
+    }};
+}
+
 impl<'a> TryFrom<&'a [u8]> for Atom<'a> {
     type Error = ValidationError;
 
     fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
+        let value = from_utf8(value).map_err(invalid_utf8)?;
         Self::validate(value)?;
 
-        // Safety: `unwrap` can't panic due to `validate`.
-        Ok(Self(Cow::Borrowed(from_utf8(value).unwrap())))
+        Ok(Self(Cow::Borrowed(value)))
     }
 }
 
@@ -172,10 +237,10 @@ impl<'a> TryFrom<Vec<u8>> for Atom<'a> {
     type Error = ValidationError;
 
     fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+        let value = String::from_utf8(value).map_err(|error| invalid_utf8(error.utf8_error()))?;
         Self::validate(&value)?;
 
-        // Safety: `unwrap` can't panic due to `validate`.
-        Ok(Self(Cow::Owned(String::from_utf8(value).unwrap())))
+        Ok(Self(Cow::Owned(value)))
     }
 }
 
@@ -215,8 +280,14 @@ impl<'a> AsRef<str> for Atom<'a> {
     }
 }
 
+impl<'a> From<Atom<'a>> for String {
+    fn from(atom: Atom<'a>) -> Self {
+        atom.0.into_owned()
+    }
+}
+
 impl<'a> Display for Atom<'a> {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         write!(f, "{}", self.0)
     }
 }
@@ -246,12 +317,12 @@ impl<'a> Display for Atom<'a> {
 /// ```
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
 pub struct AtomExt<'a>(pub(crate) Cow<'a, str>);
 
 // We want a slightly more dense `Debug` implementation.
 impl<'a> Debug for AtomExt<'a> {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         write!(f, "AtomExt({:?})", self.0)
     }
 }
@@ -311,10 +382,10 @@ impl<'a> TryFrom<&'a [u8]> for AtomExt<'a> {
     type Error = ValidationError;
 
     fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
+        let value = from_utf8(value).map_err(invalid_utf8)?;
         Self::validate(value)?;
 
-        // Safety: `unwrap` can't panic due to `validate`.
-        Ok(Self(Cow::Borrowed(from_utf8(value).unwrap())))
+        Ok(Self(Cow::Borrowed(value)))
     }
 }
 
@@ -322,10 +393,10 @@ impl<'a> TryFrom<Vec<u8>> for AtomExt<'a> {
     type Error = ValidationError;
 
     fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+        let value = String::from_utf8(value).map_err(|error| invalid_utf8(error.utf8_error()))?;
         Self::validate(&value)?;
 
-        // Safety: `unwrap` can't panic due to `validate`.
-        Ok(Self(Cow::Owned(String::from_utf8(value).unwrap())))
+        Ok(Self(Cow::Owned(value)))
     }
 }
 
@@ -366,6 +437,17 @@ impl<'a> AsRef<str> for AtomExt<'a> {
 /// Note: The empty string is represented as either "" (a quoted string with zero characters between
 /// double quotes) or as {0} followed by CRLF (a literal with an octet count of 0).
 ///
+/// # Conversion
+///
+/// The `TryFrom` implementations choose the representation as follows:
+///
+/// 1. [`Quoted`], when all characters are `TEXT-CHAR`s (this includes, e.g., tabs, but excludes
+///    CR and LF),
+/// 2. [`Literal`], when all characters are `CHAR8`s (i.e., not NUL),
+/// 3. an error otherwise.
+///
+/// Use [`IString::quoted`] or [`IString::literal`] to force a representation.
+///
 /// # ABNF definition
 ///
 /// ```abnf
@@ -378,7 +460,7 @@ impl<'a> AsRef<str> for AtomExt<'a> {
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
 pub enum IString<'a> {
     /// Literal, see [`Literal`].
     Literal(Literal<'a>),
@@ -387,6 +469,22 @@ pub enum IString<'a> {
 }
 
 impl<'a> IString<'a> {
+    /// Constructs a quoted string, failing when `value` can't be represented as such.
+    pub fn quoted<Q>(value: Q) -> Result<Self, Q::Error>
+    where
+        Q: TryInto<Quoted<'a>>,
+    {
+        Ok(Self::Quoted(value.try_into()?))
+    }
+
+    /// Constructs a literal, failing when `value` can't be represented as such.
+    pub fn literal<L>(value: L) -> Result<Self, L::Error>
+    where
+        L: TryInto<Literal<'a>>,
+    {
+        Ok(Self::Literal(value.try_into()?))
+    }
+
     pub fn into_inner(self) -> Cow<'a, [u8]> {
         match self {
             Self::Literal(literal) => literal.into_inner(),
@@ -494,7 +592,7 @@ impl<'a> AsRef<[u8]> for IString<'a> {
 /// ```
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
 pub struct Literal<'a> {
     pub(crate) data: Cow<'a, [u8]>,
     /// Specifies whether this is a synchronizing or non-synchronizing literal.
@@ -509,11 +607,11 @@ pub struct Literal<'a> {
 
 // We want a more readable `Debug` implementation.
 impl<'a> Debug for Literal<'a> {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         struct BStr<'a>(&'a Cow<'a, [u8]>);
 
         impl<'a> Debug for BStr<'a> {
-            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
                 write!(
                     f,
                     "b\"{}\"",
@@ -569,6 +667,28 @@ impl<'a> Literal<'a> {
         self.data
     }
 
+    /// Constructs a synchronizing literal, i.e., `{<n>}\r\n<data>`.
+    ///
+    /// The receiver must acknowledge a synchronizing literal (in commands) with a continuation
+    /// request before the data is sent.
+    pub fn try_from_sync<D>(data: D) -> Result<Self, D::Error>
+    where
+        D: TryInto<Literal<'a>>,
+    {
+        Ok(data.try_into()?.into_sync())
+    }
+
+    /// Constructs a non-synchronizing literal, i.e., `{<n>+}\r\n<data>`.
+    ///
+    /// A non-synchronizing literal is sent without waiting for a continuation request. Only use it
+    /// when the server advertised `LITERAL+` (or `LITERAL-` and the data has at most 4096 bytes).
+    pub fn try_from_non_sync<D>(data: D) -> Result<Self, D::Error>
+    where
+        D: TryInto<Literal<'a>>,
+    {
+        Ok(data.try_into()?.into_non_sync())
+    }
+
     /// Constructs a literal without validation.
     ///
     /// # Warning: IMAP conformance
@@ -669,11 +789,17 @@ impl<'a> AsRef<[u8]> for Literal<'a> {
     }
 }
 
+impl<'a> From<Literal<'a>> for Vec<u8> {
+    fn from(literal: Literal<'a>) -> Self {
+        literal.data.into_owned()
+    }
+}
+
 /// Literal mode, i.e., sync or non-sync.
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
 pub enum LiteralMode {
     /// A synchronizing literal, i.e., `{<n>}\r\n<data>`.
     Sync,
@@ -705,15 +831,22 @@ pub enum LiteralMode {
 /// ```
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
 pub struct Quoted<'a>(pub(crate) Cow<'a, str>);
 
 impl<'a> Debug for Quoted<'a> {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         write!(f, "Quoted({:?})", self.0)
     }
 }
 
+/// Formats the quoted string as it appears on the wire, i.e., surrounded by `"` and escaped.
+impl<'a> Display for Quoted<'a> {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        write!(f, "\"{}\"", escape_quoted(&self.0))
+    }
+}
+
 impl<'a> Quoted<'a> {
     pub fn validate(value: impl AsRef<[u8]>) -> Result<(), ValidationError> {
         let value = value.as_ref();
@@ -762,10 +895,10 @@ impl<'a> TryFrom<&'a [u8]> for Quoted<'a> {
     type Error = ValidationError;
 
     fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
+        let value = from_utf8(value).map_err(invalid_utf8)?;
         Quoted::validate(value)?;
 
-        // Safety: `unwrap` can't panic due to `validate`.
-        Ok(Quoted(Cow::Borrowed(from_utf8(value).unwrap())))
+        Ok(Quoted(Cow::Borrowed(value)))
     }
 }
 
@@ -773,10 +906,10 @@ impl TryFrom<Vec<u8>> for Quoted<'_> {
     type Error = ValidationError;
 
     fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+        let value = String::from_utf8(value).map_err(|error| invalid_utf8(error.utf8_error()))?;
         Quoted::validate(&value)?;
 
-        // Safety: `unwrap` can't panic due to `validate`.
-        Ok(Quoted(Cow::Owned(String::from_utf8(value).unwrap())))
+        Ok(Quoted(Cow::Owned(value)))
     }
 }
 
@@ -806,6 +939,12 @@ impl<'a> AsRef<str> for Quoted<'a> {
     }
 }
 
+impl<'a> From<Quoted<'a>> for String {
+    fn from(quoted: Quoted<'a>) -> Self {
+        quoted.0.into_owned()
+    }
+}
+
 /// Either NIL or a string.
 ///
 /// This is modeled using Rust's `Option` type.
@@ -823,6 +962,8 @@ impl<'a> AsRef<str> for Quoted<'a> {
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+// Note: `NString(None)` is serialized as `null` (and not as, e.g., `{"0":null}`).
+#[cfg_attr(feature = "serde", serde(transparent))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct NString<'a>(
     // This wrapper is merely used for formatting.
@@ -831,6 +972,14 @@ pub struct NString<'a>(
 );
 
 impl<'a> NString<'a> {
+    /// Constructs the most compact representation of `value`.
+    ///
+    /// `None` becomes `NIL`. Otherwise, a quoted string is preferred over a literal (see
+    /// [`AString::compact`]). Note that `Some("NIL")` becomes the quoted string `"NIL"`.
+    pub fn compact(value: Option<&'a str>) -> Result<Self, ValidationError> {
+        Ok(Self(value.map(IString::try_from).transpose()?))
+    }
+
     pub fn into_option(self) -> Option<Cow<'a, [u8]>> {
         self.0.map(|inner| inner.into_inner())
     }
@@ -878,13 +1027,34 @@ impl<'a> From<Quoted<'a>> for NString<'a> {
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
 pub enum AString<'a> {
     // `1*ATOM-CHAR` does not allow resp-specials, but `1*ASTRING-CHAR` does ... :-/
     Atom(AtomExt<'a>),   // 1*ASTRING-CHAR /
     String(IString<'a>), // string
 }
 
+impl<'a> AString<'a> {
+    /// Constructs the most compact representation of `value`.
+    ///
+    /// The preference order is ...
+    ///
+    /// 1. an (extended) atom, e.g., `alice` or `INBOX/foo]`,
+    /// 2. a quoted string, e.g., `"alice smith"` or `"\\"`, and
+    /// 3. a literal, e.g., for non-ASCII or line breaks.
+    ///
+    /// Fails when `value` can't be represented at all, e.g., because it contains NUL.
+    ///
+    /// Note: This is what `AString::try_from(&str)` does, too.
+    pub fn compact(value: &'a str) -> Result<Self, ValidationError> {
+        if let Ok(atom) = AtomExt::try_from(value) {
+            return Ok(AString::Atom(atom));
+        }
+
+        Ok(AString::String(IString::try_from(value)?))
+    }
+}
+
 impl<'a> TryFrom<&'a [u8]> for AString<'a> {
     type Error = ValidationError;
 
@@ -914,11 +1084,7 @@ impl<'a> TryFrom<&'a str> for AString<'a> {
     type Error = ValidationError;
 
     fn try_from(value: &'a str) -> Result<Self, Self::Error> {
-        if let Ok(atom) = AtomExt::try_from(value) {
-            return Ok(AString::Atom(atom));
-        }
-
-        Ok(AString::String(IString::try_from(value)?))
+        Self::compact(value)
     }
 }
 
@@ -973,6 +1139,8 @@ impl<'a> AsRef<[u8]> for AString<'a> {
 /// Each client command is prefixed with an identifier (typically, e.g., A0001, A0002, etc.) called
 /// a "tag".
 ///
+/// Use a [`TagGenerator`] to create unique tags. (imap-types doesn't depend on `rand`.)
+///
 /// # ABNF definition
 ///
 /// ```abnf
@@ -993,16 +1161,22 @@ impl<'a> AsRef<[u8]> for AString<'a> {
 /// ```
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(PartialEq, Eq, Hash, Clone)]
+#[derive(PartialEq, Eq, Ord, PartialOrd, Hash, Clone)]
 pub struct Tag<'a>(pub(crate) Cow<'a, str>);
 
 // We want a slightly more dense `Debug` implementation.
 impl<'a> Debug for Tag<'a> {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         write!(f, "Tag({:?})", self.0)
     }
 }
 
+impl<'a> Display for Tag<'a> {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 impl<'a> Tag<'a> {
     pub fn validate(value: impl AsRef<[u8]>) -> Result<(), ValidationError> {
         let value = value.as_ref();
@@ -1098,6 +1272,66 @@ impl<'a> AsRef<str> for Tag<'a> {
     }
 }
 
+/// Generator for unique, monotonically increasing tags, e.g., `A0001`, `A0002`, ...
+///
+/// The counter is zero-padded to (at least) four digits and wraps around after [`u64::MAX`].
+///
+/// # Example
+///
+/// ```
+/// use imap_types::core::TagGenerator;
+///
+/// let mut generator = TagGenerator::new();
+///
+/// assert_eq!(generator.next().inner(), "A0001");
+/// assert_eq!(generator.next().inner(), "A0002");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TagGenerator {
+    prefix: String,
+    counter: u64,
+}
+
+impl TagGenerator {
+    /// Create a generator with the prefix `A`.
+    pub fn new() -> Self {
+        Self {
+            prefix: String::from("A"),
+            counter: 0,
+        }
+    }
+
+    /// Create a generator with a custom prefix.
+    ///
+    /// The prefix must be a valid tag, i.e., non-empty and without `+`.
+    pub fn with_prefix<P>(prefix: P) -> Result<Self, ValidationError>
+    where
+        P: Into<String>,
+    {
+        let prefix = prefix.into();
+
+        Tag::validate(&prefix)?;
+
+        Ok(Self { prefix, counter: 0 })
+    }
+
+    /// Generate the next tag.
+    // Note: Not an `Iterator` because the generator never runs out of tags.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Tag<'static> {
+        self.counter = self.counter.wrapping_add(1);
+
+        // Note: A valid prefix followed by digits is a valid tag.
+        Tag(Cow::Owned(format!("{}{:04}", self.prefix, self.counter)))
+    }
+}
+
+impl Default for TagGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// A human-readable text string used in some server responses.
 ///
 /// # Example
@@ -1120,18 +1354,18 @@ impl<'a> AsRef<str> for Tag<'a> {
 /// ```
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(PartialEq, Eq, Hash, Clone)]
+#[derive(PartialEq, Eq, Ord, PartialOrd, Hash, Clone)]
 pub struct Text<'a>(pub(crate) Cow<'a, str>);
 
 // We want a slightly more dense `Debug` implementation.
 impl<'a> Debug for Text<'a> {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         write!(f, "Text({:?})", self.0)
     }
 }
 
 impl<'a> Display for Text<'a> {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         write!(f, "{}", self.0.as_ref())
     }
 }
@@ -1162,6 +1396,49 @@ impl<'a> Text<'a> {
         self.0
     }
 
+    /// Makes `value` usable as a text by replacing all characters that are not allowed.
+    ///
+    /// Trailing line breaks are removed and every other run of CR/LF characters is replaced by a
+    /// single space. Remaining characters that are not TEXT-CHARs, i.e., NUL and non-ASCII
+    /// characters, are replaced by `?`. Returns [`Cow::Borrowed`] when nothing was replaced.
+    ///
+    /// Note: The result may still be empty and is thus not guaranteed to be valid.
+    pub fn sanitize(value: &str) -> Cow<'_, str> {
+        let value = value.trim_end_matches(['\r', '\n']);
+
+        if value.bytes().all(is_text_char) {
+            return Cow::Borrowed(value);
+        }
+
+        let mut sanitized = String::with_capacity(value.len());
+        let mut chars = value.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\r' | '\n' => {
+                    while chars.next_if(|c| matches!(c, '\r' | '\n')).is_some() {}
+                    sanitized.push(' ');
+                }
+                c if c.is_ascii() && is_text_char(c as u8) => sanitized.push(c),
+                _ => sanitized.push('?'),
+            }
+        }
+
+        Cow::Owned(sanitized)
+    }
+
+    /// Constructs a text from arbitrary data, e.g., a log or error message.
+    ///
+    /// In contrast to the strict `TryFrom` implementations, this constructor never fails. See
+    /// [`Self::sanitize`] for how invalid characters are handled. An empty text is replaced by
+    /// `...`.
+    pub fn from_lossy(value: &'a str) -> Self {
+        match Self::sanitize(value) {
+            sanitized if sanitized.is_empty() => Self(Cow::Borrowed("...")),
+            sanitized => Self(sanitized),
+        }
+    }
+
     /// Constructs a text without validation.
     ///
     /// # Warning: IMAP conformance
@@ -1188,10 +1465,10 @@ impl<'a> TryFrom<&'a [u8]> for Text<'a> {
     type Error = ValidationError;
 
     fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
+        let value = from_utf8(value).map_err(invalid_utf8)?;
         Self::validate(value)?;
 
-        // Safety: `unwrap` can't panic due to `validate`.
-        Ok(Self(Cow::Borrowed(from_utf8(value).unwrap())))
+        Ok(Self(Cow::Borrowed(value)))
     }
 }
 
@@ -1199,10 +1476,10 @@ impl<'a> TryFrom<Vec<u8>> for Text<'a> {
     type Error = ValidationError;
 
     fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+        let value = String::from_utf8(value).map_err(|error| invalid_utf8(error.utf8_error()))?;
         Self::validate(&value)?;
 
-        // Safety: `unwrap` can't panic due to `validate`.
-        Ok(Self(Cow::Owned(String::from_utf8(value).unwrap())))
+        Ok(Self(Cow::Owned(value)))
     }
 }
 
@@ -1232,6 +1509,226 @@ impl<'a> AsRef<str> for Text<'a> {
     }
 }
 
+/// Name of an extension command or response, e.g., `XLIST`.
+///
+/// The name must be an atom that starts with "X" (and has at least one more character). Thus, it
+/// can't be the name of a known command or response, e.g., `NOOP` or `FETCH`.
+///
+/// ```abnf
+/// x-command = "X" atom <experimental command arguments>
+/// ```
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct ExtensionName<'a>(pub(crate) Cow<'a, str>);
+
+impl<'a> ExtensionName<'a> {
+    /// Validates if value can be used as an extension name.
+    pub fn validate(value: impl AsRef<[u8]>) -> Result<(), ValidationError> {
+        let value = value.as_ref();
+
+        Atom::validate(value)?;
+
+        if value.len() < 2 || !matches!(value[0], b'X' | b'x') {
+            return Err(ValidationError::new(ValidationErrorKind::Invalid));
+        }
+
+        Ok(())
+    }
+
+    /// Returns a reference to the inner value.
+    pub fn inner(&self) -> &str {
+        self.0.as_ref()
+    }
+
+    /// Consumes the name, returning the inner value.
+    pub fn into_inner(self) -> Cow<'a, str> {
+        self.0
+    }
+
+    /// Constructs an extension name without validation.
+    ///
+    /// # Warning: IMAP conformance
+    ///
+    /// The caller must ensure that `inner` is valid according to [`Self::validate`]. Failing to do
+    /// so may create invalid/unparsable IMAP messages, or even produce unintended protocol flows.
+    /// Do not call this constructor with untrusted data.
+    #[cfg(feature = "unvalidated")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unvalidated")))]
+    pub fn unvalidated<C>(inner: C) -> Self
+    where
+        C: Into<Cow<'a, str>>,
+    {
+        let inner = inner.into();
+
+        #[cfg(debug_assertions)]
+        Self::validate(inner.as_bytes()).unwrap();
+
+        Self(inner)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for ExtensionName<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        Self::validate(value)?;
+
+        Ok(Self(Cow::Borrowed(value)))
+    }
+}
+
+impl<'a> TryFrom<String> for ExtensionName<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::validate(&value)?;
+
+        Ok(Self(Cow::Owned(value)))
+    }
+}
+
+impl<'a> TryFrom<Atom<'a>> for ExtensionName<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: Atom<'a>) -> Result<Self, Self::Error> {
+        Self::validate(value.inner())?;
+
+        Ok(Self(value.into_inner()))
+    }
+}
+
+impl<'a> AsRef<str> for ExtensionName<'a> {
+    fn as_ref(&self) -> &str {
+        self.0.as_ref()
+    }
+}
+
+/// Raw arguments of an extension command or response, e.g., `CommandBody::Extension`.
+///
+/// Arguments are kept as-is (without the separating space) to allow a byte-exact round-trip. They
+/// may contain any byte except NUL, CR, and LF, and may be empty. Note that literals are not
+/// supported, so the arguments must not end with a literal prefix, e.g., `{5}`.
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(PartialEq, Eq, Hash, Clone, Default)]
+pub struct ExtensionArguments<'a>(pub(crate) Cow<'a, [u8]>);
+
+// We want a more readable `Debug` implementation.
+impl<'a> Debug for ExtensionArguments<'a> {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "ExtensionArguments(b\"{}\")",
+            crate::utils::escape_byte_string(self.0.as_ref())
+        )
+    }
+}
+
+impl<'a> ExtensionArguments<'a> {
+    /// Validates if value can be used as extension arguments.
+    pub fn validate(value: impl AsRef<[u8]>) -> Result<(), ValidationError> {
+        let value = value.as_ref();
+
+        if let Some(at) = value
+            .iter()
+            .position(|b| matches!(b, b'\x00' | b'\r' | b'\n'))
+        {
+            return Err(ValidationError::new(ValidationErrorKind::InvalidByteAt {
+                byte: value[at],
+                at,
+            }));
+        };
+
+        // A trailing `{<number>}` (or `{<number>+}`) would announce a literal.
+        if let Some(value) = value.strip_suffix(b"}") {
+            if let Some(open) = value.iter().rposition(|b| *b == b'{') {
+                let digits = &value[open + 1..];
+                let digits = digits.strip_suffix(b"+").unwrap_or(digits);
+
+                if !digits.is_empty() && digits.iter().all(u8::is_ascii_digit) {
+                    return Err(ValidationError::new(ValidationErrorKind::Invalid));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns a reference to the inner value.
+    pub fn inner(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+
+    /// Consumes the arguments, returning the inner value.
+    pub fn into_inner(self) -> Cow<'a, [u8]> {
+        self.0
+    }
+
+    /// Constructs extension arguments without validation.
+    ///
+    /// # Warning: IMAP conformance
+    ///
+    /// The caller must ensure that `inner` is valid according to [`Self::validate`]. Failing to do
+    /// so may create invalid/unparsable IMAP messages, or even produce unintended protocol flows.
+    /// Do not call this constructor with untrusted data.
+    #[cfg(feature = "unvalidated")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unvalidated")))]
+    pub fn unvalidated<C>(inner: C) -> Self
+    where
+        C: Into<Cow<'a, [u8]>>,
+    {
+        let inner = inner.into();
+
+        #[cfg(debug_assertions)]
+        Self::validate(&inner).unwrap();
+
+        Self(inner)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for ExtensionArguments<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
+        Self::validate(value)?;
+
+        Ok(Self(Cow::Borrowed(value)))
+    }
+}
+
+impl<'a> TryFrom<Vec<u8>> for ExtensionArguments<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+        Self::validate(&value)?;
+
+        Ok(Self(Cow::Owned(value)))
+    }
+}
+
+impl<'a> TryFrom<&'a str> for ExtensionArguments<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        Self::try_from(value.as_bytes())
+    }
+}
+
+impl<'a> TryFrom<String> for ExtensionArguments<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::try_from(value.into_bytes())
+    }
+}
+
+impl<'a> AsRef<[u8]> for ExtensionArguments<'a> {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
 /// A quoted char.
 ///
 /// # ABNF definition
@@ -1417,7 +1914,7 @@ impl<T, const N: usize> Debug for VecN<T, N>
 where
     T: Debug,
 {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         self.0.fmt(f)?;
         match N {
             0 => write!(f, "*"),
@@ -1457,6 +1954,17 @@ impl<T, const N: usize> VecN<T, N> {
     pub fn into_inner(self) -> Vec<T> {
         self.0
     }
+
+    /// Collects an iterator, failing when it yields less than `N` elements.
+    ///
+    /// This is the fallible counterpart of [`FromIterator`], e.g.,
+    /// `Vec1::try_from_iter(flags)` instead of `flags.into_iter().collect()`.
+    pub fn try_from_iter<I>(iter: I) -> Result<Self, ValidationError>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        Self::try_from(iter.into_iter().collect::<Vec<T>>())
+    }
 }
 
 impl<T, const N: usize> From<[T; N]> for VecN<T, N> {
@@ -1512,6 +2020,12 @@ impl<T> From<(T, T)> for Vec2<T> {
     }
 }
 
+fn invalid_utf8(error: Utf8Error) -> ValidationError {
+    ValidationError::new(ValidationErrorKind::InvalidUtf8 {
+        at: error.valid_up_to(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::from_utf8;
@@ -1899,6 +2413,258 @@ mod tests {
             IString::try_from("\"AAA").unwrap(),
             IString::Quoted("\\\"AAA".try_into().unwrap())
         );
+
+        assert_eq!(
+            IString::try_from("A\tA").unwrap(),
+            IString::Quoted("A\tA".try_into().unwrap())
+        );
+        assert_eq!(
+            IString::try_from("A\r\nA").unwrap(),
+            IString::Literal("A\r\nA".try_into().unwrap())
+        );
+        assert!(IString::try_from("A\x00A").is_err());
+    }
+
+    #[test]
+    fn test_conversion_istring_forced() {
+        assert_eq!(
+            IString::literal("AAA").unwrap(),
+            IString::Literal("AAA".try_into().unwrap())
+        );
+        assert_eq!(
+            IString::quoted("AAA").unwrap(),
+            IString::Quoted("AAA".try_into().unwrap())
+        );
+        assert!(IString::quoted("A\nA").is_err());
+        assert!(IString::literal("A\x00A").is_err());
+    }
+
+    #[test]
+    fn test_atom_eq_ignore_ascii_case() {
+        let atom = Atom::try_from("IMAP4rev1").unwrap();
+
+        assert!(atom.eq_ignore_ascii_case("imap4rev1"));
+        assert!(atom.eq_ignore_ascii_case("IMAP4REV1"));
+        assert!(!atom.eq_ignore_ascii_case("IMAP4rev2"));
+        assert_ne!(atom, Atom::try_from("imap4rev1").unwrap());
+    }
+
+    #[test]
+    fn test_atom_from_static() {
+        assert_eq!(atom!("FLAGS"), Atom::try_from("FLAGS").unwrap());
+        assert!(matches!(atom!("UID").into_inner(), Cow::Borrowed("UID")));
+
+        assert!(std::panic::catch_unwind(|| Atom::from_static("")).is_err());
+        assert!(std::panic::catch_unwind(|| Atom::from_static("FLA GS")).is_err());
+    }
+
+    #[test]
+    fn test_tag_generator() {
+        let mut generator = TagGenerator::new();
+        let mut seen = std::collections::HashSet::new();
+
+        for _ in 0..100_000 {
+            let tag = generator.next();
+            assert!(Tag::validate(tag.inner()).is_ok());
+            assert!(seen.insert(tag));
+        }
+
+        let mut generator = TagGenerator::with_prefix("imap-").unwrap();
+        assert_eq!(generator.next().inner(), "imap-0001");
+        assert_eq!(generator.next().inner(), "imap-0002");
+
+        generator.counter = u64::MAX - 1;
+        assert_eq!(generator.next().inner(), format!("imap-{}", u64::MAX));
+        assert_eq!(generator.next().inner(), "imap-0000");
+        assert_eq!(generator.next().inner(), "imap-0001");
+
+        assert!(TagGenerator::with_prefix("").is_err());
+        assert!(TagGenerator::with_prefix("A+").is_err());
+        assert!(TagGenerator::with_prefix("A B").is_err());
+    }
+
+    #[test]
+    fn test_compact() {
+        let tests = [
+            ("alice", AString::Atom(AtomExt::try_from("alice").unwrap())),
+            (
+                "INBOX/]",
+                AString::Atom(AtomExt::try_from("INBOX/]").unwrap()),
+            ),
+            (
+                "alice smith",
+                AString::String(IString::Quoted(Quoted::try_from("alice smith").unwrap())),
+            ),
+            (
+                "",
+                AString::String(IString::Quoted(Quoted::try_from("").unwrap())),
+            ),
+            (
+                "a\"b\\c",
+                AString::String(IString::Quoted(Quoted::try_from("a\"b\\c").unwrap())),
+            ),
+            (
+                "Pa²²W0rD",
+                AString::String(IString::Literal(Literal::try_from("Pa²²W0rD").unwrap())),
+            ),
+            (
+                "a\r\nb",
+                AString::String(IString::Literal(Literal::try_from("a\r\nb").unwrap())),
+            ),
+        ];
+
+        for (test, expected) in tests {
+            assert_eq!(AString::compact(test).unwrap(), expected);
+            assert_eq!(AString::try_from(test).unwrap(), expected);
+        }
+
+        assert!(AString::compact("a\x00b").is_err());
+
+        assert_eq!(NString::compact(None).unwrap(), NString(None));
+        assert_eq!(
+            NString::compact(Some("NIL")).unwrap(),
+            NString(Some(IString::Quoted(Quoted::try_from("NIL").unwrap())))
+        );
+        assert_eq!(
+            NString::compact(Some("a b")).unwrap(),
+            NString(Some(IString::Quoted(Quoted::try_from("a b").unwrap())))
+        );
+        assert!(NString::compact(Some("a\x00b")).is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Tag::try_from("A1").unwrap().to_string(), "A1");
+        assert_eq!(Quoted::try_from("").unwrap().to_string(), r#""""#);
+        assert_eq!(
+            Quoted::try_from(r#"a "b" \c"#).unwrap().to_string(),
+            r#""a \"b\" \\c""#
+        );
+    }
+
+    #[test]
+    fn test_ord() {
+        use std::collections::BTreeMap;
+
+        let mut tags = BTreeMap::new();
+        tags.insert(Tag::try_from("B2").unwrap(), 2);
+        tags.insert(Tag::try_from("A1").unwrap(), 1);
+        tags.insert(Tag::try_from("B10").unwrap(), 10);
+        assert_eq!(
+            tags.keys().map(Tag::inner).collect::<Vec<_>>(),
+            ["A1", "B10", "B2"]
+        );
+
+        assert!(Atom::try_from("a").unwrap() < Atom::try_from("b").unwrap());
+        assert!(AtomExt::try_from("a]").unwrap() < AtomExt::try_from("b]").unwrap());
+        // Byte ordering, i.e., uppercase before lowercase.
+        assert!(Text::try_from("Z").unwrap() < Text::try_from("a").unwrap());
+    }
+
+    #[test]
+    fn test_literal_mode() {
+        let sync = Literal::try_from_sync("foo").unwrap();
+        assert_eq!(sync.mode(), LiteralMode::Sync);
+        assert_eq!(sync.data(), b"foo");
+
+        let non_sync = Literal::try_from_non_sync(b"foo".as_ref()).unwrap();
+        assert_eq!(non_sync.mode(), LiteralMode::NonSync);
+        assert_eq!(non_sync.clone().into_sync(), sync);
+
+        assert!(Literal::try_from_non_sync("f\x00o").is_err());
+    }
+
+    #[test]
+    fn test_into_bytes_and_strings() {
+        fn takes_bytes(bytes: impl AsRef<[u8]>) -> usize {
+            bytes.as_ref().len()
+        }
+
+        let literal = Literal::try_from(b"\xCA\xFE".as_ref()).unwrap();
+        assert_eq!(takes_bytes(&literal), 2);
+        assert_eq!(Vec::from(literal.clone()), b"\xCA\xFE");
+        assert_eq!(literal.into_inner(), Cow::Borrowed(b"\xCA\xFE".as_ref()));
+
+        let quoted = Quoted::try_from("foo bar").unwrap();
+        assert_eq!(String::from(quoted.clone()), "foo bar");
+        assert_eq!(quoted.into_inner(), "foo bar");
+
+        let atom = Atom::try_from("FOO").unwrap();
+        assert_eq!(String::from(atom.clone()), "FOO");
+        assert_eq!(atom.into_inner(), "FOO");
+    }
+
+    #[test]
+    fn test_extension_name() {
+        for valid in ["XLIST", "xlist", "X-GM-EXT-1", "XY"] {
+            assert_eq!(ExtensionName::try_from(valid).unwrap().inner(), valid);
+        }
+
+        for invalid in [
+            "",
+            "X",
+            "NOOP",
+            "FETCH",
+            "LOGOUT",
+            "X LIST",
+            "X(",
+            "EXTENSION",
+        ] {
+            assert!(ExtensionName::try_from(invalid).is_err());
+        }
+    }
+
+    #[test]
+    fn test_extension_arguments() {
+        for valid in [
+            b"".as_ref(),
+            b"(\\Inbox) \"/\" \"INBOX\"",
+            b" \xc3\xa4 {5} x",
+            b"{}",
+            b"{a}",
+        ] {
+            assert_eq!(ExtensionArguments::try_from(valid).unwrap().inner(), valid);
+        }
+
+        for invalid in [
+            b"a\r".as_ref(),
+            b"\nb",
+            b"a\x00b",
+            b" \xc3\xa4 {5}",
+            b"~{0+}",
+        ] {
+            assert!(ExtensionArguments::try_from(invalid).is_err());
+        }
+    }
+
+    #[test]
+    fn test_text_from_lossy() {
+        let tests = [
+            ("Hello, World!", "Hello, World!"),
+            ("Mailbox not found\n", "Mailbox not found"),
+            ("Mailbox not found\r\n\r\n", "Mailbox not found"),
+            (
+                "first line\r\nsecond line\nthird",
+                "first line second line third",
+            ),
+            ("\rleading", " leading"),
+            ("Gr\u{fc}\u{df}e\x00", "Gr??e?"),
+            ("", "..."),
+            ("\r\n", "..."),
+        ];
+
+        for (test, expected) in tests {
+            let got = Text::from_lossy(test);
+            assert_eq!(got.inner(), expected);
+            assert!(Text::validate(got.inner()).is_ok());
+        }
+
+        assert!(matches!(Text::sanitize("valid"), Cow::Borrowed("valid")));
+        assert!(matches!(
+            Text::sanitize("valid\r\n"),
+            Cow::Borrowed("valid")
+        ));
+        assert!(Text::try_from("invalid\r\n").is_err());
     }
 
     #[test]
@@ -1919,5 +2685,12 @@ mod tests {
         assert!(VecN::<u8, 2>::try_from(vec![]).is_err());
         assert!(VecN::<u8, 2>::try_from(vec![1]).is_err());
         assert!(VecN::<u8, 2>::try_from(vec![1, 2]).is_ok());
+
+        assert!(Vec1::<u8>::try_from_iter([]).is_err());
+        assert_eq!(
+            Vec1::try_from_iter((1..=3).map(|i| i * 2)).unwrap(),
+            Vec1::try_from(vec![2, 4, 6]).unwrap()
+        );
+        assert!(Vec2::try_from_iter(Some(1)).is_err());
     }
 }
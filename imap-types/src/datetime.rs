@@ -1,6 +1,6 @@
 //! Date and time-related types.
 
-use std::fmt::{Debug, Formatter};
+use core::fmt::{Debug, Formatter};
 
 #[cfg(feature = "bounded-static")]
 use bounded_static::{IntoBoundedStatic, ToBoundedStatic};
@@ -61,7 +61,7 @@ impl TryFrom<chrono::DateTime<FixedOffset>> for DateTime {
 }
 
 impl Debug for DateTime {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         Debug::fmt(&self.0, f)
     }
 }
@@ -129,7 +129,7 @@ impl TryFrom<chrono::NaiveDate> for NaiveDate {
 }
 
 impl Debug for NaiveDate {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         Debug::fmt(&self.0, f)
     }
 }
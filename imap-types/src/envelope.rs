@@ -1,9 +1,12 @@
 //! Envelope-related types.
 
+use alloc::vec::Vec;
+
 #[cfg(feature = "arbitrary")]
 use arbitrary::Arbitrary;
 #[cfg(feature = "bounded-static")]
 use bounded_static::ToStatic;
+use chrono::FixedOffset;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -26,6 +29,27 @@ pub struct Envelope<'a> {
     pub message_id: NString<'a>,
 }
 
+impl<'a> Envelope<'a> {
+    /// Interprets the date field as an RFC 5322 date-time, e.g., `Tue, 1 Jul 2003 10:52:37 +0200`.
+    ///
+    /// Obsolete forms (e.g., `GMT`/`EST` zones, two-digit years), missing seconds, and a
+    /// trailing comment (e.g., `(CEST)`) are tolerated.
+    ///
+    /// Returns `None` when the date is `NIL` or malformed.
+    pub fn parsed_date(&self) -> Option<chrono::DateTime<FixedOffset>> {
+        let date = core::str::from_utf8(self.date.0.as_ref()?.as_ref()).ok()?;
+
+        let mut date = date.trim();
+
+        // Remove a trailing comment, e.g., `(CEST)`.
+        if let Some(rest) = date.strip_suffix(')') {
+            date = rest.rsplit_once('(')?.0.trim_end();
+        }
+
+        chrono::DateTime::parse_from_rfc2822(date).ok()
+    }
+}
+
 /// An address structure describes an electronic mail address.
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
@@ -53,3 +77,62 @@ pub struct Address<'a> {
     /// Host name
     pub host: NString<'a>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope(date: Option<&str>) -> Envelope {
+        Envelope {
+            date: NString(date.map(|date| date.try_into().unwrap())),
+            subject: NString(None),
+            from: vec![],
+            sender: vec![],
+            reply_to: vec![],
+            to: vec![],
+            cc: vec![],
+            bcc: vec![],
+            in_reply_to: NString(None),
+            message_id: NString(None),
+        }
+    }
+
+    #[test]
+    fn test_parsed_date() {
+        let tests = [
+            (
+                "Tue, 1 Jul 2003 10:52:37 +0200",
+                "2003-07-01T10:52:37+02:00",
+            ),
+            ("1 Jul 2003 10:52:37 +0200", "2003-07-01T10:52:37+02:00"),
+            ("Tue, 1 Jul 2003 10:52 +0200", "2003-07-01T10:52:00+02:00"),
+            ("Tue, 1 Jul 2003 10:52:37 GMT", "2003-07-01T10:52:37+00:00"),
+            ("Tue, 1 Jul 2003 10:52:37 EST", "2003-07-01T10:52:37-05:00"),
+            ("Tue, 1 Jul 03 10:52:37 +0200", "2003-07-01T10:52:37+02:00"),
+            (
+                " Tue, 1 Jul 2003 10:52:37 +0200 (CEST) ",
+                "2003-07-01T10:52:37+02:00",
+            ),
+        ];
+
+        for (test, expected) in tests {
+            assert_eq!(
+                envelope(Some(test)).parsed_date(),
+                Some(chrono::DateTime::parse_from_rfc3339(expected).unwrap()),
+                "{test}"
+            );
+        }
+
+        for test in [
+            "",
+            "yesterday",
+            "Tue, 31 Feb 2003 10:52:37 +0200",
+            "Tue, 1 Jul 2003 25:52:37 +0200",
+            "(CEST)",
+        ] {
+            assert_eq!(envelope(Some(test)).parsed_date(), None, "{test}");
+        }
+
+        assert_eq!(envelope(None).parsed_date(), None);
+    }
+}
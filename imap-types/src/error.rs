@@ -1,6 +1,6 @@
 //! Error-related types.
 
-use std::fmt::{Display, Formatter};
+use core::fmt::{Display, Formatter};
 
 use thiserror::Error;
 
@@ -13,25 +13,93 @@ pub struct ValidationError {
 }
 
 impl Display for ValidationError {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         write!(f, "Validation failed: {}", self.kind)
     }
 }
 
+/// The reason why a validation failed.
 #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
-pub(crate) enum ValidationErrorKind {
+#[non_exhaustive]
+pub enum ValidationErrorKind {
+    /// The value was empty.
     #[error("Must not be empty")]
     Empty,
+    /// The value had fewer than `min` elements.
     #[error("Must have at least {min} elements")]
     NotEnough { min: usize },
+    /// The value was invalid for another reason.
     #[error("Invalid value")]
     Invalid,
+    /// The value contained a forbidden `byte` at index `at`.
     #[error("Invalid byte b'\\x{byte:02x}' at index {at}")]
     InvalidByteAt { byte: u8, at: usize },
+    /// The value was not valid UTF-8, starting at index `at`.
+    #[error("Invalid UTF-8 at index {at}")]
+    InvalidUtf8 { at: usize },
 }
 
 impl ValidationError {
     pub(crate) fn new(kind: ValidationErrorKind) -> Self {
         Self { kind }
     }
+
+    /// Returns the reason why the validation failed.
+    pub fn kind(&self) -> &ValidationErrorKind {
+        &self.kind
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Atom, AtomExt, Charset, IString, Quoted, Text};
+
+    #[test]
+    fn test_validation_error_kind() {
+        assert_eq!(
+            Atom::try_from("").unwrap_err().kind(),
+            &ValidationErrorKind::Empty
+        );
+        assert_eq!(
+            Atom::try_from("AB(C").unwrap_err().kind(),
+            &ValidationErrorKind::InvalidByteAt { byte: b'(', at: 2 }
+        );
+        assert_eq!(
+            IString::try_from("A\x00B").unwrap_err().kind(),
+            &ValidationErrorKind::InvalidByteAt { byte: 0x00, at: 1 }
+        );
+        assert_eq!(
+            Atom::try_from("AB(C").unwrap_err().to_string(),
+            "Validation failed: Invalid byte b'\\x28' at index 2"
+        );
+    }
+
+    #[test]
+    fn test_validation_error_kind_bytes() {
+        assert!(Atom::try_from(b"ABC".as_ref()).is_ok());
+        assert!(Quoted::try_from(b"A B".as_ref()).is_ok());
+        assert!(Text::try_from(b"A B".to_vec()).is_ok());
+        assert!(Charset::try_from(b"UTF-8".as_ref()).is_ok());
+
+        // Valid UTF-8, but not an atom.
+        assert_eq!(
+            Atom::try_from(b"A B".as_ref()).unwrap_err().kind(),
+            &ValidationErrorKind::InvalidByteAt { byte: b' ', at: 1 }
+        );
+
+        for value in [b"AB\xff".as_ref(), b"AB\xc3"] {
+            let expected = ValidationErrorKind::InvalidUtf8 { at: 2 };
+
+            assert_eq!(Atom::try_from(value).unwrap_err().kind(), &expected);
+            assert_eq!(AtomExt::try_from(value).unwrap_err().kind(), &expected);
+            assert_eq!(Quoted::try_from(value).unwrap_err().kind(), &expected);
+            assert_eq!(Text::try_from(value).unwrap_err().kind(), &expected);
+            assert_eq!(Charset::try_from(value).unwrap_err().kind(), &expected);
+            assert_eq!(
+                Text::try_from(value.to_vec()).unwrap_err().kind(),
+                &expected
+            );
+        }
+    }
 }
@@ -1,12 +1,30 @@
 //! IMAP extensions.
 
+#[cfg(feature = "ext_annotate")]
+pub mod annotate;
 pub mod compress;
+#[cfg(feature = "ext_context")]
+pub mod context;
 pub mod enable;
+#[cfg(feature = "ext_gmail")]
+pub mod gmail;
 pub mod idle;
+#[cfg(feature = "ext_metadata")]
+pub mod metadata;
 pub mod r#move;
+#[cfg(feature = "ext_notify")]
+pub mod notify;
 pub mod quota;
+#[cfg(any(
+    feature = "ext_login_referrals",
+    feature = "ext_mailbox_referrals",
+    feature = "ext_urlauth"
+))]
+pub mod referral;
 #[cfg(feature = "ext_sort_thread")]
 pub mod sort;
 #[cfg(feature = "ext_sort_thread")]
 pub mod thread;
 pub mod unselect;
+#[cfg(feature = "ext_urlauth")]
+pub mod urlauth;
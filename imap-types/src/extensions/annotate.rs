@@ -0,0 +1,93 @@
+//! IMAP ANNOTATE Extension
+//!
+//! This extension defines new types ...
+//!
+//! * [`EntryAnnotations`](crate::extensions::annotate::EntryAnnotations)
+//! * [`AttributeValue`](crate::extensions::annotate::AttributeValue)
+//! * [`MessageAnnotations`](crate::extensions::annotate::MessageAnnotations)
+//! * [`AnnotateCode`](crate::extensions::annotate::AnnotateCode)
+//!
+//! ... and extends ...
+//!
+//! * the [`MessageDataItemName`](crate::fetch::MessageDataItemName) enum with a new variant [`MessageDataItemName::Annotation`](crate::fetch::MessageDataItemName#variant.Annotation),
+//! * the [`MessageDataItem`](crate::fetch::MessageDataItem) enum with a new variant [`MessageDataItem::Annotation`](crate::fetch::MessageDataItem#variant.Annotation),
+//! * the [`SearchKey`](crate::search::SearchKey) enum with a new variant [`SearchKey::Annotation`](crate::search::SearchKey#variant.Annotation), and
+//! * the [`Code`](crate::response::Code) enum with a new variant [`Code::Annotate`](crate::response::Code#variant.Annotate).
+//!
+//! Entry and attribute *specifiers* (used in FETCH and SEARCH) may contain the `*` and `%`
+//! wildcards. Entry specifiers are [`ListMailbox`](crate::mailbox::ListMailbox)es, i.e., wildcards
+//! don't require quoting (`/comment/*`). Attribute specifiers are [`AString`]s, i.e., wildcards
+//! must be quoted (`"value.*"`).
+//!
+//! See <https://datatracker.ietf.org/doc/html/rfc5257>.
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::Arbitrary;
+#[cfg(feature = "bounded-static")]
+use bounded_static::ToStatic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::core::{AString, NString, Vec1};
+
+/// Annotations of a message, as returned in a FETCH response.
+///
+/// ```abnf
+/// msg-att-dynamic =/ "ANNOTATION" SP
+///                    ( "(" entry-att *(SP entry-att) ")" /
+///                      "(" entry *(SP entry) ")" )
+/// ```
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MessageAnnotations<'a> {
+    /// Entries with their attribute values.
+    WithValues(Vec1<EntryAnnotations<'a>>),
+    /// Entries only, e.g., in an unsolicited FETCH response after a change.
+    WithoutValues(Vec1<AString<'a>>),
+}
+
+/// An annotation entry with its attribute values.
+///
+/// ```abnf
+/// entry-att = entry SP "(" att-value *(SP att-value) ")"
+/// ```
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EntryAnnotations<'a> {
+    /// Entry name, e.g., `/comment`.
+    pub entry: AString<'a>,
+    /// Attribute values.
+    pub values: Vec1<AttributeValue<'a>>,
+}
+
+/// An annotation attribute with its value.
+///
+/// ```abnf
+/// att-value = attrib SP value
+/// ```
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AttributeValue<'a> {
+    /// Attribute name, e.g., `value.priv`.
+    pub attribute: AString<'a>,
+    /// Value, where `NIL` removes the annotation.
+    pub value: NString<'a>,
+}
+
+/// The `ANNOTATE` response code.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnnotateCode {
+    /// An annotation value is too large.
+    TooBig,
+    /// A message has too many annotations.
+    TooMany,
+}
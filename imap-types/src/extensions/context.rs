@@ -0,0 +1,150 @@
+//! IMAP ESEARCH, CONTEXT, and ESORT Extensions
+//!
+//! This extension defines new types ...
+//!
+//! * [`SearchReturnOption`](crate::extensions::context::SearchReturnOption)
+//! * [`SearchReturnData`](crate::extensions::context::SearchReturnData)
+//! * [`PartialRange`](crate::extensions::context::PartialRange)
+//!
+//! ... and extends ...
+//!
+//! * the [`CommandBody`](crate::command::CommandBody) enum with new variants [`CommandBody::ESearch`](crate::command::CommandBody#variant.ESearch) and [`CommandBody::ESort`](crate::command::CommandBody#variant.ESort), and
+//! * the [`Data`](crate::response::Data) enum with a new variant [`Data::ESearch`](crate::response::Data#variant.ESearch).
+//!
+//! Note: The `RETURN` options and the `ESEARCH` response are defined by ESEARCH (RFC 4731) and
+//! extended by CONTEXT and ESORT (RFC 5267). Both are enabled by the `ext_context` feature.
+//!
+//! See <https://datatracker.ietf.org/doc/html/rfc4731> and <https://datatracker.ietf.org/doc/html/rfc5267>.
+
+use alloc::vec::Vec;
+use core::num::NonZeroU32;
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::Arbitrary;
+#[cfg(feature = "bounded-static")]
+use bounded_static::ToStatic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    command::CommandBody,
+    core::{Charset, Vec1},
+    search::SearchKey,
+    sequence::SequenceSet,
+};
+
+impl<'a> CommandBody<'a> {
+    /// Construct a SEARCH command with `RETURN` options.
+    ///
+    /// An empty list of return options is equivalent to `ALL`.
+    pub fn esearch(
+        return_options: Vec<SearchReturnOption>,
+        charset: Option<Charset<'a>>,
+        criteria: Vec1<SearchKey<'a>>,
+        uid: bool,
+    ) -> Self {
+        CommandBody::ESearch {
+            return_options,
+            charset,
+            criteria,
+            uid,
+        }
+    }
+}
+
+/// A `RETURN` option of a SEARCH or SORT command.
+///
+/// ```abnf
+/// search-return-opt = "MIN" / "MAX" / "ALL" / "COUNT" /  ; RFC 4731
+///                     "UPDATE" / "CONTEXT" /             ; RFC 5267
+///                     "PARTIAL" SP partial-range         ; RFC 5267
+/// ```
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SearchReturnOption {
+    /// Return the lowest message number/UID.
+    Min,
+    /// Return the highest message number/UID.
+    Max,
+    /// Return all message numbers/UIDs (as a sequence set).
+    All,
+    /// Return the number of matching messages.
+    Count,
+    /// Keep the result up to date, i.e., send `ADDTO`/`REMOVEFROM` updates.
+    Update,
+    /// Hint that the result will be used as context for further commands.
+    Context,
+    /// Return a window of the (sorted) result.
+    Partial(PartialRange),
+}
+
+/// A 1-based, inclusive window over a search result, e.g., `1:100`.
+///
+/// ```abnf
+/// partial-range = nz-number ":" nz-number
+/// ```
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PartialRange {
+    /// Position of the first result.
+    pub first: NonZeroU32,
+    /// Position of the last result.
+    pub last: NonZeroU32,
+}
+
+/// Data returned in an `ESEARCH` response.
+///
+/// ```abnf
+/// search-return-data = "MIN" SP nz-number /
+///                      "MAX" SP nz-number /
+///                      "ALL" SP sequence-set /
+///                      "COUNT" SP number /
+///                      ret-data-partial /
+///                      ret-data-addto /
+///                      ret-data-removefrom
+/// ```
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SearchReturnData {
+    /// Lowest message number/UID.
+    Min(NonZeroU32),
+    /// Highest message number/UID.
+    Max(NonZeroU32),
+    /// All message numbers/UIDs.
+    All(SequenceSet),
+    /// Number of matching messages.
+    Count(u32),
+    /// A window of the result.
+    ///
+    /// ```abnf
+    /// ret-data-partial = "PARTIAL" SP "(" partial-range SP partial-results ")"
+    ///
+    /// partial-results = sequence-set / "NIL"
+    /// ```
+    Partial {
+        /// The requested window.
+        range: PartialRange,
+        /// Message numbers/UIDs in the window, where `NIL` means none.
+        results: Option<SequenceSet>,
+    },
+    /// Messages that were added to the result, with their position in the result.
+    ///
+    /// ```abnf
+    /// ret-data-addto = "ADDTO" SP "(" context-position SP sequence-set
+    ///                  *(SP context-position SP sequence-set) ")"
+    /// ```
+    AddTo(Vec1<(u32, SequenceSet)>),
+    /// Messages that were removed from the result, with their position in the result.
+    ///
+    /// ```abnf
+    /// ret-data-removefrom = "REMOVEFROM" SP "(" context-position SP sequence-set
+    ///                       *(SP context-position SP sequence-set) ")"
+    /// ```
+    RemoveFrom(Vec1<(u32, SequenceSet)>),
+}
@@ -6,7 +6,7 @@
 //! * the [CommandBody](crate::command::CommandBody) enum with a new variant [CommandBody::Enable](crate::command::CommandBody#variant.Enable), and
 //! * the [Data](crate::response::Data) enum with a new variant [Data::Enabled](crate::response::Data#variant.Enabled).
 
-use std::fmt::{Display, Formatter};
+use core::fmt::{Display, Formatter};
 
 #[cfg(feature = "arbitrary")]
 use arbitrary::Arbitrary;
@@ -65,7 +65,7 @@ impl<'a> From<Atom<'a>> for CapabilityEnable<'a> {
 }
 
 impl<'a> Display for CapabilityEnable<'a> {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         match self {
             Self::Utf8(kind) => write!(f, "UTF8={}", kind),
             #[cfg(feature = "ext_condstore_qresync")]
@@ -94,7 +94,7 @@ pub enum Utf8Kind {
 }
 
 impl Display for Utf8Kind {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         f.write_str(match self {
             Self::Accept => "ACCEPT",
             Self::Only => "ONLY",
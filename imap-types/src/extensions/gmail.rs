@@ -0,0 +1,109 @@
+//! Gmail IMAP Extensions
+//!
+//! This extension defines new types ...
+//!
+//! * [`GmailLabel`](crate::extensions::gmail::GmailLabel)
+//!
+//! ... and extends ...
+//!
+//! * the [`CommandBody`](crate::command::CommandBody) enum with a new variant [`CommandBody::StoreGmailLabels`](crate::command::CommandBody#variant.StoreGmailLabels),
+//! * the [`MessageDataItemName`](crate::fetch::MessageDataItemName) enum with new variants `GmailLabels`, `GmailMsgId`, and `GmailThrId`,
+//! * the [`MessageDataItem`](crate::fetch::MessageDataItem) enum with new variants `GmailLabels`, `GmailMsgId`, and `GmailThrId`, and
+//! * the [`SearchKey`](crate::search::SearchKey) enum with a new variant [`SearchKey::GmailRaw`](crate::search::SearchKey#variant.GmailRaw).
+//!
+//! See <https://developers.google.com/gmail/imap/imap-extensions>.
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::Arbitrary;
+#[cfg(feature = "bounded-static")]
+use bounded_static::ToStatic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    command::CommandBody,
+    core::{AString, Atom},
+    error::ValidationError,
+    flag::{StoreResponse, StoreType},
+    sequence::{SeqNoOrUidSet, SequenceSet},
+};
+
+impl<'a> CommandBody<'a> {
+    /// Construct a STORE command that modifies Gmail labels.
+    pub fn store_gmail_labels<S>(
+        sequence_set: S,
+        kind: StoreType,
+        response: StoreResponse,
+        labels: Vec<GmailLabel<'a>>,
+        uid: bool,
+    ) -> Result<Self, S::Error>
+    where
+        S: TryInto<SequenceSet>,
+    {
+        Ok(CommandBody::StoreGmailLabels {
+            sequence_set: SeqNoOrUidSet::new(sequence_set.try_into()?, uid),
+            kind,
+            response,
+            labels,
+        })
+    }
+}
+
+/// A Gmail label.
+///
+/// ```abnf
+/// gmail-label = "\" atom / astring
+/// ```
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GmailLabel<'a> {
+    /// System label, e.g., `\Inbox`, `\Important`, or `\Sent` (without the backslash).
+    System(Atom<'a>),
+    /// User-defined label, e.g., `"Work"`.
+    Other(AString<'a>),
+}
+
+impl<'a> TryFrom<&'a str> for GmailLabel<'a> {
+    type Error = ValidationError;
+
+    /// Parses `\<atom>` as a system label and everything else as a user-defined label.
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        match value.strip_prefix('\\') {
+            Some(system) => Ok(Self::System(Atom::try_from(system)?)),
+            None => Ok(Self::Other(AString::try_from(value)?)),
+        }
+    }
+}
+
+impl<'a> From<AString<'a>> for GmailLabel<'a> {
+    fn from(value: AString<'a>) -> Self {
+        Self::Other(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion_gmail_label() {
+        assert_eq!(
+            GmailLabel::try_from("\\Inbox").unwrap(),
+            GmailLabel::System(Atom::try_from("Inbox").unwrap())
+        );
+        assert_eq!(
+            GmailLabel::try_from("Work").unwrap(),
+            GmailLabel::Other(AString::try_from("Work").unwrap())
+        );
+        assert_eq!(
+            GmailLabel::try_from("My Label").unwrap(),
+            GmailLabel::Other(AString::try_from("My Label").unwrap())
+        );
+        assert!(GmailLabel::try_from("\\").is_err());
+        assert!(GmailLabel::try_from("\\In box").is_err());
+    }
+}
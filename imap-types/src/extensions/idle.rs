@@ -3,6 +3,7 @@
 //! This extension adds a new method ...
 //!
 //! *  [`CommandBody::idle()`](crate::command::CommandBody#method.idle)
+//! *  [`CommandContinuationRequest::idling()`](crate::response::CommandContinuationRequest#method.idling)
 //!
 //! ... adds a new type ...
 //!
@@ -20,9 +21,32 @@ use bounded_static::ToStatic;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+use crate::response::CommandContinuationRequest;
+
+impl CommandContinuationRequest<'static> {
+    /// Create the `+ idling` continuation request that a server sends to accept an IDLE command.
+    pub fn idling() -> Self {
+        // Unwrap: "idling" is a valid text and not valid base64.
+        Self::basic(None, "idling").unwrap()
+    }
+}
+
 /// Denotes the continuation data message "DONE\r\n" to end the IDLE command.
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct IdleDone;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idling() {
+        assert!(matches!(
+            CommandContinuationRequest::idling(),
+            CommandContinuationRequest::Basic(basic) if basic.text().as_ref() == "idling"
+        ));
+    }
+}
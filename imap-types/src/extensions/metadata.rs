@@ -0,0 +1,299 @@
+//! The IMAP METADATA Extension
+//!
+//! This extension defines new types ...
+//!
+//! * [`MetadataEntry`](crate::extensions::metadata::MetadataEntry)
+//! * [`EntryValue`](crate::extensions::metadata::EntryValue)
+//! * [`GetMetadataOption`](crate::extensions::metadata::GetMetadataOption)
+//! * [`Depth`](crate::extensions::metadata::Depth)
+//! * [`MetadataResponse`](crate::extensions::metadata::MetadataResponse)
+//! * [`MetadataCode`](crate::extensions::metadata::MetadataCode)
+//!
+//! ... and extends ...
+//!
+//! * the [`Command`](crate::command::Command) enum with new variants [`Command::GetMetadata`](crate::command::CommandBody#variant.GetMetadata) and [`Command::SetMetadata`](crate::command::CommandBody#variant.SetMetadata),
+//! * the [`Data`](crate::response::Data) enum with a new variant [`Data::Metadata`](crate::response::Data#variant.Metadata), and
+//! * the [`Code`](crate::response::Code) enum with a new variant [`Code::Metadata`](crate::response::Code#variant.Metadata).
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::{Display, Formatter};
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::Arbitrary;
+#[cfg(feature = "bounded-static")]
+use bounded_static::ToStatic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    command::CommandBody,
+    core::{AString, NString, Vec1},
+    error::{ValidationError, ValidationErrorKind},
+    extensions::metadata::error::MetadataError,
+    mailbox::Mailbox,
+    response::Data,
+};
+
+impl<'a> CommandBody<'a> {
+    /// Construct a GETMETADATA command.
+    pub fn get_metadata<M, E>(
+        options: Vec<GetMetadataOption>,
+        mailbox: M,
+        entries: E,
+    ) -> Result<Self, MetadataError<M::Error, E::Error>>
+    where
+        M: TryInto<Mailbox<'a>>,
+        E: TryInto<Vec1<MetadataEntry<'a>>>,
+    {
+        Ok(CommandBody::GetMetadata {
+            options,
+            mailbox: mailbox.try_into().map_err(MetadataError::Mailbox)?,
+            entries: entries.try_into().map_err(MetadataError::Entries)?,
+        })
+    }
+
+    /// Construct a SETMETADATA command.
+    pub fn set_metadata<M, E>(
+        mailbox: M,
+        entry_values: E,
+    ) -> Result<Self, MetadataError<M::Error, E::Error>>
+    where
+        M: TryInto<Mailbox<'a>>,
+        E: TryInto<Vec1<EntryValue<'a>>>,
+    {
+        Ok(CommandBody::SetMetadata {
+            mailbox: mailbox.try_into().map_err(MetadataError::Mailbox)?,
+            entry_values: entry_values.try_into().map_err(MetadataError::Entries)?,
+        })
+    }
+}
+
+impl<'a> Data<'a> {
+    /// Construct a METADATA response.
+    pub fn metadata<M>(mailbox: M, items: MetadataResponse<'a>) -> Result<Self, M::Error>
+    where
+        M: TryInto<Mailbox<'a>>,
+    {
+        Ok(Self::Metadata {
+            mailbox: mailbox.try_into()?,
+            items,
+        })
+    }
+}
+
+/// A metadata entry name, e.g., `/shared/comment` or `/private/vendor/foo`.
+///
+/// Rules:
+///
+/// * Must start with `/shared` or `/private` (compared case-insensitively)
+/// * Must not contain `*`, `%`, or control characters (0x00-0x19)
+/// * Must not contain `//` and must not end with `/`
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MetadataEntry<'a>(AString<'a>);
+
+impl<'a> MetadataEntry<'a> {
+    pub fn validate(value: impl AsRef<[u8]>) -> Result<(), ValidationError> {
+        let value = value.as_ref();
+
+        if value.is_empty() {
+            return Err(ValidationError::new(ValidationErrorKind::Empty));
+        }
+
+        if let Some(at) = value
+            .iter()
+            .position(|b| matches!(b, 0x00..=0x19 | b'*' | b'%'))
+        {
+            return Err(ValidationError::new(ValidationErrorKind::InvalidByteAt {
+                byte: value[at],
+                at,
+            }));
+        }
+
+        let has_prefix = |prefix: &[u8]| {
+            value.len() >= prefix.len()
+                && value[..prefix.len()].eq_ignore_ascii_case(prefix)
+                && matches!(value.get(prefix.len()), None | Some(b'/'))
+        };
+
+        if !(has_prefix(b"/shared") || has_prefix(b"/private"))
+            || value.ends_with(b"/")
+            || value.windows(2).any(|window| window == b"//")
+        {
+            return Err(ValidationError::new(ValidationErrorKind::Invalid));
+        }
+
+        Ok(())
+    }
+
+    pub fn inner(&self) -> &AString<'a> {
+        &self.0
+    }
+}
+
+impl<'a> TryFrom<AString<'a>> for MetadataEntry<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: AString<'a>) -> Result<Self, Self::Error> {
+        Self::validate(&value)?;
+
+        Ok(Self(value))
+    }
+}
+
+impl<'a> TryFrom<&'a str> for MetadataEntry<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        Self::validate(value)?;
+
+        Ok(Self(AString::try_from(value)?))
+    }
+}
+
+impl<'a> TryFrom<String> for MetadataEntry<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::validate(&value)?;
+
+        Ok(Self(AString::try_from(value)?))
+    }
+}
+
+impl<'a> AsRef<[u8]> for MetadataEntry<'a> {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+/// An entry together with its value.
+///
+/// A `NIL` value removes the entry (SETMETADATA) or signals that it doesn't exist.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EntryValue<'a> {
+    pub entry: MetadataEntry<'a>,
+    pub value: NString<'a>,
+}
+
+/// Option of GETMETADATA.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GetMetadataOption {
+    /// Only return values that are at most this many octets long.
+    ///
+    /// Entries with bigger values are omitted from the METADATA response. The server announces
+    /// this with a [`MetadataCode::LongEntries`] code in the tagged `OK` response.
+    MaxSize(u32),
+    /// Also return entries below the requested ones.
+    Depth(Depth),
+}
+
+/// Depth of a GETMETADATA request.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Depth {
+    /// Only the requested entries (default).
+    Zero,
+    /// The requested entries and their immediate children.
+    One,
+    /// The requested entries and all of their descendants.
+    Infinity,
+}
+
+impl Display for Depth {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::Zero => "0",
+            Self::One => "1",
+            Self::Infinity => "infinity",
+        })
+    }
+}
+
+/// Payload of the untagged METADATA response.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MetadataResponse<'a> {
+    /// Entries with values, e.g., as a result of GETMETADATA.
+    WithValues(Vec1<EntryValue<'a>>),
+    /// Entries without values, i.e., an unsolicited notification about changed entries.
+    WithoutValues(Vec1<MetadataEntry<'a>>),
+}
+
+/// The `METADATA` response code.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MetadataCode {
+    /// Some values were not returned because of MAXSIZE. Carries the size of the biggest one.
+    ///
+    /// Note: This is the size of the single biggest omitted value (see RFC 5464, section 4.2.1),
+    /// not the total size of all omitted values.
+    LongEntries(u32),
+    /// SETMETADATA failed because a value is larger than this many octets.
+    MaxSize(u32),
+    /// SETMETADATA failed because too many entries would exist.
+    TooMany,
+    /// SETMETADATA failed because the server doesn't support private entries.
+    NoPrivate,
+}
+
+/// Error-related types.
+pub mod error {
+    use thiserror::Error;
+
+    #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+    pub enum MetadataError<M, E> {
+        #[error("Invalid mailbox: {0}")]
+        Mailbox(M),
+        #[error("Invalid entries: {0}")]
+        Entries(E),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion_metadata_entry() {
+        let tests = [
+            "/shared",
+            "/private",
+            "/shared/comment",
+            "/PRIVATE/vendor/Foo Bar",
+        ];
+
+        for test in tests {
+            assert!(MetadataEntry::try_from(test).is_ok(), "{test}");
+        }
+
+        let tests = [
+            "",
+            "shared/comment",
+            "/sharedX",
+            "/public/comment",
+            "/shared/",
+            "/shared//comment",
+            "/shared/*",
+            "/shared/%",
+            "/shared/\x10",
+        ];
+
+        for test in tests {
+            assert!(MetadataEntry::try_from(test).is_err(), "{test}");
+        }
+    }
+}
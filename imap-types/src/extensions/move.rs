@@ -1,8 +1,10 @@
 //! IMAP - MOVE Extension
 
 use crate::{
-    command::CommandBody, extensions::r#move::error::MoveError, mailbox::Mailbox,
-    sequence::SequenceSet,
+    command::CommandBody,
+    extensions::r#move::error::MoveError,
+    mailbox::Mailbox,
+    sequence::{SeqNoOrUidSet, SequenceKind, SequenceSet, TypedSequenceSet},
 };
 
 impl<'a> CommandBody<'a> {
@@ -15,23 +17,31 @@ impl<'a> CommandBody<'a> {
         S: TryInto<SequenceSet>,
         M: TryInto<Mailbox<'a>>,
     {
+        let sequence_set = sequence_set.try_into().map_err(MoveError::Sequence)?;
+
         Ok(CommandBody::Move {
-            sequence_set: sequence_set.try_into().map_err(MoveError::Sequence)?,
+            sequence_set: SeqNoOrUidSet::new(sequence_set, uid),
             mailbox: mailbox.try_into().map_err(MoveError::Mailbox)?,
-            uid,
+        })
+    }
+
+    /// Construct a MOVE or UID MOVE command depending on the kind of `sequence_set`.
+    pub fn move_typed<K, M>(sequence_set: TypedSequenceSet<K>, mailbox: M) -> Result<Self, M::Error>
+    where
+        K: SequenceKind,
+        M: TryInto<Mailbox<'a>>,
+    {
+        Ok(CommandBody::Move {
+            sequence_set: sequence_set.into(),
+            mailbox: mailbox.try_into()?,
         })
     }
 }
 
 /// Error-related types.
 pub mod error {
-    use thiserror::Error;
+    use crate::command::error::CopyError;
 
-    #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
-    pub enum MoveError<S, M> {
-        #[error("Invalid sequence: {0}")]
-        Sequence(S),
-        #[error("Invalid mailbox: {0}")]
-        Mailbox(M),
-    }
+    /// MOVE takes the same arguments as COPY and thus fails in the same way.
+    pub type MoveError<S, M> = CopyError<S, M>;
 }
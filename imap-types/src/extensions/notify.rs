@@ -0,0 +1,190 @@
+//! The IMAP NOTIFY Extension
+//!
+//! This extension defines new types ...
+//!
+//! * [`NotifySet`](crate::extensions::notify::NotifySet)
+//! * [`EventGroup`](crate::extensions::notify::EventGroup)
+//! * [`FilterMailboxes`](crate::extensions::notify::FilterMailboxes)
+//! * [`Event`](crate::extensions::notify::Event)
+//!
+//! ... and extends ...
+//!
+//! * the [`Command`](crate::command::Command) enum with a new variant [`Command::Notify`](crate::command::CommandBody#variant.Notify), and
+//! * the [`Code`](crate::response::Code) enum with new variants [`Code::NotificationOverflow`](crate::response::Code#variant.NotificationOverflow) and [`Code::BadEvent`](crate::response::Code#variant.BadEvent).
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::Arbitrary;
+#[cfg(feature = "bounded-static")]
+use bounded_static::ToStatic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    command::CommandBody,
+    core::{Atom, Vec1},
+    fetch::MessageDataItemName,
+    mailbox::Mailbox,
+};
+
+impl<'a> CommandBody<'a> {
+    /// Construct a `NOTIFY SET` command.
+    pub fn notify_set<G>(status: bool, event_groups: G) -> Result<Self, G::Error>
+    where
+        G: TryInto<Vec1<EventGroup<'a>>>,
+    {
+        Ok(CommandBody::Notify {
+            set: Some(NotifySet {
+                status,
+                event_groups: event_groups.try_into()?,
+            }),
+        })
+    }
+
+    /// Construct a `NOTIFY NONE` command.
+    pub fn notify_none() -> Self {
+        CommandBody::Notify { set: None }
+    }
+}
+
+/// Arguments of `NOTIFY SET`.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NotifySet<'a> {
+    /// Request untagged STATUS responses for mailboxes in `event_groups` (`STATUS` indicator).
+    pub status: bool,
+    /// Event groups.
+    pub event_groups: Vec1<EventGroup<'a>>,
+}
+
+/// Events to be notified about for a set of mailboxes.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EventGroup<'a> {
+    /// Mailboxes this group applies to.
+    pub filter: FilterMailboxes<'a>,
+    /// Events, or `None` for `NONE`, i.e., no notifications.
+    pub events: Option<Vec1<Event<'a>>>,
+}
+
+/// Mailbox specifier of an event group.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FilterMailboxes<'a> {
+    /// The currently selected mailbox.
+    Selected,
+    /// The currently selected mailbox, but expunges are delayed until a command allows them.
+    SelectedDelayed,
+    /// All mailboxes that may receive new mail.
+    Inboxes,
+    /// All mailboxes in the personal namespace.
+    Personal,
+    /// All subscribed mailboxes.
+    Subscribed,
+    /// The given mailboxes and all of their children.
+    Subtree(Vec1<Mailbox<'a>>),
+    /// The given mailboxes.
+    Mailboxes(Vec1<Mailbox<'a>>),
+}
+
+/// Event that can be requested with NOTIFY.
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Event<'a> {
+    /// A new message arrived.
+    ///
+    /// The server includes an untagged FETCH with `fetch_attributes` for the selected mailbox.
+    MessageNew {
+        fetch_attributes: Option<Vec1<MessageDataItemName<'a>>>,
+    },
+    MessageExpunge,
+    FlagChange,
+    AnnotationChange,
+    MailboxName,
+    SubscriptionChange,
+    MailboxMetadataChange,
+    ServerMetadataChange,
+    Other(EventOther<'a>),
+}
+
+impl<'a> From<Atom<'a>> for Event<'a> {
+    fn from(value: Atom<'a>) -> Self {
+        match value.as_ref().to_ascii_lowercase().as_ref() {
+            "messagenew" => Self::MessageNew {
+                fetch_attributes: None,
+            },
+            "messageexpunge" => Self::MessageExpunge,
+            "flagchange" => Self::FlagChange,
+            "annotationchange" => Self::AnnotationChange,
+            "mailboxname" => Self::MailboxName,
+            "subscriptionchange" => Self::SubscriptionChange,
+            "mailboxmetadatachange" => Self::MailboxMetadataChange,
+            "servermetadatachange" => Self::ServerMetadataChange,
+            _ => Self::Other(EventOther(value)),
+        }
+    }
+}
+
+impl<'a> Event<'a> {
+    /// Returns the event name as it appears on the wire.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::MessageNew { .. } => "MessageNew",
+            Self::MessageExpunge => "MessageExpunge",
+            Self::FlagChange => "FlagChange",
+            Self::AnnotationChange => "AnnotationChange",
+            Self::MailboxName => "MailboxName",
+            Self::SubscriptionChange => "SubscriptionChange",
+            Self::MailboxMetadataChange => "MailboxMetadataChange",
+            Self::ServerMetadataChange => "ServerMetadataChange",
+            Self::Other(other) => other.as_ref(),
+        }
+    }
+}
+
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EventOther<'a>(Atom<'a>);
+
+impl<'a> AsRef<str> for EventOther<'a> {
+    fn as_ref(&self) -> &str {
+        self.0.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion_event() {
+        let tests = [
+            (
+                "messagenew",
+                Event::MessageNew {
+                    fetch_attributes: None,
+                },
+            ),
+            ("MessageExpunge", Event::MessageExpunge),
+            ("FLAGCHANGE", Event::FlagChange),
+            ("MailboxName", Event::MailboxName),
+        ];
+
+        for (test, expected) in tests {
+            let got = Event::from(Atom::try_from(test).unwrap());
+            assert_eq!(got, expected);
+        }
+
+        let got = Event::from(Atom::try_from("X-Event").unwrap());
+        assert!(matches!(got, Event::Other(_)));
+        assert_eq!(got.name(), "X-Event");
+    }
+}
@@ -33,10 +33,8 @@
 //!     - [`StatusDataItem::Deleted`](crate::status::StatusDataItem::Deleted)
 //!     - [`StatusDataItem::DeletedStorage`](crate::status::StatusDataItem::DeletedStorage)
 
-use std::{
-    borrow::Cow,
-    fmt::{Display, Formatter},
-};
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::fmt::{Display, Formatter};
 
 #[cfg(feature = "arbitrary")]
 use arbitrary::Arbitrary;
@@ -117,6 +115,7 @@ impl<'a> Data<'a> {
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum Resource<'a> {
     /// The physical space estimate, in units of 1024 octets, of the mailboxes governed by the quota
     /// root.
@@ -200,7 +199,7 @@ impl<'a> From<Atom<'a>> for Resource<'a> {
 }
 
 impl<'a> Display for Resource<'a> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f.write_str(match self {
             Self::Storage => "STORAGE",
             Self::Message => "MESSAGE",
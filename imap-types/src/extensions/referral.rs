@@ -0,0 +1,163 @@
+//! IMAP4 Login Referrals and IMAP4 Mailbox Referrals
+//!
+//! This extension defines new types ...
+//!
+//! * [`ImapUrl`](crate::extensions::referral::ImapUrl)
+//! * [`UrlAuth`](crate::extensions::referral::UrlAuth)
+//!
+//! ... and extends ...
+//!
+//! * the [`Code`](crate::response::Code) enum with a new variant [`Code::Referral`](crate::response::Code#variant.Referral).
+//!
+//! See <https://datatracker.ietf.org/doc/html/rfc2221>, <https://datatracker.ietf.org/doc/html/rfc2193>,
+//! and <https://datatracker.ietf.org/doc/html/rfc5092> (IMAP URL Scheme).
+//!
+//! Note: [`ImapUrl`](crate::extensions::referral::ImapUrl) is also used by the `ext_urlauth` feature.
+
+use alloc::{borrow::Cow, format, string::ToString};
+use core::{
+    net::{IpAddr, SocketAddr},
+    num::NonZeroU32,
+};
+
+#[cfg(feature = "bounded-static")]
+use bounded_static::ToStatic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::auth::AuthMechanism;
+#[cfg(feature = "ext_urlauth")]
+use crate::extensions::urlauth::{UrlAuthAccess, UrlAuthorization};
+
+/// An IMAP URL, e.g., `imap://user@example.com/INBOX;UIDVALIDITY=1/;UID=20`.
+///
+/// ```abnf
+/// imapurl = "imap://" iserver "/" [ imailbox-ref [ iuid [ isection ] ] ]
+/// ```
+///
+/// Note: All textual components are stored percent-decoded, e.g., the mailbox of
+/// `imap://example.com/My%20Folder` is `My Folder`. They are percent-encoded again when encoding.
+/// Search criteria (`?...`) and `;PARTIAL=` are not supported (yet).
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ImapUrl<'a> {
+    /// User name, e.g., `user` in `imap://user@example.com/`.
+    pub user: Option<Cow<'a, str>>,
+    /// Authentication mechanism, e.g., `*` in `imap://;AUTH=*@example.com/`.
+    pub auth: Option<UrlAuth<'a>>,
+    /// Host name, IPv4 address, or bracketed IPv6 address, e.g., `[::1]`.
+    pub host: Cow<'a, str>,
+    /// Port.
+    pub port: Option<u16>,
+    /// Mailbox, e.g., `INBOX` or `foo/bar`.
+    pub mailbox: Option<Cow<'a, str>>,
+    /// UIDVALIDITY of the mailbox (requires `mailbox`).
+    pub uid_validity: Option<NonZeroU32>,
+    /// UID of a message in the mailbox (requires `mailbox`).
+    pub uid: Option<NonZeroU32>,
+    /// Section of the message, e.g., `1.2` (requires `uid`).
+    pub section: Option<Cow<'a, str>>,
+    /// Expiry of the URLAUTH, e.g., `2024-05-01T12:00:00Z` (RFC 4467).
+    #[cfg(feature = "ext_urlauth")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_urlauth")))]
+    pub expire: Option<Cow<'a, str>>,
+    /// Access identifier, e.g., `submit+fred` in `;URLAUTH=submit+fred` (RFC 4467).
+    #[cfg(feature = "ext_urlauth")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_urlauth")))]
+    pub access: Option<UrlAuthAccess<'a>>,
+    /// Mechanism and token, e.g., `:INTERNAL:91354a47...` (requires `access`, RFC 4467).
+    #[cfg(feature = "ext_urlauth")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_urlauth")))]
+    pub authorization: Option<UrlAuthorization<'a>>,
+}
+
+impl<'a> ImapUrl<'a> {
+    /// Create a URL that points to a server.
+    pub fn new<H>(host: H) -> Self
+    where
+        H: Into<Cow<'a, str>>,
+    {
+        Self {
+            user: None,
+            auth: None,
+            host: host.into(),
+            port: None,
+            mailbox: None,
+            uid_validity: None,
+            uid: None,
+            section: None,
+            #[cfg(feature = "ext_urlauth")]
+            expire: None,
+            #[cfg(feature = "ext_urlauth")]
+            access: None,
+            #[cfg(feature = "ext_urlauth")]
+            authorization: None,
+        }
+    }
+
+    /// Returns the host as an IP address, if it is one.
+    pub fn ip(&self) -> Option<IpAddr> {
+        let host = self
+            .host
+            .strip_prefix('[')
+            .and_then(|host| host.strip_suffix(']'))
+            .unwrap_or(&self.host);
+
+        host.parse().ok()
+    }
+}
+
+impl From<SocketAddr> for ImapUrl<'static> {
+    fn from(addr: SocketAddr) -> Self {
+        let host = match addr {
+            SocketAddr::V4(addr) => addr.ip().to_string(),
+            SocketAddr::V6(addr) => format!("[{}]", addr.ip()),
+        };
+
+        Self {
+            port: Some(addr.port()),
+            ..Self::new(host)
+        }
+    }
+}
+
+/// Authentication mechanism required by an IMAP URL.
+///
+/// ```abnf
+/// iauth = ";AUTH=" ( "*" / enc-auth-type )
+/// ```
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum UrlAuth<'a> {
+    /// Any mechanism (`*`).
+    Any,
+    /// A specific mechanism, e.g., `GSSAPI`.
+    Mechanism(AuthMechanism<'a>),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    use super::*;
+
+    #[test]
+    fn test_imap_url_ip() {
+        assert_eq!(ImapUrl::new("example.com").ip(), None);
+        assert_eq!(
+            ImapUrl::new("127.0.0.1").ip(),
+            Some(IpAddr::V4(Ipv4Addr::LOCALHOST))
+        );
+        assert_eq!(
+            ImapUrl::new("[::1]").ip(),
+            Some(IpAddr::V6(Ipv6Addr::LOCALHOST))
+        );
+
+        let url = ImapUrl::from(SocketAddr::from((Ipv6Addr::LOCALHOST, 143)));
+        assert_eq!(url.host, "[::1]");
+        assert_eq!(url.port, Some(143));
+        assert_eq!(url.ip(), Some(IpAddr::V6(Ipv6Addr::LOCALHOST)));
+    }
+}
@@ -1,4 +1,8 @@
-use std::fmt::{Display, Formatter};
+use alloc::vec::Vec;
+use core::{
+    fmt::{Display, Formatter},
+    num::NonZeroU32,
+};
 
 #[cfg(feature = "arbitrary")]
 use arbitrary::Arbitrary;
@@ -7,11 +11,23 @@ use bounded_static::ToStatic;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::core::Atom;
+use crate::{core::Atom, response::Data};
+
+impl<'a> Data<'a> {
+    /// Construct a SORT response without a mod-sequence.
+    pub fn sort(ids: Vec<NonZeroU32>) -> Self {
+        Data::Sort {
+            ids,
+            #[cfg(feature = "ext_condstore_qresync")]
+            modseq: None,
+        }
+    }
+}
 
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum SortAlgorithm<'a> {
     Display,
     Other(SortAlgorithmOther<'a>),
@@ -27,7 +43,7 @@ impl<'a> From<Atom<'a>> for SortAlgorithm<'a> {
 }
 
 impl Display for SortAlgorithm<'_> {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         match self {
             SortAlgorithm::Display => f.write_str("DISPLAY"),
             SortAlgorithm::Other(other) => f.write_str(other.as_ref()),
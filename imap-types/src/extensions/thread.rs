@@ -1,4 +1,5 @@
-use std::{
+use alloc::{vec, vec::Vec};
+use core::{
     fmt::{Display, Formatter},
     num::NonZeroU32,
 };
@@ -10,7 +11,10 @@ use bounded_static::ToStatic;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::core::{Atom, Vec1, Vec2};
+use crate::{
+    core::{Atom, Vec1, Vec2, VecN},
+    extensions::thread::error::ThreadMemberError,
+};
 
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
@@ -27,7 +31,7 @@ pub enum Thread {
 }
 
 impl Display for Thread {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         let empty_answers: Vec<Thread> = vec![];
 
         write!(f, "(")?;
@@ -82,7 +86,104 @@ impl Display for Thread {
     }
 }
 
-fn write_prefix(f: &mut Formatter, prefix: &Vec1<NonZeroU32>) -> std::fmt::Result {
+/// A message in a thread tree, i.e., a tree view of a [`Thread`].
+///
+/// `id` is `None` for a synthetic root that groups sibling threads.
+///
+/// ```text
+/// (1 (2)(3))     ((1)(2))
+///
+///     1              *
+///    / \            / \
+///   2   3          1   2
+/// ```
+///
+/// Note: Use [`ThreadMember::from`] and [`Thread::try_from`] to convert between both forms.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ThreadMember {
+    /// Message number or UID, or `None` for a synthetic root.
+    pub id: Option<NonZeroU32>,
+    /// Answers to this message (or threads under a synthetic root).
+    pub children: Vec<ThreadMember>,
+}
+
+impl From<Thread> for ThreadMember {
+    fn from(thread: Thread) -> Self {
+        match thread {
+            Thread::Members { prefix, answers } => {
+                let children = answers
+                    .map(|answers| answers.into_inner().into_iter().map(Self::from).collect())
+                    .unwrap_or_default();
+
+                // In `1 2 3`, 3 answers 2, which answers 1.
+                let mut prefix = prefix.into_inner();
+                let mut member = Self {
+                    id: prefix.pop(),
+                    children,
+                };
+
+                while let Some(id) = prefix.pop() {
+                    member = Self {
+                        id: Some(id),
+                        children: vec![member],
+                    };
+                }
+
+                member
+            }
+            Thread::Nested { answers } => Self {
+                id: None,
+                children: answers.into_inner().into_iter().map(Self::from).collect(),
+            },
+        }
+    }
+}
+
+impl TryFrom<ThreadMember> for Thread {
+    type Error = ThreadMemberError;
+
+    fn try_from(member: ThreadMember) -> Result<Self, Self::Error> {
+        let Some(id) = member.id else {
+            if member.children.len() < 2 {
+                return Err(ThreadMemberError::SyntheticRootTooFewChildren);
+            }
+
+            return Ok(Thread::Nested {
+                answers: VecN(convert(member.children)?),
+            });
+        };
+
+        let mut prefix = vec![id];
+        let mut children = member.children;
+
+        // A single answer continues the prefix.
+        while let [child] = children.as_mut_slice() {
+            let Some(id) = child.id else {
+                return Err(ThreadMemberError::SyntheticRootAsOnlyChild);
+            };
+
+            prefix.push(id);
+            children = core::mem::take(&mut child.children);
+        }
+
+        Ok(Thread::Members {
+            prefix: VecN(prefix),
+            answers: match children.is_empty() {
+                true => None,
+                false => Some(VecN(convert(children)?)),
+            },
+        })
+    }
+}
+
+fn convert(members: Vec<ThreadMember>) -> Result<Vec<Thread>, ThreadMemberError> {
+    members.into_iter().map(Thread::try_from).collect()
+}
+
+fn write_prefix(f: &mut Formatter, prefix: &Vec1<NonZeroU32>) -> core::fmt::Result {
     let (head, tail) = prefix.as_ref().split_first().unwrap();
 
     write!(f, "{}", head)?;
@@ -96,9 +197,14 @@ fn write_prefix(f: &mut Formatter, prefix: &Vec1<NonZeroU32>) -> std::fmt::Resul
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum ThreadingAlgorithm<'a> {
+    /// See RFC 5256.
     OrderedSubject,
+    /// See RFC 5256.
     References,
+    /// See draft-ietf-morg-inthread.
+    Refs,
     Other(ThreadingAlgorithmOther<'a>),
 }
 
@@ -107,16 +213,18 @@ impl<'a> From<Atom<'a>> for ThreadingAlgorithm<'a> {
         match value.as_ref().to_lowercase().as_ref() {
             "orderedsubject" => Self::OrderedSubject,
             "references" => Self::References,
+            "refs" => Self::Refs,
             _ => Self::Other(ThreadingAlgorithmOther(value)),
         }
     }
 }
 
 impl Display for ThreadingAlgorithm<'_> {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         f.write_str(match self {
             ThreadingAlgorithm::OrderedSubject => "ORDEREDSUBJECT",
             ThreadingAlgorithm::References => "REFERENCES",
+            ThreadingAlgorithm::Refs => "REFS",
             ThreadingAlgorithm::Other(other) => other.as_ref(),
         })
     }
@@ -132,3 +240,73 @@ impl<'a> AsRef<str> for ThreadingAlgorithmOther<'a> {
         self.0.as_ref()
     }
 }
+
+/// Error-related types.
+pub mod error {
+    use thiserror::Error;
+
+    #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+    pub enum ThreadMemberError {
+        #[error("A synthetic root must have at least two children")]
+        SyntheticRootTooFewChildren,
+        #[error("A synthetic root can't be the only child")]
+        SyntheticRootAsOnlyChild,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::response::Capability;
+
+    #[test]
+    fn test_conversion_threading_algorithm() {
+        let tests = [
+            ("orderedsubject", ThreadingAlgorithm::OrderedSubject),
+            ("ORDEREDSUBJECT", ThreadingAlgorithm::OrderedSubject),
+            ("References", ThreadingAlgorithm::References),
+            ("refs", ThreadingAlgorithm::Refs),
+        ];
+
+        for (test, expected) in tests {
+            let got = ThreadingAlgorithm::from(Atom::try_from(test).unwrap());
+            assert_eq!(got, expected);
+            assert!(got.to_string().eq_ignore_ascii_case(test));
+        }
+
+        let got = ThreadingAlgorithm::from(Atom::try_from("X-Foo").unwrap());
+        assert!(matches!(got, ThreadingAlgorithm::Other(_)));
+        assert_eq!(got.to_string(), "X-Foo");
+
+        assert_eq!(
+            Capability::try_from("THREAD=refs").unwrap(),
+            Capability::Thread(ThreadingAlgorithm::Refs)
+        );
+    }
+
+    #[test]
+    fn test_conversion_thread_member_invalid() {
+        let leaf = |id| ThreadMember {
+            id: NonZeroU32::new(id),
+            children: vec![],
+        };
+
+        assert_eq!(
+            Thread::try_from(ThreadMember {
+                id: None,
+                children: vec![leaf(1)],
+            }),
+            Err(ThreadMemberError::SyntheticRootTooFewChildren)
+        );
+        assert_eq!(
+            Thread::try_from(ThreadMember {
+                id: NonZeroU32::new(1),
+                children: vec![ThreadMember {
+                    id: None,
+                    children: vec![leaf(2), leaf(3)],
+                }],
+            }),
+            Err(ThreadMemberError::SyntheticRootAsOnlyChild)
+        );
+    }
+}
@@ -0,0 +1,214 @@
+//! IMAP URLAUTH Extension
+//!
+//! This extension defines new types ...
+//!
+//! * [`UrlAuthAccess`](crate::extensions::urlauth::UrlAuthAccess)
+//! * [`UrlAuthMechanism`](crate::extensions::urlauth::UrlAuthMechanism)
+//! * [`UrlAuthorization`](crate::extensions::urlauth::UrlAuthorization)
+//!
+//! ... and extends ...
+//!
+//! * the [`ImapUrl`](crate::extensions::referral::ImapUrl) struct with URLAUTH fields,
+//! * the [`CommandBody`](crate::command::CommandBody) enum with new variants [`CommandBody::GenUrlAuth`](crate::command::CommandBody#variant.GenUrlAuth), [`CommandBody::ResetKey`](crate::command::CommandBody#variant.ResetKey), and [`CommandBody::UrlFetch`](crate::command::CommandBody#variant.UrlFetch), and
+//! * the [`Data`](crate::response::Data) enum with new variants [`Data::GenUrlAuth`](crate::response::Data#variant.GenUrlAuth) and [`Data::UrlFetch`](crate::response::Data#variant.UrlFetch).
+//!
+//! See <https://datatracker.ietf.org/doc/html/rfc4467>.
+
+use alloc::borrow::Cow;
+
+#[cfg(feature = "bounded-static")]
+use bounded_static::ToStatic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    command::CommandBody,
+    core::{Atom, NString, Vec1},
+    error::{ValidationError, ValidationErrorKind},
+    extensions::referral::ImapUrl,
+    response::Data,
+};
+
+impl<'a> CommandBody<'a> {
+    /// Construct a GENURLAUTH command.
+    ///
+    /// Every URL must be a "rump", i.e., carry an access identifier but no authorization.
+    pub fn gen_url_auth<U>(urls: U) -> Result<Self, U::Error>
+    where
+        U: TryInto<Vec1<(ImapUrl<'a>, UrlAuthMechanism<'a>)>>,
+    {
+        Ok(CommandBody::GenUrlAuth {
+            urls: urls.try_into()?,
+        })
+    }
+
+    /// Construct a URLFETCH command.
+    pub fn url_fetch<U>(urls: U) -> Result<Self, U::Error>
+    where
+        U: TryInto<Vec1<ImapUrl<'a>>>,
+    {
+        Ok(CommandBody::UrlFetch {
+            urls: urls.try_into()?,
+        })
+    }
+}
+
+impl<'a> Data<'a> {
+    /// Construct a URLFETCH response.
+    ///
+    /// A `NIL` data means that the URL is invalid or access was denied.
+    pub fn url_fetch<I>(items: I) -> Result<Self, I::Error>
+    where
+        I: TryInto<Vec1<(ImapUrl<'a>, NString<'a>)>>,
+    {
+        Ok(Data::UrlFetch {
+            items: items.try_into()?,
+        })
+    }
+}
+
+/// The access identifier of a URLAUTH-authorized URL, i.e., who may use it.
+///
+/// ```abnf
+/// access = ("submit+" enc-user) / ("user+" enc-user) / "authuser" / "anonymous"
+/// ```
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum UrlAuthAccess<'a> {
+    /// A message submission entity acting on behalf of the user.
+    Submit(Cow<'a, str>),
+    /// The user only.
+    User(Cow<'a, str>),
+    /// Any authenticated user of the server.
+    AuthUser,
+    /// Anyone, including anonymous users.
+    Anonymous,
+}
+
+/// The mechanism used to generate a URLAUTH token.
+///
+/// ```abnf
+/// mechanism = "INTERNAL" / 1*(ALPHA / DIGIT / "-" / ".")
+/// ```
+///
+/// Use [`UrlAuthMechanism::try_from`] to construct a mechanism from its name.
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum UrlAuthMechanism<'a> {
+    /// The server-internal mechanism, which every server supports.
+    Internal,
+    /// Another mechanism.
+    Other(UrlAuthMechanismOther<'a>),
+}
+
+/// An (unknown) URLAUTH mechanism.
+///
+/// It's guaranteed that this type only contains letters, digits, "-", and "." and can't represent
+/// `INTERNAL` (see [`UrlAuthMechanism::Internal`]).
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UrlAuthMechanismOther<'a>(Atom<'a>);
+
+impl<'a> UrlAuthMechanism<'a> {
+    /// Validates if value is a mechanism name, i.e., is non-empty and only contains letters,
+    /// digits, "-", and ".".
+    pub fn validate(value: impl AsRef<[u8]>) -> Result<(), ValidationError> {
+        let value = value.as_ref();
+
+        if value.is_empty() {
+            return Err(ValidationError::new(ValidationErrorKind::Empty));
+        }
+
+        if let Some(position) = value
+            .iter()
+            .position(|byte| !(byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.')))
+        {
+            return Err(ValidationError::new(ValidationErrorKind::InvalidByteAt {
+                byte: value[position],
+                at: position,
+            }));
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> TryFrom<Atom<'a>> for UrlAuthMechanism<'a> {
+    type Error = ValidationError;
+
+    /// Constructs a mechanism from its name.
+    ///
+    /// `INTERNAL` (matched case-insensitively) becomes [`UrlAuthMechanism::Internal`].
+    fn try_from(value: Atom<'a>) -> Result<Self, Self::Error> {
+        Self::validate(value.as_ref())?;
+
+        if value.as_ref().eq_ignore_ascii_case("INTERNAL") {
+            Ok(Self::Internal)
+        } else {
+            Ok(Self::Other(UrlAuthMechanismOther(value)))
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for UrlAuthMechanism<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        Self::try_from(Atom::try_from(value)?)
+    }
+}
+
+impl<'a> AsRef<str> for UrlAuthMechanism<'a> {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Internal => "INTERNAL",
+            Self::Other(other) => other.0.as_ref(),
+        }
+    }
+}
+
+/// The `:<mechanism>:<token>` suffix of a URLAUTH-authorized URL.
+///
+/// ```abnf
+/// urlauth-full = ":" mechanism ":" urlauth
+///
+/// urlauth = 32*HEXDIG
+/// ```
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UrlAuthorization<'a> {
+    /// Mechanism, e.g., `INTERNAL`.
+    pub mechanism: UrlAuthMechanism<'a>,
+    /// Token (hex-encoded).
+    pub token: Cow<'a, str>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion_url_auth_mechanism() {
+        assert_eq!(
+            UrlAuthMechanism::try_from("INTERNAL").unwrap(),
+            UrlAuthMechanism::Internal
+        );
+        assert_eq!(
+            UrlAuthMechanism::try_from("internal").unwrap(),
+            UrlAuthMechanism::Internal
+        );
+        assert_eq!(
+            UrlAuthMechanism::try_from("X-FOO.1").unwrap().as_ref(),
+            "X-FOO.1"
+        );
+
+        assert!(UrlAuthMechanism::try_from("").is_err());
+        assert!(UrlAuthMechanism::try_from("X_FOO").is_err());
+        assert!(UrlAuthMechanism::try_from("X+FOO").is_err());
+        assert!(UrlAuthMechanism::try_from("X FOO").is_err());
+    }
+}
@@ -1,6 +1,7 @@
 //! Fetch-related types.
 
-use std::{
+use alloc::{vec, vec::Vec};
+use core::{
     fmt::{Display, Formatter},
     num::NonZeroU32,
 };
@@ -12,6 +13,8 @@ use bounded_static::ToStatic;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "ext_gmail")]
+use crate::extensions::gmail::GmailLabel;
 use crate::{
     body::BodyStructure,
     core::{AString, NString, Vec1},
@@ -19,6 +22,8 @@ use crate::{
     envelope::Envelope,
     flag::FlagFetch,
 };
+#[cfg(feature = "ext_annotate")]
+use crate::{extensions::annotate::MessageAnnotations, mailbox::ListMailbox};
 
 /// Shorthands for commonly-used message data items.
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
@@ -48,7 +53,7 @@ impl Macro {
 }
 
 impl Display for Macro {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f.write_str(match self {
             Macro::All => "ALL",
             Macro::Fast => "FAST",
@@ -81,6 +86,12 @@ impl<'a> From<Vec<MessageDataItemName<'a>>> for MacroOrMessageDataItemNames<'a>
     }
 }
 
+impl<'a> FromIterator<MessageDataItemName<'a>> for MacroOrMessageDataItemNames<'a> {
+    fn from_iter<I: IntoIterator<Item = MessageDataItemName<'a>>>(iter: I) -> Self {
+        MacroOrMessageDataItemNames::MessageDataItemNames(iter.into_iter().collect())
+    }
+}
+
 /// Message data item name used to request a message data item.
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
@@ -229,6 +240,49 @@ pub enum MessageDataItemName<'a> {
     /// UID
     /// ```
     Uid,
+
+    #[cfg(feature = "ext_gmail")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_gmail")))]
+    /// The Gmail labels of a message.
+    ///
+    /// ```imap
+    /// X-GM-LABELS
+    /// ```
+    GmailLabels,
+
+    #[cfg(feature = "ext_gmail")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_gmail")))]
+    /// The Gmail message ID of a message.
+    ///
+    /// ```imap
+    /// X-GM-MSGID
+    /// ```
+    GmailMsgId,
+
+    #[cfg(feature = "ext_gmail")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_gmail")))]
+    /// The Gmail thread ID of a message.
+    ///
+    /// ```imap
+    /// X-GM-THRID
+    /// ```
+    GmailThrId,
+
+    #[cfg(feature = "ext_annotate")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_annotate")))]
+    /// The annotations of a message.
+    ///
+    /// Both, entries and attributes, may contain the `*` and `%` wildcards.
+    ///
+    /// ```imap
+    /// ANNOTATION (<entries> <attributes>)
+    /// ```
+    Annotation {
+        /// Entry specifiers, e.g., `/comment` or `/altsubject/*`.
+        entries: Vec1<ListMailbox<'a>>,
+        /// Attribute specifiers, e.g., `value.priv` or `"value.*"`.
+        attributes: Vec1<AString<'a>>,
+    },
 }
 
 /// Message data item.
@@ -356,6 +410,176 @@ pub enum MessageDataItem<'a> {
     /// UID
     /// ```
     Uid(NonZeroU32),
+
+    #[cfg(feature = "ext_gmail")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_gmail")))]
+    /// The Gmail labels of a message.
+    ///
+    /// ```imap
+    /// X-GM-LABELS
+    /// ```
+    GmailLabels(Vec<GmailLabel<'a>>),
+
+    #[cfg(feature = "ext_gmail")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_gmail")))]
+    /// A 64-bit number uniquely identifying a message across all folders.
+    ///
+    /// ```imap
+    /// X-GM-MSGID
+    /// ```
+    GmailMsgId(u64),
+
+    #[cfg(feature = "ext_gmail")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_gmail")))]
+    /// A 64-bit number identifying the thread of a message.
+    ///
+    /// ```imap
+    /// X-GM-THRID
+    /// ```
+    GmailThrId(u64),
+
+    #[cfg(feature = "ext_annotate")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_annotate")))]
+    /// The annotations of a message.
+    ///
+    /// ```imap
+    /// ANNOTATION (<entry> (<attribute> <value> ...) ...)
+    /// ```
+    Annotation(MessageAnnotations<'a>),
+}
+
+impl<'a> MessageDataItem<'a> {
+    /// Returns whether this item is the server's answer to the requested `name`.
+    ///
+    /// Note: `BODY.PEEK[...]` is answered with `BODY[...]` and a `<partial>` with its origin octet.
+    ///
+    /// Every item that can be represented, including those of enabled extensions such as
+    /// `X-GM-LABELS`, is covered. Items of unsupported extensions, e.g., `MODSEQ` (RFC 7162) or
+    /// `BINARY[...]` (RFC 3516), can't be parsed into a [`MessageDataItem`] and thus can't be
+    /// looked up.
+    pub fn answers(&self, name: &MessageDataItemName) -> bool {
+        self.key() == name.response_key()
+    }
+
+    /// Returns the kind of this item.
+    pub fn key(&self) -> MessageDataItemKey<'a> {
+        match self {
+            Self::Body(_) => MessageDataItemKey::Body,
+            Self::BodyExt {
+                section, origin, ..
+            } => MessageDataItemKey::BodyExt {
+                section: section.clone(),
+                origin: *origin,
+            },
+            Self::BodyStructure(_) => MessageDataItemKey::BodyStructure,
+            Self::Envelope(_) => MessageDataItemKey::Envelope,
+            Self::Flags(_) => MessageDataItemKey::Flags,
+            Self::InternalDate(_) => MessageDataItemKey::InternalDate,
+            Self::Rfc822(_) => MessageDataItemKey::Rfc822,
+            Self::Rfc822Header(_) => MessageDataItemKey::Rfc822Header,
+            Self::Rfc822Size(_) => MessageDataItemKey::Rfc822Size,
+            Self::Rfc822Text(_) => MessageDataItemKey::Rfc822Text,
+            Self::Uid(_) => MessageDataItemKey::Uid,
+            #[cfg(feature = "ext_gmail")]
+            Self::GmailLabels(_) => MessageDataItemKey::GmailLabels,
+            #[cfg(feature = "ext_gmail")]
+            Self::GmailMsgId(_) => MessageDataItemKey::GmailMsgId,
+            #[cfg(feature = "ext_gmail")]
+            Self::GmailThrId(_) => MessageDataItemKey::GmailThrId,
+            #[cfg(feature = "ext_annotate")]
+            Self::Annotation(_) => MessageDataItemKey::Annotation,
+        }
+    }
+}
+
+/// The kind of a [`MessageDataItem`], i.e., what the server sends back for a [`MessageDataItemName`].
+///
+/// Use [`MessageDataItemName::response_key`] and [`MessageDataItem::key`] to correlate a
+/// response item with the requested one.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MessageDataItemKey<'a> {
+    /// `BODY`
+    Body,
+    /// `BODY[<section>]<<origin>>`
+    ///
+    /// Note: There is no `BODY.PEEK[...]` and a partial `<<origin>.<length>>` is reduced to `<<origin>>`.
+    BodyExt {
+        /// Section.
+        section: Option<Section<'a>>,
+        /// Origin octet of a partial fetch.
+        origin: Option<u32>,
+    },
+    /// `BODYSTRUCTURE`
+    BodyStructure,
+    /// `ENVELOPE`
+    Envelope,
+    /// `FLAGS`
+    Flags,
+    /// `INTERNALDATE`
+    InternalDate,
+    /// `RFC822`
+    Rfc822,
+    /// `RFC822.HEADER`
+    Rfc822Header,
+    /// `RFC822.SIZE`
+    Rfc822Size,
+    /// `RFC822.TEXT`
+    Rfc822Text,
+    /// `UID`
+    Uid,
+    #[cfg(feature = "ext_gmail")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_gmail")))]
+    /// `X-GM-LABELS`
+    GmailLabels,
+    #[cfg(feature = "ext_gmail")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_gmail")))]
+    /// `X-GM-MSGID`
+    GmailMsgId,
+    #[cfg(feature = "ext_gmail")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_gmail")))]
+    /// `X-GM-THRID`
+    GmailThrId,
+    #[cfg(feature = "ext_annotate")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_annotate")))]
+    /// `ANNOTATION`
+    Annotation,
+}
+
+impl<'a> MessageDataItemName<'a> {
+    /// Returns the kind of item the server sends back for this name.
+    ///
+    /// `BODY.PEEK[<section>]<<origin>.<length>>` is answered with `BODY[<section>]<<origin>>`.
+    pub fn response_key(&self) -> MessageDataItemKey<'a> {
+        match self {
+            Self::Body => MessageDataItemKey::Body,
+            Self::BodyExt {
+                section, partial, ..
+            } => MessageDataItemKey::BodyExt {
+                section: section.clone(),
+                origin: partial.map(|(origin, _)| origin),
+            },
+            Self::BodyStructure => MessageDataItemKey::BodyStructure,
+            Self::Envelope => MessageDataItemKey::Envelope,
+            Self::Flags => MessageDataItemKey::Flags,
+            Self::InternalDate => MessageDataItemKey::InternalDate,
+            Self::Rfc822 => MessageDataItemKey::Rfc822,
+            Self::Rfc822Header => MessageDataItemKey::Rfc822Header,
+            Self::Rfc822Size => MessageDataItemKey::Rfc822Size,
+            Self::Rfc822Text => MessageDataItemKey::Rfc822Text,
+            Self::Uid => MessageDataItemKey::Uid,
+            #[cfg(feature = "ext_gmail")]
+            Self::GmailLabels => MessageDataItemKey::GmailLabels,
+            #[cfg(feature = "ext_gmail")]
+            Self::GmailMsgId => MessageDataItemKey::GmailMsgId,
+            #[cfg(feature = "ext_gmail")]
+            Self::GmailThrId => MessageDataItemKey::GmailThrId,
+            #[cfg(feature = "ext_annotate")]
+            Self::Annotation { .. } => MessageDataItemKey::Annotation,
+        }
+    }
 }
 
 /// A part specifier is either a part number or one of the following:
@@ -1,6 +1,6 @@
 //! Flag-related types.
 
-use std::fmt::{Display, Formatter};
+use core::fmt::{Display, Formatter};
 
 #[cfg(feature = "arbitrary")]
 use arbitrary::Arbitrary;
@@ -9,7 +9,7 @@ use bounded_static::ToStatic;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::{core::Atom, error::ValidationError};
+use crate::{core::Atom, error::ValidationError, flag::error::FlagError};
 
 /// There are two types of flags in IMAP4rev1: System and keyword flags.
 ///
@@ -52,6 +52,27 @@ pub enum Flag<'a> {
 pub struct FlagExtension<'a>(Atom<'a>);
 
 impl<'a> Flag<'a> {
+    /// Returns the system flags defined in RFC 3501, i.e., `\Seen`, `\Answered`, `\Flagged`,
+    /// `\Deleted`, and `\Draft`.
+    ///
+    /// Note: `\Recent` is not included because it can't be altered by the client (see
+    /// [`FlagFetch::Recent`]).
+    pub fn system_flags() -> [Flag<'static>; 5] {
+        [
+            Flag::Seen,
+            Flag::Answered,
+            Flag::Flagged,
+            Flag::Deleted,
+            Flag::Draft,
+        ]
+    }
+
+    /// Constructs a system flag from its name (without the leading "\\").
+    ///
+    /// The name is matched case-insensitively. Unknown names become a [`Flag::Extension`].
+    ///
+    /// Note: `\Recent` also becomes a [`Flag::Extension`]. It can't be altered by the client and
+    /// is rejected when constructing a STORE or APPEND command (see [`FlagFetch::Recent`]).
     pub fn system(atom: Atom<'a>) -> Self {
         match atom.as_ref().to_ascii_lowercase().as_ref() {
             "answered" => Self::Answered,
@@ -63,8 +84,21 @@ impl<'a> Flag<'a> {
         }
     }
 
-    pub fn keyword(atom: Atom<'a>) -> Self {
-        Self::Keyword(atom)
+    /// Constructs a keyword, e.g., `$Label1`.
+    ///
+    /// Fails when `keyword` starts with "\\" (which denotes a system or extension flag, see
+    /// [`Flag::system`]) or is not a valid atom.
+    pub fn keyword(keyword: &'a str) -> Result<Self, FlagError> {
+        if keyword.starts_with('\\') {
+            return Err(FlagError::NotAKeyword);
+        }
+
+        Ok(Self::Keyword(Atom::try_from(keyword)?))
+    }
+
+    /// Returns whether this is `\Recent`, which is only representable as [`Flag::Extension`].
+    pub(crate) fn is_recent(&self) -> bool {
+        matches!(self, Self::Extension(FlagExtension(atom)) if atom.as_ref().eq_ignore_ascii_case("recent"))
     }
 }
 
@@ -75,13 +109,13 @@ impl<'a> TryFrom<&'a str> for Flag<'a> {
         Ok(if let Some(value) = value.strip_prefix('\\') {
             Self::system(Atom::try_from(value)?)
         } else {
-            Self::keyword(Atom::try_from(value)?)
+            Self::Keyword(Atom::try_from(value)?)
         })
     }
 }
 
 impl<'a> Display for Flag<'a> {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         match self {
             Flag::Answered => f.write_str("\\Answered"),
             Flag::Deleted => f.write_str("\\Deleted"),
@@ -176,7 +210,7 @@ impl<'a> From<Atom<'a>> for FlagNameAttribute<'a> {
 }
 
 impl<'a> Display for FlagNameAttribute<'a> {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         match self {
             Self::Noinferiors => f.write_str("\\Noinferiors"),
             Self::Noselect => f.write_str("\\Noselect"),
@@ -187,21 +221,106 @@ impl<'a> Display for FlagNameAttribute<'a> {
     }
 }
 
+/// The operation of a STORE command.
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum StoreType {
+    /// Replace the flags (`FLAGS`).
     Replace,
+    /// Add to the flags (`+FLAGS`).
     Add,
+    /// Remove from the flags (`-FLAGS`).
     Remove,
 }
 
+/// Whether the server should answer a STORE command with the new flags.
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum StoreResponse {
+    /// Request an untagged FETCH response with the new flags.
     Answer,
+    /// Don't request an untagged FETCH response (`.SILENT`).
     Silent,
 }
+
+/// Error-related types.
+pub mod error {
+    use thiserror::Error;
+
+    use crate::error::ValidationError;
+
+    #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+    pub enum FlagError {
+        #[error(transparent)]
+        Atom(#[from] ValidationError),
+        #[error("`\\Recent` can't be used here: Please use `FlagFetch::Recent`")]
+        Recent,
+        #[error("A keyword can't start with `\\`: Please use `Flag::system`")]
+        NotAKeyword,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion_flag() {
+        let tests = [
+            ("\\Seen", Flag::Seen),
+            ("\\sEEN", Flag::Seen),
+            ("\\Answered", Flag::Answered),
+            ("\\Deleted", Flag::Deleted),
+            ("\\Draft", Flag::Draft),
+            ("\\Flagged", Flag::Flagged),
+            (
+                "\\Foo",
+                Flag::Extension(FlagExtension(Atom::try_from("Foo").unwrap())),
+            ),
+            ("Seen", Flag::Keyword(Atom::try_from("Seen").unwrap())),
+            ("$Junk", Flag::Keyword(Atom::try_from("$Junk").unwrap())),
+        ];
+
+        for (test, expected) in tests {
+            assert_eq!(expected, Flag::try_from(test).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_conversion_flag_failing() {
+        assert!(Flag::try_from("\\*").is_err());
+        assert!(Flag::try_from("").is_err());
+    }
+
+    #[test]
+    fn test_flag_is_recent() {
+        assert!(Flag::try_from("\\Recent").unwrap().is_recent());
+        assert!(Flag::try_from("\\rEcEnT").unwrap().is_recent());
+        assert!(!Flag::try_from("Recent").unwrap().is_recent());
+        assert!(!Flag::Seen.is_recent());
+    }
+
+    #[test]
+    fn test_flag_keyword() {
+        assert_eq!(
+            Flag::keyword("$Label1"),
+            Ok(Flag::Keyword(Atom::try_from("$Label1").unwrap()))
+        );
+        assert_eq!(Flag::keyword("\\Seen"), Err(FlagError::NotAKeyword));
+        assert_eq!(Flag::keyword("\\Foo"), Err(FlagError::NotAKeyword));
+        assert!(matches!(Flag::keyword("$La bel"), Err(FlagError::Atom(_))));
+        assert!(matches!(Flag::keyword(""), Err(FlagError::Atom(_))));
+    }
+
+    #[test]
+    fn test_system_flags() {
+        for flag in Flag::system_flags() {
+            assert_eq!(Flag::try_from(flag.to_string().as_str()).unwrap(), flag);
+            assert!(!matches!(flag, Flag::Extension(_) | Flag::Keyword(_)));
+        }
+    }
+}
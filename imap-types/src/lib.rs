@@ -116,11 +116,18 @@
 //!
 //! |Feature              |Description                                                                            |Status    |
 //! |---------------------|---------------------------------------------------------------------------------------|----------|
+//! |ext_annotate         |IMAP ANNOTATE Extension ([RFC 5257])                                                   |Unfinished|
+//! |ext_appendlimit      |The IMAP APPENDLIMIT Extension ([RFC 7889])                                            |Unfinished|
 //! |ext_id               |IMAP4 ID extension ([RFC 2971])                                                        |Unfinished|
+//! |ext_metadata         |The IMAP METADATA Extension ([RFC 5464])                                               |Unfinished|
+//! |ext_notify           |IMAP NOTIFY Extension ([RFC 5465])                                                     |Unfinished|
 //! |ext_sort_thread      |Internet Message Access Protocol - SORT and THREAD Extensions ([RFC 5256] + [RFC 5957])|Unfinished|
 //! |ext_condstore_qresync|Quick Flag Changes Resynchronization and Quick Mailbox Resynchronization ([RFC 7162])  |Unfinished|
+//! |ext_context          |ESEARCH, CONTEXT, and ESORT Extensions ([RFC 4731] + [RFC 5267])                       |Unfinished|
+//! |ext_gmail            |Gmail IMAP Extensions ([Gmail])                                                        |Unfinished|
 //! |ext_login_referrals  |IMAP4 Login Referrals ([RFC 2221])                                                     |Unfinished|
 //! |ext_mailbox_referrals|IMAP4 Mailbox Referrals ([RFC 2193])                                                   |Unfinished|
+//! |ext_urlauth          |Internet Message Access Protocol (IMAP) - URLAUTH Extension ([RFC 4467])               |Unfinished|
 //! |starttls             |IMAP4rev1 ([RFC 3501]; section 6.2.1)                                                  |          |
 //!
 //! STARTTLS is not an IMAP extension but feature-gated because it [should be avoided](https://nostarttls.secvuln.info/).
@@ -133,6 +140,7 @@
 //! | arbitrary        | Derive `Arbitrary` implementations.                            | No                 |
 //! | bounded-static   | Derive `ToStatic/IntoStatic` implementations.                  | No                 |
 //! | serde            | Derive `serde`s `Serialize` and `Deserialize` implementations. | No                 |
+//! | std              | Use `std` (see below).                                         | Yes                |
 //! | unvalidated      | Unlock `unvalidated` constructors.                             | No                 |
 //!
 //! When using `arbitrary`, all types defined in imap-types implement the [Arbitrary] trait to ease testing.
@@ -142,9 +150,17 @@
 //! This is useful when you want to pass objects around, e.g., into other threads, a vector, etc.
 //! When the `serde` feature is used, all types implement [Serde](https://serde.rs/)'s [Serialize](https://docs.serde.rs/serde/trait.Serialize.html) and
 //! [Deserialize](https://docs.serde.rs/serde/trait.Deserialize.html) traits. (Try running `cargo run --example serde_json`.)
+//! The representation is considered stable: enums are externally tagged (serde's default), newtypes
+//! such as [`Tag`](core::Tag) or [`NString`](core::NString) are serialized as their inner value,
+//! and literals keep their data as bytes. (See `imap-types/tests/serde.rs`.)
+//!
+//! imap-types is `no_std` (but requires `alloc`) when the default `std` feature is disabled.
+//! This makes it possible to use the types on constrained targets and do the I/O elsewhere.
+//! Note that `arbitrary` requires `std`.
 //!
 //! [Arbitrary]: https://docs.rs/arbitrary/1.0.1/arbitrary/trait.Arbitrary.html
 //! [parse_command]: https://github.com/duesee/imap-codec/blob/main/imap-codec/examples/parse_command.rs
+//! [Gmail]: https://developers.google.com/gmail/imap/imap-extensions
 //! [RFC 2088]: https://datatracker.ietf.org/doc/html/rfc2088
 //! [RFC 2177]: https://datatracker.ietf.org/doc/html/rfc2177
 //! [RFC 2193]: https://datatracker.ietf.org/doc/html/rfc2193
@@ -152,14 +168,21 @@
 //! [RFC 2971]: https://datatracker.ietf.org/doc/html/rfc2971
 //! [RFC 3501]: https://datatracker.ietf.org/doc/html/rfc3501
 //! [RFC 3691]: https://datatracker.ietf.org/doc/html/rfc3691
+//! [RFC 4467]: https://datatracker.ietf.org/doc/html/rfc4467
+//! [RFC 4731]: https://datatracker.ietf.org/doc/html/rfc4731
 //! [RFC 4959]: https://datatracker.ietf.org/doc/html/rfc4959
 //! [RFC 4978]: https://datatracker.ietf.org/doc/html/rfc4978
 //! [RFC 5161]: https://datatracker.ietf.org/doc/html/rfc5161
 //! [RFC 5256]: https://datatracker.ietf.org/doc/html/rfc5256
+//! [RFC 5257]: https://datatracker.ietf.org/doc/html/rfc5257
+//! [RFC 5267]: https://datatracker.ietf.org/doc/html/rfc5267
+//! [RFC 5464]: https://datatracker.ietf.org/doc/html/rfc5464
+//! [RFC 5465]: https://datatracker.ietf.org/doc/html/rfc5465
 //! [RFC 5957]: https://datatracker.ietf.org/doc/html/rfc5957
 //! [RFC 6851]: https://datatracker.ietf.org/doc/html/rfc6851
 //! [RFC 7162]: https://datatracker.ietf.org/doc/html/rfc7162
 //! [RFC 7888]: https://datatracker.ietf.org/doc/html/rfc7888
+//! [RFC 7889]: https://datatracker.ietf.org/doc/html/rfc7889
 //! [RFC 9208]: https://datatracker.ietf.org/doc/html/rfc9208
 
 #![forbid(unsafe_code)]
@@ -167,6 +190,9 @@
 // TODO(#313)
 // #![deny(missing_docs)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 // Test examples from imap-types' README.
 #[doc = include_str!("../README.md")]
@@ -196,3 +222,24 @@ pub mod utils;
 
 #[cfg(feature = "bounded-static")]
 pub use bounded_static;
+
+/// Enabled features that add variants to public enums.
+///
+/// Used by imap-codec to reject (at compile time) a build where a feature is enabled here but not
+/// in imap-codec. Its encoders couldn't handle the additional variants otherwise.
+#[doc(hidden)]
+pub mod features {
+    pub const STARTTLS: bool = cfg!(feature = "starttls");
+    pub const EXT_ANNOTATE: bool = cfg!(feature = "ext_annotate");
+    pub const EXT_APPENDLIMIT: bool = cfg!(feature = "ext_appendlimit");
+    pub const EXT_CONDSTORE_QRESYNC: bool = cfg!(feature = "ext_condstore_qresync");
+    pub const EXT_CONTEXT: bool = cfg!(feature = "ext_context");
+    pub const EXT_GMAIL: bool = cfg!(feature = "ext_gmail");
+    pub const EXT_LOGIN_REFERRALS: bool = cfg!(feature = "ext_login_referrals");
+    pub const EXT_MAILBOX_REFERRALS: bool = cfg!(feature = "ext_mailbox_referrals");
+    pub const EXT_ID: bool = cfg!(feature = "ext_id");
+    pub const EXT_METADATA: bool = cfg!(feature = "ext_metadata");
+    pub const EXT_NOTIFY: bool = cfg!(feature = "ext_notify");
+    pub const EXT_SORT_THREAD: bool = cfg!(feature = "ext_sort_thread");
+    pub const EXT_URLAUTH: bool = cfg!(feature = "ext_urlauth");
+}
@@ -1,9 +1,15 @@
 //! Mailbox-related types.
 
-use std::{borrow::Cow, str::from_utf8};
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::str::from_utf8;
 
 #[cfg(feature = "arbitrary")]
 use arbitrary::Arbitrary;
+use base64::{
+    alphabet::IMAP_MUTF7,
+    engine::{general_purpose::NO_PAD, GeneralPurpose},
+    Engine,
+};
 #[cfg(feature = "bounded-static")]
 use bounded_static::ToStatic;
 #[cfg(feature = "serde")]
@@ -12,7 +18,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     core::{impl_try_from, AString, IString},
     error::{ValidationError, ValidationErrorKind},
-    mailbox::error::MailboxOtherError,
+    mailbox::error::{MailboxOtherError, ModifiedUtf7Error},
     utils::indicators::is_list_char,
 };
 
@@ -173,7 +179,7 @@ impl<'a> TryFrom<String> for ListMailbox<'a> {
 ///    when used in that convention.
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
 pub enum Mailbox<'a> {
     Inbox,
     Other(MailboxOther<'a>),
@@ -198,7 +204,7 @@ impl<'a> From<AString<'a>> for Mailbox<'a> {
 
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
 pub struct MailboxOther<'a>(pub(crate) AString<'a>);
 
 impl<'a> MailboxOther<'a> {
@@ -268,6 +274,104 @@ impl<'a> AsRef<[u8]> for MailboxOther<'a> {
     }
 }
 
+const MODIFIED_BASE64: GeneralPurpose = GeneralPurpose::new(&IMAP_MUTF7, NO_PAD);
+
+/// Encodes a (Unicode) mailbox name using modified UTF-7 (see RFC 3501, 5.1.3.).
+///
+/// Printable US-ASCII characters represent themselves, except for "&", which is encoded as "&-".
+/// All other characters are encoded as UTF-16 (big-endian) in modified BASE64 (using "," instead
+/// of "/" and no padding) between "&" and "-".
+pub fn mailbox_to_utf7(name: &str) -> String {
+    fn flush(out: &mut String, shifted: &mut Vec<u8>) {
+        if !shifted.is_empty() {
+            out.push('&');
+            out.push_str(&MODIFIED_BASE64.encode(&shifted));
+            out.push('-');
+            shifted.clear();
+        }
+    }
+
+    let mut out = String::with_capacity(name.len());
+    let mut shifted = Vec::new();
+
+    for c in name.chars() {
+        match c {
+            '&' => {
+                flush(&mut out, &mut shifted);
+                out.push_str("&-");
+            }
+            '\x20'..='\x7e' => {
+                flush(&mut out, &mut shifted);
+                out.push(c);
+            }
+            _ => {
+                let mut buffer = [0u16; 2];
+                for unit in c.encode_utf16(&mut buffer) {
+                    shifted.extend_from_slice(&unit.to_be_bytes());
+                }
+            }
+        }
+    }
+
+    flush(&mut out, &mut shifted);
+
+    out
+}
+
+/// Decodes a modified UTF-7 mailbox name (see RFC 3501, 5.1.3.).
+///
+/// This is the inverse of [`mailbox_to_utf7`].
+pub fn mailbox_from_utf7(wire: &str) -> Result<String, ModifiedUtf7Error> {
+    let mut out = String::with_capacity(wire.len());
+    let mut rest = wire;
+
+    while let Some(c) = rest.chars().next() {
+        let at = wire.len() - rest.len();
+
+        match c {
+            '&' => {
+                let end = match rest.find('-') {
+                    Some(end) => end,
+                    None => return Err(ModifiedUtf7Error::Unterminated { at }),
+                };
+
+                let encoded = &rest[1..end];
+
+                if encoded.is_empty() {
+                    out.push('&');
+                } else {
+                    let bytes = MODIFIED_BASE64
+                        .decode(encoded)
+                        .map_err(|_| ModifiedUtf7Error::Base64 { at })?;
+
+                    if bytes.len() % 2 != 0 {
+                        return Err(ModifiedUtf7Error::Utf16 { at });
+                    }
+
+                    let units = bytes
+                        .chunks_exact(2)
+                        .map(|chunk| u16::from_be_bytes([chunk[0], chunk[1]]));
+
+                    for c in char::decode_utf16(units) {
+                        out.push(c.map_err(|_| ModifiedUtf7Error::Utf16 { at })?);
+                    }
+                }
+
+                rest = &rest[end + 1..];
+            }
+            '\x20'..='\x7e' => {
+                out.push(c);
+                rest = &rest[1..];
+            }
+            _ => {
+                return Err(ModifiedUtf7Error::InvalidCharAt { char: c, at });
+            }
+        }
+    }
+
+    Ok(out)
+}
+
 /// Error-related types.
 pub mod error {
     use thiserror::Error;
@@ -281,6 +385,18 @@ pub mod error {
         #[error("Reserved: Please use one of the typed variants")]
         Reserved,
     }
+
+    #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+    pub enum ModifiedUtf7Error {
+        #[error("Invalid character {char:?} at index {at}")]
+        InvalidCharAt { char: char, at: usize },
+        #[error("Unterminated shift sequence at index {at}")]
+        Unterminated { at: usize },
+        #[error("Invalid modified BASE64 in shift sequence at index {at}")]
+        Base64 { at: usize },
+        #[error("Invalid UTF-16 in shift sequence at index {at}")]
+        Utf16 { at: usize },
+    }
 }
 
 #[cfg(test)]
@@ -298,6 +414,7 @@ mod tests {
             ("Inbox", Mailbox::Inbox),
             ("InboX", Mailbox::Inbox),
             ("INBOX", Mailbox::Inbox),
+            ("InBoX", Mailbox::Inbox),
             (
                 "INBO²",
                 Mailbox::Other(MailboxOther(AString::String(IString::Literal(Literal {
@@ -316,6 +433,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_modified_utf7() {
+        let tests = [
+            ("", ""),
+            ("INBOX", "INBOX"),
+            ("&", "&-"),
+            ("A&B", "A&-B"),
+            ("~peter/mail/台北/日本語", "~peter/mail/&U,BTFw-/&ZeVnLIqe-"),
+            ("Entwürfe", "Entw&APw-rfe"),
+            ("😀", "&2D3eAA-"),
+        ];
+
+        for (name, wire) in tests {
+            assert_eq!(wire, mailbox_to_utf7(name));
+            assert_eq!(name, mailbox_from_utf7(wire).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_modified_utf7_failing() {
+        let tests = [
+            ("&U,BTFw", ModifiedUtf7Error::Unterminated { at: 0 }),
+            ("a&U/BTFw-", ModifiedUtf7Error::Base64 { at: 1 }),
+            ("&AP-", ModifiedUtf7Error::Base64 { at: 0 }),
+            ("&2D0-", ModifiedUtf7Error::Utf16 { at: 0 }),
+            ("ü", ModifiedUtf7Error::InvalidCharAt { char: 'ü', at: 0 }),
+            ("\t", ModifiedUtf7Error::InvalidCharAt { char: '\t', at: 0 }),
+        ];
+
+        for (wire, expected) in tests {
+            assert_eq!(Err(expected), mailbox_from_utf7(wire));
+        }
+    }
+
     #[test]
     fn test_conversion_mailbox_failing() {
         let tests = ["\x00", "A\x00", "\x00A"];
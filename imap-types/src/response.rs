@@ -1,7 +1,16 @@
 //! # 7. Server Responses
 
-use std::{
-    borrow::Cow,
+#[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
+use alloc::boxed::Box;
+use alloc::{
+    borrow::{Cow, ToOwned},
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+#[cfg(all(feature = "ext_sort_thread", feature = "ext_condstore_qresync"))]
+use core::num::NonZeroU64;
+use core::{
     fmt::{Debug, Display, Formatter},
     num::{NonZeroU32, TryFromIntError},
 };
@@ -15,21 +24,42 @@ use bounded_static::ToStatic;
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "ext_id")]
-use crate::core::{IString, NString};
+use crate::core::IString;
+#[cfg(any(feature = "ext_id", feature = "ext_urlauth"))]
+use crate::core::NString;
+#[cfg(feature = "ext_annotate")]
+use crate::extensions::annotate::AnnotateCode;
+#[cfg(feature = "ext_context")]
+use crate::extensions::context::SearchReturnData;
+#[cfg(feature = "ext_metadata")]
+use crate::extensions::metadata::{MetadataCode, MetadataResponse};
+#[cfg(feature = "ext_notify")]
+use crate::extensions::notify::Event;
+#[cfg(any(
+    feature = "ext_login_referrals",
+    feature = "ext_mailbox_referrals",
+    feature = "ext_urlauth"
+))]
+use crate::extensions::referral::ImapUrl;
 #[cfg(feature = "ext_sort_thread")]
 use crate::extensions::sort::SortAlgorithm;
 #[cfg(feature = "ext_sort_thread")]
 use crate::extensions::thread::{Thread, ThreadingAlgorithm};
+#[cfg(feature = "ext_condstore_qresync")]
+use crate::sequence::SequenceSet;
 use crate::{
     auth::AuthMechanism,
-    core::{impl_try_from, AString, Atom, Charset, QuotedChar, Tag, Text, Vec1},
-    error::ValidationError,
+    core::{
+        impl_try_from, AString, Atom, Charset, ExtensionArguments, ExtensionName, QuotedChar, Tag,
+        Text, Vec1,
+    },
+    error::{ValidationError, ValidationErrorKind},
     extensions::{
         compress::CompressionAlgorithm,
         enable::CapabilityEnable,
         quota::{QuotaGet, Resource},
     },
-    fetch::MessageDataItem,
+    fetch::{MessageDataItem, MessageDataItemName},
     flag::{Flag, FlagNameAttribute, FlagPerm},
     mailbox::Mailbox,
     response::error::{ContinueError, FetchError},
@@ -50,11 +80,20 @@ pub struct Greeting<'a> {
 }
 
 impl<'a> Greeting<'a> {
-    pub fn new(
-        kind: GreetingKind,
-        code: Option<Code<'a>>,
-        text: &'a str,
-    ) -> Result<Self, ValidationError> {
+    /// Render this greeting for diagnostics, e.g., logging.
+    ///
+    /// See [`Response::debug_render`].
+    pub fn debug_render(&self) -> String {
+        crate::utils::summarize_literals(&format!("{:#?}", self))
+    }
+
+    /// Constructs a greeting.
+    ///
+    /// Fails when `text` is not a valid [`Text`], e.g., when it is empty.
+    pub fn new<T>(kind: GreetingKind, code: Option<Code<'a>>, text: T) -> Result<Self, T::Error>
+    where
+        T: TryInto<Text<'a>>,
+    {
         Ok(Greeting {
             kind,
             code,
@@ -62,28 +101,36 @@ impl<'a> Greeting<'a> {
         })
     }
 
-    pub fn ok(code: Option<Code<'a>>, text: &'a str) -> Result<Self, ValidationError> {
-        Ok(Greeting {
-            kind: GreetingKind::Ok,
-            code,
-            text: text.try_into()?,
-        })
+    /// Constructs an `OK` greeting, see [`GreetingKind::Ok`].
+    pub fn ok<T>(code: Option<Code<'a>>, text: T) -> Result<Self, T::Error>
+    where
+        T: TryInto<Text<'a>>,
+    {
+        Self::new(GreetingKind::Ok, code, text)
     }
 
-    pub fn preauth(code: Option<Code<'a>>, text: &'a str) -> Result<Self, ValidationError> {
-        Ok(Greeting {
-            kind: GreetingKind::PreAuth,
-            code,
-            text: text.try_into()?,
-        })
+    /// Constructs a `PREAUTH` greeting, see [`GreetingKind::PreAuth`].
+    pub fn preauth<T>(code: Option<Code<'a>>, text: T) -> Result<Self, T::Error>
+    where
+        T: TryInto<Text<'a>>,
+    {
+        Self::new(GreetingKind::PreAuth, code, text)
     }
 
-    pub fn bye(code: Option<Code<'a>>, text: &'a str) -> Result<Self, ValidationError> {
-        Ok(Greeting {
-            kind: GreetingKind::Bye,
-            code,
-            text: text.try_into()?,
-        })
+    /// Constructs a `BYE` greeting, see [`GreetingKind::Bye`].
+    pub fn bye<T>(code: Option<Code<'a>>, text: T) -> Result<Self, T::Error>
+    where
+        T: TryInto<Text<'a>>,
+    {
+        Self::new(GreetingKind::Bye, code, text)
+    }
+
+    /// Returns the text when the code is `ALERT`, see [`Status::alert_text`].
+    pub fn alert_text(&self) -> Option<&Text<'a>> {
+        match self.code {
+            Some(Code::Alert) => Some(&self.text),
+            _ => None,
+        }
     }
 }
 
@@ -129,6 +176,23 @@ pub enum Response<'a> {
     Status(Status<'a>),
 }
 
+impl<'a> Response<'a> {
+    /// Render this response for diagnostics, e.g., logging.
+    ///
+    /// Shows one field per line and summarizes literals as `<N bytes>`. The output is not IMAP
+    /// and must not be parsed.
+    pub fn debug_render(&self) -> String {
+        crate::utils::summarize_literals(&format!("{:#?}", self))
+    }
+
+    /// Returns `true` for a `BYE` response, i.e., when the server is about to close the connection.
+    ///
+    /// Note: A `BYE` greeting is a [`Greeting`] (see [`GreetingKind::Bye`]), not a response.
+    pub fn is_bye(&self) -> bool {
+        matches!(self, Self::Status(Status::Bye(_)))
+    }
+}
+
 /// Status response.
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -214,6 +278,10 @@ pub struct Tagged<'a> {
 /// continue to read response data from the server until the
 /// connection is closed; this will ensure that any pending untagged
 /// or completion responses are read and processed.
+///
+/// Note: The fourth case uses the same syntax but is modeled as a [`Greeting`] with
+/// [`GreetingKind::Bye`], i.e., means "connection refused". A `Bye` received after the greeting
+/// means that the server is shutting down the session.
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -280,6 +348,7 @@ impl<'a> Status<'a> {
 
     // ---------------------------------------------------------------------------------------------
 
+    /// Get the tag of a tagged status, or `None` for untagged statuses (including BYE).
     pub fn tag(&self) -> Option<&Tag> {
         match self {
             Self::Tagged(Tagged { tag, .. }) => Some(tag),
@@ -308,6 +377,33 @@ impl<'a> Status<'a> {
             | Self::Bye(Bye { text, .. }) => text,
         }
     }
+
+    /// Returns the text when the code is `ALERT`.
+    ///
+    /// RFC 3501 requires clients to present this text to the user.
+    pub fn alert_text(&self) -> Option<&Text<'a>> {
+        match self {
+            Self::Untagged(StatusBody {
+                code: Some(Code::Alert),
+                text,
+                ..
+            })
+            | Self::Tagged(Tagged {
+                body:
+                    StatusBody {
+                        code: Some(Code::Alert),
+                        text,
+                        ..
+                    },
+                ..
+            })
+            | Self::Bye(Bye {
+                code: Some(Code::Alert),
+                text,
+            }) => Some(text),
+            _ => None,
+        }
+    }
 }
 
 /// ## 7.2 - 7.4 Server and Mailbox Status; Mailbox Size; Message Status
@@ -315,6 +411,7 @@ impl<'a> Status<'a> {
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum Data<'a> {
     // ## 7.2. Server Responses - Server and Mailbox Status
     //
@@ -366,7 +463,7 @@ pub enum Data<'a> {
     /// OK response as part of a successful authentication.  It is
     /// unnecessary for a client to send a separate CAPABILITY command if
     /// it recognizes these automatic capabilities.
-    Capability(Vec1<Capability<'a>>),
+    Capability(Capabilities<'a>),
 
     /// ### 7.2.2. LIST Response
     ///
@@ -400,6 +497,9 @@ pub enum Data<'a> {
     /// returns a single name that matches the LSUB specification.  There
     /// can be multiple LSUB responses for a single LSUB command.  The
     /// data is identical in format to the LIST response.
+    ///
+    /// Note: `\Noselect` has a different meaning here. It signals that the mailbox is not
+    /// subscribed, but is returned because one of its inferiors is (see the `%` wildcard).
     Lsub {
         /// Name attributes
         items: Vec<FlagNameAttribute<'a>>,
@@ -418,6 +518,8 @@ pub enum Data<'a> {
         /// Name
         mailbox: Mailbox<'a>,
         /// Status parenthesized list
+        ///
+        /// Items are kept in the order in which they were received.
         items: Cow<'a, [StatusDataItem]>,
     },
 
@@ -432,8 +534,24 @@ pub enum Data<'a> {
     /// delimited by a space.
     Search(Vec<NonZeroU32>),
 
+    /// SORT response (RFC 5256).
+    ///
+    /// ```abnf
+    /// sort-data = "SORT" *(SP nz-number)
+    /// ```
+    ///
+    /// Use [`Data::sort`] to construct it without a mod-sequence.
     #[cfg(feature = "ext_sort_thread")]
-    Sort(Vec<NonZeroU32>),
+    Sort {
+        /// Message sequence numbers (or UIDs for UID SORT) in sort order.
+        ids: Vec<NonZeroU32>,
+        /// Highest mod-sequence of the returned messages, i.e., the `(MODSEQ n)` trailer (RFC 7162).
+        ///
+        /// Note: Only sent by servers when `ids` is not empty, so it's not encoded otherwise.
+        #[cfg(feature = "ext_condstore_qresync")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "ext_condstore_qresync")))]
+        modseq: Option<NonZeroU64>,
+    },
 
     #[cfg(feature = "ext_sort_thread")]
     Thread(Vec<Thread>),
@@ -532,6 +650,9 @@ pub enum Data<'a> {
     ///   response MAY be sent during a UID command.
     ///
     /// The update from the EXPUNGE response MUST be recorded by the client.
+    ///
+    /// Note: This is a message sequence number, *not* a UID. Servers that enabled QRESYNC report
+    /// expunged UIDs with `Data::Vanished` instead (see [`Data::is_expunge_like`]).
     Expunge(NonZeroU32),
 
     /// ### 7.4.2. FETCH Response
@@ -566,18 +687,108 @@ pub enum Data<'a> {
         roots: Vec<AString<'a>>,
     },
 
+    #[cfg(feature = "ext_context")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_context")))]
+    /// ESEARCH Response (RFC 4731, RFC 5267)
+    ///
+    /// Returned for SEARCH and SORT commands with `RETURN` options.
+    ESearch {
+        /// Tag of the command that produced this response.
+        correlator: Option<Tag<'a>>,
+        /// Whether the data contains UIDs (instead of message sequence numbers).
+        uid: bool,
+        /// Requested data.
+        ///
+        /// Servers may return the data in any order. The order is kept as received.
+        data: Vec<SearchReturnData>,
+    },
+
+    #[cfg(feature = "ext_condstore_qresync")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_condstore_qresync")))]
+    /// VANISHED Response (RFC 7162)
+    ///
+    /// Replaces EXPUNGE responses when QRESYNC is enabled. Unlike [`Data::Expunge`], it reports
+    /// UIDs (and not message sequence numbers).
+    ///
+    /// ```abnf
+    /// expunged-resp = "VANISHED" [SP "(EARLIER)"] SP known-uids
+    /// ```
+    Vanished {
+        /// Whether the messages were expunged earlier, i.e., in response to `SELECT ... (QRESYNC ...)`
+        /// or `UID FETCH ... (VANISHED)`. Only then may the UIDs include already expunged messages.
+        earlier: bool,
+        /// UIDs of the expunged messages.
+        known_uids: SequenceSet,
+    },
+
     #[cfg(feature = "ext_id")]
     /// ID Response
     Id {
         /// Parameters
         parameters: Option<Vec<(IString<'a>, NString<'a>)>>,
     },
+
+    #[cfg(feature = "ext_metadata")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_metadata")))]
+    /// METADATA Response
+    Metadata {
+        /// Mailbox name, or the empty string for server annotations.
+        mailbox: Mailbox<'a>,
+        /// Entries, with or without values.
+        items: MetadataResponse<'a>,
+    },
+
+    #[cfg(feature = "ext_urlauth")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_urlauth")))]
+    /// GENURLAUTH Response
+    GenUrlAuth {
+        /// URLAUTH-authorized URLs, in the order requested.
+        urls: Vec1<ImapUrl<'a>>,
+    },
+
+    #[cfg(feature = "ext_urlauth")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_urlauth")))]
+    /// URLFETCH Response
+    UrlFetch {
+        /// URLs and their data.
+        ///
+        /// The data is `NIL` when the URL is invalid or access was denied, which is different from
+        /// an empty string.
+        items: Vec1<(ImapUrl<'a>, NString<'a>)>,
+    },
+
+    /// Experimental or vendor-specific response, e.g., `XLIST`.
+    ///
+    /// This is only used for responses that aren't supported otherwise. The arguments are kept
+    /// as-is to allow a lossless round-trip.
+    ///
+    /// Note: The `name` starts with "X" (see [`ExtensionName`]).
+    Extension {
+        /// Name of the response.
+        name: ExtensionName<'a>,
+        /// Raw arguments (excluding the space following the name).
+        args: ExtensionArguments<'a>,
+    },
 }
 
 impl<'a> Data<'a> {
+    /// Returns `true` when this response reports expunged messages, i.e., for EXPUNGE and VANISHED.
+    ///
+    /// Note: EXPUNGE carries a message sequence number whereas VANISHED carries UIDs. Confusing
+    /// both corrupts the client's view of the mailbox, so always match on the variant to learn
+    /// which one it is.
+    pub fn is_expunge_like(&self) -> bool {
+        match self {
+            Self::Expunge(_) => true,
+            #[cfg(feature = "ext_condstore_qresync")]
+            Self::Vanished { .. } => true,
+            _ => false,
+        }
+    }
+
     pub fn capability<C>(caps: C) -> Result<Self, C::Error>
     where
-        C: TryInto<Vec1<Capability<'a>>>,
+        C: TryInto<Capabilities<'a>>,
     {
         Ok(Self::Capability(caps.try_into()?))
     }
@@ -621,6 +832,19 @@ impl<'a> Data<'a> {
 
         Ok(Self::Fetch { seq, items })
     }
+
+    /// Returns the first item of a FETCH response that answers the requested `name` (see
+    /// [`MessageDataItem::answers`]).
+    ///
+    /// The items keep the order in which the server sent them. `None` means that this is not a
+    /// FETCH response or that the server omitted the item, which is different from, e.g., an empty
+    /// `FLAGS ()`.
+    pub fn fetch_item(&self, name: &MessageDataItemName) -> Option<&MessageDataItem<'a>> {
+        match self {
+            Self::Fetch { items, .. } => items.as_ref().iter().find(|item| item.answers(name)),
+            _ => None,
+        }
+    }
 }
 
 /// ## 7.5. Server Responses - Command Continuation Request
@@ -641,7 +865,6 @@ impl<'a> Data<'a> {
 /// command, follows the octets of the literal.  If there are any
 /// additional command arguments, the literal octets are followed by a
 /// space and those arguments.
-#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -726,6 +949,7 @@ impl<'a> CommandContinuationRequestBasic<'a> {
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum Code<'a> {
     /// `ALERT`
     ///
@@ -752,7 +976,7 @@ pub enum Code<'a> {
     /// capabilities list.  This makes it unnecessary for a client to
     /// send a separate CAPABILITY command if it recognizes this
     /// response.
-    Capability(Vec1<Capability<'a>>), // FIXME(misuse): List must contain IMAP4REV1
+    Capability(Capabilities<'a>),
 
     /// `PARSE`
     ///
@@ -814,14 +1038,13 @@ pub enum Code<'a> {
     /// message without the \Seen flag set.
     Unseen(NonZeroU32),
 
-    /// IMAP4 Login Referrals (RFC 2221)
-    // TODO(misuse): the imap url is more complicated than that...
+    /// IMAP4 Login Referrals (RFC 2221) and IMAP4 Mailbox Referrals (RFC 2193)
     #[cfg(any(feature = "ext_mailbox_referrals", feature = "ext_login_referrals"))]
     #[cfg_attr(
         docsrs,
         doc(cfg(any(feature = "ext_mailbox_referrals", feature = "ext_login_referrals")))
     )]
-    Referral(Cow<'a, str>),
+    Referral(Box<ImapUrl<'a>>),
 
     CompressionActive,
 
@@ -832,6 +1055,26 @@ pub enum Code<'a> {
     /// Server got a non-synchronizing literal larger than 4096 bytes.
     TooBig,
 
+    /// The server could not keep up with NOTIFY events and dropped some (RFC 5465).
+    #[cfg(feature = "ext_notify")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_notify")))]
+    NotificationOverflow,
+
+    /// The listed NOTIFY events are not supported (RFC 5465).
+    #[cfg(feature = "ext_notify")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_notify")))]
+    BadEvent(Vec1<Event<'a>>),
+
+    /// GETMETADATA/SETMETADATA limits and errors (RFC 5464).
+    #[cfg(feature = "ext_metadata")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_metadata")))]
+    Metadata(MetadataCode),
+
+    /// A STORE or APPEND failed because of annotation limits (RFC 5257).
+    #[cfg(feature = "ext_annotate")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_annotate")))]
+    Annotate(AnnotateCode),
+
     /// Additional response codes defined by particular client or server
     /// implementations SHOULD be prefixed with an "X" until they are
     /// added to a revision of this protocol.  Client implementations
@@ -855,7 +1098,7 @@ impl<'a> Code<'a> {
 
     pub fn capability<C>(caps: C) -> Result<Self, C::Error>
     where
-        C: TryInto<Vec1<Capability<'a>>>,
+        C: TryInto<Capabilities<'a>>,
     {
         Ok(Self::Capability(caps.try_into()?))
     }
@@ -868,12 +1111,12 @@ impl<'a> Code<'a> {
         Ok(Self::UidNext(NonZeroU32::try_from(uidnext)?))
     }
 
-    pub fn uidvalidity(uidnext: u32) -> Result<Self, TryFromIntError> {
-        Ok(Self::UidValidity(NonZeroU32::try_from(uidnext)?))
+    pub fn uidvalidity(uidvalidity: u32) -> Result<Self, TryFromIntError> {
+        Ok(Self::UidValidity(NonZeroU32::try_from(uidvalidity)?))
     }
 
-    pub fn unseen(uidnext: u32) -> Result<Self, TryFromIntError> {
-        Ok(Self::Unseen(NonZeroU32::try_from(uidnext)?))
+    pub fn unseen(unseen: u32) -> Result<Self, TryFromIntError> {
+        Ok(Self::Unseen(NonZeroU32::try_from(unseen)?))
     }
 }
 
@@ -887,11 +1130,11 @@ pub struct CodeOther<'a>(Cow<'a, [u8]>);
 
 // We want a more readable `Debug` implementation.
 impl<'a> Debug for CodeOther<'a> {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         struct BStr<'a>(&'a Cow<'a, [u8]>);
 
         impl<'a> Debug for BStr<'a> {
-            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
                 write!(
                     f,
                     "b\"{}\"",
@@ -924,6 +1167,22 @@ impl<'a> CodeOther<'a> {
     pub fn inner(&self) -> &[u8] {
         self.0.as_ref()
     }
+
+    /// Returns the name of the code, e.g., `XVENDOR` in `[XVENDOR FOO BAR]`.
+    pub fn name(&self) -> &[u8] {
+        match self.0.iter().position(|b| *b == b' ') {
+            Some(at) => &self.0[..at],
+            None => self.0.as_ref(),
+        }
+    }
+
+    /// Returns the (verbatim) arguments of the code, e.g., `FOO BAR` in `[XVENDOR FOO BAR]`.
+    pub fn arguments(&self) -> Option<&[u8]> {
+        self.0
+            .iter()
+            .position(|b| *b == b' ')
+            .map(|at| &self.0[at + 1..])
+    }
 }
 
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
@@ -975,12 +1234,34 @@ pub enum Capability<'a> {
     Sort(Option<SortAlgorithm<'a>>),
     #[cfg(feature = "ext_sort_thread")]
     Thread(ThreadingAlgorithm<'a>),
+    /// See RFC 7889.
+    ///
+    /// `None` means that limits exist but differ per mailbox (see the `APPENDLIMIT` STATUS item).
+    #[cfg(feature = "ext_appendlimit")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_appendlimit")))]
+    AppendLimit(Option<u32>),
     /// Other/Unknown
     Other(CapabilityOther<'a>),
 }
 
+impl<'a> Capability<'a> {
+    /// Returns `true` for `IMAP4rev1` and any other `IMAP4rev<...>`, e.g., `IMAP4rev2` (RFC 9051).
+    pub fn is_imap_revision(&self) -> bool {
+        match self {
+            Self::Imap4Rev1 => true,
+            Self::Other(CapabilityOther(atom)) => {
+                let atom = atom.as_ref();
+
+                atom.len() > 8
+                    && matches!(atom.get(..8), Some(prefix) if prefix.eq_ignore_ascii_case("IMAP4rev"))
+            }
+            _ => false,
+        }
+    }
+}
+
 impl<'a> Display for Capability<'a> {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         match self {
             Self::Imap4Rev1 => write!(f, "IMAP4REV1"),
             Self::Auth(mechanism) => write!(f, "AUTH={}", mechanism),
@@ -1011,11 +1292,27 @@ impl<'a> Display for Capability<'a> {
             Self::Sort(Some(algorithm)) => write!(f, "SORT={}", algorithm),
             #[cfg(feature = "ext_sort_thread")]
             Self::Thread(algorithm) => write!(f, "THREAD={}", algorithm),
+            #[cfg(feature = "ext_appendlimit")]
+            Self::AppendLimit(None) => write!(f, "APPENDLIMIT"),
+            #[cfg(feature = "ext_appendlimit")]
+            Self::AppendLimit(Some(limit)) => write!(f, "APPENDLIMIT={}", limit),
             Self::Other(other) => write!(f, "{}", other.0),
         }
     }
 }
 
+impl<'a> Capability<'a> {
+    /// Returns `true` when this capability is named `name`, ignoring ASCII case.
+    ///
+    /// The name must include parameters, e.g., `"AUTH=PLAIN"` or `"COMPRESS=DEFLATE"`.
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            Self::Other(other) => other.0.eq_ignore_ascii_case(name),
+            _ => self.to_string().eq_ignore_ascii_case(name),
+        }
+    }
+}
+
 impl_try_from!(Atom<'a>, 'a, &'a [u8], Capability<'a>);
 impl_try_from!(Atom<'a>, 'a, Vec<u8>, Capability<'a>);
 impl_try_from!(Atom<'a>, 'a, &'a str, Capability<'a>);
@@ -1071,6 +1368,8 @@ impl<'a> From<Atom<'a>> for Capability<'a> {
             #[cfg(feature = "ext_sort_thread")]
             "sort" => Self::Sort(None),
             "unselect" => Self::Unselect,
+            #[cfg(feature = "ext_appendlimit")]
+            "appendlimit" => Self::AppendLimit(None),
             _ => {
                 // TODO(efficiency)
                 if let Some((left, right)) = split_once_cow(cow.clone(), "=") {
@@ -1103,6 +1402,21 @@ impl<'a> From<Atom<'a>> for Capability<'a> {
                                 return Self::Sort(Some(SortAlgorithm::from(atom)));
                             }
                         }
+                        #[cfg(feature = "ext_sort_thread")]
+                        "thread" => {
+                            if let Ok(atom) = Atom::try_from(right) {
+                                return Self::Thread(ThreadingAlgorithm::from(atom));
+                            }
+                        }
+                        #[cfg(feature = "ext_appendlimit")]
+                        "appendlimit" => {
+                            // Only accept the canonical form so that encoding is lossless.
+                            if let Ok(limit) = right.parse::<u32>() {
+                                if limit.to_string() == right {
+                                    return Self::AppendLimit(Some(limit));
+                                }
+                            }
+                        }
                         _ => {}
                     }
                 }
@@ -1121,6 +1435,129 @@ impl<'a> From<Atom<'a>> for Capability<'a> {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct CapabilityOther<'a>(Atom<'a>);
 
+/// A set of capabilities, e.g., as announced in a CAPABILITY response.
+///
+/// RFC 3501 requires every set to contain `IMAP4rev1`. A set that only contains a later revision,
+/// e.g., `IMAP4rev2` (RFC 9051), is accepted as well (see [`Capabilities::validate`]).
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Capabilities<'a>(pub(crate) Vec1<Capability<'a>>);
+
+impl<'a> Capabilities<'a> {
+    /// Validates if value can be used as a set of capabilities, i.e., contains `IMAP4rev1` or
+    /// another IMAP revision such as `IMAP4rev2`.
+    pub fn validate(value: &[Capability<'a>]) -> Result<(), ValidationError> {
+        if !value.iter().any(Capability::is_imap_revision) {
+            return Err(ValidationError::new(ValidationErrorKind::Invalid));
+        }
+
+        Ok(())
+    }
+
+    /// Constructs a set of capabilities without validation.
+    ///
+    /// # Warning: IMAP conformance
+    ///
+    /// The caller must ensure that `inner` is valid according to [`Self::validate`]. Failing to do
+    /// so may create invalid/unparsable IMAP messages. An exception is a (non-conforming) server
+    /// that doesn't announce `IMAP4rev1`, see `quirk_missing_imap4rev1` in imap-codec.
+    #[cfg(feature = "unvalidated")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unvalidated")))]
+    pub fn unvalidated(inner: Vec1<Capability<'a>>) -> Self {
+        Self(inner)
+    }
+
+    /// Consumes the set, returning the inner value.
+    pub fn into_inner(self) -> Vec1<Capability<'a>> {
+        self.0
+    }
+
+    /// Returns `true` when `capability` is in the set.
+    pub fn contains(&self, capability: &Capability) -> bool {
+        self.0.as_ref().contains(capability)
+    }
+
+    /// Returns `true` when `IMAP4rev1` is in the set.
+    ///
+    /// RFC 3501 requires every CAPABILITY response (and response code) to list it.
+    pub fn has_imap4rev1(&self) -> bool {
+        self.contains(&Capability::Imap4Rev1)
+    }
+
+    /// Returns `true` when the server announced `AUTH=<mechanism>`.
+    ///
+    /// Mechanism names are compared case-insensitively.
+    pub fn supports_auth(&self, mechanism: &AuthMechanism) -> bool {
+        self.0.as_ref().iter().any(|capability| match capability {
+            Capability::Auth(other) => other.as_ref().eq_ignore_ascii_case(mechanism.as_ref()),
+            _ => false,
+        })
+    }
+
+    /// Returns `true` when the server announced `UNSELECT` (RFC 3691).
+    ///
+    /// Only then may a client use [`CommandBody::Unselect`](crate::command::CommandBody::Unselect)
+    /// to leave the selected state without expunging.
+    pub fn supports_unselect(&self) -> bool {
+        self.contains(&Capability::Unselect)
+    }
+
+    /// Returns `true` when the server announced `IDLE` (RFC 2177).
+    ///
+    /// Only then may a client use [`CommandBody::Idle`](crate::command::CommandBody::Idle).
+    pub fn supports_idle(&self) -> bool {
+        self.contains(&Capability::Idle)
+    }
+
+    /// Returns `false` when the server announced `LOGINDISABLED`.
+    ///
+    /// A client must not issue a LOGIN command in this case (see RFC 3501, 6.2.3.), and should
+    /// check this before constructing one (e.g. by doing STARTTLS first).
+    ///
+    /// Note: `LOGINDISABLED` is also detected without the `starttls` feature.
+    pub fn supports_login(&self) -> bool {
+        !self.0.as_ref().iter().any(|capability| match capability {
+            #[cfg(feature = "starttls")]
+            Capability::LoginDisabled => true,
+            Capability::Other(other) => other.0.inner().eq_ignore_ascii_case("LOGINDISABLED"),
+            _ => false,
+        })
+    }
+}
+
+impl<'a> AsRef<[Capability<'a>]> for Capabilities<'a> {
+    fn as_ref(&self) -> &[Capability<'a>] {
+        self.0.as_ref()
+    }
+}
+
+impl<'a> TryFrom<Capability<'a>> for Capabilities<'a> {
+    type Error = ValidationError;
+
+    fn try_from(capability: Capability<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(Vec1::from(capability))
+    }
+}
+
+impl<'a> TryFrom<Vec1<Capability<'a>>> for Capabilities<'a> {
+    type Error = ValidationError;
+
+    fn try_from(capabilities: Vec1<Capability<'a>>) -> Result<Self, Self::Error> {
+        Self::validate(capabilities.as_ref())?;
+
+        Ok(Self(capabilities))
+    }
+}
+
+impl<'a> TryFrom<Vec<Capability<'a>>> for Capabilities<'a> {
+    type Error = ValidationError;
+
+    fn try_from(capabilities: Vec<Capability<'a>>) -> Result<Self, Self::Error> {
+        Self::try_from(Vec1::try_from(capabilities)?)
+    }
+}
+
 /// Error-related types.
 pub mod error {
     use thiserror::Error;
@@ -1146,12 +1583,151 @@ pub mod error {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_conversion_greeting() {
+        let greeting = Greeting::ok(Some(Code::Alert), "Hello").unwrap();
+        assert_eq!(greeting.kind, GreetingKind::Ok);
+        assert_eq!(greeting.code, Some(Code::Alert));
+        assert_eq!(greeting.text, Text::try_from("Hello").unwrap());
+
+        let greeting = Greeting::preauth(None, String::from("Hello")).unwrap();
+        assert_eq!(greeting.kind, GreetingKind::PreAuth);
+
+        let greeting = Greeting::bye(None, Text::try_from("Bye").unwrap()).unwrap();
+        assert_eq!(greeting.kind, GreetingKind::Bye);
+
+        assert!(Greeting::ok(None, "").is_err());
+        assert!(Greeting::bye(None, "A\r\nB").is_err());
+    }
+
+    #[test]
+    fn test_status_accessors() {
+        let tagged = Status::ok(Some(Tag::try_from("A1").unwrap()), None, "done").unwrap();
+        assert_eq!(tagged.tag(), Some(&Tag::try_from("A1").unwrap()));
+        assert_eq!(tagged.text(), &Text::try_from("done").unwrap());
+
+        let untagged = Status::no(None, Some(Code::Alert), "nope").unwrap();
+        assert_eq!(untagged.tag(), None);
+        assert_eq!(untagged.code(), Some(&Code::Alert));
+
+        let bye = Status::bye(None, "bye").unwrap();
+        assert_eq!(bye.tag(), None);
+    }
+
+    #[test]
+    fn test_alert_text() {
+        let alert = Status::ok(None, Some(Code::Alert), "System going down").unwrap();
+        assert_eq!(
+            alert.alert_text(),
+            Some(&Text::try_from("System going down").unwrap())
+        );
+        assert_eq!(Status::ok(None, None, "...").unwrap().alert_text(), None);
+        assert_eq!(
+            Status::no(None, Some(Code::Parse), "...")
+                .unwrap()
+                .alert_text(),
+            None
+        );
+
+        // The text keeps the lifetime of the status, not of the borrow.
+        fn owned_alert_text<'a>(status: &Status<'a>) -> Option<Text<'a>> {
+            status.alert_text().cloned()
+        }
+        assert_eq!(
+            owned_alert_text(&Status::bye(Some(Code::Alert), "Shutting down").unwrap()),
+            Some(Text::try_from("Shutting down").unwrap())
+        );
+
+        let greeting = Greeting::ok(Some(Code::Alert), "Maintenance at 10:00").unwrap();
+        assert_eq!(
+            greeting.alert_text(),
+            Some(&Text::try_from("Maintenance at 10:00").unwrap())
+        );
+    }
+
     #[test]
     fn test_conversion_data() {
         let _ = Data::capability(vec![Capability::Imap4Rev1]).unwrap();
         let _ = Data::fetch(1, vec![MessageDataItem::Rfc822Size(123)]).unwrap();
     }
 
+    #[test]
+    fn test_capability_matches() {
+        assert!(Capability::Imap4Rev1.matches("imap4rev1"));
+        assert!(Capability::Idle.matches("Idle"));
+        assert!(!Capability::Idle.matches("IMAP4rev1"));
+        assert!(Capability::try_from("auth=plain")
+            .unwrap()
+            .matches("AUTH=PLAIN"));
+        assert!(Capability::try_from("X-Custom")
+            .unwrap()
+            .matches("x-custom"));
+    }
+
+    #[test]
+    fn test_capabilities_supports_login() {
+        let capabilities = Capabilities::try_from(Vec1::from(Capability::Imap4Rev1)).unwrap();
+        assert!(capabilities.supports_login());
+
+        let capabilities = Capabilities::try_from(vec![
+            Capability::Imap4Rev1,
+            Capability::try_from("logindisabled").unwrap(),
+        ])
+        .unwrap();
+        assert!(!capabilities.supports_login());
+    }
+
+    #[test]
+    fn test_capabilities_supports_auth() {
+        let capabilities = Capabilities::try_from(vec![
+            Capability::Imap4Rev1,
+            Capability::try_from("AUTH=PLAIN").unwrap(),
+            Capability::try_from("AUTH=X-FOO").unwrap(),
+        ])
+        .unwrap();
+        assert!(capabilities.has_imap4rev1());
+        assert!(capabilities.supports_auth(&AuthMechanism::Plain));
+        assert!(capabilities.supports_auth(&AuthMechanism::try_from("x-foo").unwrap()));
+        assert!(!capabilities.supports_auth(&AuthMechanism::Login));
+
+        assert!(Capabilities::try_from(vec![
+            Capability::try_from("IMAP4rev2").unwrap(),
+            Capability::Idle
+        ])
+        .is_ok());
+        assert!(Capabilities::try_from(Capability::try_from("IMAP4rev").unwrap()).is_err());
+        assert!(Capabilities::try_from(Capability::try_from("IMAP4").unwrap()).is_err());
+        assert!(Capabilities::try_from(Capability::Idle).is_err());
+        assert!(Capabilities::try_from(vec![Capability::Idle, Capability::Move]).is_err());
+        assert!(Capabilities::try_from(vec![Capability::Idle, Capability::Imap4Rev1]).is_ok());
+    }
+
+    #[test]
+    fn test_capabilities_supports_unselect() {
+        let capabilities = Capabilities::try_from(vec![
+            Capability::Imap4Rev1,
+            Capability::try_from("unselect").unwrap(),
+        ])
+        .unwrap();
+        assert!(capabilities.supports_unselect());
+
+        let capabilities = Capabilities::try_from(Capability::Imap4Rev1).unwrap();
+        assert!(!capabilities.supports_unselect());
+    }
+
+    #[test]
+    fn test_capabilities_supports_idle() {
+        let capabilities = Capabilities::try_from(vec![
+            Capability::Imap4Rev1,
+            Capability::try_from("idle").unwrap(),
+        ])
+        .unwrap();
+        assert!(capabilities.supports_idle());
+
+        let capabilities = Capabilities::try_from(Capability::Imap4Rev1).unwrap();
+        assert!(!capabilities.supports_idle());
+    }
+
     #[test]
     fn test_conversion_continue_failing() {
         let tests = [
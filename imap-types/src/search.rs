@@ -1,14 +1,20 @@
 //! Search-related types.
 
+use alloc::boxed::Box;
+
 #[cfg(feature = "bounded-static")]
 use bounded_static::ToStatic;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "ext_annotate")]
+use crate::{core::NString, mailbox::ListMailbox};
 use crate::{
     core::{AString, Atom, Vec1},
     datetime::NaiveDate,
-    sequence::SequenceSet,
+    flag::Flag,
+    search::error::SearchKeyError,
+    sequence::{SeqNo, SequenceSet, TypedSequenceSet, Uid},
 };
 
 /// The defined search keys.
@@ -160,6 +166,30 @@ pub enum SearchKey<'a> {
 
     /// Messages that do not have the \Seen flag set.
     Unseen,
+
+    #[cfg(feature = "ext_gmail")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_gmail")))]
+    /// Messages matching a Gmail search query, e.g., `has:attachment in:unread`.
+    ///
+    /// ```imap
+    /// X-GM-RAW <query>
+    /// ```
+    GmailRaw(AString<'a>),
+
+    #[cfg(feature = "ext_annotate")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_annotate")))]
+    /// Messages with an annotation attribute containing `value`.
+    ///
+    /// Both, `entry` and `attribute`, may contain the `*` and `%` wildcards.
+    ///
+    /// ```imap
+    /// ANNOTATION <entry> <attribute> <value>
+    /// ```
+    Annotation {
+        entry: ListMailbox<'a>,
+        attribute: AString<'a>,
+        value: NString<'a>,
+    },
 }
 
 impl<'a> SearchKey<'a> {
@@ -169,4 +199,109 @@ impl<'a> SearchKey<'a> {
     {
         Self::Uid(sequence_set.into())
     }
+
+    /// Messages that match all `keys`, i.e., `(key1 key2 ...)`.
+    ///
+    /// Note: The search criteria of a command are ANDed implicitly, i.e., without parentheses.
+    /// Thus, use this only when a parenthesized list is required, e.g., inside of `OR` or `NOT`.
+    pub fn and<K>(keys: K) -> Result<Self, K::Error>
+    where
+        K: TryInto<Vec1<SearchKey<'a>>>,
+    {
+        Ok(Self::And(keys.try_into()?))
+    }
+
+    /// Messages that match either `a` or `b`, i.e., `OR a b`.
+    pub fn or<A, B>(a: A, b: B) -> Self
+    where
+        A: Into<SearchKey<'a>>,
+        B: Into<SearchKey<'a>>,
+    {
+        Self::Or(Box::new(a.into()), Box::new(b.into()))
+    }
+
+    /// Messages that don't match `key`, i.e., `NOT key`.
+    pub fn not<K>(key: K) -> Self
+    where
+        K: Into<SearchKey<'a>>,
+    {
+        Self::Not(Box::new(key.into()))
+    }
+}
+
+impl<'a> From<Vec1<SearchKey<'a>>> for SearchKey<'a> {
+    fn from(keys: Vec1<SearchKey<'a>>) -> Self {
+        Self::And(keys)
+    }
+}
+
+impl<'a> From<SequenceSet> for SearchKey<'a> {
+    fn from(sequence_set: SequenceSet) -> Self {
+        Self::SequenceSet(sequence_set)
+    }
+}
+
+impl<'a> From<TypedSequenceSet<SeqNo>> for SearchKey<'a> {
+    fn from(sequence_set: TypedSequenceSet<SeqNo>) -> Self {
+        Self::SequenceSet(sequence_set.into())
+    }
+}
+
+impl<'a> From<TypedSequenceSet<Uid>> for SearchKey<'a> {
+    fn from(sequence_set: TypedSequenceSet<Uid>) -> Self {
+        Self::Uid(sequence_set.into())
+    }
+}
+
+/// Messages with `flag` set, e.g., `SEEN` or `KEYWORD $Forwarded`.
+///
+/// Fails for extension flags, e.g., `\Foo`, because they can't be searched for.
+impl<'a> TryFrom<Flag<'a>> for SearchKey<'a> {
+    type Error = SearchKeyError;
+
+    fn try_from(flag: Flag<'a>) -> Result<Self, Self::Error> {
+        Ok(match flag {
+            Flag::Answered => Self::Answered,
+            Flag::Deleted => Self::Deleted,
+            Flag::Draft => Self::Draft,
+            Flag::Flagged => Self::Flagged,
+            Flag::Seen => Self::Seen,
+            Flag::Keyword(keyword) => Self::Keyword(keyword),
+            flag if flag.is_recent() => Self::Recent,
+            Flag::Extension(_) => return Err(SearchKeyError::ExtensionFlag),
+        })
+    }
+}
+
+/// Error-related types.
+pub mod error {
+    use thiserror::Error;
+
+    #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+    pub enum SearchKeyError {
+        #[error("Extension flags can't be used as search keys")]
+        ExtensionFlag,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion_flag() {
+        assert_eq!(SearchKey::try_from(Flag::Seen), Ok(SearchKey::Seen));
+        assert_eq!(
+            SearchKey::try_from(Flag::try_from("\\Recent").unwrap()),
+            Ok(SearchKey::Recent)
+        );
+        assert_eq!(
+            SearchKey::try_from(Flag::try_from("\\recent").unwrap()),
+            Ok(SearchKey::Recent)
+        );
+        assert_eq!(
+            SearchKey::try_from(Flag::try_from("\\Other").unwrap()),
+            Err(SearchKeyError::ExtensionFlag)
+        );
+    }
 }
@@ -3,7 +3,7 @@
 //! This module provides a `Secret<T>` ensuring that sensitive values are not
 //! `Debug`-printed by accident.
 
-use std::fmt::{Debug, Formatter};
+use core::fmt::{Debug, Formatter};
 
 #[cfg(feature = "arbitrary")]
 use arbitrary::Arbitrary;
@@ -44,7 +44,7 @@ impl<T> Debug for Secret<T>
 where
     T: Debug,
 {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         #[cfg(not(debug_assertions))]
         return write!(f, "/* REDACTED */");
         #[cfg(debug_assertions)]
@@ -1,8 +1,9 @@
-use std::{
-    cmp::max,
-    collections::VecDeque,
+use alloc::{collections::VecDeque, vec, vec::Vec};
+use core::{
+    cmp::{max, min},
     fmt::Debug,
     iter::Rev,
+    marker::PhantomData,
     num::NonZeroU32,
     ops::{Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive},
     str::FromStr,
@@ -11,7 +12,7 @@ use std::{
 #[cfg(feature = "arbitrary")]
 use arbitrary::Arbitrary;
 #[cfg(feature = "bounded-static")]
-use bounded_static::ToStatic;
+use bounded_static::{IntoBoundedStatic, ToBoundedStatic, ToStatic};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -439,6 +440,204 @@ impl From<RangeInclusive<NonZeroU32>> for Sequence {
 
 // -------------------------------------------------------------------------------------------------
 
+impl SequenceSet {
+    /// Create a sequence set containing a single sequence number or UID, e.g., `42`.
+    pub fn single(value: NonZeroU32) -> Self {
+        Self::from(value)
+    }
+
+    /// Create a sequence set containing a range, e.g., `1:*`.
+    ///
+    /// Either end may be [`SeqOrUid::Asterisk`].
+    pub fn range<F, T>(from: F, to: T) -> Self
+    where
+        F: Into<SeqOrUid>,
+        T: Into<SeqOrUid>,
+    {
+        Self::from(Sequence::Range(from.into(), to.into()))
+    }
+
+    /// Create a compact sequence set from sequence numbers or UIDs.
+    ///
+    /// The values are sorted and deduplicated, and consecutive values are coalesced into ranges.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::num::NonZeroU32;
+    ///
+    /// use imap_types::sequence::SequenceSet;
+    ///
+    /// let uids = [5, 1, 2, 3].map(|uid| NonZeroU32::new(uid).unwrap());
+    ///
+    /// assert_eq!(
+    ///     SequenceSet::from_uids(uids).unwrap(),
+    ///     SequenceSet::try_from("1:3,5").unwrap()
+    /// );
+    /// ```
+    pub fn from_uids<I>(uids: I) -> Result<Self, ValidationError>
+    where
+        I: IntoIterator<Item = NonZeroU32>,
+    {
+        let mut uids: Vec<_> = uids.into_iter().collect();
+        uids.sort();
+        uids.dedup();
+
+        let to_sequence = |start: NonZeroU32, end: NonZeroU32| {
+            if start == end {
+                Sequence::from(start)
+            } else {
+                Sequence::from(start..=end)
+            }
+        };
+
+        let mut sequences = Vec::new();
+        let mut uids = uids.into_iter();
+
+        if let Some(first) = uids.next() {
+            let (mut start, mut end) = (first, first);
+
+            for uid in uids {
+                if end.checked_add(1) == Some(uid) {
+                    end = uid;
+                } else {
+                    sequences.push(to_sequence(start, end));
+                    (start, end) = (uid, uid);
+                }
+            }
+
+            sequences.push(to_sequence(start, end));
+        }
+
+        Self::try_from(sequences)
+    }
+
+    /// Returns `true` when `value` is in the set.
+    ///
+    /// `*` is resolved to `largest`, i.e., the largest sequence number or UID in use.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::num::NonZeroU32;
+    ///
+    /// use imap_types::sequence::SequenceSet;
+    ///
+    /// let seq = SequenceSet::try_from("1:3,*").unwrap();
+    /// let n = |n| NonZeroU32::new(n).unwrap();
+    ///
+    /// assert!(seq.contains(n(2), n(10)));
+    /// assert!(seq.contains(n(10), n(10)));
+    /// assert!(!seq.contains(n(5), n(10)));
+    /// ```
+    pub fn contains(&self, value: NonZeroU32, largest: NonZeroU32) -> bool {
+        let value = u32::from(value);
+
+        simplify(self.clone(), largest, true)
+            .into_iter()
+            .any(|(a, b)| a <= value && value <= b)
+    }
+
+    /// Returns a set with all values that are in `self` or `other`.
+    ///
+    /// `*` is resolved to `largest` before combining the sets. The result is normalized, i.e.,
+    /// sorted, and overlapping or adjacent ranges are coalesced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::num::NonZeroU32;
+    ///
+    /// use imap_types::sequence::SequenceSet;
+    ///
+    /// let a = SequenceSet::try_from("1:3,7").unwrap();
+    /// let b = SequenceSet::try_from("4:5,6:*").unwrap();
+    ///
+    /// assert_eq!(
+    ///     a.union(&b, NonZeroU32::new(10).unwrap()),
+    ///     SequenceSet::try_from("1:10").unwrap()
+    /// );
+    /// ```
+    pub fn union(&self, other: &Self, largest: NonZeroU32) -> Self {
+        let mut ranges = simplify(self.clone(), largest, true);
+        ranges.extend(simplify(other.clone(), largest, true));
+
+        // Safety: `unwrap` can't panic because a sequence set is never empty.
+        Self::from_ranges(cleanup(ranges)).unwrap()
+    }
+
+    /// Returns a set with all values that are in both `self` and `other`, or `None` if there are
+    /// no such values.
+    ///
+    /// `*` is resolved to `largest` before combining the sets. The result is normalized, i.e.,
+    /// sorted, and overlapping or adjacent ranges are coalesced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::num::NonZeroU32;
+    ///
+    /// use imap_types::sequence::SequenceSet;
+    ///
+    /// let a = SequenceSet::try_from("1:5,8:*").unwrap();
+    /// let b = SequenceSet::try_from("4:9").unwrap();
+    /// let largest = NonZeroU32::new(10).unwrap();
+    ///
+    /// assert_eq!(
+    ///     a.intersection(&b, largest),
+    ///     Some(SequenceSet::try_from("4:5,8:9").unwrap())
+    /// );
+    /// assert_eq!(
+    ///     a.intersection(&SequenceSet::try_from("6:7").unwrap(), largest),
+    ///     None
+    /// );
+    /// ```
+    pub fn intersection(&self, other: &Self, largest: NonZeroU32) -> Option<Self> {
+        let left = cleanup(simplify(self.clone(), largest, true));
+        let right = cleanup(simplify(other.clone(), largest, true));
+
+        let mut ranges = VecDeque::new();
+        let (mut left, mut right) = (left.into_iter().peekable(), right.into_iter().peekable());
+
+        while let (Some(&(a1, b1)), Some(&(a2, b2))) = (left.peek(), right.peek()) {
+            let (a, b) = (max(a1, a2), min(b1, b2));
+
+            if a <= b {
+                ranges.push_back((a, b));
+            }
+
+            // Advance the range that ends first.
+            if b1 < b2 {
+                left.next();
+            } else {
+                right.next();
+            }
+        }
+
+        Self::from_ranges(ranges)
+    }
+
+    /// Create a sequence set from sorted, non-overlapping ranges.
+    fn from_ranges(ranges: VecDeque<(u32, u32)>) -> Option<Self> {
+        let sequences: Vec<_> = ranges
+            .into_iter()
+            .map(|(a, b)| {
+                // Safety: `unwrap` can't panic because all values stem from `NonZeroU32`s.
+                let a = NonZeroU32::new(a).unwrap();
+                let b = NonZeroU32::new(b).unwrap();
+
+                if a == b {
+                    Sequence::from(a)
+                } else {
+                    Sequence::from(a..=b)
+                }
+            })
+            .collect();
+
+        Self::try_from(sequences).ok()
+    }
+}
+
 impl<'a> SequenceSet {
     /// Iterate over a sorted, deduplicated set of sequence numbers or UIDs.
     ///
@@ -502,6 +701,260 @@ impl<'a> SequenceSet {
     }
 }
 
+// -------------------------------------------------------------------------------------------------
+
+/// A message sequence number.
+///
+/// Use it together with [`TypedSequenceSet`] to not confuse sequence numbers with [`Uid`]s.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SeqNo(NonZeroU32);
+
+/// A unique identifier (UID) of a message.
+///
+/// Use it together with [`TypedSequenceSet`] to not confuse UIDs with [`SeqNo`]s.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Uid(NonZeroU32);
+
+macro_rules! impl_sequence_kind {
+    ($kind:ident, $uid:literal) => {
+        impl $kind {
+            /// Create from the raw number.
+            pub const fn new(value: NonZeroU32) -> Self {
+                Self(value)
+            }
+
+            /// Get the raw number.
+            pub const fn get(&self) -> NonZeroU32 {
+                self.0
+            }
+        }
+
+        impl From<NonZeroU32> for $kind {
+            fn from(value: NonZeroU32) -> Self {
+                Self(value)
+            }
+        }
+
+        impl TryFrom<u32> for $kind {
+            type Error = ValidationError;
+
+            fn try_from(value: u32) -> Result<Self, Self::Error> {
+                NonZeroU32::new(value)
+                    .map(Self)
+                    .ok_or(ValidationError::new(ValidationErrorKind::Invalid))
+            }
+        }
+
+        impl From<$kind> for NonZeroU32 {
+            fn from(value: $kind) -> Self {
+                value.0
+            }
+        }
+
+        impl private::Sealed for $kind {}
+
+        impl SequenceKind for $kind {
+            const UID: bool = $uid;
+        }
+    };
+}
+
+impl_sequence_kind!(SeqNo, false);
+impl_sequence_kind!(Uid, true);
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Marker trait implemented by [`SeqNo`] and [`Uid`].
+pub trait SequenceKind: Copy + Into<NonZeroU32> + private::Sealed {
+    /// Whether the UID variant of a command must be used.
+    const UID: bool;
+}
+
+/// A sequence set that is known to contain either sequence numbers ([`SeqNo`]) or UIDs ([`Uid`]).
+///
+/// The wire format is the same as for [`SequenceSet`]. However, commands constructed from a
+/// `TypedSequenceSet`, e.g., with [`CommandBody::fetch_typed`](crate::command::CommandBody::fetch_typed),
+/// derive the `uid` flag from `K`. Thus, `UID COPY` and `COPY` can't be confused.
+///
+/// # Example
+///
+/// ```
+/// use imap_types::{
+///     command::CommandBody,
+///     sequence::{SeqNoOrUidSet, TypedSequenceSet, Uid},
+/// };
+///
+/// let uid = Uid::try_from(42).unwrap();
+/// let command = CommandBody::copy_typed(TypedSequenceSet::from(uid), "Archive").unwrap();
+///
+/// assert!(matches!(
+///     command,
+///     CommandBody::Copy {
+///         sequence_set: SeqNoOrUidSet::Uid(_),
+///         ..
+///     }
+/// ));
+/// ```
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct TypedSequenceSet<K> {
+    sequence_set: SequenceSet,
+    kind: PhantomData<K>,
+}
+
+impl<K: SequenceKind> TypedSequenceSet<K> {
+    fn new(sequence_set: SequenceSet) -> Self {
+        Self {
+            sequence_set,
+            kind: PhantomData,
+        }
+    }
+
+    /// Create a compact set from the given values, see [`SequenceSet::from_uids`].
+    pub fn from_values<I>(values: I) -> Result<Self, ValidationError>
+    where
+        I: IntoIterator<Item = K>,
+    {
+        SequenceSet::from_uids(values.into_iter().map(Into::into)).map(Self::new)
+    }
+
+    /// Returns a reference to the inner (untyped) sequence set.
+    pub fn inner(&self) -> &SequenceSet {
+        &self.sequence_set
+    }
+
+    /// Consumes the typed sequence set, returning the inner (untyped) sequence set.
+    pub fn into_inner(self) -> SequenceSet {
+        self.sequence_set
+    }
+}
+
+impl<K: SequenceKind> From<K> for TypedSequenceSet<K> {
+    fn from(value: K) -> Self {
+        Self::new(SequenceSet::single(value.into()))
+    }
+}
+
+impl<K: SequenceKind> From<RangeInclusive<K>> for TypedSequenceSet<K> {
+    fn from(range: RangeInclusive<K>) -> Self {
+        let (start, end) = range.into_inner();
+
+        Self::new(SequenceSet::range(start.into(), end.into()))
+    }
+}
+
+impl<K: SequenceKind> From<RangeFrom<K>> for TypedSequenceSet<K> {
+    fn from(range: RangeFrom<K>) -> Self {
+        Self::new(SequenceSet::range(range.start.into(), SeqOrUid::Asterisk))
+    }
+}
+
+impl<K: SequenceKind> From<RangeFull> for TypedSequenceSet<K> {
+    fn from(range: RangeFull) -> Self {
+        Self::new(SequenceSet::from(range))
+    }
+}
+
+impl<K> Debug for TypedSequenceSet<K> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(&self.sequence_set, f)
+    }
+}
+
+impl<K> AsRef<SequenceSet> for TypedSequenceSet<K> {
+    fn as_ref(&self) -> &SequenceSet {
+        &self.sequence_set
+    }
+}
+
+impl<K> From<TypedSequenceSet<K>> for SequenceSet {
+    fn from(value: TypedSequenceSet<K>) -> Self {
+        value.sequence_set
+    }
+}
+
+#[cfg(feature = "bounded-static")]
+impl<K: SequenceKind + 'static> IntoBoundedStatic for TypedSequenceSet<K> {
+    type Static = Self;
+
+    fn into_static(self) -> Self::Static {
+        self
+    }
+}
+
+#[cfg(feature = "bounded-static")]
+impl<K: SequenceKind + 'static> ToBoundedStatic for TypedSequenceSet<K> {
+    type Static = Self;
+
+    fn to_static(&self) -> Self::Static {
+        self.clone()
+    }
+}
+
+/// A sequence set of either sequence numbers or UIDs.
+///
+/// This is what FETCH, STORE, COPY, and MOVE operate on. A [`SeqNoOrUidSet::Uid`] denotes the UID
+/// variant of the command, e.g., `UID FETCH`.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SeqNoOrUidSet {
+    /// Message sequence numbers.
+    SeqNo(TypedSequenceSet<SeqNo>),
+    /// UIDs.
+    Uid(TypedSequenceSet<Uid>),
+}
+
+impl SeqNoOrUidSet {
+    /// Interpret `sequence_set` as UIDs if `uid` is set, and as sequence numbers otherwise.
+    pub fn new(sequence_set: SequenceSet, uid: bool) -> Self {
+        if uid {
+            Self::Uid(TypedSequenceSet::new(sequence_set))
+        } else {
+            Self::SeqNo(TypedSequenceSet::new(sequence_set))
+        }
+    }
+
+    /// Whether the set contains UIDs, i.e., the UID variant of a command is used.
+    pub fn is_uid(&self) -> bool {
+        matches!(self, Self::Uid(_))
+    }
+
+    /// Consumes the set, returning the inner (untyped) sequence set.
+    pub fn into_inner(self) -> SequenceSet {
+        match self {
+            Self::SeqNo(set) => set.into_inner(),
+            Self::Uid(set) => set.into_inner(),
+        }
+    }
+}
+
+impl<K: SequenceKind> From<TypedSequenceSet<K>> for SeqNoOrUidSet {
+    fn from(value: TypedSequenceSet<K>) -> Self {
+        Self::new(value.into_inner(), K::UID)
+    }
+}
+
+impl AsRef<SequenceSet> for SeqNoOrUidSet {
+    fn as_ref(&self) -> &SequenceSet {
+        match self {
+            Self::SeqNo(set) => set.as_ref(),
+            Self::Uid(set) => set.as_ref(),
+        }
+    }
+}
+
 impl SeqOrUid {
     pub fn expand(&self, largest: NonZeroU32) -> NonZeroU32 {
         match self {
@@ -861,6 +1314,68 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_sequence_set_constructors() {
+        let n = |value| NonZeroU32::new(value).unwrap();
+
+        assert_eq!(
+            SequenceSet::single(n(42)),
+            SequenceSet::try_from("42").unwrap()
+        );
+        assert_eq!(
+            SequenceSet::range(n(1), SeqOrUid::Asterisk),
+            SequenceSet::try_from("1:*").unwrap()
+        );
+        assert_eq!(
+            SequenceSet::range(n(3), n(7)),
+            SequenceSet::try_from("3:7").unwrap()
+        );
+
+        let tests = [
+            (vec![1, 2, 3, 5], "1:3,5"),
+            (vec![5, 3, 2, 1, 3], "1:3,5"),
+            (vec![7], "7"),
+            (vec![1, 3, 5], "1,3,5"),
+            (vec![u32::MAX - 1, u32::MAX, 1], "1,4294967294:4294967295"),
+        ];
+
+        for (test, expected) in tests {
+            assert_eq!(
+                SequenceSet::from_uids(test.into_iter().map(n)).unwrap(),
+                SequenceSet::try_from(expected).unwrap()
+            );
+        }
+
+        assert!(SequenceSet::from_uids([]).is_err());
+    }
+
+    #[test]
+    fn test_typed_sequence_set() {
+        let uid = |value| Uid::try_from(value).unwrap();
+
+        assert!(Uid::try_from(0).is_err());
+        assert!(SeqNo::try_from(0).is_err());
+        assert_eq!(uid(42).get(), NonZeroU32::new(42).unwrap());
+
+        let tests: [(TypedSequenceSet<Uid>, &str); 5] = [
+            (TypedSequenceSet::from(uid(42)), "42"),
+            (TypedSequenceSet::from(uid(1)..=uid(3)), "1:3"),
+            (TypedSequenceSet::from(uid(5)..), "5:*"),
+            (TypedSequenceSet::from(..), "1:*"),
+            (
+                TypedSequenceSet::from_values([1, 2, 3, 5].map(uid)).unwrap(),
+                "1:3,5",
+            ),
+        ];
+
+        for (test, expected) in tests {
+            assert_eq!(
+                SequenceSet::from(test),
+                SequenceSet::try_from(expected).unwrap()
+            );
+        }
+    }
+
     #[test]
     fn test_clean() {
         let tests = vec![
@@ -891,4 +1406,48 @@ mod tests {
             assert_eq!(naive, clean);
         }
     }
+
+    #[test]
+    fn test_set_algebra() {
+        let seq = |s| SequenceSet::try_from(s).unwrap();
+        let largest = NonZeroU32::new(20).unwrap();
+
+        let tests = [
+            // Overlapping
+            ("1:5", "3:8", "1:8", Some("3:5")),
+            ("8:3", "1:4", "1:8", Some("3:4")),
+            ("1:10", "2,4,6", "1:10", Some("2,4,6")),
+            // Adjacent
+            ("1:3", "4:6", "1:6", None),
+            ("5,1,3", "2,4", "1:5", None),
+            // Disjoint
+            ("1:3", "5:6", "1:3,5:6", None),
+            // Asterisk
+            ("*", "19", "19:20", None),
+            ("15:*", "*:18", "15:20", Some("18:20")),
+            ("1:3,*", "3:*", "1:20", Some("3,20")),
+            ("*:*", "1:*", "1:20", Some("20")),
+        ];
+
+        for (left, right, union, intersection) in tests {
+            let (left, right) = (seq(left), seq(right));
+
+            assert_eq!(left.union(&right, largest), seq(union));
+            assert_eq!(right.union(&left, largest), seq(union));
+            assert_eq!(left.intersection(&right, largest), intersection.map(seq));
+            assert_eq!(right.intersection(&left, largest), intersection.map(seq));
+
+            for value in (1..=largest.get()).map(|value| NonZeroU32::new(value).unwrap()) {
+                let in_left = left.contains(value, largest);
+                let in_right = right.contains(value, largest);
+
+                assert_eq!(in_left, left.iter(largest).any(|v| v == value));
+                assert_eq!(in_left || in_right, seq(union).contains(value, largest));
+                assert_eq!(
+                    in_left && in_right,
+                    intersection.is_some_and(|i| seq(i).contains(value, largest))
+                );
+            }
+        }
+    }
 }
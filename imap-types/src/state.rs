@@ -55,7 +55,7 @@ use bounded_static::ToStatic;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::{core::Tag, mailbox::Mailbox};
+use crate::{core::Tag, mailbox::Mailbox, response::GreetingKind};
 
 /// State of the IMAP4rev1 connection.
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
@@ -93,13 +93,29 @@ pub enum State<'a> {
     IdleSelected(Tag<'a>, Mailbox<'a>),
 }
 
+impl<'a> From<GreetingKind> for State<'a> {
+    /// Returns the state entered after receiving a greeting.
+    ///
+    /// A `PREAUTH` greeting skips the [`State::NotAuthenticated`] state.
+    fn from(kind: GreetingKind) -> Self {
+        match kind {
+            GreetingKind::Ok => State::NotAuthenticated,
+            GreetingKind::PreAuth => State::Authenticated,
+            GreetingKind::Bye => State::Logout,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(feature = "bounded-static")]
     use bounded_static::{IntoBoundedStatic, ToBoundedStatic};
 
     use super::*;
-    use crate::{core::Tag, mailbox::Mailbox};
+    use crate::{
+        command::CommandBody, core::Tag, extensions::enable::CapabilityEnable,
+        fetch::MessageDataItemName, mailbox::Mailbox,
+    };
 
     #[test]
     fn test_conversion() {
@@ -124,4 +140,70 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_state_from_greeting_kind() {
+        assert_eq!(State::from(GreetingKind::Ok), State::NotAuthenticated);
+        assert_eq!(State::from(GreetingKind::PreAuth), State::Authenticated);
+        assert_eq!(State::from(GreetingKind::Bye), State::Logout);
+    }
+
+    #[test]
+    fn test_command_is_valid_in() {
+        let selected = State::Selected(Mailbox::Inbox);
+        let idle = State::IdleSelected(Tag::try_from("A").unwrap(), Mailbox::Inbox);
+
+        let tests = [
+            // (command, not authenticated, authenticated, selected)
+            (CommandBody::Noop, true, true, true),
+            (CommandBody::Logout, true, true, true),
+            (
+                CommandBody::login("alice", "password").unwrap(),
+                true,
+                false,
+                false,
+            ),
+            (CommandBody::select("INBOX").unwrap(), false, true, true),
+            (
+                CommandBody::status("INBOX", vec![]).unwrap(),
+                false,
+                true,
+                true,
+            ),
+            (CommandBody::Idle, false, true, true),
+            (
+                CommandBody::enable(vec![CapabilityEnable::try_from("UTF8=ACCEPT").unwrap()])
+                    .unwrap(),
+                false,
+                true,
+                false,
+            ),
+            (
+                CommandBody::fetch("1", vec![MessageDataItemName::Uid], false).unwrap(),
+                false,
+                false,
+                true,
+            ),
+            (CommandBody::Expunge, false, false, true),
+            (CommandBody::Unselect, false, false, true),
+            (CommandBody::Close, false, false, true),
+        ];
+
+        for (command, not_authenticated, authenticated, in_selected) in tests {
+            assert_eq!(
+                command.is_valid_in(&State::NotAuthenticated),
+                not_authenticated,
+                "{command:?}"
+            );
+            assert_eq!(
+                command.is_valid_in(&State::Authenticated),
+                authenticated,
+                "{command:?}"
+            );
+            assert_eq!(command.is_valid_in(&selected), in_selected, "{command:?}");
+            assert!(!command.is_valid_in(&State::Greeting));
+            assert!(!command.is_valid_in(&State::Logout));
+            assert!(!command.is_valid_in(&idle));
+        }
+    }
 }
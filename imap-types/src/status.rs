@@ -1,4 +1,4 @@
-use std::num::NonZeroU32;
+use core::num::NonZeroU32;
 
 #[cfg(feature = "arbitrary")]
 use arbitrary::Arbitrary;
@@ -38,6 +38,11 @@ pub enum StatusDataItemName {
     #[cfg(feature = "ext_condstore_qresync")]
     #[cfg_attr(docsrs, doc(cfg(feature = "ext_condstore_qresync")))]
     HighestModSeq,
+
+    /// The maximum size of a message that can be appended to the mailbox.
+    #[cfg(feature = "ext_appendlimit")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_appendlimit")))]
+    AppendLimit,
 }
 
 /// Status data item.
@@ -69,4 +74,11 @@ pub enum StatusDataItem {
 
     /// The amount of storage space that can be reclaimed by performing EXPUNGE on the mailbox.
     DeletedStorage(u64),
+
+    /// The maximum size of a message that can be appended to the mailbox.
+    ///
+    /// `None` (`NIL`) means that there is no limit.
+    #[cfg(feature = "ext_appendlimit")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_appendlimit")))]
+    AppendLimit(Option<u32>),
 }
@@ -1,6 +1,6 @@
 //! Functions that may come in handy.
 
-use std::borrow::Cow;
+use alloc::{borrow::Cow, format, string::String, vec::Vec};
 
 /// Converts bytes into a ready-to-be-printed form.
 pub fn escape_byte_string<B>(bytes: B) -> String
@@ -31,18 +31,67 @@ where
         .join("")
 }
 
+/// Replaces the data of every [`Literal`](crate::core::Literal) in a pretty-printed (`{:#?}`)
+/// `Debug` output with `<N bytes>`.
+pub(crate) fn summarize_literals(rendered: &str) -> String {
+    // Counts the bytes of a string escaped by `escape_byte_string`.
+    fn unescaped_len(escaped: &str) -> usize {
+        let mut chars = escaped.chars();
+        let mut len = 0;
+
+        while let Some(char) = chars.next() {
+            if char == '\\' && chars.next() == Some('x') {
+                chars.nth(1);
+            }
+
+            len += 1;
+        }
+
+        len
+    }
+
+    let mut lines = Vec::new();
+    let mut in_literal = false;
+
+    for line in rendered.lines() {
+        let trimmed = line.trim_start();
+
+        let summarized = if in_literal {
+            trimmed
+                .strip_prefix("data: b\"")
+                .and_then(|data| data.strip_suffix("\","))
+                .map(|data| {
+                    let indent = &line[..line.len() - trimmed.len()];
+
+                    format!("{}data: <{} bytes>,", indent, unescaped_len(data))
+                })
+        } else {
+            None
+        };
+
+        in_literal = trimmed == "Literal {" || trimmed.ends_with(": Literal {");
+
+        match summarized {
+            Some(summarized) => lines.push(Cow::Owned(summarized)),
+            None => lines.push(Cow::Borrowed(line)),
+        }
+    }
+
+    lines.join("\n")
+}
+
 pub mod indicators {
     /// Any 7-bit US-ASCII character, excluding NUL
     ///
     /// CHAR = %x01-7F
-    pub fn is_char(byte: u8) -> bool {
+    pub const fn is_char(byte: u8) -> bool {
         matches!(byte, 0x01..=0x7f)
     }
 
     /// Controls
     ///
     /// CTL = %x00-1F / %x7F
-    pub fn is_ctl(byte: u8) -> bool {
+    pub const fn is_ctl(byte: u8) -> bool {
         matches!(byte, 0x00..=0x1f | 0x7f)
     }
 
@@ -51,7 +100,7 @@ pub mod indicators {
     }
 
     /// `quoted-specials = DQUOTE / "\"`
-    pub fn is_quoted_specials(byte: u8) -> bool {
+    pub const fn is_quoted_specials(byte: u8) -> bool {
         byte == b'"' || byte == b'\\'
     }
 
@@ -61,12 +110,12 @@ pub mod indicators {
     }
 
     /// `ATOM-CHAR = <any CHAR except atom-specials>`
-    pub fn is_atom_char(b: u8) -> bool {
+    pub const fn is_atom_char(b: u8) -> bool {
         is_char(b) && !is_atom_specials(b)
     }
 
     /// `atom-specials = "(" / ")" / "{" / SP / CTL / list-wildcards / quoted-specials / resp-specials`
-    pub fn is_atom_specials(i: u8) -> bool {
+    pub const fn is_atom_specials(i: u8) -> bool {
         match i {
             b'(' | b')' | b'{' | b' ' => true,
             c if is_ctl(c) => true,
@@ -78,13 +127,13 @@ pub mod indicators {
     }
 
     /// `list-wildcards = "%" / "*"`
-    pub fn is_list_wildcards(i: u8) -> bool {
+    pub const fn is_list_wildcards(i: u8) -> bool {
         i == b'%' || i == b'*'
     }
 
     #[inline]
     /// `resp-specials = "]"`
-    pub fn is_resp_specials(i: u8) -> bool {
+    pub const fn is_resp_specials(i: u8) -> bool {
         i == b']'
     }
 
@@ -109,32 +158,51 @@ pub mod indicators {
     }
 }
 
+/// Escape a string for use in a quoted string, i.e., replace `\` with `\\` and `"` with `\"`.
+///
+/// Borrows from `unescaped` when there is nothing to escape and allocates at most once otherwise.
 pub fn escape_quoted(unescaped: &str) -> Cow<str> {
-    let mut escaped = Cow::Borrowed(unescaped);
-
-    if escaped.contains('\\') {
-        escaped = Cow::Owned(escaped.replace('\\', "\\\\"));
+    if !unescaped.contains(['\\', '"']) {
+        return Cow::Borrowed(unescaped);
     }
 
-    if escaped.contains('\"') {
-        escaped = Cow::Owned(escaped.replace('"', "\\\""));
+    let mut escaped = String::with_capacity(unescaped.len() + 2);
+
+    for char in unescaped.chars() {
+        if matches!(char, '\\' | '"') {
+            escaped.push('\\');
+        }
+
+        escaped.push(char);
     }
 
-    escaped
+    Cow::Owned(escaped)
 }
 
+/// Unescape a quoted string, i.e., replace `\\` with `\` and `\"` with `"`.
+///
+/// Borrows from `escaped` when there is nothing to unescape and allocates at most once otherwise.
 pub fn unescape_quoted(escaped: &str) -> Cow<str> {
-    let mut unescaped = Cow::Borrowed(escaped);
-
-    if unescaped.contains("\\\\") {
-        unescaped = Cow::Owned(unescaped.replace("\\\\", "\\"));
+    if !escaped.contains('\\') {
+        return Cow::Borrowed(escaped);
     }
 
-    if unescaped.contains("\\\"") {
-        unescaped = Cow::Owned(unescaped.replace("\\\"", "\""));
+    let mut unescaped = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        if char == '\\' {
+            if let Some(&next @ ('\\' | '"')) = chars.peek() {
+                unescaped.push(next);
+                chars.next();
+                continue;
+            }
+        }
+
+        unescaped.push(char);
     }
 
-    unescaped
+    Cow::Owned(unescaped)
 }
 
 #[cfg(test)]
@@ -175,6 +243,8 @@ mod tests {
             let got = unescape_quoted(test);
             assert_eq!(expected, got);
         }
+
+        assert!(matches!(unescape_quoted("alice"), Cow::Borrowed("alice")));
     }
 
     #[test]
@@ -184,6 +254,37 @@ mod tests {
         assert_eq!(input, unescape_quoted(escape_quoted(input).as_ref()));
     }
 
+    #[test]
+    fn test_escape_unescape_quoted_exhaustive() {
+        // All strings up to length 6 over an alphabet that contains both specials.
+        let alphabet = ['a', '\\', '"', ' ', 'ß'];
+        let mut inputs = vec![String::new()];
+        let mut previous = inputs.clone();
+
+        for _ in 0..6 {
+            previous = previous
+                .iter()
+                .flat_map(|prefix| alphabet.iter().map(move |char| format!("{prefix}{char}")))
+                .collect();
+            inputs.extend(previous.iter().cloned());
+        }
+
+        for input in inputs {
+            let escaped = escape_quoted(&input);
+
+            // Every special is escaped and nothing else is.
+            let specials = input.chars().filter(|c| matches!(c, '\\' | '"')).count();
+            assert_eq!(escaped.chars().count(), input.chars().count() + specials);
+            assert_eq!(
+                matches!(escaped, Cow::Borrowed(_)),
+                specials == 0,
+                "{input:?}"
+            );
+
+            assert_eq!(input, unescape_quoted(&escaped), "{input:?}");
+        }
+    }
+
     #[test]
     fn test_escape_byte_string() {
         for byte in 0u8..=255 {
@@ -217,4 +318,16 @@ mod tests {
             assert_eq!(expected, got);
         }
     }
+
+    #[test]
+    fn test_summarize_literals() {
+        let literal = crate::core::Literal::try_from(b"a\"\\\r\n\xff".as_ref()).unwrap();
+
+        let rendered = summarize_literals(&format!("{:#?}", Some(literal)));
+
+        assert_eq!(
+            rendered,
+            "Some(\n    Literal {\n        data: <6 bytes>,\n        mode: Sync,\n    },\n)"
+        );
+    }
 }
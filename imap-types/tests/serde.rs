@@ -0,0 +1,127 @@
+//! Golden tests for the `serde` representation.
+//!
+//! Changing any of these is a breaking change for users that store serialized messages.
+#![cfg(feature = "serde")]
+
+use std::fmt::Debug;
+
+use imap_types::{
+    command::{Command, CommandBody},
+    core::{AString, Atom, IString, Literal, NString, Quoted, Tag, Vec1},
+    fetch::MessageDataItem,
+    flag::Flag,
+    response::{Code, Data, Response, Status},
+    sequence::SequenceSet,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+
+fn golden<T>(object: T, expected: Value)
+where
+    T: Debug + PartialEq + Serialize + DeserializeOwned,
+{
+    let got = serde_json::to_value(&object).unwrap();
+    assert_eq!(expected, got, "{object:?}");
+
+    let object_again: T = serde_json::from_value(got).unwrap();
+    assert_eq!(object, object_again);
+}
+
+#[test]
+fn test_serde_core() {
+    golden(Tag::try_from("A1").unwrap(), json!("A1"));
+    golden(Atom::try_from("FOO").unwrap(), json!("FOO"));
+    golden(
+        IString::Quoted(Quoted::try_from("a b").unwrap()),
+        json!({"Quoted": "a b"}),
+    );
+    golden(
+        IString::Literal(Literal::try_from("ab").unwrap()),
+        json!({"Literal": {"data": [97, 98], "mode": "Sync"}}),
+    );
+    golden(
+        IString::Literal(Literal::try_from_non_sync("").unwrap()),
+        json!({"Literal": {"data": [], "mode": "NonSync"}}),
+    );
+    golden(NString(None), json!(null));
+    golden(
+        NString(Some(IString::Quoted(Quoted::try_from("").unwrap()))),
+        json!({"Quoted": ""}),
+    );
+    golden(
+        AString::try_from("alice").unwrap(),
+        json!({"Atom": "alice"}),
+    );
+    golden(Vec1::from(1u32), json!([1]));
+}
+
+#[test]
+fn test_serde_command() {
+    golden(
+        Command::new("A1", CommandBody::login("alice", "pass word").unwrap()).unwrap(),
+        json!({
+            "tag": "A1",
+            "body": {
+                "Login": {
+                    "username": {"Atom": "alice"},
+                    "password": {"String": {"Quoted": "pass word"}},
+                }
+            }
+        }),
+    );
+    golden(
+        Command::new("A2", CommandBody::Noop).unwrap(),
+        json!({"tag": "A2", "body": "Noop"}),
+    );
+    golden(
+        SequenceSet::try_from("1:*").unwrap(),
+        json!([{"Range": [{"Value": 1}, "Asterisk"]}]),
+    );
+    golden(Flag::Seen, json!("Seen"));
+    golden(
+        Flag::Keyword(Atom::try_from("Work").unwrap()),
+        json!({"Keyword": "Work"}),
+    );
+}
+
+#[test]
+fn test_serde_response() {
+    golden(
+        Response::Status(
+            Status::ok(
+                Some(Tag::try_from("A1").unwrap()),
+                Some(Code::Alert),
+                "done",
+            )
+            .unwrap(),
+        ),
+        json!({
+            "Status": {
+                "Tagged": {
+                    "tag": "A1",
+                    "body": {"kind": "Ok", "code": "Alert", "text": "done"},
+                }
+            }
+        }),
+    );
+    golden(
+        Response::Data(
+            Data::fetch(
+                1,
+                vec![
+                    MessageDataItem::Rfc822Size(42),
+                    MessageDataItem::Rfc822(NString(None)),
+                ],
+            )
+            .unwrap(),
+        ),
+        json!({
+            "Data": {
+                "Fetch": {
+                    "seq": 1,
+                    "items": [{"Rfc822Size": 42}, {"Rfc822": null}],
+                }
+            }
+        }),
+    );
+}